@@ -0,0 +1,46 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Record the Git commit the binary is built from in the environment variable `CRGP_GIT_HASH`, if available.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() {
+    let hash: String = match git(&["rev-parse", "HEAD"]) {
+        Some(hash) => hash,
+        None => return
+    };
+    println!("cargo:rustc-env=CRGP_GIT_HASH={hash}", hash = hash);
+
+    // Rebuild once another commit is checked out: `HEAD` names the current branch (or commit), and the branch's ref
+    // changes with each commit. Packed refs are only stored in `packed-refs`.
+    let git_directory: PathBuf = match git(&["rev-parse", "--git-dir"]) {
+        Some(directory) => PathBuf::from(directory),
+        None => return
+    };
+    let mut watched_files: Vec<PathBuf> = vec![git_directory.join("HEAD"), git_directory.join("packed-refs")];
+    if let Some(reference) = git(&["rev-parse", "--symbolic-full-name", "HEAD"]) {
+        watched_files.push(git_directory.join(reference));
+    }
+    for file in watched_files.into_iter().filter(|file| file.is_file()) {
+        println!("cargo:rerun-if-changed={file}", file = file.display());
+    }
+}
+
+/// Run `git` with the given `arguments`. Return its trimmed output if it succeeded.
+fn git(arguments: &[&str]) -> Option<String> {
+    let output = match Command::new("git").args(arguments).output() {
+        Ok(output) => output,
+        Err(_) => return None
+    };
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Measure the effect of reserving memory for the activations of each cascade on `GALE`.
+//!
+//! By default, the small data sets in the `data` folder are used. For meaningful numbers, set the environment
+//! variables `CRGP_BENCH_RETWEETS` and `CRGP_BENCH_SOCIAL_GRAPH` to a larger Retweet data set dominated by large
+//! cascades and its social graph, and `CRGP_BENCH_ACTIVATION_CAPACITY` to the typical size of these cascades.
+
+#![feature(test)]
+
+extern crate crgp_lib;
+extern crate find_folder;
+extern crate test;
+
+use std::env;
+use std::path::PathBuf;
+
+use crgp_lib::Configuration;
+use crgp_lib::configuration::InputSource;
+use crgp_lib::configuration::OutputTarget;
+use find_folder::Search;
+use test::Bencher;
+
+/// Get the configuration for the benchmark data sets, without writing any results.
+fn get_configuration() -> Configuration {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+    let retweets: String = env::var("CRGP_BENCH_RETWEETS")
+        .unwrap_or_else(|_| String::from(data_path.join("retweets.json").to_str().unwrap()));
+    let social_graph: String = env::var("CRGP_BENCH_SOCIAL_GRAPH")
+        .unwrap_or_else(|_| String::from(data_path.join("social_graph").to_str().unwrap()));
+
+    Configuration::default(InputSource::new(&retweets), InputSource::new(&social_graph))
+        .output_target(OutputTarget::None)
+}
+
+#[bench]
+fn default(bencher: &mut Bencher) {
+    let configuration: Configuration = get_configuration();
+
+    bencher.iter(|| {
+        crgp_lib::run(configuration.clone()).expect("The computation failed")
+    });
+}
+
+#[bench]
+fn reserved(bencher: &mut Bencher) {
+    let capacity: usize = env::var("CRGP_BENCH_ACTIVATION_CAPACITY")
+        .ok()
+        .and_then(|capacity| capacity.parse().ok())
+        .unwrap_or(1024);
+    let configuration: Configuration = get_configuration()
+        .activation_set_initial_capacity(capacity);
+
+    bencher.iter(|| {
+        crgp_lib::run(configuration.clone()).expect("The computation failed")
+    });
+}
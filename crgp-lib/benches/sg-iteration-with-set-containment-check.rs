@@ -429,7 +429,12 @@ mod sg {
     fn get_user_id(path: &PathBuf) -> Option<UserID> {
         if let Some(stem) = path.file_stem() {
             if let Some(stem) = stem.to_str() {
-                match stem[7..].parse::<UserID>() {
+                // Slicing off the prefix is only safe if the stem actually starts with it.
+                if !stem.starts_with("friends") {
+                    return None;
+                }
+
+                match stem["friends".len()..].parse::<UserID>() {
                     Ok(id) => return Some(id),
                     Err(_) => return None,
                 }
@@ -21,6 +21,21 @@ pub enum Algorithm {
     ///
     /// `LEAF` = Local Edges, Activations, and Filtering
     LEAF,
+
+    /// Store the entire social graph on all workers, send each Retweet to the single worker responsible for its
+    /// cascade, and activate the user and produce influence edges on that worker. Avoids broadcasting the Retweets at
+    /// the cost of replicating the social graph.
+    ///
+    /// `PACE` = Partitioned Activations, Copied Edges
+    PACE,
+
+    /// Distribute the activations and friendships as in `GALE`, but produce an influence edge from each activated
+    /// friend of the retweeting user, regardless of the activation times. The influence edges are a superset of those
+    /// found by `GALE`, including edges which cannot be actual influences; they are meant for graph-theoretic analyses
+    /// of the activated users.
+    ///
+    /// `FULL` = Full Activation
+    FullActivation,
 }
 
 impl fmt::Display for Algorithm {
@@ -28,6 +43,8 @@ impl fmt::Display for Algorithm {
         let algorithm_name: &str = match *self {
             Algorithm::GALE => "GALE",
             Algorithm::LEAF => "LEAF",
+            Algorithm::PACE => "PACE",
+            Algorithm::FullActivation => "FULL",
         };
         write!(formatter, "{algorithm}", algorithm = algorithm_name)
     }
@@ -48,4 +65,16 @@ mod tests {
         let algorithm = Algorithm::LEAF;
         assert_eq!(format!("{}", algorithm), String::from("LEAF"));
     }
+
+    #[test]
+    fn fmt_display_pace() {
+        let algorithm = Algorithm::PACE;
+        assert_eq!(format!("{}", algorithm), String::from("PACE"));
+    }
+
+    #[test]
+    fn fmt_display_full_activation() {
+        let algorithm = Algorithm::FullActivation;
+        assert_eq!(format!("{}", algorithm), String::from("FULL"));
+    }
 }
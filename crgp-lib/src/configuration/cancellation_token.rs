@@ -0,0 +1,69 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for cancelling a running computation.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// A flag shared with other threads to request the cancellation of a running computation.
+///
+/// Two tokens are equal if they share the same flag.
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    /// The shared flag. If `true`, cancellation has been requested.
+    flag: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token from the given shared `flag`.
+    pub fn new(flag: Arc<AtomicBool>) -> CancellationToken {
+        CancellationToken {
+            flag: flag,
+        }
+    }
+
+    /// Determine if cancellation has been requested.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &CancellationToken) -> bool {
+        Arc::ptr_eq(&self.flag, &other.flag)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use super::*;
+
+    #[test]
+    fn is_cancelled() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::new(flag.clone());
+        assert!(!token.is_cancelled());
+
+        flag.store(true, Ordering::SeqCst);
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn eq() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::new(flag.clone());
+        assert_eq!(token, CancellationToken::new(flag));
+        assert_ne!(token, CancellationToken::new(Arc::new(AtomicBool::new(false))));
+    }
+}
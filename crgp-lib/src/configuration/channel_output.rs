@@ -0,0 +1,95 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for delivering the result in-process via a channel.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc::Sender;
+
+use UserId;
+use social_graph::InfluenceEdge;
+
+/// A handle to a channel to which each reconstructed influence edge is sent as soon as it is written.
+///
+/// The sender is cloned by each worker; the edges are sent in the order in which the first worker writes them (see
+/// `Write::write`). Once the computation has finished, all clones are dropped; the configuration kept in the returned
+/// `Statistics` does not include the handle. Thus, if no other senders exist, the channel is closed and the receiver's
+/// iterator ends, even while the statistics are still in use. Since the channel is shared in memory, it can only be
+/// used if the computation runs in a single process.
+///
+/// Clones of the handle share the same sender. Two handles are equal if they share the same sender.
+#[derive(Clone)]
+pub struct ChannelOutput {
+    /// The sender to the channel.
+    sender: Arc<Mutex<Sender<InfluenceEdge<UserId>>>>,
+}
+
+impl ChannelOutput {
+    /// Create a handle to the channel of the given `sender`.
+    pub fn new(sender: Sender<InfluenceEdge<UserId>>) -> ChannelOutput {
+        ChannelOutput {
+            sender: Arc::new(Mutex::new(sender)),
+        }
+    }
+
+    /// Get a clone of the sender, which can be used without locking the shared one.
+    #[doc(hidden)]
+    pub fn sender(&self) -> Sender<InfluenceEdge<UserId>> {
+        match self.sender.lock() {
+            Ok(sender) => sender.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        }
+    }
+}
+
+impl fmt::Debug for ChannelOutput {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ChannelOutput")
+    }
+}
+
+impl PartialEq for ChannelOutput {
+    fn eq(&self, other: &ChannelOutput) -> bool {
+        Arc::ptr_eq(&self.sender, &other.sender)
+    }
+}
+
+impl Eq for ChannelOutput {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use social_graph::InfluenceEdge;
+    use super::*;
+
+    /// Create an influence edge from the `influencer` to the `influencee` in cascade `4` of user `0`.
+    fn edge(influencer: i64, influencee: i64, timestamp: u64, retweet_id: u64) -> InfluenceEdge<UserId> {
+        InfluenceEdge::new(UserId::from(influencer), UserId::from(influencee), timestamp, retweet_id, 4,
+                           UserId::from(0))
+    }
+
+    #[test]
+    fn sender() {
+        let (sender, receiver) = channel();
+        let output = ChannelOutput::new(sender);
+        output.sender().send(edge(0, 1, 2, 3)).expect("Could not send the edge");
+
+        // Clones share the sender.
+        let clone = output.clone();
+        assert_eq!(clone, output);
+        clone.sender().send(edge(1, 2, 5, 6)).expect("Could not send the edge");
+        let (other_sender, _other_receiver) = channel();
+        assert_ne!(output, ChannelOutput::new(other_sender));
+
+        // The channel is closed once all handles are dropped.
+        drop(output);
+        drop(clone);
+        let edges: Vec<InfluenceEdge<UserId>> = receiver.iter().collect();
+        assert_eq!(edges, vec![edge(0, 1, 2, 3), edge(1, 2, 5, 6)]);
+    }
+}
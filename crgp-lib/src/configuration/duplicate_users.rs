@@ -0,0 +1,45 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for handling users with multiple friend files.
+
+use std::fmt;
+
+/// Specify how users with more than one friend file in the social graph (e.g. in different TAR archives) are handled.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DuplicateUserPolicy {
+    /// Merge the friend lists from all of the user's friend files.
+    Merge,
+
+    /// Only use the first friend file found for the user and skip all others.
+    KeepFirst,
+
+    /// Abort loading the social graph with an error.
+    Fail,
+}
+
+impl fmt::Display for DuplicateUserPolicy {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let policy: &str = match *self {
+            DuplicateUserPolicy::Merge => "Merge",
+            DuplicateUserPolicy::KeepFirst => "Keep First",
+            DuplicateUserPolicy::Fail => "Fail",
+        };
+        write!(formatter, "{policy}", policy = policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", DuplicateUserPolicy::Merge), String::from("Merge"));
+        assert_eq!(format!("{}", DuplicateUserPolicy::KeepFirst), String::from("Keep First"));
+        assert_eq!(format!("{}", DuplicateUserPolicy::Fail), String::from("Fail"));
+    }
+}
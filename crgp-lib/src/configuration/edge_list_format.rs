@@ -0,0 +1,177 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for parsing social graphs given as flat edge lists.
+
+use std::fmt;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+
+use Error;
+use Result;
+use twitter::UserId;
+
+/// Specify the base in which user IDs are given.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum IdBase {
+    /// IDs are given as decimal numbers, e.g. `255`.
+    Decimal,
+
+    /// IDs are given as hexadecimal numbers, with or without a leading `0x`, e.g. `ff` or `0xFF`.
+    Hexadecimal,
+}
+
+impl IdBase {
+    /// Get the radix of the base.
+    pub fn radix(&self) -> u32 {
+        match *self {
+            IdBase::Decimal => 10,
+            IdBase::Hexadecimal => 16,
+        }
+    }
+}
+
+impl fmt::Display for IdBase {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let base: &str = match *self {
+            IdBase::Decimal => "Decimal",
+            IdBase::Hexadecimal => "Hexadecimal",
+        };
+        write!(formatter, "{base}", base = base)
+    }
+}
+
+/// Specify how each line of a flat edge list is parsed. Each line consists of exactly two user IDs separated by the
+/// `delimiter`: the user and one of their friends, e.g. `1\t2` or `1,2`. Whitespace around the IDs is ignored.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EdgeListFormat {
+    /// The character separating the two IDs on each line.
+    pub delimiter: char,
+
+    /// The base in which the IDs are given.
+    pub id_base: IdBase,
+}
+
+impl EdgeListFormat {
+    /// Create a format for lines with IDs in the given `id_base`, separated by the `delimiter`.
+    pub fn new(delimiter: char, id_base: IdBase) -> EdgeListFormat {
+        EdgeListFormat {
+            delimiter: delimiter,
+            id_base: id_base,
+        }
+    }
+
+    /// Parse the `line` with the given (1-based) `line_number` into a pair of the user and their friend. Errors if the
+    /// line does not consist of exactly two fields or if an ID cannot be parsed. The error message contains the line
+    /// number.
+    pub fn parse_line(&self, line: &str, line_number: usize) -> Result<(UserId, UserId)> {
+        let fields: Vec<&str> = line.split(self.delimiter).collect();
+        if fields.len() != 2 {
+            let message: String = format!("Line {number}: expected exactly 2 fields separated by {delimiter:?}, found \
+                                           {fields}",
+                                          number = line_number, delimiter = self.delimiter, fields = fields.len());
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+
+        let user: UserId = self.parse_id(fields[0], line_number)?;
+        let friend: UserId = self.parse_id(fields[1], line_number)?;
+        Ok((user, friend))
+    }
+
+    /// Parse a single `id` on the line with the given `line_number`.
+    fn parse_id(&self, id: &str, line_number: usize) -> Result<UserId> {
+        let id: &str = id.trim();
+        let digits: &str = if self.id_base == IdBase::Hexadecimal && (id.starts_with("0x") || id.starts_with("0X")) {
+            &id[2..]
+        } else {
+            id
+        };
+
+        match i64::from_str_radix(digits, self.id_base.radix()) {
+            Ok(id) => Ok(UserId::from(id)),
+            Err(error) => {
+                let message: String = format!("Line {number}: invalid {base} user ID '{id}': {error}",
+                                              number = line_number, base = self.id_base, id = id, error = error);
+                Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)))
+            }
+        }
+    }
+}
+
+impl Default for EdgeListFormat {
+    /// Tab-separated decimal IDs.
+    fn default() -> EdgeListFormat {
+        EdgeListFormat::new('\t', IdBase::Decimal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as StdError;
+    use super::*;
+
+    /// Create the pair of the `user` and their `friend` given by their raw IDs.
+    fn pair(user: i64, friend: i64) -> (UserId, UserId) {
+        (UserId::from(user), UserId::from(friend))
+    }
+
+    #[test]
+    fn default() {
+        let format = EdgeListFormat::default();
+        assert_eq!(format.delimiter, '\t');
+        assert_eq!(format.id_base, IdBase::Decimal);
+    }
+
+    #[test]
+    fn parse_line_decimal() {
+        let tab = EdgeListFormat::new('\t', IdBase::Decimal);
+        assert_eq!(tab.parse_line("1\t2", 1).expect("Could not parse the line"), pair(1, 2));
+
+        let comma = EdgeListFormat::new(',', IdBase::Decimal);
+        assert_eq!(comma.parse_line("10, 20", 1).expect("Could not parse the line"), pair(10, 20));
+
+        let space = EdgeListFormat::new(' ', IdBase::Decimal);
+        assert_eq!(space.parse_line("255 16", 1).expect("Could not parse the line"), pair(255, 16));
+
+        // Hexadecimal digits are invalid.
+        assert!(tab.parse_line("ff\t10", 1).is_err());
+    }
+
+    #[test]
+    fn parse_line_hexadecimal() {
+        let tab = EdgeListFormat::new('\t', IdBase::Hexadecimal);
+        assert_eq!(tab.parse_line("A\tB", 1).expect("Could not parse the line"), pair(10, 11));
+
+        let comma = EdgeListFormat::new(',', IdBase::Hexadecimal);
+        assert_eq!(comma.parse_line("0xff,0X10", 1).expect("Could not parse the line"), pair(255, 16));
+
+        let space = EdgeListFormat::new(' ', IdBase::Hexadecimal);
+        assert_eq!(space.parse_line("ff 10", 1).expect("Could not parse the line"), pair(255, 16));
+
+        assert!(tab.parse_line("g\t1", 1).is_err());
+    }
+
+    #[test]
+    fn parse_line_invalid_fields() {
+        let format = EdgeListFormat::new(',', IdBase::Decimal);
+
+        let error = format.parse_line("1,2,3", 7).expect_err("Parsed a line with three fields");
+        assert_eq!(error.description(), "Line 7: expected exactly 2 fields separated by ',', found 3");
+
+        // A line using another delimiter consists of a single field only.
+        let error = format.parse_line("1\t2", 8).expect_err("Parsed a line with a wrong delimiter");
+        assert_eq!(error.description(), "Line 8: expected exactly 2 fields separated by ',', found 1");
+
+        let error = format.parse_line("1,a", 9).expect_err("Parsed an invalid ID");
+        assert!(error.description().starts_with("Line 9: invalid Decimal user ID 'a'"));
+    }
+
+    #[test]
+    fn fmt_display_id_base() {
+        assert_eq!(format!("{}", IdBase::Decimal), String::from("Decimal"));
+        assert_eq!(format!("{}", IdBase::Hexadecimal), String::from("Hexadecimal"));
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for providing the social graph programmatically instead of loading it from a data set.
+
+use std::fmt;
+use std::sync::Arc;
+
+use UserId;
+
+/// A function providing the friend list of each user, together with the users for whom it will be queried.
+///
+/// The function is called once for each of the users, in their given order, and returns the IDs of the user's friends,
+/// or `None` if the user is not part of the social graph. It is only invoked on the worker loading the social graph,
+/// i.e. on the first worker of the first process, but it must be thread-safe since the worker runs in its own thread.
+///
+/// Clones of the callback share the same function and users. Two callbacks are equal if they are clones of each other.
+#[derive(Clone)]
+pub struct FriendListCallback {
+    /// The function returning the friends of a user, given by their ID.
+    callback: Arc<Fn(UserId) -> Option<Vec<UserId>> + Send + Sync>,
+
+    /// The users for whom the function is queried.
+    users: Arc<Vec<UserId>>,
+}
+
+impl FriendListCallback {
+    /// Create a callback querying the friend list of each of the `users` from the given function.
+    pub fn new<F>(users: Vec<UserId>, callback: F) -> FriendListCallback
+        where F: Fn(UserId) -> Option<Vec<UserId>> + Send + Sync + 'static
+    {
+        FriendListCallback {
+            callback: Arc::new(callback),
+            users: Arc::new(users),
+        }
+    }
+
+    /// Get the users for whom the friend lists are queried.
+    pub fn users(&self) -> &[UserId] {
+        &self.users
+    }
+
+    /// Get the friends of the given `user`, or `None` if the user is not part of the social graph.
+    pub fn friends(&self, user: UserId) -> Option<Vec<UserId>> {
+        (self.callback)(user)
+    }
+}
+
+impl fmt::Debug for FriendListCallback {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "FriendListCallback for {number} users", number = self.users.len())
+    }
+}
+
+impl PartialEq for FriendListCallback {
+    fn eq(&self, other: &FriendListCallback) -> bool {
+        Arc::ptr_eq(&self.users, &other.users)
+    }
+}
+
+impl Eq for FriendListCallback {}
+
+#[cfg(test)]
+mod tests {
+    use UserId;
+    use super::*;
+
+    /// Convert the raw `ids` to user IDs.
+    fn user_ids(ids: &[i64]) -> Vec<UserId> {
+        ids.iter().map(|&id| UserId::from(id)).collect()
+    }
+
+    #[test]
+    fn friends() {
+        let callback = FriendListCallback::new(user_ids(&[1, 2, 3]), |user: UserId| {
+            let user: i64 = i64::from(user);
+            if user < 3 {
+                Some((0..user).map(UserId::from).collect())
+            } else {
+                None
+            }
+        });
+
+        assert_eq!(callback.users().to_vec(), user_ids(&[1, 2, 3]));
+        assert_eq!(callback.friends(UserId::from(1)), Some(user_ids(&[0])));
+        assert_eq!(callback.friends(UserId::from(2)), Some(user_ids(&[0, 1])));
+        assert_eq!(callback.friends(UserId::from(3)), None);
+    }
+
+    #[test]
+    fn eq() {
+        let callback = FriendListCallback::new(user_ids(&[1]), |_| None);
+        assert_eq!(callback, callback.clone());
+        assert_ne!(callback, FriendListCallback::new(user_ids(&[1]), |_| None));
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for Google Cloud Storage access.
+
+use std::fmt;
+
+use Result;
+use gcs::Bucket;
+use gcs::credentials_from_env;
+
+/// Configuration for accessing Google Cloud Storage. The access token will be loaded from the respective environment
+/// variable when requesting the bucket.
+///
+/// The access token will never be written when serializing the GCS configuration!
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GCS {
+    /// The bucket to access.
+    pub bucket: String,
+
+    /// Private field to prevent initialization without the provided methods.
+    ///
+    /// All other fields should be public for easy access without getter functions. However, adding more fields later
+    /// could break code if the `GCS` configuration were manually initialized.
+    #[serde(skip_serializing)]
+    _prevent_outside_initialization: bool,
+}
+
+impl GCS {
+    /// Initialize a configuration for accessing Google Cloud Storage.
+    pub fn new(bucket: &str) -> GCS {
+        GCS {
+            bucket: String::from(bucket),
+            _prevent_outside_initialization: true,
+        }
+    }
+
+    /// Get a connection to Google Cloud Storage.
+    pub fn get_bucket(&self) -> Result<Bucket> {
+        let access_token: String = credentials_from_env()?;
+        Ok(Bucket::new(&self.bucket, &access_token))
+    }
+}
+
+impl fmt::Display for GCS {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{bucket}", bucket = self.bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::remove_var;
+    use std::env::set_var;
+    use gcs::ACCESS_TOKEN_VAR_NAME;
+    use gcs::Bucket;
+    use Result;
+    use super::*;
+
+    #[test]
+    fn new() {
+        let gcs = GCS::new("bucket");
+        assert_eq!(gcs.bucket, String::from("bucket"));
+        assert!(gcs._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn get_bucket() {
+        let gcs = GCS::new("bucket");
+
+        // The access token is only set in this test, since tests run in parallel.
+        set_var(ACCESS_TOKEN_VAR_NAME, "Access Token");
+        let bucket: Result<Bucket> = gcs.get_bucket();
+        assert_eq!(bucket.expect("Could not get the bucket"), Bucket::new("bucket", "Access Token"));
+
+        remove_var(ACCESS_TOKEN_VAR_NAME);
+        assert!(gcs.get_bucket().is_err());
+    }
+
+    #[test]
+    fn fmt_display() {
+        let gcs = GCS::new("bucket");
+        assert_eq!(format!("{}", gcs), String::from("bucket"));
+    }
+}
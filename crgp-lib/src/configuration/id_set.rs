@@ -0,0 +1,131 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Read sets of IDs (e.g. of users or cascades) from text files with one ID per line.
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use Result;
+use read_text_source;
+use configuration::InputSource;
+
+/// The prefix of comment lines in ID files.
+const COMMENT_PREFIX: &'static str = "#";
+
+/// Load the text file given by `source` and insert all IDs into the `out` set. Errors on any I/O error opening the
+/// file.
+///
+/// Each line of the file contains a single ID, surrounded by optional whitespace. Blank lines and lines starting with
+/// `#` are skipped. Lines that cannot be read or parsed are skipped with a warning giving their line number.
+pub fn read_id_set<T>(source: InputSource, out: &mut HashSet<T>) -> Result<()>
+    where T: Eq + FromStr + Hash,
+          T::Err: Display
+{
+    let reader = read_text_source(source.clone())?;
+    let _ = parse_ids(reader, &source, out);
+    Ok(())
+}
+
+/// Parse the lines of the `reader` (read from the `source`) into IDs and insert them into the `out` set. Return the
+/// number of skipped malformed lines.
+fn parse_ids<R, T>(reader: R, source: &InputSource, out: &mut HashSet<T>) -> usize
+    where R: BufRead,
+          T: Eq + FromStr + Hash,
+          T::Err: Display
+{
+    let mut malformed_lines: usize = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line: String = match line {
+            Ok(line) => line,
+            Err(message) => {
+                warn!("Invalid line {number} in file {file}: {error}", number = index + 1, file = source,
+                      error = message);
+                malformed_lines += 1;
+                continue;
+            }
+        };
+
+        let id: &str = line.trim();
+        if id.is_empty() || id.starts_with(COMMENT_PREFIX) {
+            continue;
+        }
+
+        match id.parse::<T>() {
+            Ok(id) => {
+                let _ = out.insert(id);
+            },
+            Err(message) => {
+                warn!("Could not parse ID '{id}' on line {number} in file {file}: {error}",
+                      id = id, number = index + 1, file = source, error = message);
+                malformed_lines += 1;
+            }
+        }
+    }
+
+    malformed_lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use UserId;
+    use configuration::InputSource;
+
+    #[test]
+    fn parse_ids() {
+        let source = InputSource::new("path/to/ids.txt");
+        let contents = Cursor::new("# Selected users\n1\n\n  2 \n# 3\n-4\n\t\n");
+
+        let mut ids: HashSet<UserId> = HashSet::new();
+        assert_eq!(super::parse_ids(contents, &source, &mut ids), 0);
+
+        let expected: HashSet<UserId> = [1, 2, -4].iter().map(|&id| UserId::from(id)).collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn parse_ids_malformed() {
+        let source = InputSource::new("path/to/ids.txt");
+
+        // Negative IDs cannot be parsed as unsigned IDs.
+        let contents = Cursor::new("1\nabc\n-2\n3 4\n5\n");
+        let mut ids: HashSet<u64> = HashSet::new();
+        assert_eq!(super::parse_ids(contents, &source, &mut ids), 3);
+
+        let expected: HashSet<u64> = [1, 5].iter().cloned().collect();
+        assert_eq!(ids, expected);
+
+        // Lines that are not valid UTF-8 cannot be read.
+        let contents = Cursor::new(b"1\n\xff\xfe\n2\n".to_vec());
+        let mut ids: HashSet<u64> = HashSet::new();
+        assert_eq!(super::parse_ids(contents, &source, &mut ids), 1);
+
+        let expected: HashSet<u64> = [1, 2].iter().cloned().collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn read_id_set() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let source = InputSource::new(data_path.join("retweeting_users.txt"));
+
+        let mut ids: HashSet<UserId> = HashSet::new();
+        super::read_id_set(source, &mut ids).expect("Could not read the IDs");
+
+        let expected: HashSet<UserId> = [0, 1, 2, 3].iter().map(|&id| UserId::from(id)).collect();
+        assert_eq!(ids, expected);
+
+        let mut ids: HashSet<UserId> = HashSet::new();
+        assert!(super::read_id_set(InputSource::new("path/to/missing/file.txt"), &mut ids).is_err());
+    }
+}
@@ -7,20 +7,28 @@
 //! Configuration for input sources.
 
 use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
 
+use configuration::GCS;
 use configuration::S3;
 
 /// Configuration of an input source, for either social graph or cascade data sets.
 ///
-/// Supports AWS S3.
+/// Supports AWS S3, and Google Cloud Storage for social graphs given as TAR archives. At most one of them can be set.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct InputSource {
     /// Path to the input file.
-    pub path: String,
+    ///
+    /// The path is not required to be valid UTF-8 for local files. Paths on AWS S3 are converted lossily.
+    pub path: PathBuf,
 
     /// Optionally, configuration to access AWS S3.
     pub s3: Option<S3>,
 
+    /// Optionally, configuration to access Google Cloud Storage.
+    pub gcs: Option<GCS>,
+
     /// Private field to prevent initialization without the provided methods.
     ///
     /// All other fields should be public for easy access without getter functions. However, adding more fields later
@@ -30,11 +38,15 @@ pub struct InputSource {
 }
 
 impl InputSource {
-    /// Initialize a new input source from a path. The AWS S3 configuration will be set to `None`.
-    pub fn new(path: &str) -> InputSource {
+    /// Initialize a new input source from a path. The AWS S3 and Google Cloud Storage configurations will be set to
+    /// `None`.
+    ///
+    /// The `path` can be given as anything that can be referenced as a `Path`, e.g. a `&str` or a `PathBuf`.
+    pub fn new<P: AsRef<Path>>(path: P) -> InputSource {
         InputSource {
-            path: String::from(path),
+            path: path.as_ref().to_path_buf(),
             s3: None,
+            gcs: None,
             _prevent_outside_initialization: true,
         }
     }
@@ -44,28 +56,51 @@ impl InputSource {
         self.s3 = s3_configuration;
         self
     }
+
+    /// Set the Google Cloud Storage configuration.
+    pub fn gcs(mut self, gcs_configuration: Option<GCS>) -> InputSource {
+        self.gcs = gcs_configuration;
+        self
+    }
 }
 
 impl fmt::Display for InputSource {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        match self.s3 {
-            Some(ref s3) => write!(formatter, "{path} on S3 {s3}", path = self.path, s3 = s3),
-            None => write!(formatter, "{path}", path = self.path)
+        match (&self.s3, &self.gcs) {
+            (&Some(ref s3), _) => write!(formatter, "{path} on S3 {s3}", path = self.path.display(), s3 = s3),
+            (&None, &Some(ref gcs)) => write!(formatter, "{path} on GCS {gcs}", path = self.path.display(), gcs = gcs),
+            (&None, &None) => write!(formatter, "{path}", path = self.path.display())
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+    use configuration::GCS;
     use configuration::S3;
     use super::*;
 
     #[test]
     fn new() {
         let input = InputSource::new("path/to/source");
-        assert_eq!(input.path, String::from("path/to/source"));
+        assert_eq!(input.path, PathBuf::from("path/to/source"));
+        assert_eq!(input.s3, None);
+        assert_eq!(input.gcs, None);
+        assert!(input._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn new_from_path() {
+        let path = PathBuf::from("path").join("to").join("source");
+        let input = InputSource::new(&path);
+        assert_eq!(input.path, path);
         assert_eq!(input.s3, None);
         assert!(input._prevent_outside_initialization);
+
+        let input = InputSource::new(path.clone());
+        assert_eq!(input.path, path);
+        assert_eq!(input, InputSource::new(path.as_path()));
     }
 
     #[test]
@@ -73,11 +108,22 @@ mod tests {
         let s3_config = S3::new("bucket", "region");
         let input = InputSource::new("path/to/source")
             .s3(Some(s3_config.clone()));
-        assert_eq!(input.path, String::from("path/to/source"));
+        assert_eq!(input.path, PathBuf::from("path/to/source"));
         assert_eq!(input.s3, Some(s3_config));
         assert!(input._prevent_outside_initialization);
     }
 
+    #[test]
+    fn gcs() {
+        let gcs_config = GCS::new("bucket");
+        let input = InputSource::new("path/to/source")
+            .gcs(Some(gcs_config.clone()));
+        assert_eq!(input.path, PathBuf::from("path/to/source"));
+        assert_eq!(input.s3, None);
+        assert_eq!(input.gcs, Some(gcs_config));
+        assert!(input._prevent_outside_initialization);
+    }
+
     #[test]
     fn fmt_display_no_s3() {
         let input = InputSource::new("path/to/source");
@@ -91,4 +137,11 @@ mod tests {
             .s3(Some(s3_config.clone()));
         assert_eq!(format!("{}", input), format!("path/to/source on S3 {}", s3_config));
     }
+
+    #[test]
+    fn fmt_display_with_gcs() {
+        let input = InputSource::new("path/to/source")
+            .gcs(Some(GCS::new("bucket")));
+        assert_eq!(format!("{}", input), String::from("path/to/source on GCS bucket"));
+    }
 }
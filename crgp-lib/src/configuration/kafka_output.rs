@@ -0,0 +1,40 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for producing the result to Apache Kafka.
+
+use std::fmt;
+
+/// Specify how errors are handled when producing the result to Kafka.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum KafkaErrorPolicy {
+    /// Log the error and continue with the computation. The affected influence edges will be lost.
+    Log,
+
+    /// Log the error and fail the computation.
+    Fail,
+}
+
+impl fmt::Display for KafkaErrorPolicy {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let policy: &str = match *self {
+            KafkaErrorPolicy::Log => "Log",
+            KafkaErrorPolicy::Fail => "Fail",
+        };
+        write!(formatter, "{policy}", policy = policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", KafkaErrorPolicy::Log), String::from("Log"));
+        assert_eq!(format!("{}", KafkaErrorPolicy::Fail), String::from("Fail"));
+    }
+}
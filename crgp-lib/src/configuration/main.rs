@@ -6,16 +6,30 @@
 
 //! The main configuration object.
 
+use std::collections::HashSet;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 
 use timely_communication::initialize::Configuration as TimelyConfiguration;
 
 use Error;
 use Result;
+use UserId;
 use configuration::Algorithm;
+use configuration::CancellationToken;
+use configuration::DuplicateUserPolicy;
+use configuration::EdgeListFormat;
+use configuration::FriendListCallback;
 use configuration::InputSource;
+use configuration::OutputFormat;
 use configuration::OutputTarget;
+use configuration::Preset;
+use configuration::ProgressCallback;
+use configuration::Ratio;
+use configuration::TimeUnit;
+use configuration::TimestampFormat;
 
 /// Configuration for the `CRGP` algorithm.
 ///
@@ -28,8 +42,12 @@ use configuration::OutputTarget;
 ///
 /// use crgp_lib::Configuration;
 /// use crgp_lib::configuration::Algorithm;
+/// use crgp_lib::configuration::DuplicateUserPolicy;
+/// use crgp_lib::configuration::EdgeListFormat;
 /// use crgp_lib::configuration::InputSource;
+/// use crgp_lib::configuration::OutputFormat;
 /// use crgp_lib::configuration::OutputTarget;
+/// use crgp_lib::configuration::TimestampFormat;
 ///
 /// let retweets = InputSource::new("path/to/retweets.json");
 /// let social_graph = InputSource::new("path/to/social/graph");
@@ -40,38 +58,281 @@ use configuration::OutputTarget;
 ///     .pad_with_dummy_users(true)
 ///     .workers(2);
 ///
+/// assert!(configuration.additional_social_graphs.is_empty());
+/// assert_eq!(configuration.activation_grace_seconds, 0);
+/// assert_eq!(configuration.activation_log, None);
+/// assert_eq!(configuration.activation_set_initial_capacity, 0);
 /// assert_eq!(configuration.algorithm, Algorithm::GALE);
+/// assert_eq!(configuration.assert_sorted_retweets, false);
 /// assert_eq!(configuration.batch_size, 50000);
+/// assert_eq!(configuration.buffer_output, false);
+/// assert_eq!(configuration.cancellation_token, None);
+/// assert_eq!(configuration.cascade_size_quantiles, false);
+/// assert_eq!(configuration.cascade_timeout, None);
+/// assert_eq!(configuration.cascades_time_ordered, false);
+/// assert_eq!(configuration.deduplicate_edges, false);
+/// assert_eq!(configuration.dry_run, false);
+/// assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::Merge);
+/// assert_eq!(configuration.edge_list_format, EdgeListFormat::default());
+/// assert_eq!(configuration.edge_timeseries, None);
+/// assert_eq!(configuration.emit_time_delta, None);
+/// assert_eq!(configuration.flush_on_cascade_complete, false);
+/// assert_eq!(configuration.friend_expansion_hops, 0);
+/// assert_eq!(configuration.friend_list_callback, None);
+/// assert_eq!(configuration.graph_load_statistics, None);
+/// assert_eq!(configuration.graph_owner_workers, None);
+/// assert_eq!(configuration.hash_output, false);
 /// assert_eq!(configuration.hosts, None);
+/// assert_eq!(configuration.include_quotes, false);
+/// assert_eq!(configuration.max_dummy_ratio, None);
+/// assert_eq!(configuration.max_expanded_friends, 100_000);
+/// assert_eq!(configuration.max_expected_friends, 100_000_000);
+/// assert_eq!(configuration.max_output_edges, None);
+/// assert_eq!(configuration.max_retweets, None);
+/// assert_eq!(configuration.minimal_stats, false);
 /// assert_eq!(configuration.number_of_processes, 1);
 /// assert_eq!(configuration.number_of_workers, 2);
+/// assert_eq!(configuration.output_format, OutputFormat::Edges);
+/// assert_eq!(configuration.output_root_users, None);
 /// assert_eq!(configuration.output_target,
 ///            OutputTarget::Directory(PathBuf::from("results")));
 /// assert_eq!(configuration.pad_with_dummy_users, true);
 /// assert_eq!(configuration.process_id, 0);
+/// assert_eq!(configuration.progress_callback, None);
+/// assert_eq!(configuration.progress_json, false);
+/// assert_eq!(configuration.repetitions, 1);
 /// assert_eq!(configuration.report_connection_progress, false);
+/// assert_eq!(configuration.report_top_cascades, 0);
 /// assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+/// assert_eq!(configuration.s3_max_retries, 3);
+/// assert_eq!(configuration.sample_output, None);
+/// assert_eq!(configuration.screen_names, None);
+/// assert_eq!(configuration.seed, 0);
+/// assert_eq!(configuration.seed_authors, None);
+/// assert!(configuration.selected_user_ids.is_empty());
 /// assert_eq!(configuration.selected_users, None);
 /// assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+/// assert_eq!(configuration.sort_friends, false);
+/// assert_eq!(configuration.stream_retweets, false);
+/// assert_eq!(configuration.strict, false);
+/// assert_eq!(configuration.sync_timeout, None);
+/// assert_eq!(configuration.timestamp_format, TimestampFormat::Auto);
+/// assert_eq!(configuration.timestamp_join_file, None);
+/// assert_eq!(configuration.track_friendship_utilization, false);
 /// ```
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Configuration {
+    /// The number of seconds by which a friend's activation may follow a Retweet for the friend to still be
+    /// considered as an influencer, e.g. to compensate for imprecise or skewed timestamps. A negative grace window
+    /// instead requires the activation to precede the Retweet by at least that many seconds. Only activations by
+    /// Retweets processed before the influenced Retweet are known to `GALE` and `PACE`, thus a positive grace window
+    /// mainly affects unordered input there.
+    pub activation_grace_seconds: i64,
+
+    /// If given, the activation of each user within each cascade, i.e. their first Retweet within it, is written to
+    /// this file as lines of `cascade_id;user_id;activation_time`. The poster of the original Tweet is not activated
+    /// by a Retweet and thus not included. If the computation is distributed across more than one process, each
+    /// process writes the activations of the users owned by its workers to this path, suffixed with `.` and its
+    /// process ID.
+    pub activation_log: Option<PathBuf>,
+
+    /// The number of users for which the activations of each cascade reserve memory when the cascade is started, to
+    /// avoid repeatedly growing them in large cascades. Cascades with fewer activations waste the reserved memory. If
+    /// `0`, the activations grow as needed. Only used by `GALE` and `PACE`.
+    pub activation_set_initial_capacity: usize,
+
+    /// Further local directories of TAR archives (each in the same layout as `social_graph`) which are loaded after
+    /// `social_graph` if it is a local directory as well. Users with friend files in more than one directory are
+    /// handled according to `duplicate_users`.
+    pub additional_social_graphs: Vec<PathBuf>,
+
     /// The algorithm used for reconstruction.
     pub algorithm: Algorithm,
 
-    /// Number of Retweets being processed at once.
+    /// Fail when loading the Retweets if any Retweet was created before its predecessor in the data set.
+    pub assert_sorted_retweets: bool,
+
+    /// Number of Retweets being processed at once. A batch size of `0` is replaced by `1` when running the computation.
     pub batch_size: usize,
 
+    /// Buffer the lines written to text targets (i.e. STDOUT and directories) locally on the writing worker and write
+    /// the lines of each batch at once when the batch is done. Thus, the output never contains partial lines or lines
+    /// interleaved with other output written concurrently, e.g. log messages. All influence edges are written by the
+    /// first worker, so the output is never split across per-worker files. Without buffering, each line is written
+    /// immediately.
+    pub buffer_output: bool,
+
+    /// A token to cancel the computation from another thread. Cancellation is checked after each batch of Retweets.
+    /// If cancelled, no further Retweets will be processed, and the statistics will be flagged as cancelled.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub cancellation_token: Option<CancellationToken>,
+
+    /// Estimate the 50th, 90th, and 99th percentile of the sizes of the cascades, i.e. of their final numbers of
+    /// influence edges, once the cascades are complete (see `cascade_timeout`). The estimates are given in the
+    /// statistics.
+    ///
+    /// The sizes are counted in a histogram with a bounded number of buckets instead of being buffered, thus the
+    /// memory used is independent of the number of cascades. The relative error of each estimate is at most `1/128`,
+    /// i.e. below `0.8%`; sizes below `128` are exact.
+    pub cascade_size_quantiles: bool,
+
+    /// The number of seconds without a new Retweet after which a cascade is considered complete, assuming the
+    /// Retweets are sorted by their creation time. If `None`, cascades are only complete once all Retweets have been
+    /// processed.
+    ///
+    /// All algorithms drop the activations of a cascade once it is complete. Thus, the memory required for long runs
+    /// with many cascades is only bounded if a timeout is given; without one, the activations of all cascades are kept
+    /// until the end of the input. Only the IDs of timed out cascades are kept to detect late Retweets.
+    ///
+    /// A later Retweet of a complete cascade is reconstructed as if it started a new cascade, without the influences
+    /// of users activated before the timeout. `GALE` and `PACE` log such late Retweets and count them in the
+    /// statistics.
+    pub cascade_timeout: Option<u64>,
+
+    /// Guarantee that the Retweets within each cascade are ordered by their creation time, i.e. no Retweet of a
+    /// cascade has been created before the original Tweet or any earlier Retweet of the cascade. If a Retweet has been
+    /// created after the previous one, `GALE` and `PACE` then consider every user activated so far in the cascade as
+    /// activated before it instead of comparing their activation times. Retweets created within the same second are
+    /// compared as usual. `LEAF` ignores this option, since its filter may already know the activations of later
+    /// Retweets.
+    ///
+    /// **Warning:** Enabling this option for unordered input yields wrong results. Debug builds assert the ordering.
+    pub cascades_time_ordered: bool,
+
+    /// Write at most one influence edge from each influencer to each influencee per cascade, e.g. if a user retweets
+    /// more than once within a cascade. Of duplicate edges found at the same time, the earliest one is kept. The pairs
+    /// written for each cascade are kept in memory until the cascade is complete.
+    pub deduplicate_edges: bool,
+
+    /// Only validate the inputs instead of running the computation: load a small sample of the social graph and the
+    /// first batch of Retweets, and report their counts. Retweets on AWS S3 are only validated by listing the bucket
+    /// prefix of their path. No output is written, and all times in the statistics are `0`.
+    pub dry_run: bool,
+
+    /// How to handle users with more than one friend file in the social graph.
+    pub duplicate_users: DuplicateUserPolicy,
+
+    /// How the lines of a social graph given as a flat edge list are parsed, i.e. the delimiter between the two
+    /// user IDs on each line and the base of the IDs.
+    pub edge_list_format: EdgeListFormat,
+
+    /// If given, the number of influence edges emitted per batch of Retweets is written to this file as lines of
+    /// `batch_index;edges;cumulative_edges`.
+    pub edge_timeseries: Option<PathBuf>,
+
+    /// Append the time between the activation of the influencer and the influence, i.e. the Retweet, to each
+    /// influence edge written as `OutputFormat::Edges` or `OutputFormat::EdgesWithScreenNames`, in the given unit:
+    /// `cascade_id;retweet_id;influencee;influencer;timestamp;-1;delta`. If the influencer is the poster of the
+    /// original Tweet, they have not been activated by a Retweet, and the delta is written as `-1`. All other output
+    /// formats are not affected.
+    pub emit_time_delta: Option<TimeUnit>,
+
+    /// Buffer the influence edges of each cascade until the cascade is complete (see `cascade_timeout`), then write
+    /// them sorted by their time and free them. Thus, the output is grouped by cascade, while only the edges of
+    /// incomplete cascades are kept in memory.
+    ///
+    /// Requires a `cascade_timeout`: without one, all cascades are only complete at the end of the computation, i.e.
+    /// the entire result would be buffered. Such configurations are rejected by `validate()`.
+    pub flush_on_cascade_complete: bool,
+
+    /// Expand each user's friend list with all users reachable within this many additional hops in the social graph
+    /// before the reconstruction, e.g. with `1` hop, the friends of each friend are added. If `0`, the friend lists are
+    /// not expanded.
+    ///
+    /// The entire social graph must be kept in memory on the first worker for the expansion, and the number of
+    /// friendships can grow exponentially with the number of hops. See also `max_expanded_friends`.
+    pub friend_expansion_hops: u8,
+
+    /// A callback providing the friend list of each of its users, e.g. for a social graph generated on the fly. If
+    /// given, the social graph is queried from the callback instead of being loaded from `social_graph`, thus neither
+    /// dummy users nor `additional_social_graphs` are supported, and the friend lists are not expanded. Only
+    /// `selected_users` and `selected_user_ids` are queried, if given. The callback is only invoked on the first
+    /// worker of the first process.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub friend_list_callback: Option<FriendListCallback>,
+
+    /// A file (e.g. `graph_load.toml`) to which the statistics of loading the social graph are written in TOML format
+    /// as soon as the social graph has been processed, i.e. before any Retweets are processed: the number of users,
+    /// the numbers of given, expected, and dummy friendships, and the time to process the social graph (in
+    /// nanoseconds). The file is only written by the first process.
+    pub graph_load_statistics: Option<PathBuf>,
+
+    /// The indices of the workers (across all processes) storing the friendships of the social graph. If `None`, the
+    /// friendships are distributed across all workers.
+    ///
+    /// The friends of each user are stored on exactly one of these workers, chosen by the user's ID; all processing of
+    /// the user's Retweets that requires their friends is routed to this worker. The list must not be empty, and each
+    /// index must be smaller than the total number of workers. The social graph is still read by the first worker,
+    /// which passes the friendships on to their owners. `PACE` ignores this option, since it stores the entire social
+    /// graph on all workers.
+    pub graph_owner_workers: Option<Vec<usize>>,
+
+    /// Compute the SHA-256 hash of all bytes written to the result file in each output directory, and write it next
+    /// to the file in the format of `sha256sum` (e.g. to `cascs.csv.sha256`) once the computation has finished, even if
+    /// it has been cancelled. The hash and the number of bytes written to the result file in the first output directory
+    /// are also given in the statistics. Only the bytes that have actually been written to the file are hashed.
+    pub hash_output: bool,
+
     /// A list of host addresses, each in the form `address:port`, where address may be a hostname or an IPv4 address.
     pub hosts: Option<Vec<String>>,
 
+    /// Treat quote Tweets, i.e. Tweets without an original Tweet but with a `quoted_status_id`, as Retweets of the
+    /// quoted Tweet, which becomes the root of their cascade. The quoted Tweet must be included as `quoted_status` to
+    /// determine the cascade's root user; other quote Tweets are skipped.
+    pub include_quotes: bool,
+
+    /// The maximum share of friendships with dummy users among all friendships (from `0.0` to `1.0`) after padding
+    /// the social graph with dummy users. A higher share usually indicates broken meta data in the social graph and
+    /// is logged as a warning, or, if `strict`, fails the computation. `None` disables the check.
+    pub max_dummy_ratio: Option<Ratio>,
+
+    /// The maximum number of friends per user when expanding friend lists. Expansion for a user stops once this number
+    /// is reached.
+    pub max_expanded_friends: usize,
+
+    /// The maximum number of friends a user may claim to have in the meta data of their friend file. Larger claims
+    /// will be clamped to this value.
+    ///
+    /// The claimed number of friends determines how many dummy users will be created when padding the social graph.
+    /// Corrupt meta data could otherwise cause enormous allocations.
+    pub max_expected_friends: u64,
+
+    /// Write at most this many influence edges, e.g. for a quick look at a huge run. All further edges are still
+    /// produced and counted, but not written. This is a prefix of the output, not a sample: which edges are written
+    /// depends on the order in which they are emitted, which varies between runs unless the output is deterministic
+    /// (e.g. with `flush_on_cascade_complete`). If `None`, all edges are written.
+    ///
+    /// With `OutputFormat::DistinctPairs`, only distinct pairs count towards the maximum; with `OutputFormat::Matrix`,
+    /// the edges added to the matrices. If a `sample_output` is drawn, the maximum applies to the sample.
+    pub max_output_edges: Option<usize>,
+
+    /// Process at most this many Retweets, i.e. only the first ones in the order in which they are given, e.g. for
+    /// profiling on a prefix of a huge data set. The number of Retweets in the statistics is the number actually
+    /// processed. If `None`, all Retweets are processed.
+    pub max_retweets: Option<usize>,
+
+    /// Skip all derived and aggregated statistics to minimize the overhead of measuring the computation. The
+    /// `top_cascades`, the cascade size quantiles, the friendship utilization, and the widest fan-out are not
+    /// determined and remain at their defaults (`0`, `0.0`, or empty) in the statistics, even if requested by
+    /// `report_top_cascades`, `cascade_size_quantiles`, or `track_friendship_utilization`. The raw counts, the times
+    /// of all phases, and the Retweet processing rate are still given.
+    pub minimal_stats: bool,
+
     /// Number of processes involved in the computation.
     pub number_of_processes: usize,
 
     /// Number of per-process worker threads.
     pub number_of_workers: usize,
 
-    /// Target for writing results.
+    /// Format in which the results are written.
+    pub output_format: OutputFormat,
+
+    /// A text file listing the IDs of users (one per line) whose cascades will be written. All cascades are
+    /// reconstructed, but only the influence edges of cascades whose original Tweet has been posted by one of these
+    /// users are written. If `None`, all cascades are written.
+    pub output_root_users: Option<InputSource>,
+
+    /// Target for writing results. Use `OutputTarget::Multi` to write to more than one target at once.
     #[serde(skip_serializing)]
     pub output_target: OutputTarget,
 
@@ -85,19 +346,120 @@ pub struct Configuration {
     /// Identity of this process, from `0` to `number_of_processes - 1`.
     pub process_id: usize,
 
+    /// A callback receiving the number of Retweets processed so far and their total number after each batch, e.g. to
+    /// display the progress of long runs. The callback runs synchronously in the processing loop, and is only invoked
+    /// on the first worker of the first process.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub progress_callback: Option<ProgressCallback>,
+
+    /// Periodically write the progress of the computation as JSON objects to STDERR, one per line, e.g.
+    /// `{"phase":"process_retweets","done":100,"total":1000,"rate":50}`, where `rate` is the number of items
+    /// processed per second.
+    ///
+    /// Events are written at the end of each phase and after each batch of Retweets.
+    pub progress_json: bool,
+
+    /// The number of times the Retweets are processed. The social graph is only loaded once and kept in memory on
+    /// the first worker; each further repetition reconstructs the cascades in a fresh computation without writing any
+    /// results. Used for benchmarking.
+    pub repetitions: usize,
+
     /// Print connection progress to STDOUT when using multiple processes.
     pub report_connection_progress: bool,
 
-    /// Path to the file containing the Retweets.
+    /// The number of cascades with the most Retweets to report in the statistics, with their root users. A cascade is
+    /// counted until it completes after the `cascade_timeout`, so only this many completed cascades and the ones
+    /// retweeted within the timeout are kept while selecting them; without a timeout, all cascades are counted at the
+    /// same time. A cascade retweeted again after its completion is counted anew. `0` disables the report.
+    pub report_top_cascades: usize,
+
+    /// Path to the file containing the Retweets. The file may be gzip-compressed.
     pub retweets: InputSource,
 
+    /// How often a request to AWS S3 is retried if it fails with a transient error, i.e. a server error or throttling.
+    /// The time between the attempts doubles with each retry. Other errors, e.g. missing objects or denied access, fail
+    /// immediately.
+    pub s3_max_retries: u32,
+
+    /// If given, only write a uniform random sample of at most this many influence edges once the computation has
+    /// finished, instead of all edges. If `None`, all influence edges are written.
+    ///
+    /// The sample is drawn over all influence edges using reservoir sampling with the `seed`, not over cascades, i.e.
+    /// large cascades will be represented by more edges than small ones. Only the sampled edges are kept in memory.
+    pub sample_output: Option<usize>,
+
+    /// A text file mapping user IDs to screen names, one `id;screen_name` per line. Only used with
+    /// `OutputFormat::EdgesWithScreenNames`.
+    pub screen_names: Option<InputSource>,
+
+    /// The seed for all random decisions, e.g. when sampling the output. Runs with the same seed make the same
+    /// decisions if the data is processed in the same order.
+    pub seed: u64,
+
+    /// The IDs of the users whose cascades will be reconstructed. Retweets of Tweets posted by any other user are
+    /// skipped, i.e. their cascades do not produce any activations or influence edges. Since the original Tweet of a
+    /// Retweet of a Retweet is the very first Tweet, cascades are matched by their ultimate original author. The
+    /// skipped Retweets are included in the number of processed Retweets in the statistics, and counted separately as
+    /// `Statistics::retweets_not_seeded`. If `None`, all cascades are reconstructed.
+    pub seed_authors: Option<HashSet<UserId>>,
+
+    /// The IDs of users that will be loaded from the social graph in addition to those listed in `selected_users`.
+    /// If both are empty, all users will be loaded.
+    pub selected_user_ids: Vec<UserId>,
+
     /// Path to a file containing the user IDs (one per line) that will be loaded from the social graph. Other users in
     /// the graph will be skipped. If `None`, all users will be loaded.
-    pub selected_users: Option<PathBuf>,
-
-    /// Path to the data set containing the social graph.
+    ///
+    /// The file may be stored locally or on AWS S3, and it may be gzip-compressed. Blank lines and lines starting with
+    /// `#` are skipped.
+    pub selected_users: Option<InputSource>,
+
+    /// Path to the data set containing the social graph: either a directory of TAR archives or, if the path ends in
+    /// `.zip`, a single ZIP archive. If the path ends in `.abom`, the file is memory-mapped as a social graph dumped by
+    /// `dump_abomonated_graph`. Any other local file is read as a flat edge list (see `edge_list_format`), which
+    /// should be sorted by the users.
     pub social_graph: InputSource,
 
+    /// Sort each user's friend list before passing it on when loading the social graph from a data set (i.e. not from
+    /// the `friend_list_callback`). Dummy users have negative IDs and are thus sorted before all real users. The
+    /// reconstruction keeps the friend lists sorted to find friends by binary search and only sorts the lists it
+    /// receives unsorted, thus sorting them while loading moves this work out of the computation. It also sorts the
+    /// friend lists kept for repetitions and for tracking the friendship utilization.
+    pub sort_friends: bool,
+
+    /// Read the Retweets from a local file of JSON lines while processing them instead of loading all of them before
+    /// processing any, reducing the memory required and the time until the processing starts. The time to load the
+    /// Retweets then only covers opening the file. Retweets from AWS S3, a JSON array, or with joined timestamps are
+    /// loaded completely in any case, and the Retweets are not processed repeatedly since they are not kept in memory.
+    pub stream_retweets: bool,
+
+    /// Fail instead of only warning on input data that almost certainly indicates a mistake, e.g. a non-empty list of
+    /// `selected_users` none of whom is in the social graph, or multiple `hosts` for a single process.
+    pub strict: bool,
+
+    /// Fail the computation if a batch (of friendships or Retweets) has not been processed within this many seconds,
+    /// e.g. because a worker in another process has died. If `None`, the workers wait for each batch indefinitely.
+    pub sync_timeout: Option<u64>,
+
+    /// The format in which the creation times of the Tweets in the Retweet data set are accepted: as integers of
+    /// seconds since the epoch, as ISO-8601 (RFC 3339) strings, or, by default, as either of them. Tweets with a
+    /// creation time in another format or with a malformed one are skipped with a warning.
+    pub timestamp_format: TimestampFormat,
+
+    /// A file of `retweet_id,timestamp` lines whose timestamps override those of the Retweets (and of their original
+    /// Tweets) with the same ID. Retweets without a timestamp in the file keep their own. The file is only loaded by
+    /// the first process.
+    pub timestamp_join_file: Option<PathBuf>,
+
+    /// Determine which friendships of the social graph have been used to produce at least one influence edge. The
+    /// number of used friendships and their share among all friendships are given in the statistics. If the
+    /// `output_target` is (or contains) a directory, the unused friendships are written to `unused_friendships.csv` in
+    /// the (first) directory as lines of `user;friend`.
+    ///
+    /// **Memory cost:** The first worker keeps the entire social graph it has loaded in memory, in addition to the set
+    /// of all distinct friendships used by influence edges.
+    pub track_friendship_utilization: bool,
+
     /// Private field to prevent initialization without the provided methods.
     ///
     /// All other fields should be public for easy access without getter functions. However, adding more fields later
@@ -111,34 +473,208 @@ impl Configuration {
     ///
     /// The following default values will be set:
     ///
+    ///  * `activation_grace_seconds`: `0`
+    ///  * `activation_log`: `None`
+    ///  * `activation_set_initial_capacity`: `0`
+    ///  * `additional_social_graphs`: `[]`
     ///  * `algorithm`: `Algorithm::GALE`
+    ///  * `assert_sorted_retweets`: `false`
     ///  * `batch_size`: `50000`
+    ///  * `buffer_output`: `false`
+    ///  * `cancellation_token`: `None`
+    ///  * `cascade_size_quantiles`: `false`
+    ///  * `cascade_timeout`: `None`
+    ///  * `cascades_time_ordered`: `false`
+    ///  * `deduplicate_edges`: `false`
+    ///  * `dry_run`: `false`
+    ///  * `duplicate_users`: `DuplicateUserPolicy::Merge`
+    ///  * `edge_list_format`: `EdgeListFormat::default()`, i.e. tab-separated decimal IDs
+    ///  * `edge_timeseries`: `None`
+    ///  * `emit_time_delta`: `None`
+    ///  * `flush_on_cascade_complete`: `false`
+    ///  * `friend_expansion_hops`: `0`
+    ///  * `friend_list_callback`: `None`
+    ///  * `graph_load_statistics`: `None`
+    ///  * `graph_owner_workers`: `None`
+    ///  * `hash_output`: `false`
     ///  * `hosts`: `None`
+    ///  * `include_quotes`: `false`
+    ///  * `max_dummy_ratio`: `None`
+    ///  * `max_expanded_friends`: `100_000`
+    ///  * `max_expected_friends`: `100_000_000`
+    ///  * `max_output_edges`: `None`
+    ///  * `max_retweets`: `None`
+    ///  * `minimal_stats`: `false`
     ///  * `number_of_processes`: `1`
     ///  * `number_of_workers`: `1`
+    ///  * `output_format`: `OutputFormat::Edges`
+    ///  * `output_root_users`: `None`
     ///  * `output_target`: `OutputTarget::StdOut`
     ///  * `pad_with_dummy_users`: `false`
     ///  * `process_id`: `0`
+    ///  * `progress_callback`: `None`
+    ///  * `progress_json`: `false`
+    ///  * `repetitions`: `1`
     ///  * `report_connection_progress`: `false`
+    ///  * `report_top_cascades`: `0`
+    ///  * `s3_max_retries`: `3`
+    ///  * `sample_output`: `None`
+    ///  * `screen_names`: `None`
+    ///  * `seed`: `0`
+    ///  * `seed_authors`: `None`
+    ///  * `selected_user_ids`: `[]`
     ///  * `selected_users`: `None`
+    ///  * `sort_friends`: `false`
+    ///  * `stream_retweets`: `false`
+    ///  * `strict`: `false`
+    ///  * `sync_timeout`: `None`
+    ///  * `timestamp_format`: `TimestampFormat::Auto`
+    ///  * `timestamp_join_file`: `None`
+    ///  * `track_friendship_utilization`: `false`
     pub fn default(retweets: InputSource, social_graph: InputSource) -> Configuration {
         Configuration {
+            activation_grace_seconds: 0,
+            activation_log: None,
+            activation_set_initial_capacity: 0,
+            additional_social_graphs: Vec::new(),
             algorithm: Algorithm::GALE,
+            assert_sorted_retweets: false,
             batch_size: 50000,
+            buffer_output: false,
+            cancellation_token: None,
+            cascade_size_quantiles: false,
+            cascade_timeout: None,
+            cascades_time_ordered: false,
+            deduplicate_edges: false,
+            dry_run: false,
+            duplicate_users: DuplicateUserPolicy::Merge,
+            edge_list_format: EdgeListFormat::default(),
+            edge_timeseries: None,
+            emit_time_delta: None,
+            flush_on_cascade_complete: false,
+            friend_expansion_hops: 0,
+            friend_list_callback: None,
+            graph_load_statistics: None,
+            graph_owner_workers: None,
+            hash_output: false,
             hosts: None,
+            include_quotes: false,
+            max_dummy_ratio: None,
+            max_expanded_friends: 100_000,
+            max_expected_friends: 100_000_000,
+            max_output_edges: None,
+            max_retweets: None,
+            minimal_stats: false,
             number_of_processes: 1,
             number_of_workers: 1,
+            output_format: OutputFormat::Edges,
+            output_root_users: None,
             output_target: OutputTarget::StdOut,
             pad_with_dummy_users: false,
             process_id: 0,
+            progress_callback: None,
+            progress_json: false,
+            repetitions: 1,
             report_connection_progress: false,
+            report_top_cascades: 0,
             retweets: retweets,
+            s3_max_retries: 3,
+            sample_output: None,
+            screen_names: None,
+            seed: 0,
+            seed_authors: None,
+            selected_user_ids: Vec::new(),
             selected_users: None,
             social_graph: social_graph,
+            sort_friends: false,
+            stream_retweets: false,
+            strict: false,
+            sync_timeout: None,
+            timestamp_format: TimestampFormat::Auto,
+            timestamp_join_file: None,
+            track_friendship_utilization: false,
             _prevent_outside_initialization: true,
         }
     }
 
+    /// Initialize a configuration for a common setup. The configuration is built from the default values, with the
+    /// fields changed as documented for each `preset`. All further fields can be changed with the usual builder
+    /// methods.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use crgp_lib::Configuration;
+    /// use crgp_lib::configuration::InputSource;
+    /// use crgp_lib::configuration::Preset;
+    ///
+    /// let retweets = InputSource::new("path/to/retweets.json");
+    /// let social_graph = InputSource::new("path/to/social/graph");
+    ///
+    /// let configuration = Configuration::preset(Preset::Cluster, retweets, social_graph)
+    ///     .hosts(Some(vec![String::from("host1:2101"), String::from("host2:2101")]))
+    ///     .processes(2);
+    ///
+    /// assert_eq!(configuration.batch_size, 500_000);
+    /// assert_eq!(configuration.report_connection_progress, true);
+    /// ```
+    pub fn preset(preset: Preset, retweets: InputSource, social_graph: InputSource) -> Configuration {
+        let configuration = Configuration::default(retweets, social_graph);
+        match preset {
+            Preset::LocalDev => {
+                configuration
+                    .assert_sorted_retweets(true)
+                    .batch_size(1_000)
+                    .output_format(OutputFormat::Edges)
+                    .output_target(OutputTarget::StdOut)
+                    .pad_with_dummy_users(false)
+                    .workers(1)
+            },
+            Preset::SingleNode => {
+                configuration
+                    .batch_size(500_000)
+                    .pad_with_dummy_users(true)
+                    .processes(1)
+                    .report_connection_progress(false)
+            },
+            Preset::Cluster => {
+                configuration
+                    .batch_size(500_000)
+                    .pad_with_dummy_users(true)
+                    .progress_json(true)
+                    .report_connection_progress(true)
+            }
+        }
+    }
+
+    /// Set the number of seconds by which an influencer's activation may follow the influenced Retweet.
+    #[inline]
+    pub fn activation_grace_seconds(mut self, seconds: i64) -> Configuration {
+        self.activation_grace_seconds = seconds;
+        self
+    }
+
+    /// Set the file to which the activations of users within cascades are written.
+    #[inline]
+    pub fn activation_log(mut self, path: Option<PathBuf>) -> Configuration {
+        self.activation_log = path;
+        self
+    }
+
+    /// Set the number of activations for which each cascade initially reserves memory.
+    #[inline]
+    pub fn activation_set_initial_capacity(mut self, capacity: usize) -> Configuration {
+        self.activation_set_initial_capacity = capacity;
+        self
+    }
+
+    /// Set further local directories of TAR archives from which the social graph is loaded.
+    #[inline]
+    pub fn additional_social_graphs(mut self, directories: Vec<PathBuf>) -> Configuration {
+        self.additional_social_graphs = directories;
+        self
+    }
+
     /// Choose the algorithm.
     #[inline]
     pub fn algorithm(mut self, algorithm: Algorithm) -> Configuration {
@@ -146,13 +682,143 @@ impl Configuration {
         self
     }
 
-    /// Set the batch size.
+    /// Toggle the check that the Retweets are sorted by their creation time.
+    #[inline]
+    pub fn assert_sorted_retweets(mut self, assert_sorted: bool) -> Configuration {
+        self.assert_sorted_retweets = assert_sorted;
+        self
+    }
+
+    /// Set the batch size. It must be positive; a batch size of `0` is replaced by `1` (with a warning) when running
+    /// the computation.
     #[inline]
     pub fn batch_size(mut self, batch_size: usize) -> Configuration {
         self.batch_size = batch_size;
         self
     }
 
+    /// Set whether the lines written to text targets are buffered per batch.
+    #[inline]
+    pub fn buffer_output(mut self, buffer_output: bool) -> Configuration {
+        self.buffer_output = buffer_output;
+        self
+    }
+
+    /// Set a shared flag to cancel the computation from another thread by setting the flag to `true`.
+    #[inline]
+    pub fn cancellation_token(mut self, flag: Arc<AtomicBool>) -> Configuration {
+        self.cancellation_token = Some(CancellationToken::new(flag));
+        self
+    }
+
+    /// Set whether the quantiles of the cascade sizes are estimated.
+    #[inline]
+    pub fn cascade_size_quantiles(mut self, estimate: bool) -> Configuration {
+        self.cascade_size_quantiles = estimate;
+        self
+    }
+
+    /// Set the number of seconds without a new Retweet after which a cascade is considered complete.
+    #[inline]
+    pub fn cascade_timeout(mut self, timeout: Option<u64>) -> Configuration {
+        self.cascade_timeout = timeout;
+        self
+    }
+
+    /// Set whether the Retweets within each cascade are guaranteed to be ordered by their creation time.
+    #[inline]
+    pub fn cascades_time_ordered(mut self, ordered: bool) -> Configuration {
+        self.cascades_time_ordered = ordered;
+        self
+    }
+
+    /// Set whether at most one influence edge is written from each influencer to each influencee per cascade.
+    #[inline]
+    pub fn deduplicate_edges(mut self, deduplicate_edges: bool) -> Configuration {
+        self.deduplicate_edges = deduplicate_edges;
+        self
+    }
+
+    /// Set whether the inputs are only validated instead of running the computation.
+    #[inline]
+    pub fn dry_run(mut self, dry_run: bool) -> Configuration {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set how users with more than one friend file in the social graph are handled.
+    #[inline]
+    pub fn duplicate_users(mut self, policy: DuplicateUserPolicy) -> Configuration {
+        self.duplicate_users = policy;
+        self
+    }
+
+    /// Set how the lines of a social graph given as a flat edge list are parsed.
+    #[inline]
+    pub fn edge_list_format(mut self, format: EdgeListFormat) -> Configuration {
+        self.edge_list_format = format;
+        self
+    }
+
+    /// Set the file to which the number of influence edges emitted per batch of Retweets is written.
+    #[inline]
+    pub fn edge_timeseries(mut self, path: Option<PathBuf>) -> Configuration {
+        self.edge_timeseries = path;
+        self
+    }
+
+    /// Set the unit in which the time between the activation of the influencer and the influence is appended to
+    /// each influence edge, or `None` to omit it.
+    #[inline]
+    pub fn emit_time_delta(mut self, unit: Option<TimeUnit>) -> Configuration {
+        self.emit_time_delta = unit;
+        self
+    }
+
+    /// Set whether the influence edges are buffered per cascade and written once the cascade is complete.
+    #[inline]
+    pub fn flush_on_cascade_complete(mut self, flush: bool) -> Configuration {
+        self.flush_on_cascade_complete = flush;
+        self
+    }
+
+    /// Set the number of additional hops by which each user's friend list will be expanded.
+    #[inline]
+    pub fn friend_expansion_hops(mut self, hops: u8) -> Configuration {
+        self.friend_expansion_hops = hops;
+        self
+    }
+
+    /// Set a callback providing the friend list of each of the given `users` to query the social graph from.
+    #[inline]
+    pub fn friend_list_callback<F>(mut self, users: Vec<UserId>, callback: F) -> Configuration
+        where F: Fn(UserId) -> Option<Vec<UserId>> + Send + Sync + 'static
+    {
+        self.friend_list_callback = Some(FriendListCallback::new(users, callback));
+        self
+    }
+
+    /// Set the file to which the statistics of loading the social graph are written.
+    #[inline]
+    pub fn graph_load_statistics(mut self, graph_load_statistics: Option<PathBuf>) -> Configuration {
+        self.graph_load_statistics = graph_load_statistics;
+        self
+    }
+
+    /// Set the indices of the workers storing the friendships of the social graph.
+    #[inline]
+    pub fn graph_owner_workers(mut self, workers: Vec<usize>) -> Configuration {
+        self.graph_owner_workers = Some(workers);
+        self
+    }
+
+    /// Set whether the SHA-256 hash of the result files is computed.
+    #[inline]
+    pub fn hash_output(mut self, hash_output: bool) -> Configuration {
+        self.hash_output = hash_output;
+        self
+    }
+
     /// Set the host list.
     #[inline]
     pub fn hosts(mut self, hosts: Option<Vec<String>>) -> Configuration {
@@ -160,6 +826,70 @@ impl Configuration {
         self
     }
 
+    /// Set whether quote Tweets are treated as Retweets of the quoted Tweet.
+    #[inline]
+    pub fn include_quotes(mut self, include_quotes: bool) -> Configuration {
+        self.include_quotes = include_quotes;
+        self
+    }
+
+    /// Set the maximum share of friendships with dummy users among all friendships after padding the social graph. A
+    /// share of `NaN` disables the check.
+    #[inline]
+    pub fn max_dummy_ratio(mut self, max_dummy_ratio: Option<f64>) -> Configuration {
+        self.max_dummy_ratio = max_dummy_ratio.and_then(Ratio::new);
+        self
+    }
+
+    /// Set the maximum number of friends per user when expanding friend lists.
+    #[inline]
+    pub fn max_expanded_friends(mut self, max_expanded_friends: usize) -> Configuration {
+        self.max_expanded_friends = max_expanded_friends;
+        self
+    }
+
+    /// Set the maximum number of friends a user may claim to have in the meta data of their friend file.
+    #[inline]
+    pub fn max_expected_friends(mut self, max_expected_friends: u64) -> Configuration {
+        self.max_expected_friends = max_expected_friends;
+        self
+    }
+
+    /// Set the maximum number of influence edges written. If `None`, all edges are written.
+    #[inline]
+    pub fn max_output_edges(mut self, max_edges: Option<usize>) -> Configuration {
+        self.max_output_edges = max_edges;
+        self
+    }
+
+    /// Set the maximum number of Retweets processed. If `None`, all Retweets are processed.
+    #[inline]
+    pub fn max_retweets(mut self, max_retweets: Option<usize>) -> Configuration {
+        self.max_retweets = max_retweets;
+        self
+    }
+
+    /// Set whether all derived and aggregated statistics are skipped.
+    #[inline]
+    pub fn minimal_stats(mut self, minimal_stats: bool) -> Configuration {
+        self.minimal_stats = minimal_stats;
+        self
+    }
+
+    /// Set the format in which the results are written.
+    #[inline]
+    pub fn output_format(mut self, format: OutputFormat) -> Configuration {
+        self.output_format = format;
+        self
+    }
+
+    /// Set the file listing the users whose cascades will be written.
+    #[inline]
+    pub fn output_root_users(mut self, users: Option<InputSource>) -> Configuration {
+        self.output_root_users = users;
+        self
+    }
+
     /// Set the target for writing results.
     #[inline]
     pub fn output_target(mut self, target: OutputTarget) -> Configuration {
@@ -188,6 +918,38 @@ impl Configuration {
         self
     }
 
+    /// Set the number of involved processes to the number of `hosts`, if any are given.
+    #[inline]
+    pub fn processes_from_hosts(mut self) -> Configuration {
+        if let Some(ref hosts) = self.hosts {
+            self.number_of_processes = hosts.len();
+        }
+        self
+    }
+
+    /// Set a callback receiving the number of processed Retweets and their total number after each batch.
+    #[inline]
+    pub fn progress_callback<F>(mut self, callback: F) -> Configuration
+        where F: Fn(u64, u64) + Send + Sync + 'static
+    {
+        self.progress_callback = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Toggle writing the progress as JSON objects to STDERR.
+    #[inline]
+    pub fn progress_json(mut self, progress_json: bool) -> Configuration {
+        self.progress_json = progress_json;
+        self
+    }
+
+    /// Set the number of times the Retweets are processed.
+    #[inline]
+    pub fn repetitions(mut self, repetitions: usize) -> Configuration {
+        self.repetitions = repetitions;
+        self
+    }
+
     /// Toggle connection progress reports.
     #[inline]
     pub fn report_connection_progress(mut self, report: bool) -> Configuration {
@@ -195,14 +957,114 @@ impl Configuration {
         self
     }
 
+    /// Set the number of cascades with the most Retweets to report in the statistics.
+    #[inline]
+    pub fn report_top_cascades(mut self, number: usize) -> Configuration {
+        self.report_top_cascades = number;
+        self
+    }
+
+    /// Set how often a request to AWS S3 failing with a transient error is retried.
+    #[inline]
+    pub fn s3_max_retries(mut self, retries: u32) -> Configuration {
+        self.s3_max_retries = retries;
+        self
+    }
+
+    /// Set the maximum number of influence edges in the written sample, or `None` to write all edges.
+    #[inline]
+    pub fn sample_output(mut self, sample_output: Option<usize>) -> Configuration {
+        self.sample_output = sample_output;
+        self
+    }
+
+    /// Set the file mapping user IDs to screen names.
+    #[inline]
+    pub fn screen_names(mut self, screen_names: Option<InputSource>) -> Configuration {
+        self.screen_names = screen_names;
+        self
+    }
+
+    /// Set the seed for all random decisions.
+    #[inline]
+    pub fn seed(mut self, seed: u64) -> Configuration {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the IDs of the users whose cascades will be reconstructed.
+    #[inline]
+    pub fn seed_authors(mut self, authors: Option<HashSet<UserId>>) -> Configuration {
+        self.seed_authors = authors;
+        self
+    }
+
+    /// Set the IDs of users that will be loaded from the social graph in addition to those listed in `selected_users`.
+    /// If both are empty, all users will be loaded.
+    #[inline]
+    pub fn selected_user_ids(mut self, ids: Vec<UserId>) -> Configuration {
+        self.selected_user_ids = ids;
+        self
+    }
+
     /// Set the path to a file containing the user IDs (one per line) that will be loaded from the social graph. Other
     /// users in the graph will be skipped. If `None`, all users will be loaded.
     #[inline]
-    pub fn selected_users(mut self, users: Option<PathBuf>) -> Configuration {
+    pub fn selected_users(mut self, users: Option<InputSource>) -> Configuration {
         self.selected_users = users;
         self
     }
 
+    /// Toggle sorting each user's friend list when loading the social graph.
+    #[inline]
+    pub fn sort_friends(mut self, sort_friends: bool) -> Configuration {
+        self.sort_friends = sort_friends;
+        self
+    }
+
+    /// Set whether the Retweets are read while processing them.
+    #[inline]
+    pub fn stream_retweets(mut self, stream_retweets: bool) -> Configuration {
+        self.stream_retweets = stream_retweets;
+        self
+    }
+
+    /// Set whether to fail on input data that almost certainly indicates a mistake.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Configuration {
+        self.strict = strict;
+        self
+    }
+
+    /// Set the number of seconds after which a batch that has not been processed fails the computation. If `None`, the
+    /// workers wait indefinitely.
+    #[inline]
+    pub fn sync_timeout(mut self, sync_timeout: Option<u64>) -> Configuration {
+        self.sync_timeout = sync_timeout;
+        self
+    }
+
+    /// Set the format in which the creation times of the Tweets are accepted.
+    #[inline]
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Configuration {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// Set the file of `retweet_id,timestamp` lines whose timestamps override those of the Retweets with the same ID.
+    #[inline]
+    pub fn timestamp_join_file(mut self, path: Option<PathBuf>) -> Configuration {
+        self.timestamp_join_file = path;
+        self
+    }
+
+    /// Set whether the friendships used to produce influence edges are tracked.
+    #[inline]
+    pub fn track_friendship_utilization(mut self, track: bool) -> Configuration {
+        self.track_friendship_utilization = track;
+        self
+    }
+
     /// Set the number of per-process workers.
     #[inline]
     pub fn workers(mut self, workers: usize) -> Configuration {
@@ -210,95 +1072,1578 @@ impl Configuration {
         self
     }
 
-    /// Determine the configuration for `timely`.
-    ///
-    /// This function mimics `timely_communication::initialize::Configuration::from_args()`.
-    #[doc(hidden)]
-    #[inline]
-    pub fn get_timely_configuration(&mut self) -> Result<TimelyConfiguration> {
-        if self.process_id >= self.number_of_processes {
-            return Err(Error::from(String::from("the process ID is not in range of all processes")));
-        }
-
-        if self.number_of_processes > 1 {
-            // Cluster of processes.
-
-            // If no hosts are given, run on localhost.
-            let mut host_addresses = Vec::<String>::new();
-            if let Some(ref hosts) = self.hosts {
-                if hosts.len() != self.number_of_processes {
-                    return Err(Error::from(String::from(format!("{hosts} hosts given, but expected {processes}",
-                                                                hosts = hosts.len(),
-                                                                processes = self.number_of_processes))));
-                }
-                host_addresses = hosts.clone();
-            } else {
-                for index in 0..self.number_of_processes {
-                    host_addresses.push(format!("localhost:{port}", port = 2101 + index));
-                }
+    /// Compare this configuration to the `other` one field by field. Return the name of each differing field together
+    /// with its value in this and in the other configuration, in debug formatting. The fields are listed in the order
+    /// in which they are declared. The `cancellation_token`, the `friend_list_callback`, and the `progress_callback`
+    /// are not compared, since they only exist at runtime.
+    ///
+    /// ```rust
+    /// use crgp_lib::Configuration;
+    /// use crgp_lib::configuration::InputSource;
+    ///
+    /// let retweets = InputSource::new("path/to/retweets.json");
+    /// let social_graph = InputSource::new("path/to/social/graph");
+    /// let mine = Configuration::default(retweets.clone(), social_graph.clone())
+    ///     .batch_size(500);
+    /// let theirs = Configuration::default(retweets, social_graph);
+    ///
+    /// assert_eq!(mine.describe_diff(&theirs), vec![("batch_size", String::from("500"), String::from("50000"))]);
+    /// ```
+    pub fn describe_diff(&self, other: &Configuration) -> Vec<(&'static str, String, String)> {
+        let mut differences: Vec<(&'static str, String, String)> = Vec::new();
+
+        macro_rules! compare {
+            ($this:ident, $other:ident, $($field:ident),*) => {
+                $(
+                    if $this.$field != $other.$field {
+                        differences.push((stringify!($field), format!("{:?}", $this.$field),
+                                          format!("{:?}", $other.$field)));
+                    }
+                )*
+            }
+        }
+
+        let this: &Configuration = self;
+        compare!(this, other,
+                 activation_grace_seconds, activation_log, activation_set_initial_capacity, additional_social_graphs,
+                 algorithm, assert_sorted_retweets, batch_size, buffer_output, cascade_size_quantiles, cascade_timeout,
+                 cascades_time_ordered, deduplicate_edges, dry_run, duplicate_users, edge_list_format, edge_timeseries,
+                 emit_time_delta, flush_on_cascade_complete, friend_expansion_hops, graph_load_statistics,
+                 graph_owner_workers, hash_output, hosts, include_quotes, max_dummy_ratio, max_expanded_friends,
+                 max_expected_friends, max_output_edges, max_retweets, minimal_stats, number_of_processes,
+                 number_of_workers, output_format, output_root_users, output_target, pad_with_dummy_users, process_id,
+                 progress_json, repetitions, report_connection_progress, report_top_cascades, retweets, s3_max_retries,
+                 sample_output, screen_names, seed, seed_authors, selected_user_ids, selected_users, social_graph,
+                 sort_friends, stream_retweets, strict, sync_timeout, timestamp_format, timestamp_join_file,
+                 track_friendship_utilization);
+
+        differences
+    }
+
+    /// Determine the configuration for `timely`.
+    ///
+    /// This function mimics `timely_communication::initialize::Configuration::from_args()`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn get_timely_configuration(&mut self) -> Result<TimelyConfiguration> {
+        self.validate()?;
+
+        if self.number_of_processes > 1 {
+            // Cluster of processes.
+
+            // If no hosts are given, run on localhost.
+            let mut host_addresses = Vec::<String>::new();
+            if let Some(ref hosts) = self.hosts {
+                host_addresses = hosts.clone();
+            } else {
+                for index in 0..self.number_of_processes {
+                    host_addresses.push(format!("localhost:{port}", port = 2101 + index));
+                }
+
+                self.hosts = Some(host_addresses.clone());
+            }
+
+            Ok(TimelyConfiguration::Cluster(self.number_of_workers, self.process_id, host_addresses,
+                                            self.report_connection_progress))
+        } else if self.number_of_workers > 1 {
+            // One process, multiple workers.
+            Ok(TimelyConfiguration::Process(self.number_of_workers))
+        } else {
+            // Single process, single thread.
+            Ok(TimelyConfiguration::Thread)
+        }
+    }
+
+    /// Ensure the options of the configuration are consistent with each other, e.g. that the process ID is in range of
+    /// all processes and that a host is given for each process. Errors describing the first inconsistency found.
+    ///
+    /// The configuration is validated when running the computation, but this function allows failing before any
+    /// other preparations. Of all input files, only the existence of a local file of `selected_users` is checked.
+    pub fn validate(&self) -> Result<()> {
+        if self.number_of_workers == 0 {
+            return Err(Error::from(String::from("at least one worker per process is required")));
+        }
+
+        if self.process_id >= self.number_of_processes {
+            return Err(Error::from(String::from("the process ID is not in range of all processes")));
+        }
+
+        if self.number_of_processes > 1 {
+            if let Some(ref hosts) = self.hosts {
+                if hosts.len() != self.number_of_processes {
+                    return Err(Error::from(format!("{hosts} hosts given, but expected {processes}",
+                                                   hosts = hosts.len(), processes = self.number_of_processes)));
+                }
+            }
+        }
+
+        if self.flush_on_cascade_complete && self.cascade_timeout.is_none() {
+            return Err(Error::from(String::from("flushing complete cascades requires a cascade timeout")));
+        }
+
+        self.validate_output_target()?;
+        self.validate_graph_owner_workers()?;
+        self.validate_single_process_hosts()?;
+        self.validate_selected_users()?;
+
+        Ok(())
+    }
+
+    /// Ensure a local file of selected users exists.
+    fn validate_selected_users(&self) -> Result<()> {
+        if let Some(ref selected_users) = self.selected_users {
+            let is_local: bool = selected_users.s3.is_none() && selected_users.gcs.is_none();
+            if is_local && !selected_users.path.is_file() {
+                return Err(Error::from(format!("the file of selected users \"{path}\" does not exist",
+                                               path = selected_users.path.display())));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the designated graph owners are valid worker indices.
+    fn validate_graph_owner_workers(&self) -> Result<()> {
+        if let Some(ref owners) = self.graph_owner_workers {
+            if owners.is_empty() {
+                return Err(Error::from(String::from("at least one graph owner worker is required")));
+            }
+
+            let total_workers: usize = self.number_of_processes * self.number_of_workers;
+            if let Some(owner) = owners.iter().find(|&&owner| owner >= total_workers) {
+                return Err(Error::from(format!("graph owner worker {owner} is not in range of all {total} workers",
+                                               owner = owner, total = total_workers)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the hosts are not silently ignored when running a single process.
+    ///
+    /// If multiple hosts are given for a single process, only a warning is logged, unless `strict` is set.
+    fn validate_single_process_hosts(&self) -> Result<()> {
+        if self.number_of_processes != 1 {
+            return Ok(());
+        }
+
+        if let Some(ref hosts) = self.hosts {
+            if hosts.len() > 1 {
+                if self.strict {
+                    return Err(Error::from(format!("{hosts} hosts given, but expected 1", hosts = hosts.len())));
+                }
+
+                warn!("{hosts} hosts given, but only a single process is used; ignoring the hosts",
+                      hosts = hosts.len());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the output target can be used with the configured number of processes.
+    ///
+    /// Neither the in-memory `petgraph` graph nor the channel can be shared across processes.
+    fn validate_output_target(&self) -> Result<()> {
+        if self.number_of_processes <= 1 {
+            return Ok(());
+        }
+
+        for target in self.output_target.targets() {
+            let name: Option<&str> = match *target {
+                #[cfg(feature = "petgraph")]
+                OutputTarget::PetGraph(_) => Some("petgraph"),
+                OutputTarget::Channel(_) => Some("channel"),
+                _ => None
+            };
+            if let Some(name) = name {
+                return Err(Error::from(format!("the {name} output target requires a single process", name = name)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Configuration {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let hosts: String = match self.hosts {
+            Some(ref hosts) => {
+                let mut hosts_list = String::from("[");
+                let mut joined_hosts: String = hosts
+                    .iter()
+                    .fold(String::new(), |acc, s| {
+                        acc + s + ", "
+                    });
+                let _ = joined_hosts.pop();
+                let _ = joined_hosts.pop();
+                hosts_list += &joined_hosts;
+                hosts_list += "]";
+                hosts_list
+            }
+            None => String::from("[]")
+        };
+
+        write!(formatter,
+               "(Algorithm: {algorithm}, Batch Size: {batch}, Hosts: {hosts}, Number of Processes: {processes}, \
+                Number of Workers: {workers}, Output Target: {output}, Insert Dummy Users: {dummies}, \
+                Process ID: {id}, Report Connection Progress: {progress}, Retweet Data Set: {retweets}, \
+                Social Graph: {graph})",
+               algorithm = self.algorithm, batch = self.batch_size, hosts = hosts,
+               processes = self.number_of_processes, workers = self.number_of_workers, output = self.output_target,
+               dummies = self.pad_with_dummy_users, id = self.process_id, progress = self.report_connection_progress,
+               retweets = self.retweets, graph = self.social_graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use configuration::Algorithm;
+    use configuration::CancellationToken;
+    use configuration::ChannelOutput;
+    use configuration::DuplicateUserPolicy;
+    use configuration::IdBase;
+    use configuration::OutputFormat;
+    use configuration::OutputTarget;
+    use configuration::Preset;
+    use configuration::S3;
+    use serde_json;
+    use std::error::Error;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::mpsc::channel;
+    use timely_communication::initialize::Configuration as TimelyConfiguration;
+
+    use super::*;
+
+    #[test]
+    fn default() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph);
+
+        assert!(configuration.additional_social_graphs.is_empty());
+        assert_eq!(configuration.activation_grace_seconds, 0);
+        assert_eq!(configuration.activation_log, None);
+        assert_eq!(configuration.activation_set_initial_capacity, 0);
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.assert_sorted_retweets, false);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.buffer_output, false);
+        assert_eq!(configuration.cancellation_token, None);
+        assert_eq!(configuration.cascade_size_quantiles, false);
+        assert_eq!(configuration.cascade_timeout, None);
+        assert_eq!(configuration.cascades_time_ordered, false);
+        assert_eq!(configuration.deduplicate_edges, false);
+        assert_eq!(configuration.dry_run, false);
+        assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::Merge);
+        assert_eq!(configuration.edge_list_format, EdgeListFormat::default());
+        assert_eq!(configuration.edge_timeseries, None);
+        assert_eq!(configuration.emit_time_delta, None);
+        assert_eq!(configuration.flush_on_cascade_complete, false);
+        assert_eq!(configuration.friend_expansion_hops, 0);
+        assert_eq!(configuration.friend_list_callback, None);
+        assert_eq!(configuration.graph_load_statistics, None);
+        assert_eq!(configuration.graph_owner_workers, None);
+        assert_eq!(configuration.hash_output, false);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.include_quotes, false);
+        assert_eq!(configuration.max_dummy_ratio, None);
+        assert_eq!(configuration.max_expanded_friends, 100_000);
+        assert_eq!(configuration.max_expected_friends, 100_000_000);
+        assert_eq!(configuration.max_output_edges, None);
+        assert_eq!(configuration.max_retweets, None);
+        assert_eq!(configuration.minimal_stats, false);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_format, OutputFormat::Edges);
+        assert_eq!(configuration.output_root_users, None);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.progress_callback, None);
+        assert_eq!(configuration.progress_json, false);
+        assert_eq!(configuration.repetitions, 1);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.report_top_cascades, 0);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.s3_max_retries, 3);
+        assert_eq!(configuration.sample_output, None);
+        assert_eq!(configuration.screen_names, None);
+        assert_eq!(configuration.seed, 0);
+        assert_eq!(configuration.seed_authors, None);
+        assert!(configuration.selected_user_ids.is_empty());
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.sort_friends, false);
+        assert_eq!(configuration.stream_retweets, false);
+        assert_eq!(configuration.strict, false);
+        assert_eq!(configuration.sync_timeout, None);
+        assert_eq!(configuration.timestamp_format, TimestampFormat::Auto);
+        assert_eq!(configuration.timestamp_join_file, None);
+        assert_eq!(configuration.track_friendship_utilization, false);
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn activation_grace_seconds() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .activation_grace_seconds(-3);
+
+        assert_eq!(configuration.activation_grace_seconds, -3);
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn activation_log() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .activation_log(Some(PathBuf::from("path/to/activations.csv")));
+
+        assert_eq!(configuration.activation_log, Some(PathBuf::from("path/to/activations.csv")));
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn activation_set_initial_capacity() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .activation_set_initial_capacity(1000);
+
+        assert_eq!(configuration.activation_set_initial_capacity, 1000);
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn additional_social_graphs() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .additional_social_graphs(vec![PathBuf::from("path/to/other/social/graph")]);
+
+        assert_eq!(configuration.additional_social_graphs, vec![PathBuf::from("path/to/other/social/graph")]);
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn algorithm() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .algorithm(Algorithm::LEAF);
+
+        assert_eq!(configuration.algorithm, Algorithm::LEAF);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn assert_sorted_retweets() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .assert_sorted_retweets(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.assert_sorted_retweets, true);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn batch_size() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .batch_size(1);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 1);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn buffer_output() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .buffer_output(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.buffer_output, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .cancellation_token(flag.clone());
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.cancellation_token, Some(CancellationToken::new(flag)));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn cascade_size_quantiles() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .cascade_size_quantiles(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.cascade_size_quantiles, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn cascade_timeout() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .cascade_timeout(Some(60));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.cascade_timeout, Some(60));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn cascades_time_ordered() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .cascades_time_ordered(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.cascades_time_ordered, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn deduplicate_edges() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .deduplicate_edges(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.deduplicate_edges, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn dry_run() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .dry_run(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.dry_run, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn duplicate_users() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .duplicate_users(DuplicateUserPolicy::KeepFirst);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::KeepFirst);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn edge_list_format() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .edge_list_format(EdgeListFormat::new(',', IdBase::Hexadecimal));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.edge_list_format, EdgeListFormat::new(',', IdBase::Hexadecimal));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn edge_timeseries() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .edge_timeseries(Some(PathBuf::from("path/to/timeseries.csv")));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.edge_timeseries, Some(PathBuf::from("path/to/timeseries.csv")));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn emit_time_delta() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .emit_time_delta(Some(TimeUnit::Microseconds));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.emit_time_delta, Some(TimeUnit::Microseconds));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn flush_on_cascade_complete() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .flush_on_cascade_complete(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.flush_on_cascade_complete, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn friend_expansion_hops() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .friend_expansion_hops(2);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.friend_expansion_hops, 2);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn friend_list_callback() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .friend_list_callback(vec![UserId::from(1), UserId::from(2)], |user| {
+                Some(vec![UserId::from(i64::from(user) - 1)])
+            });
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+
+        let callback = configuration.friend_list_callback.expect("The callback has not been set");
+        assert_eq!(callback.users().to_vec(), vec![UserId::from(1), UserId::from(2)]);
+        assert_eq!(callback.friends(UserId::from(2)), Some(vec![UserId::from(1)]));
+    }
+
+    #[test]
+    fn graph_load_statistics() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .graph_load_statistics(Some(PathBuf::from("path/to/graph_load.toml")));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.graph_load_statistics, Some(PathBuf::from("path/to/graph_load.toml")));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn graph_owner_workers() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .graph_owner_workers(vec![1, 3]);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.graph_owner_workers, Some(vec![1, 3]));
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn hash_output() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .hash_output(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hash_output, true);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn hosts() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let hosts = vec![
+            String::from("host1:2101"),
+            String::from("host1:2102"),
+            String::from("host1:2103"),
+        ];
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .hosts(Some(hosts));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, Some(vec![
+            String::from("host1:2101"),
+            String::from("host1:2102"),
+            String::from("host1:2103"),
+        ]));
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn include_quotes() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .include_quotes(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.include_quotes, true);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn max_dummy_ratio() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .max_dummy_ratio(Some(0.5));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.max_dummy_ratio, Ratio::new(0.5));
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn max_expanded_friends() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .max_expanded_friends(42);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.max_expanded_friends, 42);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn max_expected_friends() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .max_expected_friends(42);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.max_expected_friends, 42);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn max_output_edges() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .max_output_edges(Some(42));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.max_output_edges, Some(42));
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn max_retweets() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .max_retweets(Some(5));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.max_retweets, Some(5));
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn minimal_stats() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .minimal_stats(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.minimal_stats, true);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn output_format() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .output_format(OutputFormat::DistinctPairs);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_format, OutputFormat::DistinctPairs);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn output_root_users() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .output_root_users(Some(InputSource::new("path/to/root_users.txt")));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_root_users, Some(InputSource::new("path/to/root_users.txt")));
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn output_target() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let output = PathBuf::from("results");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .output_target(OutputTarget::Directory(output));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target,
+        OutputTarget::Directory(PathBuf::from("results")));
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn pad_with_dummy_users() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .pad_with_dummy_users(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, true);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn preset_cluster() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::preset(Preset::Cluster, retweets, social_graph);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.assert_sorted_retweets, false);
+        assert_eq!(configuration.batch_size, 500_000);
+        assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::Merge);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_format, OutputFormat::Edges);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, true);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.progress_json, true);
+        assert_eq!(configuration.report_connection_progress, true);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn preset_local_dev() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::preset(Preset::LocalDev, retweets, social_graph);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.assert_sorted_retweets, true);
+        assert_eq!(configuration.batch_size, 1_000);
+        assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::Merge);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_format, OutputFormat::Edges);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.progress_json, false);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn preset_single_node() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::preset(Preset::SingleNode, retweets, social_graph);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.assert_sorted_retweets, false);
+        assert_eq!(configuration.batch_size, 500_000);
+        assert_eq!(configuration.duplicate_users, DuplicateUserPolicy::Merge);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_format, OutputFormat::Edges);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, true);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.progress_json, false);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn process_id() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .process_id(42);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 42);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn processes() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .processes(42);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 42);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn processes_from_hosts() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        // Without hosts, the number of processes is unchanged.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .processes(2)
+            .processes_from_hosts();
+        assert_eq!(configuration.number_of_processes, 2);
+
+        // With hosts, there is one process per host.
+        let configuration = Configuration::default(retweets, social_graph)
+            .hosts(Some(vec![
+                String::from("host1:2101"),
+                String::from("host1:2102"),
+                String::from("host1:2103"),
+            ]))
+            .processes_from_hosts();
+        assert_eq!(configuration.number_of_processes, 3);
+        assert_eq!(configuration.hosts, Some(vec![
+            String::from("host1:2101"),
+            String::from("host1:2102"),
+            String::from("host1:2103"),
+        ]));
+    }
+
+    #[test]
+    fn progress_callback() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let reports: Arc<Mutex<Vec<(u64, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+        let callback_reports = reports.clone();
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .progress_callback(move |processed, total| {
+                callback_reports.lock().expect("The reports have been poisoned").push((processed, total));
+            });
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+
+        let callback = configuration.progress_callback.expect("The callback has not been set");
+        callback.report(1, 2);
+        assert_eq!(*reports.lock().expect("The reports have been poisoned"), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn progress_json() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .progress_json(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.progress_json, true);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn repetitions() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .repetitions(5);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.repetitions, 5);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn report_connection_progress() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .report_connection_progress(true);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, true);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn report_top_cascades() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .report_top_cascades(5);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.report_top_cascades, 5);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn s3_max_retries() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets, social_graph)
+            .s3_max_retries(5);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.s3_max_retries, 5);
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn sample_output() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
 
-                self.hosts = Some(host_addresses.clone());
-            }
+        let configuration = Configuration::default(retweets, social_graph)
+            .sample_output(Some(10));
 
-            Ok(TimelyConfiguration::Cluster(self.number_of_workers, self.process_id, host_addresses,
-                                            self.report_connection_progress))
-        } else if self.number_of_workers > 1 {
-            // One process, multiple workers.
-            Ok(TimelyConfiguration::Process(self.number_of_workers))
-        } else {
-            // Single process, single thread.
-            Ok(TimelyConfiguration::Thread)
-        }
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.sample_output, Some(10));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
     }
-}
 
-impl fmt::Display for Configuration {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        let hosts: String = match self.hosts {
-            Some(ref hosts) => {
-                let mut hosts_list = String::from("[");
-                let mut joined_hosts: String = hosts
-                    .iter()
-                    .fold(String::new(), |acc, s| {
-                        acc + s + ", "
-                    });
-                let _ = joined_hosts.pop();
-                let _ = joined_hosts.pop();
-                hosts_list += &joined_hosts;
-                hosts_list += "]";
-                hosts_list
-            }
-            None => String::from("[]")
-        };
+    #[test]
+    fn screen_names() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
 
-        write!(formatter,
-               "(Algorithm: {algorithm}, Batch Size: {batch}, Hosts: {hosts}, Number of Processes: {processes}, \
-                Number of Workers: {workers}, Output Target: {output}, Insert Dummy Users: {dummies}, \
-                Process ID: {id}, Report Connection Progress: {progress}, Retweet Data Set: {retweets}, \
-                Social Graph: {graph})",
-               algorithm = self.algorithm, batch = self.batch_size, hosts = hosts,
-               processes = self.number_of_processes, workers = self.number_of_workers, output = self.output_target,
-               dummies = self.pad_with_dummy_users, id = self.process_id, progress = self.report_connection_progress,
-               retweets = self.retweets, graph = self.social_graph)
+        let configuration = Configuration::default(retweets, social_graph)
+            .screen_names(Some(InputSource::new("path/to/screen_names.csv")));
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.screen_names, Some(InputSource::new("path/to/screen_names.csv")));
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use configuration::Algorithm;
-    use configuration::OutputTarget;
-    use std::error::Error;
-    use std::path::PathBuf;
-    use timely_communication::initialize::Configuration as TimelyConfiguration;
+    #[test]
+    fn seed() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
 
-    use super::*;
+        let configuration = Configuration::default(retweets, social_graph)
+            .seed(42);
+
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
+        assert_eq!(configuration.batch_size, 50000);
+        assert_eq!(configuration.hosts, None);
+        assert_eq!(configuration.number_of_processes, 1);
+        assert_eq!(configuration.number_of_workers, 1);
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
+        assert_eq!(configuration.pad_with_dummy_users, false);
+        assert_eq!(configuration.process_id, 0);
+        assert_eq!(configuration.report_connection_progress, false);
+        assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.seed, 42);
+        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert!(configuration._prevent_outside_initialization);
+    }
 
     #[test]
-    fn default() {
+    fn seed_authors() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
-        let configuration = Configuration::default(retweets, social_graph);
+        let configuration = Configuration::default(retweets, social_graph)
+            .seed_authors(Some([0, 42].iter().map(|&id| UserId::from(id)).collect()));
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
@@ -310,20 +2655,21 @@ mod tests {
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.seed_authors, Some([0, 42].iter().map(|&id| UserId::from(id)).collect()));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn algorithm() {
+    fn selected_user_ids() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .algorithm(Algorithm::LEAF);
+            .selected_user_ids(vec![UserId::from(1), UserId::from(4)]);
 
-        assert_eq!(configuration.algorithm, Algorithm::LEAF);
+        assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
         assert_eq!(configuration.hosts, None);
         assert_eq!(configuration.number_of_processes, 1);
@@ -333,21 +2679,23 @@ mod tests {
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
+        assert_eq!(configuration.selected_user_ids, vec![UserId::from(1), UserId::from(4)]);
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn batch_size() {
+    fn selected_users() {
         let retweets = InputSource::new("path/to/retweets.json");
+        let selected_users = InputSource::new("path/to/selected/users.txt");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .batch_size(1);
+            .selected_users(Some(selected_users));
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
-        assert_eq!(configuration.batch_size, 1);
+        assert_eq!(configuration.batch_size, 50000);
         assert_eq!(configuration.hosts, None);
         assert_eq!(configuration.number_of_processes, 1);
         assert_eq!(configuration.number_of_workers, 1);
@@ -356,31 +2704,22 @@ mod tests {
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
-        assert_eq!(configuration.selected_users, None);
+        assert_eq!(configuration.selected_users, Some(InputSource::new("path/to/selected/users.txt")));
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn hosts() {
+    fn sort_friends() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
-        let hosts = vec![
-            String::from("host1:2101"),
-            String::from("host1:2102"),
-            String::from("host1:2103"),
-        ];
 
         let configuration = Configuration::default(retweets, social_graph)
-            .hosts(Some(hosts));
+            .sort_friends(true);
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
-        assert_eq!(configuration.hosts, Some(vec![
-            String::from("host1:2101"),
-            String::from("host1:2102"),
-            String::from("host1:2103"),
-        ]));
+        assert_eq!(configuration.hosts, None);
         assert_eq!(configuration.number_of_processes, 1);
         assert_eq!(configuration.number_of_workers, 1);
         assert_eq!(configuration.output_target, OutputTarget::StdOut);
@@ -390,41 +2729,41 @@ mod tests {
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.sort_friends, true);
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn output_target() {
+    fn stream_retweets() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
-        let output = PathBuf::from("results");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .output_target(OutputTarget::Directory(output));
+            .stream_retweets(true);
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
         assert_eq!(configuration.hosts, None);
         assert_eq!(configuration.number_of_processes, 1);
         assert_eq!(configuration.number_of_workers, 1);
-        assert_eq!(configuration.output_target,
-        OutputTarget::Directory(PathBuf::from("results")));
+        assert_eq!(configuration.output_target, OutputTarget::StdOut);
         assert_eq!(configuration.pad_with_dummy_users, false);
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.stream_retweets, true);
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn pad_with_dummy_users() {
+    fn strict() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .pad_with_dummy_users(true);
+            .strict(true);
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
@@ -432,22 +2771,23 @@ mod tests {
         assert_eq!(configuration.number_of_processes, 1);
         assert_eq!(configuration.number_of_workers, 1);
         assert_eq!(configuration.output_target, OutputTarget::StdOut);
-        assert_eq!(configuration.pad_with_dummy_users, true);
+        assert_eq!(configuration.pad_with_dummy_users, false);
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.strict, true);
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn process_id() {
+    fn sync_timeout() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .process_id(42);
+            .sync_timeout(Some(60));
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
@@ -456,26 +2796,27 @@ mod tests {
         assert_eq!(configuration.number_of_workers, 1);
         assert_eq!(configuration.output_target, OutputTarget::StdOut);
         assert_eq!(configuration.pad_with_dummy_users, false);
-        assert_eq!(configuration.process_id, 42);
+        assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.sync_timeout, Some(60));
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn processes() {
+    fn timestamp_format() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .processes(42);
+            .timestamp_format(TimestampFormat::Rfc3339);
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
         assert_eq!(configuration.hosts, None);
-        assert_eq!(configuration.number_of_processes, 42);
+        assert_eq!(configuration.number_of_processes, 1);
         assert_eq!(configuration.number_of_workers, 1);
         assert_eq!(configuration.output_target, OutputTarget::StdOut);
         assert_eq!(configuration.pad_with_dummy_users, false);
@@ -484,16 +2825,17 @@ mod tests {
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.timestamp_format, TimestampFormat::Rfc3339);
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn report_connection_progress() {
+    fn timestamp_join_file() {
         let retweets = InputSource::new("path/to/retweets.json");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .report_connection_progress(true);
+            .timestamp_join_file(Some(PathBuf::from("path/to/timestamps.csv")));
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
@@ -503,21 +2845,21 @@ mod tests {
         assert_eq!(configuration.output_target, OutputTarget::StdOut);
         assert_eq!(configuration.pad_with_dummy_users, false);
         assert_eq!(configuration.process_id, 0);
-        assert_eq!(configuration.report_connection_progress, true);
+        assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
         assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.timestamp_join_file, Some(PathBuf::from("path/to/timestamps.csv")));
         assert!(configuration._prevent_outside_initialization);
     }
 
     #[test]
-    fn selected_users() {
+    fn track_friendship_utilization() {
         let retweets = InputSource::new("path/to/retweets.json");
-        let selected_users = PathBuf::from("path/to/selected/users.txt");
         let social_graph = InputSource::new("path/to/social/graph");
 
         let configuration = Configuration::default(retweets, social_graph)
-            .selected_users(Some(selected_users));
+            .track_friendship_utilization(true);
 
         assert_eq!(configuration.algorithm, Algorithm::GALE);
         assert_eq!(configuration.batch_size, 50000);
@@ -529,8 +2871,9 @@ mod tests {
         assert_eq!(configuration.process_id, 0);
         assert_eq!(configuration.report_connection_progress, false);
         assert_eq!(configuration.retweets, InputSource::new("path/to/retweets.json"));
-        assert_eq!(configuration.selected_users, Some(PathBuf::from("path/to/selected/users.txt")));
+        assert_eq!(configuration.selected_users, None);
         assert_eq!(configuration.social_graph, InputSource::new("path/to/social/graph"));
+        assert_eq!(configuration.track_friendship_utilization, true);
         assert!(configuration._prevent_outside_initialization);
     }
 
@@ -557,6 +2900,179 @@ mod tests {
         assert!(configuration._prevent_outside_initialization);
     }
 
+    #[test]
+    fn describe_diff() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone());
+        assert!(configuration.describe_diff(&configuration.clone()).is_empty());
+
+        let other = Configuration::default(retweets, social_graph)
+            .algorithm(Algorithm::LEAF)
+            .cascade_timeout(Some(10))
+            .workers(4)
+            .cancellation_token(Arc::new(AtomicBool::new(false)));
+        let differences: Vec<(&'static str, String, String)> = configuration.describe_diff(&other);
+        assert_eq!(differences, vec![
+            ("algorithm", String::from("GALE"), String::from("LEAF")),
+            ("cascade_timeout", String::from("None"), String::from("Some(10)")),
+            ("number_of_workers", String::from("1"), String::from("4")),
+        ]);
+
+        // The differences are symmetric.
+        let differences: Vec<(&'static str, String, String)> = other.describe_diff(&configuration);
+        assert_eq!(differences[0], ("algorithm", String::from("LEAF"), String::from("GALE")));
+        assert_eq!(differences.len(), 3);
+    }
+
+    #[test]
+    fn describe_diff_all_fields() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let other = Configuration::default(InputSource::new("other/retweets.json"),
+                                           InputSource::new("other/social/graph"))
+            .activation_grace_seconds(5)
+            .activation_log(Some(PathBuf::from("activations.csv")))
+            .activation_set_initial_capacity(42)
+            .additional_social_graphs(vec![PathBuf::from("more/social/graph")])
+            .algorithm(Algorithm::LEAF)
+            .assert_sorted_retweets(true)
+            .batch_size(500)
+            .buffer_output(true)
+            .cascade_size_quantiles(true)
+            .cascade_timeout(Some(10))
+            .cascades_time_ordered(true)
+            .deduplicate_edges(true)
+            .dry_run(true)
+            .duplicate_users(DuplicateUserPolicy::Fail)
+            .edge_list_format(EdgeListFormat::new(',', IdBase::Hexadecimal))
+            .edge_timeseries(Some(PathBuf::from("timeseries.csv")))
+            .emit_time_delta(Some(TimeUnit::Seconds))
+            .flush_on_cascade_complete(true)
+            .friend_expansion_hops(1)
+            .graph_load_statistics(Some(PathBuf::from("graph_load.json")))
+            .graph_owner_workers(vec![0])
+            .hash_output(true)
+            .hosts(Some(vec![String::from("localhost:2101")]))
+            .include_quotes(true)
+            .max_dummy_ratio(Some(0.5))
+            .max_expanded_friends(42)
+            .max_expected_friends(42)
+            .max_output_edges(Some(42))
+            .max_retweets(Some(42))
+            .minimal_stats(true)
+            .output_format(OutputFormat::DistinctPairs)
+            .output_root_users(Some(InputSource::new("root_users.txt")))
+            .output_target(OutputTarget::None)
+            .pad_with_dummy_users(true)
+            .process_id(1)
+            .processes(2)
+            .progress_json(true)
+            .repetitions(2)
+            .report_connection_progress(true)
+            .report_top_cascades(5)
+            .s3_max_retries(5)
+            .sample_output(Some(42))
+            .screen_names(Some(InputSource::new("screen_names.csv")))
+            .seed(42)
+            .seed_authors(Some(HashSet::new()))
+            .selected_user_ids(vec![UserId::from(42)])
+            .selected_users(Some(InputSource::new("selected_users.txt")))
+            .sort_friends(true)
+            .stream_retweets(true)
+            .strict(true)
+            .sync_timeout(Some(60))
+            .timestamp_format(TimestampFormat::Epoch)
+            .timestamp_join_file(Some(PathBuf::from("timestamps.csv")))
+            .track_friendship_utilization(true)
+            .workers(4);
+
+        let differences: Vec<(&'static str, String, String)> = configuration.describe_diff(&other);
+        for &(field, ref this_value, ref other_value) in &differences {
+            assert_ne!(this_value, other_value, "{} is reported without a difference", field);
+        }
+
+        // Each field except for the runtime ones is reported, i.e. all serialized fields and the output target.
+        let serialized: serde_json::Value = serde_json::to_value(&configuration)
+            .expect("Could not serialize the configuration");
+        let mut fields: Vec<String> = serialized.as_object()
+            .expect("The configuration is not serialized as an object")
+            .keys()
+            .cloned()
+            .collect();
+        fields.push(String::from("output_target"));
+        fields.sort();
+        let mut differing_fields: Vec<String> = differences.iter()
+            .map(|&(field, _, _)| String::from(field))
+            .collect();
+        differing_fields.sort();
+        assert_eq!(differing_fields, fields);
+    }
+
+    #[test]
+    fn validate() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone());
+        assert!(configuration.validate().is_ok());
+
+        // No workers.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .workers(0);
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "at least one worker per process is required");
+
+        // Process ID out of range.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .processes(2)
+            .process_id(2);
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "the process ID is not in range of all processes");
+
+        // Wrong number of hosts for multiple processes.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .processes(3)
+            .hosts(Some(vec![String::from("host1:2101"), String::from("host2:2101")]));
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "2 hosts given, but expected 3");
+
+        // Multiple hosts for a single process in strict mode.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .hosts(Some(vec![String::from("host1:2101"), String::from("host2:2101")]))
+            .strict(true);
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "2 hosts given, but expected 1");
+
+        // Graph owner out of range.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .workers(2)
+            .graph_owner_workers(vec![2]);
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "graph owner worker 2 is not in range of all 2 workers");
+
+        // Flushing complete cascades without a cascade timeout.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .flush_on_cascade_complete(true);
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "flushing complete cascades requires a cascade timeout");
+        assert!(configuration.cascade_timeout(Some(60)).validate().is_ok());
+
+        // Missing file of selected users.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .selected_users(Some(InputSource::new("path/to/selected_users.csv")));
+        assert_eq!(configuration.validate().expect_err("unexpectedly valid configuration").description(),
+                   "the file of selected users \"path/to/selected_users.csv\" does not exist");
+
+        // Selected users on AWS S3 are not checked.
+        let configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .selected_users(Some(InputSource::new("path/to/selected_users.csv")
+                                     .s3(Some(S3::new("bucket", "eu-central-1")))));
+        assert!(configuration.validate().is_ok());
+    }
+
     #[test]
     fn get_timely_configuration() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -674,6 +3190,96 @@ mod tests {
             String::from("localhost:2102"),
             String::from("localhost:2103")
         ]));
+
+        // Single process with multiple hosts: the hosts are ignored with a warning.
+        let hosts = vec![
+            String::from("host1:2101"),
+            String::from("host1:2102"),
+            String::from("host1:2103")
+        ];
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .hosts(Some(hosts.clone()));
+        let timely_config = configuration.get_timely_configuration();
+        match timely_config.expect("Failed to get the Timely configuration") {
+            TimelyConfiguration::Thread => {
+                assert!(true)
+            },
+            _ => assert!(false, "wrong timely configuration, expected `TimelyConfiguration::Thread`")
+        }
+
+        // Single process with multiple hosts in strict mode: the conflict is an error.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .hosts(Some(hosts.clone()))
+            .strict(true);
+        let timely_config = configuration.get_timely_configuration();
+        assert!(timely_config.is_err());
+        assert_eq!(timely_config.map(|_| ())
+            .expect_err("unexpectedly succeeded getting the Timely configuration")
+            .description(),
+        "3 hosts given, but expected 1");
+
+        // Single process with a single host in strict mode.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .hosts(Some(vec![String::from("host1:2101")]))
+            .strict(true);
+        assert!(configuration.get_timely_configuration().is_ok());
+
+        // Number of processes inferred from the hosts.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .hosts(Some(hosts.clone()))
+            .process_id(1)
+            .processes_from_hosts()
+            .strict(true);
+        let timely_config = configuration.get_timely_configuration();
+        match timely_config.expect("Failed to get the Timely configuration") {
+            TimelyConfiguration::Cluster(workers, id, cluster_hosts, _report) => {
+                assert_eq!(workers, 1);
+                assert_eq!(id, 1);
+                assert_eq!(cluster_hosts, hosts);
+            },
+            _ => assert!(false, "wrong timely configuration, expected `TimelyConfiguration::Cluster(..)`")
+        }
+
+        // The channel cannot be shared across processes.
+        let (sender, _receiver) = channel();
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .processes(2)
+            .output_target(OutputTarget::Multi(vec![OutputTarget::StdOut,
+                                                    OutputTarget::Channel(ChannelOutput::new(sender))]));
+        let timely_config = configuration.get_timely_configuration();
+        assert_eq!(timely_config.map(|_| ())
+            .expect_err("unexpectedly succeeded getting the Timely configuration")
+            .description(),
+        "the channel output target requires a single process");
+
+        // Graph owners, no owner given.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .graph_owner_workers(Vec::new());
+        let timely_config = configuration.get_timely_configuration();
+        assert!(timely_config.is_err());
+        assert_eq!(timely_config.map(|_| ())
+            .expect_err("unexpectedly succeeded getting the Timely configuration")
+            .description(),
+        "at least one graph owner worker is required");
+
+        // Graph owners, owner out of range.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .workers(2)
+            .processes(2)
+            .graph_owner_workers(vec![1, 4]);
+        let timely_config = configuration.get_timely_configuration();
+        assert!(timely_config.is_err());
+        assert_eq!(timely_config.map(|_| ())
+            .expect_err("unexpectedly succeeded getting the Timely configuration")
+            .description(),
+        "graph owner worker 4 is not in range of all 4 workers");
+
+        // Graph owners, valid owners.
+        let mut configuration = Configuration::default(retweets.clone(), social_graph.clone())
+            .workers(2)
+            .graph_owner_workers(vec![1]);
+        let timely_config = configuration.get_timely_configuration();
+        assert!(timely_config.is_ok());
     }
 
     #[test]
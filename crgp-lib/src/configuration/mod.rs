@@ -9,13 +9,51 @@
 //! Algorithm configuration.
 
 pub use self::algorithm::Algorithm;
+pub use self::cancellation_token::CancellationToken;
+pub use self::channel_output::ChannelOutput;
+pub use self::duplicate_users::DuplicateUserPolicy;
+pub use self::edge_list_format::EdgeListFormat;
+pub use self::edge_list_format::IdBase;
+pub use self::friend_list_callback::FriendListCallback;
+pub use self::gcs::GCS;
+pub use self::id_set::read_id_set;
 pub use self::input::InputSource;
+#[cfg(feature = "rdkafka")]
+pub use self::kafka_output::KafkaErrorPolicy;
 pub use self::main::Configuration;
 pub use self::output::OutputTarget;
+pub use self::output_format::MAX_MATRIX_USERS;
+pub use self::output_format::OutputFormat;
+#[cfg(feature = "petgraph")]
+pub use self::petgraph_output::EdgeData;
+#[cfg(feature = "petgraph")]
+pub use self::petgraph_output::PetGraphOutput;
+pub use self::preset::Preset;
+pub use self::progress_callback::ProgressCallback;
+pub use self::ratio::Ratio;
 pub use self::s3::S3;
+pub use self::time_unit::TimeUnit;
+pub use self::timestamp_format::TimestampFormat;
 
 mod algorithm;
+mod cancellation_token;
+mod channel_output;
+mod duplicate_users;
+mod edge_list_format;
+mod friend_list_callback;
+mod gcs;
+mod id_set;
 mod input;
+#[cfg(feature = "rdkafka")]
+mod kafka_output;
 mod main;
 mod output;
+mod output_format;
+#[cfg(feature = "petgraph")]
+mod petgraph_output;
+mod preset;
+mod progress_callback;
+mod ratio;
 mod s3;
+mod time_unit;
+mod timestamp_format;
@@ -9,6 +9,12 @@
 use std::fmt;
 use std::path::PathBuf;
 
+use configuration::ChannelOutput;
+#[cfg(feature = "rdkafka")]
+use configuration::KafkaErrorPolicy;
+#[cfg(feature = "petgraph")]
+use configuration::PetGraphOutput;
+
 /// Specify where the result will be written to.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum OutputTarget {
@@ -20,14 +26,104 @@ pub enum OutputTarget {
 
     /// Do not write the result at all.
     None,
+
+    /// Collect the result in an in-memory `petgraph` graph, accessible via the given handle after the computation.
+    ///
+    /// Requires the `petgraph` feature. Only available if the computation runs in a single process.
+    #[cfg(feature = "petgraph")]
+    #[serde(skip)]
+    PetGraph(PetGraphOutput),
+
+    /// Write the result to the specified file as a single directed graph in the DOT language of Graphviz once the
+    /// computation has finished. Intended for visualizing small cascades, since all edges are kept in memory until
+    /// then.
+    Dot(PathBuf),
+
+    /// Write each influence edge to the specified file as a single line containing a JSON object:
+    /// `{"influencer":...,"influencee":...,"cascade":...,"retweet":...,"timestamp":...}`. The file is created on the
+    /// first write and kept open for the remaining computation.
+    JsonLines(PathBuf),
+
+    /// Send each influence edge to the channel of the given handle as soon as it is written.
+    ///
+    /// Only available if the computation runs in a single process.
+    #[serde(skip)]
+    Channel(ChannelOutput),
+
+    /// Produce each influence edge as a JSON message keyed by its cascade ID to the given Kafka `topic`.
+    ///
+    /// Requires the `rdkafka` feature and the native `librdkafka` library.
+    #[cfg(feature = "rdkafka")]
+    Kafka {
+        /// A comma-separated list of Kafka brokers, e.g. `"host1:9092,host2:9092"`.
+        brokers: String,
+
+        /// The topic to which the influence edges will be produced.
+        topic: String,
+
+        /// How to handle errors when producing the influence edges.
+        error_policy: KafkaErrorPolicy,
+    },
+
+    /// Write the result to all of the given targets simultaneously. If writing to one target fails, the other targets
+    /// are still written.
+    Multi(Vec<OutputTarget>),
+}
+
+impl OutputTarget {
+    /// Get all single targets, i.e. resolve (possibly nested) `Multi` targets into the targets within them.
+    pub fn targets(&self) -> Vec<&OutputTarget> {
+        match *self {
+            OutputTarget::Multi(ref targets) => targets.iter().flat_map(|target| target.targets()).collect(),
+            _ => vec![self]
+        }
+    }
+
+    /// Get the target without any `Channel` targets, which are replaced by `None`. Thus, the target does not keep the
+    /// senders of the channels alive.
+    pub fn without_channels(self) -> OutputTarget {
+        match self {
+            OutputTarget::Channel(_) => OutputTarget::None,
+            OutputTarget::Multi(targets) => {
+                OutputTarget::Multi(targets.into_iter().map(OutputTarget::without_channels).collect())
+            },
+            target => target
+        }
+    }
+
+    /// Get the directory of the first `Directory` target, if any.
+    pub fn directory(&self) -> Option<&PathBuf> {
+        self.targets()
+            .into_iter()
+            .filter_map(|target| match *target {
+                OutputTarget::Directory(ref directory) => Some(directory),
+                _ => None
+            })
+            .next()
+    }
 }
 
 impl fmt::Display for OutputTarget {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         let target: &str = match *self {
             OutputTarget::Directory(ref path) => return write!(formatter, "\"{path}\"", path = path.display()),
+            OutputTarget::Dot(ref path) => return write!(formatter, "DOT file \"{path}\"", path = path.display()),
+            OutputTarget::JsonLines(ref path) => {
+                return write!(formatter, "JSON lines file \"{path}\"", path = path.display())
+            },
             OutputTarget::StdOut => "STDOUT",
             OutputTarget::None => "[disabled]",
+            #[cfg(feature = "petgraph")]
+            OutputTarget::PetGraph(_) => "petgraph",
+            OutputTarget::Channel(_) => "channel",
+            #[cfg(feature = "rdkafka")]
+            OutputTarget::Kafka { ref brokers, ref topic, .. } => {
+                return write!(formatter, "Kafka topic \"{topic}\" on {brokers}", topic = topic, brokers = brokers)
+            },
+            OutputTarget::Multi(ref targets) => {
+                let targets: Vec<String> = targets.iter().map(|target| format!("{}", target)).collect();
+                return write!(formatter, "[{targets}]", targets = targets.join(", "))
+            },
         };
         write!(formatter, "{output}", output = target)
     }
@@ -36,6 +132,9 @@ impl fmt::Display for OutputTarget {
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
+    use std::sync::mpsc::channel;
+    use UserId;
+    use social_graph::InfluenceEdge;
     use super::*;
 
     #[test]
@@ -55,4 +154,78 @@ mod tests {
         let output = OutputTarget::None;
         assert_eq!(format!("{}", output), String::from("[disabled]"));
     }
+
+    #[test]
+    fn fmt_display_dot() {
+        let output = OutputTarget::Dot(PathBuf::from(String::from("path/to/cascade.dot")));
+        assert_eq!(format!("{}", output), String::from("DOT file \"path/to/cascade.dot\""));
+    }
+
+    #[test]
+    fn fmt_display_json_lines() {
+        let output = OutputTarget::JsonLines(PathBuf::from(String::from("path/to/cascs.jsonl")));
+        assert_eq!(format!("{}", output), String::from("JSON lines file \"path/to/cascs.jsonl\""));
+    }
+
+    #[test]
+    fn fmt_display_channel() {
+        let (sender, _receiver) = channel();
+        let output = OutputTarget::Channel(ChannelOutput::new(sender));
+        assert_eq!(format!("{}", output), String::from("channel"));
+    }
+
+    #[test]
+    fn fmt_display_multi() {
+        let output = OutputTarget::Multi(vec![OutputTarget::StdOut,
+                                              OutputTarget::Directory(PathBuf::from(String::from("path/to/dir")))]);
+        assert_eq!(format!("{}", output), String::from("[STDOUT, \"path/to/dir\"]"));
+    }
+
+    #[test]
+    fn targets() {
+        let output = OutputTarget::StdOut;
+        assert_eq!(output.targets(), vec![&OutputTarget::StdOut]);
+
+        let directory = OutputTarget::Directory(PathBuf::from(String::from("path/to/dir")));
+        let output = OutputTarget::Multi(vec![OutputTarget::StdOut,
+                                              OutputTarget::Multi(vec![OutputTarget::None, directory.clone()])]);
+        assert_eq!(output.targets(), vec![&OutputTarget::StdOut, &OutputTarget::None, &directory]);
+    }
+
+    #[test]
+    fn without_channels() {
+        let (sender, receiver) = channel::<InfluenceEdge<UserId>>();
+        let channel_output = ChannelOutput::new(sender);
+        let directory = OutputTarget::Directory(PathBuf::from(String::from("path/to/dir")));
+
+        assert_eq!(OutputTarget::StdOut.without_channels(), OutputTarget::StdOut);
+        assert_eq!(OutputTarget::Channel(channel_output.clone()).without_channels(), OutputTarget::None);
+        let output = OutputTarget::Multi(vec![directory.clone(), OutputTarget::Channel(channel_output.clone())]);
+        assert_eq!(output.without_channels(), OutputTarget::Multi(vec![directory, OutputTarget::None]));
+
+        // Without any remaining handles, the channel is closed.
+        drop(channel_output);
+        assert!(receiver.iter().next().is_none());
+    }
+
+    #[test]
+    fn directory() {
+        assert_eq!(OutputTarget::StdOut.directory(), None);
+
+        let path = PathBuf::from(String::from("path/to/dir"));
+        let output = OutputTarget::Multi(vec![OutputTarget::StdOut, OutputTarget::Directory(path.clone()),
+                                              OutputTarget::Directory(PathBuf::from(String::from("other/dir")))]);
+        assert_eq!(output.directory(), Some(&path));
+    }
+
+    #[cfg(feature = "rdkafka")]
+    #[test]
+    fn fmt_display_kafka() {
+        let output = OutputTarget::Kafka {
+            brokers: String::from("localhost:9092"),
+            topic: String::from("influences"),
+            error_policy: KafkaErrorPolicy::Log,
+        };
+        assert_eq!(format!("{}", output), String::from("Kafka topic \"influences\" on localhost:9092"));
+    }
 }
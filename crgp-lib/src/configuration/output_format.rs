@@ -0,0 +1,139 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for how results are formatted.
+
+use std::fmt;
+
+/// The maximum number of users in a cascade for which an adjacency matrix is written (see `OutputFormat::Matrix`).
+pub const MAX_MATRIX_USERS: usize = 32;
+
+/// Specify how the influence edges will be formatted when writing the result.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum OutputFormat {
+    /// Write each influence edge with its cascade, Retweet, and timestamp:
+    /// `cascade_id;retweet_id;influencee;influencer;timestamp;-1`.
+    Edges,
+
+    /// Write each influence edge like `Edges`, but render the influencee and the influencer as `id:@screen_name` if
+    /// their screen name is given in `Configuration::screen_names`, e.g. `123:@alice`. Users without a known screen
+    /// name, all users if no screen names are given, and dummy users are rendered by their bare ID:
+    /// `cascade_id;retweet_id;123:@alice;456;timestamp;-1`.
+    EdgesWithScreenNames,
+
+    /// Write each distinct pair of influencer and influencee exactly once, regardless of the cascade it occurred in:
+    /// `influencer;influencee`.
+    ///
+    /// To find duplicates, all pairs seen so far must be kept in memory during the entire computation. The memory
+    /// required grows linearly with the number of distinct pairs.
+    DistinctPairs,
+
+    /// Write a dense adjacency matrix of the influences for each cascade, meant for teaching and debugging on small
+    /// examples. The matrix is preceded by a line `# Cascade cascade_id`, followed by a header row with the IDs of
+    /// all users in the cascade. Each further row starts with the ID of an influencer, followed by a `1` for each
+    /// user they influenced, and `0` otherwise: `influencer;0;1;...`. All values are separated by `;`.
+    ///
+    /// The influence edges are buffered per cascade and the matrices are written once the computation has finished,
+    /// in the same way an output sample is. Cascades with more than `MAX_MATRIX_USERS` (i.e. 32) users are skipped
+    /// with a warning. This format only applies to the directory and STDOUT output targets; all other targets
+    /// receive the influence edges as usual.
+    Matrix,
+
+    /// Write each influence edge in binary form as encoded by `abomonation::encode`, to be read again quickly by
+    /// `read_edges`, e.g. when chaining computations. The edges are written to the file `cascs.abom` instead of
+    /// `cascs.csv`.
+    ///
+    /// The file contains the in-memory representation of the edges, thus it can only be read by the same version of
+    /// this crate, compiled for the same target architecture. It is not an archival format. This format only applies to
+    /// the directory output target; STDOUT receives the influence edges as in `Edges`, all other targets as usual.
+    Abomonation,
+
+    /// Write the influence edges for the bulk import into Neo4j with `neo4j-admin import`: the relationships are
+    /// streamed to the result file `cascs.csv` as `:START_ID,:END_ID,cascade:long,:TYPE`, i.e.
+    /// `influencer,influencee,cascade_id,INFLUENCED`, and all users occurring in them are written to the nodes file
+    /// `users.csv` as `userId:ID,:LABEL`, i.e. `user,User`, once the computation has finished. Both files start with
+    /// their header.
+    ///
+    /// To write each user only once, the IDs of all users seen so far must be kept in memory during the entire
+    /// computation. The memory required grows linearly with the number of distinct users in the written edges. This
+    /// format only applies to the directory output target; STDOUT receives the influence edges as in `Edges`, all
+    /// other targets as usual.
+    Neo4j,
+
+    /// Write a summary of each completed cascade instead of its influence edges:
+    /// `cascade_id;root_user;retweets;edges;max_depth;dummy_edges`, where `retweets` is the number of Retweets in the
+    /// cascade, `edges` its number of influence edges, `max_depth` the largest number of influence edges on the
+    /// shortest path from the root user to any user in the cascade, and `dummy_edges` the number of influence edges
+    /// from or to a dummy user.
+    ///
+    /// The influence edges are buffered per cascade until the cascade is complete (see
+    /// `Configuration::cascade_timeout`), then summarized and freed. Cascades without any influence edges are not
+    /// summarized, since their root user is not known. Sampling the output does not apply to this format. This format
+    /// only applies to the directory and STDOUT output targets; all other targets receive the influence edges as usual.
+    CascadeSummary,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let format: &str = match *self {
+            OutputFormat::Edges => "Edges",
+            OutputFormat::EdgesWithScreenNames => "Edges with Screen Names",
+            OutputFormat::DistinctPairs => "Distinct Pairs",
+            OutputFormat::Matrix => "Matrix",
+            OutputFormat::Abomonation => "Abomonation",
+            OutputFormat::Neo4j => "Neo4j",
+            OutputFormat::CascadeSummary => "Cascade Summary",
+        };
+        write!(formatter, "{format}", format = format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fmt_display_edges() {
+        let format = OutputFormat::Edges;
+        assert_eq!(format!("{}", format), String::from("Edges"));
+    }
+
+    #[test]
+    fn fmt_display_edges_with_screen_names() {
+        let format = OutputFormat::EdgesWithScreenNames;
+        assert_eq!(format!("{}", format), String::from("Edges with Screen Names"));
+    }
+
+    #[test]
+    fn fmt_display_distinct_pairs() {
+        let format = OutputFormat::DistinctPairs;
+        assert_eq!(format!("{}", format), String::from("Distinct Pairs"));
+    }
+
+    #[test]
+    fn fmt_display_matrix() {
+        let format = OutputFormat::Matrix;
+        assert_eq!(format!("{}", format), String::from("Matrix"));
+    }
+
+    #[test]
+    fn fmt_display_abomonation() {
+        let format = OutputFormat::Abomonation;
+        assert_eq!(format!("{}", format), String::from("Abomonation"));
+    }
+
+    #[test]
+    fn fmt_display_neo4j() {
+        let format = OutputFormat::Neo4j;
+        assert_eq!(format!("{}", format), String::from("Neo4j"));
+    }
+
+    #[test]
+    fn fmt_display_cascade_summary() {
+        let format = OutputFormat::CascadeSummary;
+        assert_eq!(format!("{}", format), String::from("Cascade Summary"));
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for collecting the result in an in-memory `petgraph` graph.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use petgraph::graph::DiGraph;
+use petgraph::graph::NodeIndex;
+
+use UserId;
+use social_graph::InfluenceEdge;
+use twitter::User;
+
+/// The data associated with each edge in the reconstructed influence graph.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EdgeData {
+    /// The ID of the Retweet cascade for which the influence is valid.
+    pub cascade_id: u64,
+
+    /// The ID of the Retweet.
+    pub retweet_id: u64,
+
+    /// The time at which the influence is established.
+    pub timestamp: u64,
+}
+
+/// A handle to an in-memory `petgraph` graph collecting the reconstructed influences of all cascades.
+///
+/// Nodes are users, given by their ID; each user has exactly one node. Edges point from the influencer to the
+/// influencee. Since the graph is shared in memory, it can only be used if the computation runs in a single process.
+///
+/// Clones of the handle share the same graph. Two handles are equal if they share the same graph.
+#[derive(Clone)]
+pub struct PetGraphOutput {
+    /// The graph and the index of each user's node.
+    inner: Arc<Mutex<(DiGraph<UserId, EdgeData>, HashMap<UserId, NodeIndex>)>>,
+}
+
+impl PetGraphOutput {
+    /// Create a handle to a new, empty graph.
+    pub fn new() -> PetGraphOutput {
+        PetGraphOutput {
+            inner: Arc::new(Mutex::new((DiGraph::new(), HashMap::new()))),
+        }
+    }
+
+    /// Get a copy of the graph in its current state.
+    pub fn graph(&self) -> DiGraph<UserId, EdgeData> {
+        match self.inner.lock() {
+            Ok(inner) => inner.0.clone(),
+            Err(poisoned) => poisoned.into_inner().0.clone()
+        }
+    }
+
+    /// Add the given `influence` to the graph, creating nodes for its users if necessary.
+    #[doc(hidden)]
+    pub fn add_influence(&self, influence: &InfluenceEdge<User>) {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner()
+        };
+        let (ref mut graph, ref mut nodes) = *inner;
+
+        let influencer: NodeIndex = *nodes.entry(influence.influencer.id)
+            .or_insert_with(|| graph.add_node(influence.influencer.id));
+        let influencee: NodeIndex = *nodes.entry(influence.influencee.id)
+            .or_insert_with(|| graph.add_node(influence.influencee.id));
+        let data = EdgeData {
+            cascade_id: influence.cascade_id,
+            retweet_id: influence.retweet_id,
+            timestamp: influence.timestamp,
+        };
+        let _ = graph.add_edge(influencer, influencee, data);
+    }
+}
+
+impl fmt::Debug for PetGraphOutput {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "PetGraphOutput")
+    }
+}
+
+impl PartialEq for PetGraphOutput {
+    fn eq(&self, other: &PetGraphOutput) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for PetGraphOutput {}
+
+#[cfg(test)]
+mod tests {
+    use social_graph::InfluenceEdge;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn add_influence() {
+        let output = PetGraphOutput::new();
+        output.add_influence(&InfluenceEdge::new(User::new(0), User::new(1), 2, 3, 4, User::new(0)));
+        output.add_influence(&InfluenceEdge::new(User::new(1), User::new(2), 5, 6, 4, User::new(0)));
+
+        let graph = output.graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+
+        // Clones share the graph.
+        let clone = output.clone();
+        assert_eq!(clone, output);
+        clone.add_influence(&InfluenceEdge::new(User::new(0), User::new(2), 5, 6, 4, User::new(0)));
+        let graph = output.graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_ne!(output, PetGraphOutput::new());
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Named combinations of configuration values for common setups.
+
+use std::fmt;
+
+/// Common setups for running `CRGP`, used with `Configuration::preset`.
+///
+/// Each preset only changes the fields listed for it; all other fields keep the values of `Configuration::default`.
+/// Settings specific to the machine or cluster, such as the number of workers or the host list, must still be set
+/// explicitly.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Preset {
+    /// Local development with a single thread on small data sets:
+    ///
+    ///  * `assert_sorted_retweets`: `true`
+    ///  * `batch_size`: `1_000`
+    ///  * `number_of_workers`: `1`
+    ///  * `output_format`: `OutputFormat::Edges`
+    ///  * `output_target`: `OutputTarget::StdOut`
+    ///  * `pad_with_dummy_users`: `false`
+    LocalDev,
+
+    /// A single machine with enough memory to process large batches:
+    ///
+    ///  * `batch_size`: `500_000`
+    ///  * `number_of_processes`: `1`
+    ///  * `pad_with_dummy_users`: `true`
+    ///  * `report_connection_progress`: `false`
+    SingleNode,
+
+    /// Multiple processes on a cluster of machines:
+    ///
+    ///  * `batch_size`: `500_000`
+    ///  * `pad_with_dummy_users`: `true`
+    ///  * `progress_json`: `true`
+    ///  * `report_connection_progress`: `true`
+    Cluster,
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let preset: &str = match *self {
+            Preset::LocalDev => "Local Development",
+            Preset::SingleNode => "Single Node",
+            Preset::Cluster => "Cluster",
+        };
+        write!(formatter, "{preset}", preset = preset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", Preset::LocalDev), String::from("Local Development"));
+        assert_eq!(format!("{}", Preset::SingleNode), String::from("Single Node"));
+        assert_eq!(format!("{}", Preset::Cluster), String::from("Cluster"));
+    }
+}
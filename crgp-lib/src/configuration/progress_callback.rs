@@ -0,0 +1,82 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for reporting the progress of processing the Retweets to the caller.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A function receiving the number of Retweets processed so far and the total number of Retweets.
+///
+/// The function is called after each batch of Retweets has been processed, synchronously in the processing loop, i.e.
+/// no further Retweets are processed until it returns. It is only invoked on the first worker of the first process,
+/// which knows the total number of Retweets, but it must be thread-safe since the worker runs in its own thread. If the
+/// Retweets are streamed, their total number is unknown and the number of processed Retweets is given instead.
+///
+/// Clones of the callback share the same function. Two callbacks are equal if they are clones of each other.
+#[derive(Clone)]
+pub struct ProgressCallback {
+    /// The function receiving the progress.
+    callback: Arc<Fn(u64, u64) + Send + Sync>,
+}
+
+impl ProgressCallback {
+    /// Create a callback passing the progress to the given function.
+    pub fn new<F>(callback: F) -> ProgressCallback
+        where F: Fn(u64, u64) + Send + Sync + 'static
+    {
+        ProgressCallback {
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Report that `processed` of `total` Retweets have been processed.
+    pub fn report(&self, processed: u64, total: u64) {
+        (self.callback)(processed, total)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ProgressCallback")
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &ProgressCallback) -> bool {
+        Arc::ptr_eq(&self.callback, &other.callback)
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use super::*;
+
+    #[test]
+    fn report() {
+        let reports: Arc<Mutex<Vec<(u64, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+        let callback = {
+            let reports = reports.clone();
+            ProgressCallback::new(move |processed: u64, total: u64| {
+                reports.lock().expect("The reports have been poisoned").push((processed, total));
+            })
+        };
+
+        callback.report(1, 2);
+        callback.report(2, 2);
+        assert_eq!(*reports.lock().expect("The reports have been poisoned"), vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn eq() {
+        let callback = ProgressCallback::new(|_, _| {});
+        assert_eq!(callback, callback.clone());
+        assert_ne!(callback, ProgressCallback::new(|_, _| {}));
+    }
+}
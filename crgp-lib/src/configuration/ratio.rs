@@ -0,0 +1,82 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration of ratios.
+
+use std::fmt;
+
+use serde::de::Deserialize;
+use serde::de::Deserializer;
+use serde::de::Error as DeserializationError;
+
+/// A ratio, e.g. a share of `0.5` for 50%.
+///
+/// Unlike a plain `f64`, a ratio is never `NaN`, thus it is totally comparable for equality and configurations holding
+/// a ratio implement `Eq`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Serialize)]
+pub struct Ratio(f64);
+
+impl Ratio {
+    /// Create a ratio of the given `value`. Return `None` if `value` is `NaN`.
+    pub fn new(value: f64) -> Option<Ratio> {
+        if value.is_nan() {
+            None
+        } else {
+            Some(Ratio(value))
+        }
+    }
+
+    /// Get the value of this ratio.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Eq for Ratio {}
+
+impl<'de> Deserialize<'de> for Ratio {
+    fn deserialize<D>(deserializer: D) -> Result<Ratio, D::Error>
+        where D: Deserializer<'de>
+    {
+        let value: f64 = f64::deserialize(deserializer)?;
+        Ratio::new(value).ok_or_else(|| D::Error::custom("a ratio must not be NaN"))
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{value}", value = self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64;
+
+    use serde_json;
+
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(Ratio::new(0.5).map(|ratio| ratio.value()), Some(0.5));
+        assert_eq!(Ratio::new(f64::NAN), None);
+    }
+
+    #[test]
+    fn eq() {
+        assert_eq!(Ratio::new(0.5), Ratio::new(0.5));
+        assert_ne!(Ratio::new(0.5), Ratio::new(0.25));
+    }
+
+    #[test]
+    fn serde() {
+        let ratio: Ratio = Ratio::new(0.5).unwrap();
+        assert_eq!(serde_json::to_string(&ratio).unwrap(), "0.5");
+        assert_eq!(serde_json::from_str::<Ratio>("0.5").unwrap(), ratio);
+        assert!(serde_json::from_str::<Ratio>("\"half\"").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for the unit of written time spans.
+
+use std::fmt;
+
+/// Specify the unit in which time spans, e.g. the time between an influencer's activation and the influence, are
+/// written.
+///
+/// Tweets are timestamped with a resolution of seconds, thus time spans in finer units are always multiples of a
+/// second.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TimeUnit {
+    /// Seconds.
+    Seconds,
+
+    /// Milliseconds.
+    Milliseconds,
+
+    /// Microseconds.
+    Microseconds,
+}
+
+impl TimeUnit {
+    /// Convert the given number of `seconds` to this unit, saturating at `u64::MAX`.
+    pub fn convert_seconds(&self, seconds: u64) -> u64 {
+        let factor: u64 = match *self {
+            TimeUnit::Seconds => 1,
+            TimeUnit::Milliseconds => 1_000,
+            TimeUnit::Microseconds => 1_000_000,
+        };
+        seconds.saturating_mul(factor)
+    }
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let unit: &str = match *self {
+            TimeUnit::Seconds => "Seconds",
+            TimeUnit::Milliseconds => "Milliseconds",
+            TimeUnit::Microseconds => "Microseconds",
+        };
+        write!(formatter, "{unit}", unit = unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_seconds() {
+        assert_eq!(TimeUnit::Seconds.convert_seconds(3), 3);
+        assert_eq!(TimeUnit::Milliseconds.convert_seconds(3), 3_000);
+        assert_eq!(TimeUnit::Microseconds.convert_seconds(3), 3_000_000);
+        assert_eq!(TimeUnit::Microseconds.convert_seconds(u64::max_value()), u64::max_value());
+    }
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", TimeUnit::Seconds), String::from("Seconds"));
+        assert_eq!(format!("{}", TimeUnit::Milliseconds), String::from("Milliseconds"));
+        assert_eq!(format!("{}", TimeUnit::Microseconds), String::from("Microseconds"));
+    }
+}
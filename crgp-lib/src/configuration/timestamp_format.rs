@@ -0,0 +1,91 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Configuration for the format of the timestamps in the Retweet data set.
+
+use std::fmt;
+
+use serde_json::Value;
+
+/// Specify in which format the creation times of the Tweets in the Retweet data set are accepted.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TimestampFormat {
+    /// Accept both integers of seconds since the epoch and ISO-8601 (RFC 3339) strings.
+    Auto,
+
+    /// Only accept integers of seconds since the epoch.
+    Epoch,
+
+    /// Only accept ISO-8601 (RFC 3339) strings, e.g. `2018-10-10T20:19:24Z`.
+    Rfc3339,
+}
+
+impl TimestampFormat {
+    /// Determine if the JSON `value` of a Tweet gives its creation time in this format, as well as those of its
+    /// original and quoted Tweets, if included. Values without a creation time are accepted, since they are not
+    /// valid Tweets anyway.
+    pub fn accepts(&self, value: &Value) -> bool {
+        let created_at: Option<&Value> = value.get("created_at");
+        let is_accepted: bool = match (*self, created_at) {
+            (TimestampFormat::Auto, _) | (_, None) => true,
+            (TimestampFormat::Epoch, Some(created_at)) => created_at.is_number(),
+            (TimestampFormat::Rfc3339, Some(created_at)) => created_at.is_string(),
+        };
+
+        is_accepted && ["retweeted_status", "quoted_status"].iter()
+            .filter_map(|key| value.get(key))
+            .filter(|tweet| tweet.is_object())
+            .all(|tweet| self.accepts(tweet))
+    }
+}
+
+impl fmt::Display for TimestampFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let format: &str = match *self {
+            TimestampFormat::Auto => "Auto",
+            TimestampFormat::Epoch => "Epoch",
+            TimestampFormat::Rfc3339 => "RFC 3339",
+        };
+        write!(formatter, "{format}", format = format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use super::*;
+
+    #[test]
+    fn accepts() {
+        let epoch: Value = serde_json::from_str("{\"created_at\":1,\"retweeted_status\":{\"created_at\":0}}")
+            .expect("Could not parse the Tweet");
+        let rfc3339: Value = serde_json::from_str("{\"created_at\":\"1970-01-01T00:00:01Z\",\
+                                                   \"retweeted_status\":{\"created_at\":\"1970-01-01T00:00:00Z\"}}")
+            .expect("Could not parse the Tweet");
+        let mixed: Value = serde_json::from_str("{\"created_at\":1,\
+                                                 \"retweeted_status\":{\"created_at\":\"1970-01-01T00:00:00Z\"}}")
+            .expect("Could not parse the Tweet");
+
+        assert!(TimestampFormat::Auto.accepts(&epoch));
+        assert!(TimestampFormat::Auto.accepts(&rfc3339));
+        assert!(TimestampFormat::Auto.accepts(&mixed));
+
+        assert!(TimestampFormat::Epoch.accepts(&epoch));
+        assert!(!TimestampFormat::Epoch.accepts(&rfc3339));
+        assert!(!TimestampFormat::Epoch.accepts(&mixed));
+
+        assert!(!TimestampFormat::Rfc3339.accepts(&epoch));
+        assert!(TimestampFormat::Rfc3339.accepts(&rfc3339));
+        assert!(!TimestampFormat::Rfc3339.accepts(&mixed));
+    }
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", TimestampFormat::Auto), String::from("Auto"));
+        assert_eq!(format!("{}", TimestampFormat::Epoch), String::from("Epoch"));
+        assert_eq!(format!("{}", TimestampFormat::Rfc3339), String::from("RFC 3339"));
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Convenience module for more simple Google Cloud Storage access.
+
+/// The name of the environment variable with the OAuth 2.0 access token for Google Cloud Storage.
+pub const ACCESS_TOKEN_VAR_NAME: &str = "GCS_ACCESS_TOKEN";
+
+/// The base URL of the JSON API of Google Cloud Storage.
+const API_URL: &str = "https://storage.googleapis.com/storage/v1/b";
+
+use std::env::var;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+
+use curl::easy::Easy;
+use curl::easy::List;
+use serde_json;
+
+use Error;
+use Result;
+
+/// Load the OAuth 2.0 access token for Google Cloud Storage from the respective environment variable.
+///
+/// Required environment variables:
+///
+///  * `GCS_ACCESS_TOKEN` (e.g. as printed by `gcloud auth print-access-token`)
+///
+/// Return an error if required environment variables are missing.
+pub fn credentials_from_env() -> Result<String> {
+    let access_token: String = var(ACCESS_TOKEN_VAR_NAME)?;
+    Ok(access_token)
+}
+
+/// A bucket on Google Cloud Storage, accessed via the JSON API.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bucket {
+    /// The name of the bucket.
+    pub name: String,
+
+    /// The OAuth 2.0 access token used to authorize all requests.
+    access_token: String,
+}
+
+impl Bucket {
+    /// Access the bucket `name` using the `access_token`.
+    pub fn new(name: &str, access_token: &str) -> Bucket {
+        Bucket {
+            name: String::from(name),
+            access_token: String::from(access_token),
+        }
+    }
+
+    /// Get the names of all objects in the bucket whose names start with the `prefix`. Errors if the objects cannot be
+    /// listed.
+    pub fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut names: Vec<String> = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url: String = format!("{api}/{bucket}/o?prefix={prefix}", api = API_URL,
+                                          bucket = encode(&self.name), prefix = encode(prefix));
+            if let Some(ref token) = page_token {
+                url.push_str(&format!("&pageToken={token}", token = encode(token)));
+            }
+
+            let contents: Vec<u8> = self.request(&url)?;
+            let (page, next_page_token): (Vec<String>, Option<String>) = parse_object_list(&contents)?;
+            names.extend(page);
+            page_token = next_page_token;
+            if page_token.is_none() {
+                return Ok(names);
+            }
+        }
+    }
+
+    /// Get the contents of the object `name`. Errors if the object cannot be downloaded.
+    pub fn get(&self, name: &str) -> Result<Vec<u8>> {
+        let url: String = format!("{api}/{bucket}/o/{object}?alt=media", api = API_URL, bucket = encode(&self.name),
+                                  object = encode(name));
+        self.request(&url)
+    }
+
+    /// Send an authorized `GET` request to the `url` and return the body of the response. Errors if the request fails
+    /// or is not answered with HTTP code `200`.
+    fn request(&self, url: &str) -> Result<Vec<u8>> {
+        let mut contents: Vec<u8> = Vec::new();
+        let mut handle = Easy::new();
+        let code: u32 = {
+            let mut headers = List::new();
+            headers.append(&format!("Authorization: Bearer {token}", token = self.access_token))
+                .map_err(curl_error)?;
+            handle.url(url).map_err(curl_error)?;
+            handle.http_headers(headers).map_err(curl_error)?;
+            {
+                let mut transfer = handle.transfer();
+                transfer.write_function(|data| {
+                    contents.extend_from_slice(data);
+                    Ok(data.len())
+                }).map_err(curl_error)?;
+                transfer.perform().map_err(curl_error)?;
+            }
+            handle.response_code().map_err(curl_error)?
+        };
+
+        if code != 200 {
+            let message: String = format!("Could not access Google Cloud Storage bucket \"{bucket}\": HTTP error \
+                                           {code}",
+                                          bucket = self.name, code = code);
+            error!("{}", message);
+            return Err(Error::from(IOError::new(IOErrorKind::Other, message)));
+        }
+
+        Ok(contents)
+    }
+}
+
+/// A page of an object listing as returned by the JSON API.
+#[derive(Debug, Deserialize)]
+struct ObjectList {
+    /// The objects on this page. Omitted if there are none.
+    #[serde(default)]
+    items: Vec<Object>,
+
+    /// The token to request the next page, if there is one.
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+/// The metadata of an object as returned by the JSON API. Only the required fields are parsed.
+#[derive(Debug, Deserialize)]
+struct Object {
+    /// The name of the object.
+    name: String,
+}
+
+/// Parse a page of an object listing from the JSON `contents`. Return the names of the objects on the page and the
+/// token for the next page, if there is one.
+fn parse_object_list(contents: &[u8]) -> Result<(Vec<String>, Option<String>)> {
+    let list: ObjectList = match serde_json::from_slice(contents) {
+        Ok(list) => list,
+        Err(message) => {
+            let message: String = format!("Invalid object listing from Google Cloud Storage: {error}",
+                                          error = message);
+            error!("{}", message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let names: Vec<String> = list.items.into_iter()
+        .map(|object| object.name)
+        .collect();
+    Ok((names, list.next_page_token))
+}
+
+/// Percent-encode the URL `component`, keeping only unreserved characters.
+fn encode(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        match byte {
+            b'A'...b'Z' | b'a'...b'z' | b'0'...b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte))
+        }
+    }
+    encoded
+}
+
+/// Convert the curl `error` into an IO error.
+fn curl_error(error: ::curl::Error) -> Error {
+    let message: String = format!("Could not access Google Cloud Storage: {error}", error = error);
+    error!("{}", message);
+    Error::from(IOError::new(IOErrorKind::Other, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(super::encode("social_graph/000/00.tar"), String::from("social_graph%2F000%2F00.tar"));
+        assert_eq!(super::encode("a b~c"), String::from("a%20b~c"));
+        assert_eq!(super::encode(""), String::new());
+    }
+
+    #[test]
+    fn parse_object_list() {
+        let page: &[u8] = b"{\"kind\": \"storage#objects\", \"nextPageToken\": \"next\", \
+                            \"items\": [{\"name\": \"00.tar\", \"size\": \"42\"}, {\"name\": \"01.tar\"}]}";
+        let (names, next_page_token) = super::parse_object_list(page).expect("Could not parse the listing");
+        assert_eq!(names, vec![String::from("00.tar"), String::from("01.tar")]);
+        assert_eq!(next_page_token, Some(String::from("next")));
+
+        // The last page of an empty bucket.
+        let (names, next_page_token) = super::parse_object_list(b"{\"kind\": \"storage#objects\"}")
+            .expect("Could not parse the listing");
+        assert!(names.is_empty());
+        assert_eq!(next_page_token, None);
+
+        assert!(super::parse_object_list(b"not JSON").is_err());
+    }
+
+    #[test]
+    fn bucket_new() {
+        let bucket = Bucket::new("bucket", "Access Token");
+        assert_eq!(bucket.name, String::from("bucket"));
+        assert_eq!(bucket.access_token, String::from("Access Token"));
+    }
+}
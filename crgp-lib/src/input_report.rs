@@ -0,0 +1,216 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Check whether a Retweet data set and a social graph fit together before running the reconstruction.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use Configuration;
+use Result;
+use UserId;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar;
+use twitter;
+use twitter::Retweet;
+use twitter::User;
+
+/// The coverage of the users in a Retweet data set by a social graph.
+///
+/// A Retweet can only be attributed to an influencer if its user has a friend list in the social graph. The poster of
+/// an original Tweet can only be an influencer if they are followed by at least one user in the social graph. Low
+/// coverages usually indicate that the Retweets and the social graph use different ID spaces.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct InputReport {
+    /// The number of Retweets in the data set.
+    pub retweets: u64,
+
+    /// The number of distinct cascades, i.e. of distinct original Tweets.
+    pub cascades: u64,
+
+    /// The number of distinct users who retweeted.
+    pub retweeting_users: u64,
+
+    /// The number of distinct users who retweeted and have a friend list in the social graph.
+    pub retweeting_users_in_graph: u64,
+
+    /// The number of distinct users who posted an original Tweet, i.e. the roots of the cascades.
+    pub root_users: u64,
+
+    /// The number of distinct cascade roots who have a friend list in the social graph.
+    pub root_users_in_graph: u64,
+
+    /// The number of distinct cascade roots who are followed by at least one user in the social graph.
+    pub followed_root_users: u64,
+
+    /// The number of users with a friend list in the social graph.
+    pub users_in_graph: u64,
+
+    /// The number of friendships in the social graph.
+    pub friendships_in_graph: u64,
+}
+
+impl InputReport {
+    /// The fraction of retweeting users who have a friend list in the social graph, or `0.0` if there are no Retweets.
+    pub fn retweeting_user_coverage(&self) -> f64 {
+        fraction(self.retweeting_users_in_graph, self.retweeting_users)
+    }
+
+    /// The fraction of cascade roots who are followed by at least one user in the social graph, or `0.0` if there are
+    /// no Retweets.
+    pub fn root_user_coverage(&self) -> f64 {
+        fraction(self.followed_root_users, self.root_users)
+    }
+}
+
+impl fmt::Display for InputReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter,
+               "{retweets} Retweets in {cascades} cascades; {retweeting_in_graph} of {retweeting} retweeting users \
+                ({retweeting_coverage:.2}%) have a friend list; {followed} of {roots} cascade roots \
+                ({root_coverage:.2}%) are followed, {roots_in_graph} have a friend list; {users} users with \
+                {friendships} friendships in the social graph",
+               retweets = self.retweets, cascades = self.cascades, retweeting_in_graph = self.retweeting_users_in_graph,
+               retweeting = self.retweeting_users, retweeting_coverage = self.retweeting_user_coverage() * 100.0,
+               followed = self.followed_root_users, roots = self.root_users,
+               root_coverage = self.root_user_coverage() * 100.0, roots_in_graph = self.root_users_in_graph,
+               users = self.users_in_graph, friendships = self.friendships_in_graph)
+    }
+}
+
+/// Load the Retweets and the social graph given in the `configuration` and report how well the social graph covers the
+/// users in the Retweets, without running the reconstruction.
+///
+/// The Retweets are loaded first, then the social graph is streamed without keeping it in memory. The social graph is
+/// loaded as it would be for the reconstruction (e.g. respecting `selected_users` and `duplicate_users`), but without
+/// dummy users and without expanding the friend lists, since neither says anything about the compatibility of the
+/// inputs.
+pub fn validate_inputs(configuration: &Configuration) -> Result<InputReport> {
+    info!("Loading Retweets...");
+    let retweets: Vec<Retweet> = twitter::get::from_source(configuration.retweets.clone(),
+                                                           configuration.assert_sorted_retweets,
+                                                           configuration.include_quotes,
+                                                           configuration.timestamp_format)?;
+
+    let mut cascades: HashSet<u64> = HashSet::new();
+    let mut retweeting_users: HashSet<UserId> = HashSet::new();
+    let mut root_users: HashSet<UserId> = HashSet::new();
+    for retweet in &retweets {
+        let _ = cascades.insert(retweet.retweeted_status.id);
+        let _ = retweeting_users.insert(retweet.user.id);
+        let _ = root_users.insert(retweet.retweeted_status.user.id);
+    }
+
+    info!("Loading social graph...");
+    let mut retweeting_users_in_graph: HashSet<UserId> = HashSet::new();
+    let mut root_users_in_graph: HashSet<UserId> = HashSet::new();
+    let mut followed_root_users: HashSet<UserId> = HashSet::new();
+    let load_statistics: LoadStatistics = tar::load_with(
+        configuration.social_graph.clone(), &configuration.additional_social_graphs, false,
+        configuration.max_expected_friends, configuration.s3_max_retries, configuration.edge_list_format,
+        configuration.selected_users.clone(), &configuration.selected_user_ids, configuration.duplicate_users, 0,
+        configuration.max_expanded_friends, configuration.strict, configuration.sort_friends,
+        &mut |user: User, friends: Vec<User>| {
+            if retweeting_users.contains(&user.id) {
+                let _ = retweeting_users_in_graph.insert(user.id);
+            }
+            if root_users.contains(&user.id) {
+                let _ = root_users_in_graph.insert(user.id);
+            }
+            for friend in friends {
+                if root_users.contains(&friend.id) {
+                    let _ = followed_root_users.insert(friend.id);
+                }
+            }
+        })?;
+
+    let report = InputReport {
+        retweets: retweets.len() as u64,
+        cascades: cascades.len() as u64,
+        retweeting_users: retweeting_users.len() as u64,
+        retweeting_users_in_graph: retweeting_users_in_graph.len() as u64,
+        root_users: root_users.len() as u64,
+        root_users_in_graph: root_users_in_graph.len() as u64,
+        followed_root_users: followed_root_users.len() as u64,
+        users_in_graph: load_statistics.users,
+        friendships_in_graph: load_statistics.given_friendships,
+    };
+    info!("Input report: {}", report);
+
+    Ok(report)
+}
+
+/// Divide `part` by `total`, or return `0.0` if `total` is `0`.
+fn fraction(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use configuration::InputSource;
+    use super::*;
+
+    #[test]
+    fn validate_inputs_matching() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let retweets = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+        let social_graph = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let report: InputReport = validate_inputs(&configuration).expect("Validating the inputs failed");
+        assert_eq!(report.retweets, 6);
+        assert_eq!(report.cascades, 2);
+        assert_eq!(report.retweeting_users, 4);
+        assert_eq!(report.retweeting_users_in_graph, 4);
+        assert_eq!(report.root_users, 2);
+        assert_eq!(report.root_users_in_graph, 2);
+        assert_eq!(report.followed_root_users, 2);
+        assert_eq!(report.retweeting_user_coverage(), 1.0);
+        assert_eq!(report.root_user_coverage(), 1.0);
+    }
+
+    #[test]
+    fn validate_inputs_mismatched() {
+        // The Retweets use IDs that are (mostly) not part of the social graph.
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let retweets = InputSource::new(data_path.join("retweets_mismatched.json").to_str().unwrap());
+        let social_graph = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let report: InputReport = validate_inputs(&configuration).expect("Validating the inputs failed");
+        assert_eq!(report.retweets, 4);
+        assert_eq!(report.cascades, 2);
+        assert_eq!(report.retweeting_users, 4);
+        assert_eq!(report.retweeting_users_in_graph, 1);
+        assert_eq!(report.root_users, 2);
+        assert_eq!(report.root_users_in_graph, 0);
+        assert_eq!(report.followed_root_users, 0);
+        assert_eq!(report.retweeting_user_coverage(), 0.25);
+        assert_eq!(report.root_user_coverage(), 0.0);
+    }
+
+    #[test]
+    fn validate_inputs_invalid_retweets() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let retweets = InputSource::new(data_path.join("retweets.invalid.json").to_str().unwrap());
+        let social_graph = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+        let configuration = Configuration::default(retweets, social_graph);
+
+        assert!(validate_inputs(&configuration).is_err());
+    }
+
+    #[test]
+    fn fraction() {
+        assert_eq!(super::fraction(1, 4), 0.25);
+        assert_eq!(super::fraction(0, 0), 0.0);
+    }
+}
@@ -18,37 +18,64 @@
 
 #[macro_use]
 extern crate abomonation;
+extern crate chrono;
+extern crate curl;
 #[cfg(test)]
 extern crate find_folder;
 extern crate fine_grained;
+extern crate flate2;
 #[macro_use]
 extern crate log;
 #[macro_use]
 extern crate lazy_static;
+extern crate memmap;
+#[cfg(feature = "petgraph")]
+extern crate petgraph;
 #[cfg(test)]
 #[macro_use]
 extern crate quickcheck;
+extern crate rand;
+#[cfg(feature = "rdkafka")]
+extern crate rdkafka;
 extern crate regex;
 extern crate s3;
+extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate sha2;
 extern crate tar;
 extern crate timely;
 extern crate timely_communication;
+extern crate zip;
 
 pub use configuration::Configuration;
 pub use error::Error;
 pub use error::Result;
+pub use input_report::InputReport;
+pub use input_report::validate_inputs;
+pub use reconstruction::TopCascade;
 pub use reconstruction::run;
+pub use reconstruction::run_collect;
+pub use social_graph::AbomonatedEdges;
+pub use social_graph::InfluenceEdge;
+pub use social_graph::read_edges;
+pub use social_graph::source::cache_s3_locally;
+pub use social_graph::source::dump_abomonated_graph;
 pub use statistics::Statistics;
-use twitter::UserID;
+pub use text_source::read_hosts;
+pub use text_source::read_text_source;
+pub use twitter::UserId;
 
 pub mod aws_s3;
 pub mod configuration;
 mod error;
+pub mod gcs;
+mod input_report;
 mod reconstruction;
+pub mod schema;
 mod social_graph;
 mod statistics;
+mod text_source;
 mod timely_extensions;
 mod twitter;
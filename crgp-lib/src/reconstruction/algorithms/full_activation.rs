@@ -0,0 +1,36 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! The `FULL` algorithm.
+
+use Configuration;
+use reconstruction::Counters;
+use reconstruction::algorithms::GraphHandle;
+use reconstruction::algorithms::ProbeHandle;
+use reconstruction::algorithms::RetweetHandle;
+use reconstruction::algorithms::Scope;
+use reconstruction::algorithms::gale;
+use social_graph::GraphOwners;
+
+/// The `FULL` algorithm: `GALE` without comparing the activation times.
+///
+/// The activations and friendships are distributed as in `GALE`, but in step 4.2, an influence edge is produced for
+/// each activated friend `u` of the retweeting user `u*`, no matter whether `u` has been activated before or after the
+/// Retweet. Only activations by Retweets processed so far are known, thus, `u` must have retweeted earlier in the
+/// input, or be the poster of the original Tweet.
+///
+/// **Note:** The influence edges are a superset of those found by `GALE` on the same input, including edges to users
+/// who cannot have been influenced by their friend, since they retweeted first. They are meant for graph-theoretic
+/// analyses of the activated users, not for the reconstruction of the actual influences.
+///
+/// The workers destined to store the friendships are given by `owners`. The influence edges are written as specified
+/// in the `configuration`, which also determines when a cascade is complete. Influence edges from a user to themselves
+/// are never produced, but counted in the `counters`, which also keep the maximum number of cascades with activations
+/// kept in memory at the same time and the number of written influence edges.
+pub fn computation<'a>(scope: &mut Scope<'a>, configuration: &Configuration, owners: GraphOwners, counters: Counters)
+    -> (GraphHandle, RetweetHandle, ProbeHandle) {
+    gale::dataflow(scope, configuration, owners, counters, true)
+}
@@ -6,16 +6,23 @@
 
 //! The `GALE` algorithm.
 
+
 use timely::dataflow::operators::Broadcast;
 use timely::dataflow::operators::Input;
+use timely::dataflow::operators::Inspect;
 use timely::dataflow::operators::Probe;
 
-use configuration::OutputTarget;
+use Configuration;
+use reconstruction::Counters;
 use reconstruction::algorithms::GraphHandle;
 use reconstruction::algorithms::ProbeHandle;
 use reconstruction::algorithms::RetweetHandle;
 use reconstruction::algorithms::Scope;
+use social_graph::GraphOwners;
+use timely_extensions::operators::CascadeSizes;
+use timely_extensions::operators::CompleteCascades;
 use timely_extensions::operators::Reconstruct;
+use timely_extensions::operators::WidestFanOut;
 use timely_extensions::operators::Write;
 
 /// The `GALE` algorithm: **G**lobal **A**ctivations, **L**ocal **E**dges
@@ -31,16 +38,63 @@ use timely_extensions::operators::Write;
 ///         1. Only for activation iteration: `u` is a friend of `u*`; and
 ///         2. (The Retweet occurred after the activation of `u`, or
 ///         3. `u` is the poster of the original Tweet).
-pub fn computation<'a>(scope: &mut Scope<'a>, output: OutputTarget) -> (GraphHandle, RetweetHandle, ProbeHandle) {
+///
+/// If the Retweets within each cascade are ordered by time (see `Configuration::cascades_time_ordered`), all
+/// activations of a cascade precede a Retweet created after the previous one, thus, the activation times are not
+/// compared in step 4.2.
+///
+/// The workers destined to store the friendships are given by `owners`. The influence edges are written as specified
+/// in the `configuration`, which also determines when a cascade is complete. Influence edges from a user to themselves
+/// are never produced, but counted in the `counters`, which also keep the maximum number of cascades with activations
+/// kept in memory at the same time and the number of written influence edges.
+pub fn computation<'a>(scope: &mut Scope<'a>, configuration: &Configuration, owners: GraphOwners, counters: Counters)
+    -> (GraphHandle, RetweetHandle, ProbeHandle) {
+    dataflow(scope, configuration, owners, counters, false)
+}
+
+/// Create the dataflow of the `GALE` algorithm as `computation` does. If `ignore_activation_times`, step 4.2 only
+/// requires `u` to be activated, regardless of when (see `full_activation`).
+pub fn dataflow<'a>(scope: &mut Scope<'a>, configuration: &Configuration, owners: GraphOwners, counters: Counters,
+                    ignore_activation_times: bool) -> (GraphHandle, RetweetHandle, ProbeHandle) {
     // Create the inputs.
     let (graph_input, graph_stream) = scope.new_input();
     let (retweet_input, retweet_stream) = scope.new_input();
 
     // The actual algorithm;
-    let probe = retweet_stream
+    let influences = retweet_stream
         .broadcast()
-        .reconstruct(graph_stream)
-        .write(output)
+        .reconstruct(graph_stream, Some(owners), configuration.seed_authors.clone(), counters.suppressed_self_edges,
+                     configuration.cascade_timeout, configuration.cascades_time_ordered,
+                     configuration.activation_grace_seconds, ignore_activation_times,
+                     configuration.activation_set_initial_capacity, counters.peak_live_cascades,
+                     counters.late_retweets, counters.activation_log, counters.iteration_counts);
+
+    // Report completed cascades.
+    let completed_cascades = influences
+        .complete_cascades(&retweet_stream, configuration.cascade_timeout)
+        .inspect(|&(cascade_id, edges, retweets)| {
+            trace!("Completed cascade {cascade} with {edges} influence edges from {retweets} Retweets",
+                   cascade = cascade_id, edges = edges, retweets = retweets);
+        });
+
+    // Estimate the distribution of the cascade sizes.
+    if configuration.cascade_size_quantiles {
+        let _ = completed_cascades.cascade_sizes(counters.cascade_sizes);
+    }
+
+    // Report the widest fan-out of each completed cascade, unless only minimal statistics are requested.
+    if !configuration.minimal_stats {
+        let _ = influences
+            .widest_fan_out(&completed_cascades, counters.widest_fan_out)
+            .inspect(|&(cascade_id, influencer, fan_out)| {
+                trace!("Widest fan-out in cascade {cascade}: {influencer} with {fan_out} influence edges",
+                       cascade = cascade_id, influencer = influencer, fan_out = fan_out);
+            });
+    }
+
+    let probe = influences
+        .write(configuration, &completed_cascades, counters.emitted_edges, counters.skipped_edges,
+               counters.used_friendships, counters.output_hash, counters.output_error)
         .probe();
 
     (graph_input, retweet_input, probe)
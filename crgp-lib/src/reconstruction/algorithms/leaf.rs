@@ -8,21 +8,35 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::rc::Rc;
 
+use timely::dataflow::operators::Broadcast;
 use timely::dataflow::operators::Filter;
 use timely::dataflow::operators::Input;
+use timely::dataflow::operators::Inspect;
+use timely::dataflow::operators::Map;
 use timely::dataflow::operators::Probe;
 use timely::dataflow::operators::exchange::Exchange;
+use timely_communication::allocator::Allocate;
 
-use configuration::OutputTarget;
+use Configuration;
+use UserId;
+use reconstruction::Counters;
 use reconstruction::algorithms::GraphHandle;
 use reconstruction::algorithms::ProbeHandle;
 use reconstruction::algorithms::RetweetHandle;
 use reconstruction::algorithms::Scope;
+use social_graph::GraphOwners;
 use social_graph::InfluenceEdge;
+use timely_extensions::operators::CascadeSizes;
+use timely_extensions::operators::CompleteCascades;
 use timely_extensions::operators::FindPossibleInfluences;
+use timely_extensions::operators::WidestFanOut;
 use timely_extensions::operators::Write;
+use timely_extensions::operators::is_activated_before;
+use timely_extensions::operators::retweet_activation;
+use twitter::Retweet;
 use twitter::User;
 
 /// The `LEAF` algorithm: **L**ocal **E**dges, **A**ctivations, and **F**iltering
@@ -34,9 +48,17 @@ use twitter::User;
 ///     2. For all friends `u'` of `u*`, create possible influences from `u'` to `u*` for this cascade.
 ///     3. Send each possible influence to the worker `w'` storing `u'` friendships.
 /// 4. On `w'`: produce an actual influence from the possible influence if:
-///     1. `u'` has been activated before the Retweet occurred, or
+///     1. `u'` has been activated before the Retweet occurred (allowing for the configured grace window), or
 ///     2. `u'` is the poster of the original Tweet.
-pub fn computation<'a>(scope: &mut Scope<'a>, output: OutputTarget) -> (GraphHandle, RetweetHandle, ProbeHandle) {
+///
+/// The workers destined to store the friendships are given by `owners`. The influence edges are written as specified
+/// in the `configuration`, which also determines when a cascade is complete. Influence edges from a user to themselves
+/// are never produced, but counted in the `counters`, which also keep the maximum number of cascades with activations
+/// kept in memory at the same time and the number of written influence edges. If `Configuration::seed_authors` are
+/// given, Retweets of Tweets posted by any other user are dropped before step 2. The activations of a cascade are
+/// dropped on all workers once it is complete.
+pub fn computation<'a>(scope: &mut Scope<'a>, configuration: &Configuration, owners: GraphOwners, counters: Counters)
+    -> (GraphHandle, RetweetHandle, ProbeHandle) {
     // Create the inputs.
     let (graph_input, graph_stream) = scope.new_input();
     let (retweet_input, retweet_stream) = scope.new_input();
@@ -45,26 +67,83 @@ pub fn computation<'a>(scope: &mut Scope<'a>, output: OutputTarget) -> (GraphHan
     // those users who have retweeted within this cascade before, per worker. Since this map
     // is required within two closures, dynamic borrow checks are required.
     let activations: Rc<RefCell<HashMap<u64, HashMap<User, u64>>>> = Rc::new(RefCell::new(HashMap::new()));
+    let activation_grace_seconds: i64 = configuration.activation_grace_seconds;
+
+    // Only reconstruct the cascades of the seed authors, if given.
+    let seed_authors: Option<HashSet<UserId>> = configuration.seed_authors.clone();
+    let seeded_retweets = retweet_stream
+        .filter(move |retweet: &Retweet| {
+            match seed_authors {
+                Some(ref seed_authors) => seed_authors.contains(&retweet.retweeted_status.user.id),
+                None => true
+            }
+        });
 
     // The actual algorithm.
-    let probe = graph_stream
-        .find_possible_influences(retweet_stream, activations.clone())
-        .exchange(|influence: &InfluenceEdge<User>| influence.influencer.id as u64)
+    let influences = graph_stream
+        .find_possible_influences(seeded_retweets, activations.clone(), owners.clone(),
+                                  counters.suppressed_self_edges, counters.peak_live_cascades, counters.activation_log,
+                                  counters.iteration_counts)
+        .exchange(move |influence: &InfluenceEdge<User>| owners.route(&influence.influencer))
+        .map(move |influence: InfluenceEdge<User>| {
+            let activation: Option<u64> = match activations.borrow().get(&influence.cascade_id) {
+                Some(users) => users.get(&influence.influencer).cloned(),
+                None => None
+            };
+            let activation: Option<u64> = retweet_activation(influence.influencer, influence.original_user,
+                                                             activation);
+            influence.influencer_activation(activation)
+        })
         .filter(move |influence: &InfluenceEdge<User>| {
-            let is_influencer_activated: bool = match activations.borrow()
-                .get(&influence.cascade_id)
-                {
-                    Some(users) => match users.get(&influence.influencer) {
-                        Some(activation_timestamp) => &influence.timestamp > activation_timestamp,
-                        None => false
-                    },
-                    None => false
-                };
+            let is_influencer_activated: bool = match influence.influencer_activation {
+                Some(activation_timestamp) => {
+                    is_activated_before(activation_timestamp, influence.timestamp, activation_grace_seconds)
+                },
+                None => false
+            };
             let is_influencer_original_user: bool = influence.influencer == influence.original_user;
 
             is_influencer_activated || is_influencer_original_user
+        });
+
+    // Report completed cascades.
+    let completed_cascades = influences
+        .complete_cascades(&retweet_stream, configuration.cascade_timeout)
+        .inspect(|&(cascade_id, edges, retweets)| {
+            trace!("Completed cascade {cascade} with {edges} influence edges from {retweets} Retweets",
+                   cascade = cascade_id, edges = edges, retweets = retweets);
+        });
+
+    // Estimate the distribution of the cascade sizes.
+    if configuration.cascade_size_quantiles {
+        let _ = completed_cascades.cascade_sizes(counters.cascade_sizes);
+    }
+
+    // Report the widest fan-out of each completed cascade, unless only minimal statistics are requested.
+    if !configuration.minimal_stats {
+        let _ = influences
+            .widest_fan_out(&completed_cascades, counters.widest_fan_out)
+            .inspect(|&(cascade_id, influencer, fan_out)| {
+                trace!("Widest fan-out in cascade {cascade}: {influencer} with {fan_out} influence edges",
+                       cascade = cascade_id, influencer = influencer, fan_out = fan_out);
+            });
+    }
+
+    // Drop the activations of completed cascades on all workers: all possible influences of a completed cascade have
+    // passed the filter. The completions are only written after the activations have been dropped, thus the probe
+    // ensures that a later Retweet of the cascade starts it anew.
+    let worker_index: usize = scope.index();
+    let completed_activations = activations.clone();
+    let dropped_cascades = completed_cascades
+        .broadcast()
+        .inspect(move |&(cascade_id, _edges, _retweets)| {
+            let _ = completed_activations.borrow_mut().remove(&cascade_id);
         })
-        .write(output)
+        .filter(move |_| worker_index == 0);
+
+    let probe = influences
+        .write(configuration, &dropped_cascades, counters.emitted_edges, counters.skipped_edges,
+               counters.used_friendships, counters.output_hash, counters.output_error)
         .probe();
 
     (graph_input, retweet_input, probe)
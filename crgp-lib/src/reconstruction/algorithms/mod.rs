@@ -17,8 +17,10 @@ use timely_communication::allocator::Generic;
 use twitter::Retweet;
 use twitter::User;
 
+pub mod full_activation;
 pub mod gale;
 pub mod leaf;
+pub mod pace;
 
 /// The timely dataflow handle for introducing friendships into the graph.
 pub type GraphHandle = InputHandle<u64, (User, Vec<User>)>;
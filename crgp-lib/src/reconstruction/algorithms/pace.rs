@@ -0,0 +1,94 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! The `PACE` algorithm.
+
+use timely::dataflow::operators::Broadcast;
+use timely::dataflow::operators::Input;
+use timely::dataflow::operators::Inspect;
+use timely::dataflow::operators::Probe;
+use timely::dataflow::operators::exchange::Exchange;
+
+use Configuration;
+use reconstruction::Counters;
+use reconstruction::algorithms::GraphHandle;
+use reconstruction::algorithms::ProbeHandle;
+use reconstruction::algorithms::RetweetHandle;
+use reconstruction::algorithms::Scope;
+use timely_extensions::operators::CascadeSizes;
+use timely_extensions::operators::CompleteCascades;
+use timely_extensions::operators::Reconstruct;
+use timely_extensions::operators::WidestFanOut;
+use timely_extensions::operators::Write;
+use twitter::Retweet;
+
+/// The `PACE` algorithm: **P**artitioned **A**ctivations, **C**opied **E**dges
+///
+/// 1. Broadcast all friendship edges (`(u1, u2)`, `u1` follows `u2`) to all workers, i.e. each worker stores a copy of
+///    the entire social graph.
+/// 2. Send the current Retweet `r*` to the worker `w*` responsible for its cascade, determined by the cascade's ID.
+/// 3. On `w*`, mark the retweeting user `u*` as active for the Retweet's cascade, and, if this is the first Retweet in
+///    the cascade, the original user.
+/// 4. On `w*`, produce the influence edges like `GALE` does (see `gale::computation`).
+///
+/// Each cascade is handled entirely by a single worker, which may run in any process. Thus, unlike `GALE`, the Retweets
+/// are not broadcast to all workers, and unlike `LEAF`, no possible influences are exchanged between workers. In turn:
+///
+///  * Each worker stores the entire social graph, multiplying the memory required for it by the number of workers,
+///    and the social graph is sent to every worker once.
+///  * The work is only balanced across the workers if there are many cascades of similar sizes. A single large cascade
+///    is processed by a single worker.
+///
+/// `PACE` thus suits data sets with many small cascades on a social graph that fits into the memory of each worker.
+/// The designated owners of the social graph (see `Configuration::graph_owner_workers`) are ignored. The influence
+/// edges are written as specified in the `configuration`, which also determines when a cascade is complete. Influence
+/// edges from a user to themselves are never produced, but counted in the `counters`, which also keep the maximum
+/// number of cascades with activations kept in memory at the same time and the number of written influence edges.
+pub fn computation<'a>(scope: &mut Scope<'a>, configuration: &Configuration, counters: Counters)
+    -> (GraphHandle, RetweetHandle, ProbeHandle) {
+    // Create the inputs.
+    let (graph_input, graph_stream) = scope.new_input();
+    let (retweet_input, retweet_stream) = scope.new_input();
+
+    // The actual algorithm.
+    let influences = retweet_stream
+        .exchange(|retweet: &Retweet| retweet.retweeted_status.id)
+        .reconstruct(graph_stream.broadcast(), None, configuration.seed_authors.clone(),
+                     counters.suppressed_self_edges, configuration.cascade_timeout,
+                     configuration.cascades_time_ordered, configuration.activation_grace_seconds, false,
+                     configuration.activation_set_initial_capacity, counters.peak_live_cascades,
+                     counters.late_retweets, counters.activation_log, counters.iteration_counts);
+
+    // Report completed cascades.
+    let completed_cascades = influences
+        .complete_cascades(&retweet_stream, configuration.cascade_timeout)
+        .inspect(|&(cascade_id, edges, retweets)| {
+            trace!("Completed cascade {cascade} with {edges} influence edges from {retweets} Retweets",
+                   cascade = cascade_id, edges = edges, retweets = retweets);
+        });
+
+    // Estimate the distribution of the cascade sizes.
+    if configuration.cascade_size_quantiles {
+        let _ = completed_cascades.cascade_sizes(counters.cascade_sizes);
+    }
+
+    // Report the widest fan-out of each completed cascade, unless only minimal statistics are requested.
+    if !configuration.minimal_stats {
+        let _ = influences
+            .widest_fan_out(&completed_cascades, counters.widest_fan_out)
+            .inspect(|&(cascade_id, influencer, fan_out)| {
+                trace!("Widest fan-out in cascade {cascade}: {influencer} with {fan_out} influence edges",
+                       cascade = cascade_id, influencer = influencer, fan_out = fan_out);
+            });
+    }
+
+    let probe = influences
+        .write(configuration, &completed_cascades, counters.emitted_edges, counters.skipped_edges,
+               counters.used_friendships, counters.output_hash, counters.output_error)
+        .probe();
+
+    (graph_input, retweet_input, probe)
+}
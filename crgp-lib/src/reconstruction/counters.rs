@@ -0,0 +1,116 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Counters shared by all workers within a process.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use timely_extensions::operators::ActivationLog;
+use timely_extensions::operators::IterationCounts;
+use timely_extensions::operators::OutputHash;
+use timely_extensions::operators::QuantileSketch;
+use twitter::User;
+
+/// Counters updated by the operators of the computation and shared by all workers within this process. Cloning the
+/// counters shares them.
+#[derive(Clone, Debug, Default)]
+pub struct Counters {
+    /// The number of influence edges from a user to themselves which have not been produced.
+    pub suppressed_self_edges: Arc<AtomicUsize>,
+
+    /// The maximum number of cascades whose activations have been kept in memory at the same time on any worker.
+    pub peak_live_cascades: Arc<AtomicUsize>,
+
+    /// The number of Retweets that have started a cascade anew after its activations had been dropped on timeout.
+    pub late_retweets: Arc<AtomicUsize>,
+
+    /// The number of influence edges received by the write operator.
+    pub emitted_edges: Arc<AtomicUsize>,
+
+    /// The number of influence edges not written since the maximum number of output edges had been reached. Only set
+    /// once the computation has finished.
+    pub skipped_edges: Arc<AtomicUsize>,
+
+    /// How often the friends and how often the activations have been iterated to find influencers.
+    pub iteration_counts: IterationCounts,
+
+    /// The widest fan-out of any user in any cascade as `(cascade_id, influencer, fan_out)`, if there are any influence
+    /// edges.
+    pub widest_fan_out: Arc<Mutex<Option<(u64, User, u64)>>>,
+
+    /// The distinct friendships used by the written influence edges as pairs of user and friend, i.e. of influencee and
+    /// influencer. Only tracked if requested in the configuration.
+    pub used_friendships: Arc<Mutex<HashSet<(User, User)>>>,
+
+    /// The sizes of the completed cascades, i.e. their numbers of influence edges. Only filled on the first worker,
+    /// and only if requested in the configuration.
+    pub cascade_sizes: Arc<Mutex<QuantileSketch>>,
+
+    /// The log to which the activations of users within cascades are written. Only given if requested in the
+    /// configuration.
+    pub activation_log: Option<Arc<Mutex<ActivationLog>>>,
+
+    /// The hash of the result file in the first output directory. Only set on the first worker once the computation
+    /// has finished, and only if requested in the configuration.
+    pub output_hash: Arc<Mutex<Option<OutputHash>>>,
+
+    /// The first error of the write operator which must fail the computation, e.g. producing to Kafka if its error
+    /// policy is to fail. Only checked once the computation has finished.
+    pub output_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Counters {
+    /// Create new counters, all set to `0`.
+    pub fn new() -> Counters {
+        Counters::default()
+    }
+
+    /// Get the current number of influence edges received by the write operator.
+    pub fn emitted_edges(&self) -> u64 {
+        self.emitted_edges.load(Ordering::SeqCst) as u64
+    }
+
+    /// Get the current widest fan-out of any user in any cascade as `(cascade_id, influencer, fan_out)`.
+    pub fn widest_fan_out(&self) -> Option<(u64, User, u64)> {
+        *self.widest_fan_out.lock().expect("The widest fan-out has been poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use super::*;
+
+    #[test]
+    fn clone_shares_counters() {
+        let counters = Counters::new();
+        assert_eq!(counters.emitted_edges(), 0);
+
+        let clone = counters.clone();
+        let _ = clone.emitted_edges.fetch_add(42, Ordering::SeqCst);
+        let _ = clone.suppressed_self_edges.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(counters.emitted_edges(), 42);
+        assert_eq!(counters.suppressed_self_edges.load(Ordering::SeqCst), 1);
+        assert_eq!(counters.peak_live_cascades.load(Ordering::SeqCst), 0);
+        assert_eq!(counters.late_retweets.load(Ordering::SeqCst), 0);
+        assert_eq!(counters.skipped_edges.load(Ordering::SeqCst), 0);
+        assert_eq!(counters.widest_fan_out(), None);
+        assert!(counters.used_friendships.lock().unwrap().is_empty());
+        assert!(counters.activation_log.is_none());
+        assert!(counters.output_error.lock().unwrap().is_none());
+
+        *clone.output_error.lock().unwrap() = Some(String::from("Failed"));
+        assert_eq!(*counters.output_error.lock().unwrap(), Some(String::from("Failed")));
+
+        clone.iteration_counts.add_activation_iteration(3);
+        assert_eq!(counters.iteration_counts.activation_iterations(), 1);
+        assert_eq!(counters.iteration_counts.iterated_activations(), 3);
+    }
+}
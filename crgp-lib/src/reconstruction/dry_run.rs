@@ -0,0 +1,60 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Validate the inputs without running the computation.
+
+use Configuration;
+use Result;
+use Statistics;
+use configuration::InputSource;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar;
+use twitter;
+use twitter::Retweet;
+
+/// The maximum number of users loaded from each social graph.
+const SAMPLED_USERS: u64 = 100;
+
+/// Validate the inputs of the computation with the given `configuration` by loading a sample of the social graph (and
+/// of each additional social graph) and the first batch of Retweets. No output is written. The returned statistics
+/// contain the counts of the samples, all times are `0`.
+///
+/// Social graphs given by a callback are not sampled, since the callback is only available once the computation runs.
+pub fn dry_run(configuration: Configuration) -> Result<Statistics> {
+    info!("Validating the inputs without running the computation");
+
+    let mut sample = LoadStatistics::default();
+    if configuration.friend_list_callback.is_none() {
+        let mut inputs: Vec<InputSource> = vec![configuration.social_graph.clone()];
+        inputs.extend(configuration.additional_social_graphs.iter().map(InputSource::new));
+        for input in inputs {
+            info!("Loading a sample of the social graph {input}", input = input);
+            let input_sample: LoadStatistics = tar::load_sample(input, configuration.max_expected_friends,
+                                                                configuration.s3_max_retries,
+                                                                configuration.edge_list_format,
+                                                                configuration.selected_users.clone(),
+                                                                &configuration.selected_user_ids, SAMPLED_USERS)?;
+            sample.users += input_sample.users;
+            sample.given_friendships += input_sample.given_friendships;
+        }
+        info!("Found {friendships} friendships for {users} users in the sample of the social graph",
+              friendships = sample.given_friendships, users = sample.users);
+    }
+
+    info!("Loading the first batch of Retweets from {input}", input = configuration.retweets);
+    let retweets: Vec<Retweet> = twitter::get::sample_from_source(configuration.retweets.clone(),
+                                                                  configuration.assert_sorted_retweets,
+                                                                  configuration.include_quotes,
+                                                                  configuration.timestamp_format,
+                                                                  configuration.batch_size)?;
+    info!("Found {number} Retweets in the first batch", number = retweets.len());
+
+    Ok(Statistics::new(configuration)
+        .was_dry_run(true)
+        .number_of_friendships(sample.given_friendships)
+        .number_of_real_friendships(sample.given_friendships)
+        .number_of_retweets(retweets.len() as u64))
+}
@@ -0,0 +1,92 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Record the number of influence edges emitted per batch of Retweets as a time series.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use Result;
+
+/// The header line of the time series file.
+const HEADER: &'static str = "batch_index;edges;cumulative_edges";
+
+/// A time series of the influence edges emitted per batch of Retweets, written to a file as lines of
+/// `batch_index;edges;cumulative_edges`, preceded by a header line. The batch index starts at `1`.
+#[derive(Debug)]
+pub struct EdgeTimeSeries {
+    /// The writer for the time series file.
+    writer: BufWriter<File>,
+
+    /// The index of the last recorded batch.
+    batch_index: u64,
+
+    /// The total number of influence edges emitted up to the last recorded batch.
+    cumulative_edges: u64,
+}
+
+impl EdgeTimeSeries {
+    /// Create the time series file at `path` and write its header.
+    pub fn create(path: &Path) -> Result<EdgeTimeSeries> {
+        let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+
+        Ok(EdgeTimeSeries {
+            writer: writer,
+            batch_index: 0,
+            cumulative_edges: 0,
+        })
+    }
+
+    /// Record the next batch, given the total number of influence edges emitted so far (`cumulative_edges`).
+    pub fn record(&mut self, cumulative_edges: u64) -> Result<()> {
+        self.batch_index += 1;
+        let edges: u64 = cumulative_edges.saturating_sub(self.cumulative_edges);
+        self.cumulative_edges = cumulative_edges;
+
+        writeln!(self.writer, "{batch};{edges};{cumulative}", batch = self.batch_index, edges = edges,
+                 cumulative = cumulative_edges)?;
+        Ok(())
+    }
+
+    /// Make sure all recorded batches have been written to the file.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use super::*;
+
+    #[test]
+    fn record() {
+        let path: PathBuf = env::temp_dir().join("crgp_edge_timeseries_record.csv");
+        {
+            let mut timeseries = EdgeTimeSeries::create(&path).expect("Could not create the time series");
+            timeseries.record(4).expect("Could not record the batch");
+            timeseries.record(4).expect("Could not record the batch");
+            timeseries.record(7).expect("Could not record the batch");
+            timeseries.flush().expect("Could not flush the time series");
+        }
+
+        let mut contents = String::new();
+        let _ = File::open(&path).expect("Could not open the time series")
+            .read_to_string(&mut contents)
+            .expect("Could not read the time series");
+        assert_eq!(contents, "batch_index;edges;cumulative_edges\n1;4;4\n2;0;4\n3;3;7\n");
+
+        let _ = remove_file(&path);
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Determine how much of the social graph has been used to produce influence edges.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use Result;
+use twitter::User;
+
+/// The name of the file in the output directory to which the unused friendships are written.
+pub const UNUSED_FRIENDSHIPS_FILENAME: &'static str = "unused_friendships.csv";
+
+/// The utilization of the friendships in the social graph.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FriendshipUtilization {
+    /// The number of friendships in the social graph.
+    pub friendships: u64,
+
+    /// The number of friendships used to produce at least one influence edge.
+    pub used_friendships: u64,
+}
+
+impl FriendshipUtilization {
+    /// Compare the friendships in the social `graph`, given as pairs of a user and their friends, to the `used`
+    /// friendships, given as pairs of a user and their friend, i.e. of influencee and influencer. If `unused_output` is
+    /// given, write each unused friendship to this file as a line of `user;friend`.
+    pub fn analyze(graph: &[(User, Vec<User>)], used: &HashSet<(User, User)>, unused_output: Option<&Path>)
+        -> Result<FriendshipUtilization> {
+        let mut writer: Option<BufWriter<File>> = match unused_output {
+            Some(path) => Some(BufWriter::new(File::create(path)?)),
+            None => None
+        };
+
+        let mut utilization = FriendshipUtilization::default();
+        for &(user, ref friends) in graph {
+            for &friend in friends {
+                utilization.friendships += 1;
+                if used.contains(&(user, friend)) {
+                    utilization.used_friendships += 1;
+                } else if let Some(ref mut writer) = writer {
+                    writeln!(writer, "{user};{friend}", user = user, friend = friend)?;
+                }
+            }
+        }
+
+        if let Some(ref mut writer) = writer {
+            writer.flush()?;
+        }
+
+        Ok(utilization)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn analyze() {
+        let graph: Vec<(User, Vec<User>)> = vec![
+            (User::new(0), vec![User::new(1), User::new(2)]),
+            (User::new(1), vec![User::new(0)]),
+            (User::new(2), vec![]),
+        ];
+        let mut used: HashSet<(User, User)> = HashSet::new();
+        let _ = used.insert((User::new(0), User::new(2)));
+        let _ = used.insert((User::new(1), User::new(0)));
+
+        let path: PathBuf = env::temp_dir().join("crgp_friendship_utilization_analyze.csv");
+        let utilization = FriendshipUtilization::analyze(&graph, &used, Some(&path))
+            .expect("Could not analyze the friendship utilization");
+        assert_eq!(utilization.friendships, 3);
+        assert_eq!(utilization.used_friendships, 2);
+
+        let mut unused = String::new();
+        let _ = File::open(&path).expect("Could not open the unused friendships")
+            .read_to_string(&mut unused)
+            .expect("Could not read the unused friendships");
+        assert_eq!(unused, "0;1\n");
+
+        let _ = remove_file(&path);
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Write the statistics of loading the social graph to their own file.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use Result;
+use social_graph::source::LoadStatistics;
+
+/// Write the `statistics` of loading the social graph and the `time_to_process_social_graph` (in nanoseconds) to the
+/// file at `path` in TOML format. Errors on any I/O error.
+pub fn write_graph_load_statistics(path: &Path, statistics: &LoadStatistics, time_to_process_social_graph: u64)
+    -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(format_graph_load_statistics(statistics, time_to_process_social_graph).as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Format the `statistics` of loading the social graph and the `time_to_process_social_graph` as a TOML table.
+fn format_graph_load_statistics(statistics: &LoadStatistics, time_to_process_social_graph: u64) -> String {
+    format!("users = {users}\n\
+             given_friendships = {given}\n\
+             expected_friendships = {expected}\n\
+             dummy_friendships = {dummies}\n\
+             time_to_process_social_graph = {time}\n",
+            users = statistics.users, given = statistics.given_friendships,
+            expected = statistics.expected_friendships, dummies = statistics.dummy_friendships,
+            time = time_to_process_social_graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use social_graph::source::LoadStatistics;
+
+    #[test]
+    fn write_graph_load_statistics() {
+        let statistics = LoadStatistics {
+            users: 5,
+            given_friendships: 8,
+            expected_friendships: 51,
+            dummy_friendships: 43,
+            expanded_friendships: 0,
+            duplicate_users: 0,
+        };
+
+        let path: PathBuf = env::temp_dir().join("crgp_graph_load_statistics.toml");
+        super::write_graph_load_statistics(&path, &statistics, 42).expect("Could not write the statistics");
+
+        let mut contents = String::new();
+        let _ = File::open(&path).expect("Could not open the statistics")
+            .read_to_string(&mut contents)
+            .expect("Could not read the statistics");
+        assert_eq!(contents, "users = 5\n\
+                              given_friendships = 8\n\
+                              expected_friendships = 51\n\
+                              dummy_friendships = 43\n\
+                              time_to_process_social_graph = 42\n");
+
+        let _ = remove_file(&path);
+    }
+}
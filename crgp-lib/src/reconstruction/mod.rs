@@ -7,8 +7,18 @@
 //! Execute the reconstruction.
 
 pub use self::run::run;
+pub use self::run::run_collect;
+pub use self::top_cascades::TopCascade;
+use self::counters::Counters;
 use self::simplify_result::SimplifyResult;
 
 pub mod algorithms;
+mod counters;
+mod dry_run;
+mod edge_timeseries;
+mod friendship_utilization;
+mod graph_load;
+mod progress;
 mod run;
 mod simplify_result;
+mod top_cascades;
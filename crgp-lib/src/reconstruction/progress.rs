@@ -0,0 +1,83 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Machine-readable progress reports.
+
+use std::io::Write;
+use std::io::stderr;
+
+use serde_json;
+
+/// The progress within a phase of the computation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ProgressEvent<'a> {
+    /// The name of the phase.
+    phase: &'a str,
+
+    /// The number of items processed so far.
+    done: u64,
+
+    /// The total number of items in this phase.
+    total: u64,
+
+    /// The number of items processed per second.
+    rate: u64,
+}
+
+impl<'a> ProgressEvent<'a> {
+    /// Create a progress event for the given `phase`, in which `done` of `total` items have been processed within
+    /// `elapsed` nanoseconds.
+    pub fn new(phase: &'a str, done: u64, total: u64, elapsed: u64) -> ProgressEvent<'a> {
+        let rate: u64 = if elapsed == 0 {
+            0
+        } else {
+            (done as f64 / (elapsed as f64 / 1_000_000_000.0)) as u64
+        };
+
+        ProgressEvent {
+            phase: phase,
+            done: done,
+            total: total,
+            rate: rate,
+        }
+    }
+
+    /// Get the JSON representation of this event.
+    pub fn to_json(&self) -> String {
+        // Serializing this struct cannot fail.
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Write the JSON representation of this event as a single line to STDERR.
+    pub fn report(&self) {
+        let _ = writeln!(stderr(), "{}", self.to_json());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let event = ProgressEvent::new("process_retweets", 100, 1000, 2_000_000_000);
+        assert_eq!(event.phase, "process_retweets");
+        assert_eq!(event.done, 100);
+        assert_eq!(event.total, 1000);
+        assert_eq!(event.rate, 50);
+
+        // No time has elapsed.
+        let event = ProgressEvent::new("process_retweets", 100, 1000, 0);
+        assert_eq!(event.rate, 0);
+    }
+
+    #[test]
+    fn to_json() {
+        let event = ProgressEvent::new("process_retweets", 100, 1000, 2_000_000_000);
+        assert_eq!(event.to_json(), String::from("{\"phase\":\"process_retweets\",\"done\":100,\"total\":1000,\
+                                                  \"rate\":50}"));
+    }
+}
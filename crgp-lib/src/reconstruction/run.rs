@@ -6,31 +6,135 @@
 
 //! Run the reconstruction.
 
+use std::collections::HashSet;
+use std::io::BufReader;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+use std::time::Instant;
+use std::usize;
 
 use fine_grained::Stopwatch;
+use timely::Data;
+use timely::dataflow::operators::input::Handle as InputHandle;
+use timely::dataflow::scopes::Root;
 use timely::execute::execute as timely_execute;
+use timely_communication::allocator::Generic;
 use timely_communication::initialize::Configuration as TimelyConfiguration;
 use timely_communication::initialize::WorkerGuards;
 
 use Configuration;
+use Error;
 use Result;
 use Statistics;
+use UserId;
 use configuration::Algorithm;
+use configuration::ChannelOutput;
 use configuration::InputSource;
 use configuration::OutputTarget;
+use configuration::ProgressCallback;
+use reconstruction::Counters;
 use reconstruction::SimplifyResult;
+use reconstruction::dry_run::dry_run;
+use reconstruction::edge_timeseries::EdgeTimeSeries;
+use reconstruction::friendship_utilization::FriendshipUtilization;
+use reconstruction::friendship_utilization::UNUSED_FRIENDSHIPS_FILENAME;
+use reconstruction::graph_load::write_graph_load_statistics;
+use reconstruction::progress::ProgressEvent;
+use reconstruction::top_cascades::CascadeCounter;
+use reconstruction::top_cascades::TopCascade;
+use reconstruction::top_cascades::top_cascades;
+use reconstruction::algorithms::GraphHandle;
+use reconstruction::algorithms::ProbeHandle;
+use reconstruction::algorithms::RetweetHandle;
+use reconstruction::algorithms::full_activation;
 use reconstruction::algorithms::gale;
 use reconstruction::algorithms::leaf;
+use reconstruction::algorithms::pace;
+use social_graph::GraphOwners;
+use social_graph::InfluenceEdge;
+use social_graph::source::LoadStatistics;
+use social_graph::source::callback;
 use social_graph::source::tar;
 use timely_extensions::Sync;
+use timely_extensions::operators::ActivationLog;
 use twitter;
 use twitter::Retweet;
+use twitter::User;
+use twitter::get::RetweetLines;
+use twitter::get::RetweetLoadStatistics;
 
 /// Execute the reconstruction.
 pub fn run(mut configuration: Configuration) -> Result<Statistics> {
 
+    // Retweets cannot be processed in empty batches. The CLI rejects such batch sizes, library callers might not.
+    if configuration.batch_size == 0 {
+        warn!("The batch size must be positive, processing the Retweets in batches of 1 instead of 0");
+        configuration.batch_size = 1;
+    }
+
+    // Minimal statistics skip all derived statistics, even if they have been requested explicitly.
+    if configuration.minimal_stats {
+        if configuration.report_top_cascades > 0 || configuration.cascade_size_quantiles ||
+           configuration.track_friendship_utilization {
+            warn!("Only minimal statistics are requested, neither reporting the top cascades, nor estimating the \
+                   cascade sizes, nor tracking the friendship utilization");
+        }
+        configuration.report_top_cascades = 0;
+        configuration.cascade_size_quantiles = false;
+        configuration.track_friendship_utilization = false;
+    }
+
+    // Only Retweets from a local file without joined timestamps can be streamed, and they are not kept for repetitions.
+    if configuration.stream_retweets {
+        if configuration.retweets.s3.is_some() || configuration.timestamp_join_file.is_some() {
+            warn!("Only Retweets from a local file without joined timestamps can be read while processing them, \
+                   loading them completely instead");
+            configuration.stream_retweets = false;
+        } else if configuration.repetitions > 1 {
+            warn!("The Retweets are read while processing them, thus they are processed only once instead of \
+                   {repetitions} times", repetitions = configuration.repetitions);
+            configuration.repetitions = 1;
+        }
+    }
+
+    // PACE stores the entire social graph on every worker.
+    if configuration.algorithm == Algorithm::PACE && configuration.graph_owner_workers.is_some() {
+        warn!("PACE stores the social graph on all workers, ignoring the designated graph owners");
+    }
+
+    // The number of suppressed self-influences, the maximum number of cascades with activations kept in memory, and
+    // the number of written influence edges, shared by all workers within this process.
+    let mut counters: Counters = Counters::new();
+    let cascade_sizes = counters.cascade_sizes.clone();
+    let estimate_cascade_sizes: bool = configuration.cascade_size_quantiles;
+    let output_hash = counters.output_hash.clone();
+    let output_error = counters.output_error.clone();
+    let emitted_edges = counters.emitted_edges.clone();
+    let skipped_edges = counters.skipped_edges.clone();
+
     let timely_configuration: TimelyConfiguration = configuration.get_timely_configuration()?;
+
+    // Only validate the inputs if requested, without creating any output.
+    if configuration.dry_run {
+        return dry_run(configuration);
+    }
+
+    // Create the activation log shared by all workers within this process.
+    if let Some(ref path) = configuration.activation_log {
+        let path: PathBuf = if configuration.number_of_processes > 1 {
+            PathBuf::from(format!("{path}.{process}", path = path.display(), process = configuration.process_id))
+        } else {
+            path.clone()
+        };
+        counters.activation_log = Some(Arc::new(Mutex::new(ActivationLog::create(&path)?)));
+    }
     let result: WorkerGuards<Result<Statistics>> = timely_execute(timely_configuration,
                                                                   move |computation| -> Result<Statistics> {
         let index = computation.index();
@@ -43,17 +147,9 @@ pub fn run(mut configuration: Configuration) -> Result<Statistics> {
          * DATAFLOW GRAPH *
          ******************/
 
-        // Clone parts of the configuration so we can use them in the next closure.
-        let algorithm = configuration.algorithm;
-        let output_target: OutputTarget = configuration.output_target.clone();
-
         // Reconstruct the cascade.
-        let (mut graph_input, mut retweet_input, probe) = computation.dataflow::<u64, _, _>(move |scope| {
-            match algorithm {
-                Algorithm::GALE => gale::computation(scope, output_target),
-                Algorithm::LEAF => leaf::computation(scope, output_target)
-            }
-        });
+        let (mut graph_input, mut retweet_input, mut probe) = build_dataflow(computation, &configuration,
+                                                                             counters.clone());
         let time_to_setup: u64 = stopwatch.lap();
 
 
@@ -62,34 +158,96 @@ pub fn run(mut configuration: Configuration) -> Result<Statistics> {
          * SOCIAL GRAPH *
          ****************/
 
-        // Load the social graph into the computation (only on the first worker).
-        let counts: (u64, u64, u64, u64) = if index == 0 {
+        // Load the social graph into the computation (only on the first worker). If the Retweets are processed
+        // repeatedly, the friendships are also kept in memory to pass them into the computation of each repetition.
+        // The same holds if their utilization is tracked.
+        let mut resident_graph: Vec<(User, Vec<User>)> = Vec::new();
+        let load_statistics: LoadStatistics = if index == 0 {
             info!("Loading social graph...");
             let input: InputSource = configuration.social_graph.clone();
-            let selected_users: Option<PathBuf> = configuration.selected_users.clone();
-            tar::load(input, configuration.pad_with_dummy_users, selected_users, &mut graph_input)?
+            let selected_users: Option<InputSource> = configuration.selected_users.clone();
+            let selected_user_ids: &[UserId] = &configuration.selected_user_ids;
+            let is_graph_resident: bool = configuration.repetitions > 1 || configuration.track_friendship_utilization;
+            match configuration.friend_list_callback {
+                Some(ref friend_list_callback) if is_graph_resident => {
+                    callback::load_with(friend_list_callback, selected_users, selected_user_ids,
+                                        &mut |user: User, friends: Vec<User>| {
+                                            resident_graph.push((user, friends.clone()));
+                                            graph_input.send((user, friends));
+                                        })?
+                },
+                Some(ref friend_list_callback) => {
+                    callback::load(friend_list_callback, selected_users, selected_user_ids, &mut graph_input)?
+                },
+                None if is_graph_resident => {
+                    tar::load_with(input, &configuration.additional_social_graphs, configuration.pad_with_dummy_users,
+                                   configuration.max_expected_friends, configuration.s3_max_retries,
+                                   configuration.edge_list_format, selected_users, selected_user_ids,
+                                   configuration.duplicate_users, configuration.friend_expansion_hops,
+                                   configuration.max_expanded_friends, configuration.strict,
+                                   configuration.sort_friends, &mut |user: User, friends: Vec<User>| {
+                                       resident_graph.push((user, friends.clone()));
+                                       graph_input.send((user, friends));
+                                   })?
+                },
+                None => {
+                    tar::load(input, &configuration.additional_social_graphs, configuration.pad_with_dummy_users,
+                              configuration.max_expected_friends, configuration.s3_max_retries,
+                              configuration.edge_list_format, selected_users, selected_user_ids,
+                              configuration.duplicate_users, configuration.friend_expansion_hops,
+                              configuration.max_expanded_friends, configuration.strict, configuration.sort_friends,
+                              &mut graph_input)?
+                }
+            }
         } else {
-                (0, 0, 0, 0)
+            LoadStatistics::default()
         };
-        let (number_of_users, number_of_given_friendships, number_of_expected_friendships, number_of_dummies) = counts;
 
         // Process the entire social graph before continuing.
-        computation.sync(&probe, &mut graph_input, &mut retweet_input);
+        sync_batch(computation, &configuration, &probe, &mut graph_input, &mut retweet_input)?;
         let time_to_process_social_network: u64 = stopwatch.lap();
 
         // Log loading information (only on the first worker).
         let friendships_in_social_graph: u64 = if index == 0 {
             info!("Finished loading the social graph in {time}ns", time = time_to_process_social_network);
             info!("Found {given} of {actual} friendships in the data set for {users} users",
-                  given = number_of_given_friendships, actual = number_of_expected_friendships,
-                  users = number_of_users);
+                  given = load_statistics.given_friendships, actual = load_statistics.expected_friendships,
+                  users = load_statistics.users);
+
+            // Write the load statistics right away, independent of whether the Retweets are processed successfully.
+            if let Some(ref path) = configuration.graph_load_statistics {
+                write_graph_load_statistics(path, &load_statistics, time_to_process_social_network)?;
+            }
 
-            let mut friendships_in_social_graph: u64 = number_of_given_friendships;
+            let mut friendships_in_social_graph: u64 = load_statistics.given_friendships;
             if configuration.pad_with_dummy_users {
-                info!("Created {number} dummy friends", number = number_of_dummies);
+                info!("Created {number} dummy friends", number = load_statistics.dummy_friendships);
 
                 // For the statistics, add the dummy friends to the size of the social graph.
-                friendships_in_social_graph += number_of_dummies;
+                friendships_in_social_graph += load_statistics.dummy_friendships;
+
+                // Mostly dummy friendships usually indicate broken meta data in the social graph.
+                if let Some(max_dummy_ratio) = configuration.max_dummy_ratio.map(|ratio| ratio.value()) {
+                    let dummy_ratio: f64 = load_statistics.dummy_friendship_ratio();
+                    if dummy_ratio > max_dummy_ratio {
+                        let message: String = format!("{ratio:.2}% of all friendships are with dummy users, exceeding \
+                                                       the maximum of {max:.2}%",
+                                                      ratio = dummy_ratio * 100.0, max = max_dummy_ratio * 100.0);
+                        if configuration.strict {
+                            error!("{}", message);
+                            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+                        }
+                        warn!("{}", message);
+                    }
+                }
+            }
+
+            // Also add the friendships added by the expansion.
+            friendships_in_social_graph += load_statistics.expanded_friendships;
+
+            if configuration.progress_json {
+                ProgressEvent::new("load_social_graph", friendships_in_social_graph, friendships_in_social_graph,
+                                   time_to_process_social_network).report();
             }
             friendships_in_social_graph
         } else {
@@ -102,36 +260,226 @@ pub fn run(mut configuration: Configuration) -> Result<Statistics> {
          * RETWEETS *
          ************/
 
-        // Load the retweets (on the first worker).
-        let retweets: Vec<Retweet> = if index == 0 {
-            twitter::get::from_source(configuration.retweets.clone())?
+        // Load the retweets (on the first worker). If they are streamed, they are only opened here and read while
+        // processing them.
+        let mut retweet_load_statistics = RetweetLoadStatistics::default();
+        let mut streamed_retweets: Option<RetweetLines<BufReader<Box<Read>>>> = if index == 0 &&
+                                                                                configuration.stream_retweets {
+            let streamed_retweets = twitter::get::stream_from_file(&configuration.retweets.path,
+                                                                   configuration.assert_sorted_retweets,
+                                                                   configuration.include_quotes,
+                                                                   configuration.timestamp_format)?;
+            if streamed_retweets.is_none() {
+                warn!("The Retweets are given as a JSON array, loading them completely before processing them");
+            }
+            streamed_retweets
+        } else {
+            None
+        };
+        let mut retweets: Vec<Retweet> = if index == 0 && streamed_retweets.is_none() {
+            twitter::get::from_source_joined(configuration.retweets.clone(),
+                                             configuration.timestamp_join_file.as_ref().map(PathBuf::as_path),
+                                             configuration.assert_sorted_retweets, configuration.include_quotes,
+                                             configuration.timestamp_format,
+                                             &mut retweet_load_statistics)?
         } else {
             Vec::new()
         };
+        if let Some(max_retweets) = configuration.max_retweets {
+            if retweets.len() > max_retweets {
+                info!("Only processing the first {max} of {total} Retweets", max = max_retweets,
+                      total = retweets.len());
+                retweets.truncate(max_retweets);
+            }
+        }
         let time_to_load_retweets: u64 = stopwatch.lap();
 
-        let number_of_retweets: u64 = retweets.len() as u64;
+        // The total number of streamed Retweets is only known once all of them have been processed.
+        let total_number_of_retweets: Option<u64> = if streamed_retweets.is_some() {
+            None
+        } else {
+            Some(retweets.len() as u64)
+        };
         info!("Finished loading Retweets in {time}ns", time = time_to_load_retweets);
+        let report_progress: bool = configuration.progress_json && index == 0;
+        let progress_callback: Option<ProgressCallback> = if index == 0 {
+            configuration.progress_callback.clone()
+        } else {
+            None
+        };
+        if report_progress {
+            let loaded_retweets: u64 = total_number_of_retweets.unwrap_or(0);
+            ProgressEvent::new("load_retweets", loaded_retweets, loaded_retweets, time_to_load_retweets).report();
+        }
+
+        // Record the influence edges emitted per batch (on the first worker, which writes all edges).
+        let mut edge_timeseries: Option<EdgeTimeSeries> = match configuration.edge_timeseries {
+            Some(ref path) if index == 0 => Some(EdgeTimeSeries::create(path)?),
+            _ => None
+        };
 
-        // Process the retweets.
+        // Process the retweets. The cascades of streamed Retweets are counted while processing them, since the
+        // Retweets are not kept.
         info!("Processing Retweets");
-        let batch_size: usize = configuration.batch_size;
-        for (round, retweet) in retweets.iter().enumerate() {
-            retweet_input.send(retweet.clone());
-
-            // Sync the computation after each batch.
-            let is_batch_complete: bool = round % batch_size == (batch_size - 1);
-            if is_batch_complete {
-                trace!("Processed {amount} of {total} Retweets...", amount = round + 1, total = number_of_retweets);
-                computation.sync(&probe, &mut retweet_input, &mut graph_input);
+        let processing_start: Instant = Instant::now();
+        let mut cascade_counter: Option<CascadeCounter> = if streamed_retweets.is_some() &&
+                                                             configuration.report_top_cascades > 0 {
+            Some(CascadeCounter::new(configuration.report_top_cascades, configuration.cascade_timeout))
+        } else {
+            None
+        };
+        let mut batch_latencies: Vec<u64> = Vec::new();
+        let mut retweets_not_seeded: u64 = 0;
+        let (number_of_retweets, mut cancelled) = {
+            let mut batch_start: Instant = processing_start;
+            let mut on_batch = |processed_retweets: u64| {
+                batch_latencies.push(elapsed_nanoseconds(batch_start));
+                batch_start = Instant::now();
+
+                if report_progress {
+                    ProgressEvent::new("process_retweets", processed_retweets,
+                                       total_number_of_retweets.unwrap_or(processed_retweets),
+                                       elapsed_nanoseconds(processing_start)).report();
+                }
+                if let Some(ref callback) = progress_callback {
+                    callback.report(processed_retweets, total_number_of_retweets.unwrap_or(processed_retweets));
+                }
+
+                if let Some(ref mut timeseries) = edge_timeseries {
+                    if let Err(message) = timeseries.record(counters.emitted_edges()) {
+                        error!("Could not write the edge time series: {error}", error = message);
+                    }
+                }
+            };
+            match streamed_retweets {
+                Some(ref mut streamed_retweets) => {
+                    let counter: &mut Option<CascadeCounter> = &mut cascade_counter;
+                    let not_seeded: &mut u64 = &mut retweets_not_seeded;
+                    let seed_authors: &Option<HashSet<UserId>> = &configuration.seed_authors;
+                    let max_retweets: usize = configuration.max_retweets.unwrap_or(usize::MAX);
+                    let streamed_retweets = streamed_retweets.take(max_retweets)
+                        .inspect(move |retweet: &Result<Retweet>| {
+                            if let Ok(ref retweet) = *retweet {
+                                if !is_seeded(retweet, seed_authors) {
+                                    *not_seeded += 1;
+                                }
+                                if let Some(ref mut counter) = *counter {
+                                    counter.add(retweet);
+                                }
+                            }
+                        });
+                    process_retweets(computation, &configuration, streamed_retweets, None, &mut graph_input,
+                                     &mut retweet_input, &probe, &mut on_batch)?
+                },
+                None => {
+                    let processed = process_retweets(computation, &configuration, retweets.iter().cloned().map(Ok),
+                                                     total_number_of_retweets, &mut graph_input, &mut retweet_input,
+                                                     &probe, &mut on_batch)?;
+                    retweets_not_seeded = retweets[..processed.0 as usize].iter()
+                        .filter(|retweet| !is_seeded(retweet, &configuration.seed_authors))
+                        .count() as u64;
+                    processed
+                }
             }
-        }
-        computation.sync(&probe, &mut retweet_input, &mut graph_input);
+        };
         let time_to_process_retweets: u64 = stopwatch.lap();
+        if let Some(ref streamed_retweets) = streamed_retweets {
+            retweet_load_statistics = streamed_retweets.statistics();
+            if retweet_load_statistics.missing_cascade > 0 {
+                warn!("Dropped {number} Retweets without the ID of their original Tweet",
+                      number = retweet_load_statistics.missing_cascade);
+            }
+            if retweet_load_statistics.skipped_tweets > 0 {
+                warn!("Skipped {number} Tweets which are not Retweets",
+                      number = retweet_load_statistics.skipped_tweets);
+            }
+        }
+
+        // Find the largest cascades among the processed Retweets (on the first worker, which has loaded them). Streamed
+        // Retweets are not kept, thus, they have only been counted if the top cascades are reported.
+        let top_cascades: Vec<TopCascade> = match cascade_counter {
+            Some(counter) => counter.top(),
+            None if streamed_retweets.is_some() => Vec::new(),
+            None => top_cascades(&retweets[..number_of_retweets as usize], configuration.report_top_cascades,
+                                 configuration.cascade_timeout)
+        };
+        if let Some(ref activation_log) = counters.activation_log {
+            let mut activation_log = activation_log.lock().expect("The activation log has been poisoned");
+            if let Err(message) = activation_log.flush() {
+                error!("Could not write the activation log: {error}", error = message);
+            }
+        }
+        if let Some(ref mut timeseries) = edge_timeseries {
+            if let Err(message) = timeseries.flush() {
+                error!("Could not write the edge time series: {error}", error = message);
+            }
+        }
+        if report_progress {
+            ProgressEvent::new("process_retweets", number_of_retweets,
+                               total_number_of_retweets.unwrap_or(number_of_retweets),
+                               time_to_process_retweets).report();
+        }
 
         info!("Finished processing {amount} Retweets in {time}ns", amount = number_of_retweets,
               time = time_to_process_retweets);
 
+        // Compare the friendships used by the influence edges to the social graph (only on the first worker).
+        let is_utilization_tracked: bool = index == 0 && configuration.track_friendship_utilization;
+        let friendship_utilization: FriendshipUtilization = if is_utilization_tracked {
+            let unused_output: Option<PathBuf> = configuration.output_target.directory()
+                .map(|directory| directory.join(UNUSED_FRIENDSHIPS_FILENAME));
+            let used_friendships = counters.used_friendships.lock().expect("The used friendships have been poisoned");
+            let utilization = FriendshipUtilization::analyze(&resident_graph, &used_friendships,
+                                                             unused_output.as_ref().map(|path| path.as_path()))?;
+            info!("Used {used} of {total} friendships to produce influence edges", used = utilization.used_friendships,
+                  total = utilization.friendships);
+            utilization
+        } else {
+            FriendshipUtilization::default()
+        };
+
+
+
+        /***************
+         * REPETITIONS *
+         ***************/
+
+        let mut retweet_processing_times: Vec<u64> = vec![time_to_process_retweets];
+        if !cancelled && configuration.repetitions > 1 {
+            // The repetitions neither write any results nor count the suppressed self-influences, live cascades, and
+            // influence edges again.
+            let repetition_configuration: Configuration = configuration.clone().output_target(OutputTarget::None);
+            for repetition in 1..configuration.repetitions {
+                // Replacing the handles closes the inputs of the previous repetition's computation.
+                let (new_graph_input, new_retweet_input, new_probe) = build_dataflow(computation,
+                                                                                     &repetition_configuration,
+                                                                                     Counters::new());
+                graph_input = new_graph_input;
+                retweet_input = new_retweet_input;
+                probe = new_probe;
+
+                for &(user, ref friends) in &resident_graph {
+                    graph_input.send((user, friends.clone()));
+                }
+                sync_batch(computation, &configuration, &probe, &mut graph_input, &mut retweet_input)?;
+
+                let repetition_start: Instant = Instant::now();
+                let (_, repetition_cancelled) = process_retweets(computation, &repetition_configuration,
+                                                                 retweets.iter().cloned().map(Ok),
+                                                                 total_number_of_retweets, &mut graph_input,
+                                                                 &mut retweet_input, &probe, &mut |_| {})?;
+                if repetition_cancelled {
+                    cancelled = true;
+                    break;
+                }
+
+                let time: u64 = elapsed_nanoseconds(repetition_start);
+                info!("Finished repetition {repetition} of {total} in {time}ns", repetition = repetition + 1,
+                      total = configuration.repetitions, time = time);
+                retweet_processing_times.push(time);
+            }
+        }
+
 
 
         /**********
@@ -139,20 +487,194 @@ pub fn run(mut configuration: Configuration) -> Result<Statistics> {
          **********/
 
         stopwatch.stop();
+        let (widest_fan_out_cascade, widest_fan_out_influencer, widest_fan_out) = match counters.widest_fan_out() {
+            Some((cascade_id, influencer, fan_out)) => (cascade_id, influencer.id, fan_out),
+            None => (0, UserId::from(0), 0)
+        };
         let statistics = Statistics::new(configuration.clone())
+            .cancelled(cancelled)
             .number_of_friendships(friendships_in_social_graph)
+            .number_of_real_friendships(load_statistics.given_friendships)
+            .number_of_dummy_friendships(load_statistics.dummy_friendships)
+            .number_of_duplicate_users(load_statistics.duplicate_users)
             .number_of_retweets(number_of_retweets)
+            .retweets_missing_cascade(retweet_load_statistics.missing_cascade)
+            .retweets_not_seeded(retweets_not_seeded)
+            .number_of_skipped_tweets(retweet_load_statistics.skipped_tweets)
+            .self_edges_suppressed(counters.suppressed_self_edges.load(Ordering::SeqCst) as u64)
+            .peak_live_cascades(counters.peak_live_cascades.load(Ordering::SeqCst) as u64)
+            .late_retweets(counters.late_retweets.load(Ordering::SeqCst) as u64)
+            .widest_fan_out(widest_fan_out_cascade, widest_fan_out_influencer, widest_fan_out)
+            .top_cascades(top_cascades)
+            .iterations(counters.iteration_counts.friend_iterations(), counters.iteration_counts.iterated_friends(),
+                        counters.iteration_counts.activation_iterations(),
+                        counters.iteration_counts.iterated_activations())
+            .friendship_utilization(friendship_utilization.friendships, friendship_utilization.used_friendships)
             .time_to_setup(time_to_setup)
             .time_to_process_social_graph(time_to_process_social_network)
             .time_to_load_retweets(time_to_load_retweets)
             .time_to_process_retweets(time_to_process_retweets)
+            .retweet_processing_times(retweet_processing_times)
+            .batch_latencies(batch_latencies)
             .total_time(stopwatch.total_time());
 
+        debug_assert!(statistics.is_consistent(), "Inconsistent statistics: {}", statistics);
+
         // Log the statistics.
         info!("Statistics: {}", statistics);
 
         Ok(statistics)
     })?;
+    let mut statistics: Statistics = result.simplify()?;
+
+    // Errors of the write operator are only all known once the writing worker has finished.
+    if let Some(message) = output_error.lock().expect("The output error has been poisoned").take() {
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::Other, message)));
+    }
+
+    // The influence edges are only all written once the writing worker has finished. The repetitions do not count them
+    // again.
+    statistics = statistics.number_of_influences(emitted_edges.load(Ordering::SeqCst) as u64)
+        .number_of_skipped_influences(skipped_edges.load(Ordering::SeqCst) as u64);
+
+    // The result file is only hashed completely once the writing worker has finished.
+    if let Some(hash) = output_hash.lock().expect("The output hash has been poisoned").take() {
+        statistics = statistics.output_hash(hash.sha256, hash.bytes);
+    }
+
+    // Cascades that have not timed out are only complete once all workers have finished, thus their sizes can only be
+    // evaluated afterwards.
+    if !estimate_cascade_sizes {
+        return Ok(statistics);
+    }
+    let sketch = cascade_sizes.lock().expect("The cascade size sketch has been poisoned");
+    info!("Estimated the cascade sizes from {number} completed cascades", number = sketch.len());
+    Ok(statistics.cascade_size_quantiles(sketch.quantile(0.5).unwrap_or(0), sketch.quantile(0.9).unwrap_or(0),
+                                         sketch.quantile(0.99).unwrap_or(0)))
+}
+
+/// Execute the reconstruction as `run` does, but instead of writing the influence edges to the configured output
+/// target, collect all of them in memory. The edges are returned in the order in which the first worker has written
+/// them, together with the statistics, which are the same as if the output target had been `OutputTarget::None`.
+///
+/// Since all edges are kept in memory, this is intended for tests and small data sets. Only available if the
+/// computation runs in a single process, otherwise an error is returned. No edges are returned for dry runs.
+pub fn run_collect(mut configuration: Configuration) -> Result<(Statistics, Vec<InfluenceEdge<UserId>>)> {
+    if configuration.number_of_processes > 1 {
+        let message: String = format!("Cannot collect the influence edges from {processes} processes",
+                                      processes = configuration.number_of_processes);
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidInput, message)));
+    }
+
+    let (sender, receiver) = channel();
+    configuration.output_target = OutputTarget::Channel(ChannelOutput::new(sender));
+
+    // The statistics do not keep the sender (see `Statistics::new`), thus, all senders have been dropped once the
+    // computation has finished, and the channel is closed after the last edge.
+    let statistics: Statistics = run(configuration)?;
+    let edges: Vec<InfluenceEdge<UserId>> = receiver.iter().collect();
+    Ok((statistics, edges))
+}
+
+/// Create the dataflow graph for the reconstruction within the `computation`, updating the `counters`. Return the
+/// handles for the social graph and Retweet inputs and for the progress.
+fn build_dataflow(computation: &mut Root<Generic>, configuration: &Configuration, counters: Counters)
+    -> (GraphHandle, RetweetHandle, ProbeHandle) {
+    let owners: GraphOwners = GraphOwners::new(configuration.graph_owner_workers.clone());
+    computation.dataflow::<u64, _, _>(move |scope| {
+        match configuration.algorithm {
+            Algorithm::GALE => {
+                gale::computation(scope, configuration, owners, counters)
+            },
+            Algorithm::LEAF => {
+                leaf::computation(scope, configuration, owners, counters)
+            },
+            Algorithm::PACE => {
+                pace::computation(scope, configuration, counters)
+            },
+            Algorithm::FullActivation => {
+                full_activation::computation(scope, configuration, owners, counters)
+            }
+        }
+    })
+}
+
+/// Wait for the `computation` to finish the current batch of data (see `Sync::sync`). If the `configuration` sets a
+/// `sync_timeout`, fail if the batch has not been processed within this many seconds instead of waiting any longer.
+fn sync_batch<D1: Data, D2: Data>(computation: &mut Root<Generic>, configuration: &Configuration, probe: &ProbeHandle,
+                                  input1: &mut InputHandle<u64, D1>, input2: &mut InputHandle<u64, D2>) -> Result<()> {
+    match configuration.sync_timeout {
+        Some(seconds) => computation.sync_with_timeout(probe, input1, input2, Duration::from_secs(seconds)),
+        None => {
+            computation.sync(probe, input1, input2);
+            Ok(())
+        }
+    }
+}
+
+/// Pass the `retweets` into the `computation` in batches, syncing after each batch. The `total_number_of_retweets`
+/// is only used in log messages, if known. Once a batch has been processed, `on_batch` is called with the number of
+/// Retweets processed so far. Return the number of Retweets passed into the computation, and whether the processing
+/// has been cancelled before all Retweets were processed. Errors on the first Retweet that could not be read.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+fn process_retweets<I>(computation: &mut Root<Generic>, configuration: &Configuration, retweets: I,
+                       total_number_of_retweets: Option<u64>, graph_input: &mut GraphHandle,
+                       retweet_input: &mut RetweetHandle, probe: &ProbeHandle, on_batch: &mut FnMut(u64))
+                       -> Result<(u64, bool)>
+    where I: Iterator<Item = Result<Retweet>> {
+    let total: String = match total_number_of_retweets {
+        Some(total) => total.to_string(),
+        None => String::from("?")
+    };
+    let batch_size: usize = configuration.batch_size;
+    let mut number_of_retweets: u64 = 0;
+    let mut processed_in_batch: usize = 0;
+    for retweet in retweets {
+        retweet_input.send(retweet?);
+        number_of_retweets += 1;
+        processed_in_batch += 1;
+
+        // Sync the computation after each batch.
+        if processed_in_batch == batch_size {
+            processed_in_batch = 0;
+            trace!("Processed {amount} of {total} Retweets...", amount = number_of_retweets, total = total);
+            sync_batch(computation, configuration, probe, retweet_input, graph_input)?;
+            on_batch(number_of_retweets);
+
+            // Stop processing further Retweets if cancellation has been requested.
+            if let Some(ref token) = configuration.cancellation_token {
+                if token.is_cancelled() {
+                    warn!("Cancelled after {amount} of {total} Retweets", amount = number_of_retweets, total = total);
+                    sync_batch(computation, configuration, probe, retweet_input, graph_input)?;
+                    return Ok((number_of_retweets, true));
+                }
+            }
+        }
+    }
+
+    // The last batch might not be complete. Syncing an empty batch does not hurt, but ensures all Retweets have been
+    // processed in any case.
+    sync_batch(computation, configuration, probe, retweet_input, graph_input)?;
+    if processed_in_batch > 0 {
+        on_batch(number_of_retweets);
+    }
+
+    Ok((number_of_retweets, false))
+}
+
+/// Determine if the `retweet` belongs to a cascade of the `seed_authors`, i.e. if it will be reconstructed. Without
+/// seed authors, all Retweets will be reconstructed.
+fn is_seeded(retweet: &Retweet, seed_authors: &Option<HashSet<UserId>>) -> bool {
+    match *seed_authors {
+        Some(ref seed_authors) => seed_authors.contains(&retweet.retweeted_status.user.id),
+        None => true
+    }
+}
 
-    result.simplify()
+/// Get the number of nanoseconds elapsed since `start`.
+fn elapsed_nanoseconds(start: Instant) -> u64 {
+    let elapsed = start.elapsed();
+    elapsed.as_secs() * 1_000_000_000 + u64::from(elapsed.subsec_nanos())
 }
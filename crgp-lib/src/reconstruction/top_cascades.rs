@@ -0,0 +1,240 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Find the cascades with the most Retweets.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+use UserId;
+use timely_extensions::operators::CascadeTracker;
+use twitter::Retweet;
+
+/// A Retweet cascade and its number of Retweets.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TopCascade {
+    /// The ID of the cascade, i.e. of its original Tweet.
+    pub cascade_id: u64,
+
+    /// The user who posted the original Tweet.
+    pub root_user: UserId,
+
+    /// The number of Retweets in the cascade.
+    pub retweets: u64,
+}
+
+impl TopCascade {
+    /// The rank of the cascade: larger cascades have a higher rank. Of cascades with the same number of Retweets, the
+    /// one with the smaller ID has the higher rank.
+    fn rank(&self) -> (u64, u64) {
+        (self.retweets, u64::max_value() - self.cascade_id)
+    }
+}
+
+/// A cascade within the heap of the largest cascades, ordered such that the cascade with the lowest rank is on top.
+#[derive(Debug, Eq, PartialEq)]
+struct LowestRankFirst(TopCascade);
+
+impl Ord for LowestRankFirst {
+    fn cmp(&self, other: &LowestRankFirst) -> Ordering {
+        other.0.rank().cmp(&self.0.rank())
+    }
+}
+
+impl PartialOrd for LowestRankFirst {
+    fn partial_cmp(&self, other: &LowestRankFirst) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Find the `n` cascades with the most Retweets among the `retweets`, largest first. Cascades of the same size are
+/// ordered by their ID. A cascade is complete once it has not been retweeted for `timeout` seconds, if given; a later
+/// Retweet of it starts it anew, like in the computation.
+///
+/// Only the Retweets of incomplete cascades are counted, and only the `n` largest completed cascades are kept (see
+/// `CascadeCounter`), thus the cascades are never sorted as a whole.
+pub fn top_cascades(retweets: &[Retweet], n: usize, timeout: Option<u64>) -> Vec<TopCascade> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut counter = CascadeCounter::new(n, timeout);
+    for retweet in retweets {
+        counter.add(retweet);
+    }
+    counter.top()
+}
+
+/// Count the Retweets per cascade one by one, e.g. while they are read, to find the largest cascades afterwards.
+///
+/// The Retweets of a cascade are counted until the cascade is complete, i.e. until it has not been retweeted for the
+/// timeout, assuming the Retweets are ordered by their creation time. Its final count is then offered to a heap of the
+/// `n` largest cascades. Thus, the memory is bounded by `n` and the number of incomplete cascades: these are the
+/// cascades retweeted within (up to twice) the timeout before the latest Retweet, since completed cascades are only
+/// removed once per timeout. Without a timeout, no cascade is complete before the end, and all cascades are counted at
+/// the same time.
+#[derive(Debug)]
+pub struct CascadeCounter {
+    /// The number of cascades to find.
+    n: usize,
+
+    /// The number of seconds without a Retweet after which a cascade is complete.
+    timeout: Option<u64>,
+
+    /// The number of Retweets in each incomplete cascade.
+    live_cascades: CascadeTracker,
+
+    /// For each incomplete cascade, given by its ID, the poster of its original Tweet.
+    root_users: HashMap<u64, UserId>,
+
+    /// The creation time of the Retweet after which the complete cascades have last been removed.
+    last_completion: u64,
+
+    /// The `n` largest completed cascades.
+    largest: BinaryHeap<LowestRankFirst>,
+}
+
+impl CascadeCounter {
+    /// Create a counter without any Retweets to find the `n` largest cascades, completing cascades after `timeout`
+    /// seconds without a Retweet, if given.
+    pub fn new(n: usize, timeout: Option<u64>) -> CascadeCounter {
+        CascadeCounter {
+            n: n,
+            timeout: timeout,
+            live_cascades: CascadeTracker::new(timeout),
+            root_users: HashMap::new(),
+            last_completion: 0,
+            largest: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    /// Count the `retweet` in its cascade. Once per timeout, the complete cascades are offered to the largest ones.
+    pub fn add(&mut self, retweet: &Retweet) {
+        let cascade_id: u64 = retweet.retweeted_status.id;
+        let _ = self.live_cascades.add_retweet(cascade_id, retweet.created_at);
+        let _ = self.root_users.entry(cascade_id)
+            .or_insert(retweet.retweeted_status.user.id);
+
+        if let Some(timeout) = self.timeout {
+            if retweet.created_at.saturating_sub(self.last_completion) > timeout {
+                self.last_completion = retweet.created_at;
+                let completed: Vec<(u64, u64, u64)> = self.live_cascades.complete_timed_out();
+                self.offer_all(completed);
+            }
+        }
+    }
+
+    /// Find the `n` cascades with the most Retweets counted so far, like `top_cascades` does.
+    pub fn top(mut self) -> Vec<TopCascade> {
+        let completed: Vec<(u64, u64, u64)> = self.live_cascades.complete_all();
+        self.offer_all(completed);
+
+        // Popping the heap yields the cascades with the lowest rank first.
+        let mut top: Vec<TopCascade> = Vec::with_capacity(self.largest.len());
+        while let Some(LowestRankFirst(cascade)) = self.largest.pop() {
+            top.push(cascade);
+        }
+        top.reverse();
+        top
+    }
+
+    /// Offer the `completed` cascades, given as their IDs, numbers of influence edges, and numbers of Retweets, to the
+    /// largest cascades, and forget their root users.
+    fn offer_all(&mut self, completed: Vec<(u64, u64, u64)>) {
+        for (cascade_id, _edges, number_of_retweets) in completed {
+            let root_user: UserId = match self.root_users.remove(&cascade_id) {
+                Some(root_user) => root_user,
+                None => continue
+            };
+            if self.n == 0 {
+                continue;
+            }
+
+            self.largest.push(LowestRankFirst(TopCascade {
+                cascade_id: cascade_id,
+                root_user: root_user,
+                retweets: number_of_retweets,
+            }));
+            if self.largest.len() > self.n {
+                let _ = self.largest.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitter::Retweet;
+    use twitter::Tweet;
+    use super::*;
+
+    /// Create `size` Retweets of the Tweet `cascade_id` posted by the `root_user`.
+    fn cascade(cascade_id: u64, root_user: i64, size: u64) -> Vec<Retweet> {
+        let original = Tweet::new(cascade_id, root_user, 0);
+        (0..size)
+            .map(|index| Retweet::new(cascade_id * 100 + index, original.clone(), index as i64, index + 1))
+            .collect()
+    }
+
+    #[test]
+    fn top_cascades() {
+        let mut retweets: Vec<Retweet> = Vec::new();
+        retweets.extend(cascade(1, 10, 2));
+        retweets.extend(cascade(2, 20, 5));
+        retweets.extend(cascade(3, 30, 1));
+        retweets.extend(cascade(4, 40, 4));
+        retweets.extend(cascade(5, 50, 3));
+
+        let top: Vec<TopCascade> = super::top_cascades(&retweets, 3, None);
+        assert_eq!(top, vec![
+            TopCascade { cascade_id: 2, root_user: UserId::from(20), retweets: 5 },
+            TopCascade { cascade_id: 4, root_user: UserId::from(40), retweets: 4 },
+            TopCascade { cascade_id: 5, root_user: UserId::from(50), retweets: 3 },
+        ]);
+
+        // Fewer cascades than requested.
+        assert_eq!(super::top_cascades(&retweets, 10, None).len(), 5);
+        assert!(super::top_cascades(&retweets, 0, None).is_empty());
+        assert!(super::top_cascades(&[], 3, None).is_empty());
+    }
+
+    #[test]
+    fn top_cascades_timeout() {
+        // The cascades follow each other in time.
+        let mut retweets: Vec<Retweet> = Vec::new();
+        for (cascade_id, size) in vec![(1, 2), (2, 5), (3, 1), (4, 4), (5, 3)] {
+            let original = Tweet::new(cascade_id, cascade_id as i64 * 10, cascade_id * 100);
+            retweets.extend((0..size).map(|index| {
+                Retweet::new(cascade_id * 100 + index, original.clone(), index as i64, cascade_id * 100 + index)
+            }));
+        }
+
+        // Completed cascades are removed while counting, without changing the result.
+        let mut counter = CascadeCounter::new(3, Some(10));
+        for retweet in &retweets {
+            counter.add(retweet);
+        }
+        assert!(counter.live_cascades.len() < 5);
+        assert!(counter.root_users.len() < 5);
+        assert!(counter.largest.len() <= 3);
+        assert_eq!(counter.top(), super::top_cascades(&retweets, 3, None));
+    }
+
+    #[test]
+    fn top_cascades_ties() {
+        let mut retweets: Vec<Retweet> = Vec::new();
+        retweets.extend(cascade(7, 70, 2));
+        retweets.extend(cascade(3, 30, 2));
+        retweets.extend(cascade(5, 50, 2));
+
+        let top: Vec<TopCascade> = super::top_cascades(&retweets, 2, None);
+        assert_eq!(top, vec![
+            TopCascade { cascade_id: 3, root_user: UserId::from(30), retweets: 2 },
+            TopCascade { cascade_id: 5, root_user: UserId::from(50), retweets: 2 },
+        ]);
+    }
+}
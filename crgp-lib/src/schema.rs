@@ -0,0 +1,65 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Descriptions and examples of the supported input formats.
+//!
+//! The examples are accepted as-is by the parsers of the social graph and the Retweets.
+
+/// An example path of a friend file within a TAR archive, relative to the root of the archive.
+pub const FRIEND_FILE_PATH_EXAMPLE: &'static str = "000/000/friends1.csv";
+
+/// An example of a friend file's contents: user `1` (screen name `U1`) claims to have `3` friends, two of which
+/// (users `2` and `3`) are given.
+pub const FRIEND_FILE_EXAMPLE: &'static str = "1;U1;0;3;\n2\n3\n";
+
+/// An example of a single line in the Retweet data set: user `2` retweets Tweet `1` posted by user `0`.
+pub const RETWEET_EXAMPLE: &'static str = "{\"created_at\":1,\"id\":3,\
+                                            \"retweeted_status\":{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}},\
+                                            \"user\":{\"id\":2}}";
+
+/// Get a description of all supported input formats, including an example of each.
+pub fn describe() -> String {
+    format!("SOCIAL GRAPH\n\
+             \n\
+             The social graph is a directory of the form '[DIRECTORY]/[ddd]/[dd].tar', where 'd' is a digit. Each TAR \
+             archive contains friend files at paths of the form '[ddd]/[ddd]/friends[ID].csv', where '[ID]' is the ID \
+             of the user whose friends are listed in the file. Alternatively, the social graph is a single ZIP \
+             archive (with the extension '.zip') containing the friend files at the same paths, or a file (with the \
+             extension '.abom') dumped by 'crgp_lib::dump_abomonated_graph' with the same version of CRGP.\n\
+             \n\
+             The first line of a friend file may contain meta data: ';'-separated fields, the fourth of which (index \
+             3) is the number of friends the user claims to have. All other lines contain the ID of exactly one \
+             friend. Friend files are encoded in UTF-8; lines that are not valid UTF-8 or cannot be parsed are \
+             skipped.\n\
+             \n\
+             Example path: {path}\n\
+             Example contents:\n\
+             {friends}\n\
+             RETWEETS\n\
+             \n\
+             The Retweet data set contains one JSON object per line, or a single JSON array of such objects. The \
+             fields 'created_at' (UTC timestamp) and 'id' are unsigned integers, 'user' is an object with the integer \
+             field 'id', and 'retweeted_status' is the original Tweet with the fields 'created_at', 'id', and 'user'. \
+             Instead of an integer, 'created_at' may also be an ISO-8601 string, e.g. '2018-10-10T20:19:24Z'. \
+             Additional fields are ignored; lines (or array elements) that cannot be parsed are skipped.\n\
+             \n\
+             Example line:\n\
+             {retweet}",
+            path = FRIEND_FILE_PATH_EXAMPLE, friends = FRIEND_FILE_EXAMPLE, retweet = RETWEET_EXAMPLE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe() {
+        let description: String = super::describe();
+        assert!(description.contains(FRIEND_FILE_PATH_EXAMPLE));
+        assert!(description.contains(FRIEND_FILE_EXAMPLE));
+        assert!(description.contains(RETWEET_EXAMPLE));
+    }
+}
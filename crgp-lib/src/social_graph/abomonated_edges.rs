@@ -0,0 +1,215 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Write influence edges to and read them from files containing a stream of abomonated `InfluenceEdge`s.
+//!
+//! The file is written if the result is written to a directory in `OutputFormat::Abomonation`. It starts with the
+//! eight bytes `CRGPEDGE`, followed by the influence edges, each as encoded by `abomonation::encode`. Since the edges
+//! are appended one by one, the file can be written without buffering the result, and read back by
+//! [`read_edges`](fn.read_edges.html) without parsing any text.
+//!
+//! # Safety
+//!
+//! Abomonation stores the in-memory representation of the edges. The size of each edge is checked against the size of
+//! the file, but the layout is not. Thus, the file must have been written by the same version of this crate, compiled
+//! for the same target architecture (i.e. with the same size and endianness of `UserId` and `u64`). Files from other
+//! versions must be written again. The file must not be modified while it is being read.
+//!
+//! The magic bytes at the start of the file only protect against accidentally reading an entirely different file.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use abomonation::decode;
+use abomonation::encode;
+use memmap::MmapMut;
+use memmap::MmapOptions;
+
+use Error;
+use Result;
+use social_graph::InfluenceEdge;
+use twitter::User;
+
+/// The name of the file within the output directory to which abomonated influence edges are written.
+pub const ABOMONATED_EDGES_FILENAME: &'static str = "cascs.abom";
+
+/// The magic bytes at the start of each file containing abomonated influence edges. Their length is a multiple of
+/// eight bytes so that the encoded edges following them are aligned within the (page-aligned) mapping.
+const MAGIC_BYTES: &'static [u8] = b"CRGPEDGE";
+
+/// Start a file of abomonated influence edges on the newly created `file`, to which the edges will be written (see
+/// `write_abomonated_edge`), by writing its magic bytes. The file can be any writer, e.g. one hashing the written
+/// bytes. Errors on any I/O error.
+pub fn create_abomonated_edges<W: Write>(file: W) -> Result<BufWriter<W>> {
+    let mut writer: BufWriter<W> = BufWriter::new(file);
+    writer.write_all(MAGIC_BYTES)?;
+    Ok(writer)
+}
+
+/// Append the abomonated `influence` edge to the `writer`. Errors on any I/O error.
+pub fn write_abomonated_edge<W: Write>(writer: &mut W, influence: &InfluenceEdge<User>) -> Result<()> {
+    let mut bytes: Vec<u8> = Vec::new();
+
+    // Encoding only copies the edge's memory into the vector, which is always safe.
+    unsafe {
+        encode(influence, &mut bytes);
+    }
+
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read the influence edges from the file at the given local `path`, which has been written in
+/// `OutputFormat::Abomonation`. Errors if the file cannot be mapped or does not contain abomonated influence edges.
+///
+/// The edges are decoded lazily by the returned iterator. If the file ends within an edge, e.g. because the
+/// computation writing it has been aborted, the iteration stops before that edge with an error log message.
+///
+/// See the [module documentation](index.html) for the conditions under which the file can be read.
+pub fn read_edges(path: &Path) -> Result<AbomonatedEdges> {
+    // Map the file copy-on-write, since decoding corrects the pointers within the mapped memory. The file itself
+    // remains unchanged. Mapping the file is safe as long as it is not modified concurrently.
+    let file: File = File::open(path)?;
+    let mapping: MmapMut = unsafe { MmapOptions::new().map_copy(&file)? };
+
+    if mapping.len() < MAGIC_BYTES.len() || &mapping[..MAGIC_BYTES.len()] != MAGIC_BYTES {
+        let message: String = format!("{file} does not contain abomonated influence edges", file = path.display());
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+    }
+
+    Ok(AbomonatedEdges {
+        path: path.to_path_buf(),
+        mapping: mapping,
+        offset: MAGIC_BYTES.len(),
+    })
+}
+
+/// An iterator over the influence edges within a file of abomonated influence edges, created by
+/// [`read_edges`](fn.read_edges.html).
+#[derive(Debug)]
+pub struct AbomonatedEdges {
+    /// The path of the file, for error messages.
+    path: PathBuf,
+
+    /// The memory-mapped file.
+    mapping: MmapMut,
+
+    /// The position of the next edge within the mapped file.
+    offset: usize,
+}
+
+impl Iterator for AbomonatedEdges {
+    type Item = InfluenceEdge<User>;
+
+    fn next(&mut self) -> Option<InfluenceEdge<User>> {
+        if self.offset >= self.mapping.len() {
+            return None;
+        }
+
+        // Decoding is safe if the file has been written by this version of the crate (see the module documentation).
+        // The edges are copied out of the mapping, since the iterator cannot lend them.
+        let decoded: Option<(InfluenceEdge<User>, usize)> = {
+            let bytes: &mut [u8] = &mut self.mapping[self.offset..];
+            let length: usize = bytes.len();
+            match unsafe { decode::<InfluenceEdge<User>>(bytes) } {
+                Some((influence, remaining)) => Some((influence.clone(), length - remaining.len())),
+                None => None
+            }
+        };
+
+        match decoded {
+            Some((influence, size)) => {
+                self.offset += size;
+                Some(influence)
+            },
+            None => {
+                error!("{file} ends within an abomonated influence edge, skipping the remaining {bytes} bytes",
+                       file = self.path.display(), bytes = self.mapping.len() - self.offset);
+                self.offset = self.mapping.len();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::fs::OpenOptions;
+    use std::fs::remove_file;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use social_graph::InfluenceEdge;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn write_and_read_edges() {
+        let path: PathBuf = env::temp_dir().join("crgp_write_and_read_edges.abom");
+        let edges: Vec<InfluenceEdge<User>> = vec![
+            InfluenceEdge::new(User::new(0), User::new(2), 1, 3, 1, User::new(0)),
+            InfluenceEdge::new(User::new(2), User::new(1), 2, 4, 1, User::new(0)).influencer_activation(Some(1)),
+            InfluenceEdge::new(User::new(-1), User::new(1 << 40), 5, 8, 2, User::new(1)),
+        ];
+        {
+            let file = File::create(&path).expect("Could not create the file");
+            let mut writer = create_abomonated_edges(file).expect("Could not create the edge file");
+            for edge in &edges {
+                write_abomonated_edge(&mut writer, edge).expect("Could not write the edge");
+            }
+            writer.flush().expect("Could not flush the edge file");
+        }
+
+        let read: Vec<InfluenceEdge<User>> = read_edges(&path).expect("Could not read the edge file").collect();
+        assert_eq!(read, edges);
+        assert_eq!(read[1].influencer_activation, Some(1));
+
+        // Without any edges, only the magic bytes are written.
+        {
+            let file = File::create(&path).expect("Could not create the file");
+            let _ = create_abomonated_edges(file).expect("Could not create the edge file");
+        }
+        assert_eq!(read_edges(&path).expect("Could not read the edge file").count(), 0);
+
+        let _ = remove_file(&path);
+    }
+
+    #[test]
+    fn read_edges_invalid() {
+        // A file without the magic bytes.
+        let path: PathBuf = env::temp_dir().join("crgp_read_edges_invalid.abom");
+        {
+            let mut file = File::create(&path).expect("Could not create the file");
+            file.write_all(b"1;3;2;0;1;-1\n").expect("Could not write the file");
+        }
+        assert!(read_edges(&path).is_err());
+
+        // A truncated edge is skipped.
+        {
+            let file = File::create(&path).expect("Could not create the file");
+            let mut writer = create_abomonated_edges(file).expect("Could not create the edge file");
+            let edge = InfluenceEdge::new(User::new(0), User::new(2), 1, 3, 1, User::new(0));
+            write_abomonated_edge(&mut writer, &edge).expect("Could not write the edge");
+            write_abomonated_edge(&mut writer, &edge).expect("Could not write the edge");
+            writer.flush().expect("Could not flush the edge file");
+        }
+        {
+            let file = OpenOptions::new().write(true).open(&path).expect("Could not open the file");
+            let length: u64 = file.metadata().expect("Could not get the file's length").len();
+            file.set_len(length - 8).expect("Could not truncate the file");
+        }
+        assert_eq!(read_edges(&path).expect("Could not read the edge file").count(), 1);
+
+        let _ = remove_file(&path);
+    }
+}
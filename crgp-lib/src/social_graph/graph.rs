@@ -7,6 +7,8 @@
 //! A social graph structure with methods similar to Rust's container methods.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::Drain;
 use std::collections::hash_map::Entry;
 
 use twitter::User;
@@ -43,6 +45,57 @@ impl SocialGraph {
     pub fn get(&self, key: &User) -> Option<&Vec<User>> {
         self.graph.get(key)
     }
+
+    /// Clear the social graph, returning all users and their friends as an iterator.
+    pub fn drain(&mut self) -> Drain<User, Vec<User>> {
+        self.graph.drain()
+    }
+
+    /// Expand each user's friend list with all users reachable within `hops` additional hops in the social graph,
+    /// e.g. with `hops = 1`, the friends of each friend are added. A user never becomes their own friend. Expansion for
+    /// a user stops once their friend list contains `max_friends` friends. Return the number of added friendships.
+    ///
+    /// The expansion is computed on the social graph as it was before calling this method. Note that the number of
+    /// friendships can grow exponentially with the number of hops.
+    pub fn expand_friends(&mut self, hops: u8, max_friends: usize) -> u64 {
+        let mut expanded_graph: HashMap<User, Vec<User>> = HashMap::with_capacity(self.graph.len());
+        let mut added_friendships: u64 = 0;
+
+        for (user, friends) in &self.graph {
+            let mut reached: HashSet<User> = friends.iter().cloned().collect();
+            let _ = reached.insert(*user);
+            let mut expanded_friends: Vec<User> = friends.clone();
+            let mut frontier: Vec<User> = friends.clone();
+
+            'hops: for _ in 0..hops {
+                let mut next_frontier: Vec<User> = Vec::new();
+                for friend in &frontier {
+                    let friends_of_friend: &Vec<User> = match self.graph.get(friend) {
+                        Some(friends_of_friend) => friends_of_friend,
+                        None => continue
+                    };
+
+                    for friend_of_friend in friends_of_friend {
+                        if expanded_friends.len() >= max_friends {
+                            break 'hops;
+                        }
+
+                        if reached.insert(*friend_of_friend) {
+                            expanded_friends.push(*friend_of_friend);
+                            next_frontier.push(*friend_of_friend);
+                            added_friendships += 1;
+                        }
+                    }
+                }
+                frontier = next_frontier;
+            }
+
+            let _ = expanded_graph.insert(*user, expanded_friends);
+        }
+
+        self.graph = expanded_graph;
+        added_friendships
+    }
 }
 
 #[cfg(test)]
@@ -92,6 +145,60 @@ mod tests {
         assert_eq!(sg.graph.get(&user), Some(&friends));
     }
 
+    #[test]
+    fn drain() {
+        let mut sg = SocialGraph::new();
+        let _ = sg.graph.insert(User::new(1), vec![User::new(2)]);
+
+        let drained: Vec<(User, Vec<User>)> = sg.drain().collect();
+        assert_eq!(drained, vec![(User::new(1), vec![User::new(2)])]);
+        assert_eq!(sg.graph.len(), 0);
+    }
+
+    #[test]
+    fn expand_friends() {
+        // The social graph of the test data set.
+        let mut sg = SocialGraph::new();
+        let _ = sg.graph.insert(User::new(0), vec![User::new(1), User::new(2)]);
+        let _ = sg.graph.insert(User::new(1), vec![User::new(0), User::new(2), User::new(3)]);
+        let _ = sg.graph.insert(User::new(2), vec![User::new(0)]);
+        let _ = sg.graph.insert(User::new(3), vec![User::new(2)]);
+        let _ = sg.graph.insert(User::new(4), vec![User::new(2)]);
+
+        // No expansion.
+        let mut unexpanded = sg.clone();
+        assert_eq!(unexpanded.expand_friends(0, 100), 0);
+        assert_eq!(unexpanded.graph, sg.graph);
+
+        // One hop.
+        let mut expanded = sg.clone();
+        assert_eq!(expanded.expand_friends(1, 100), 4);
+        let mut friends: Vec<User> = expanded.get(&User::new(0)).unwrap().clone();
+        friends.sort();
+        assert_eq!(friends, vec![User::new(1), User::new(2), User::new(3)]);
+        let mut friends: Vec<User> = expanded.get(&User::new(1)).unwrap().clone();
+        friends.sort();
+        assert_eq!(friends, vec![User::new(0), User::new(2), User::new(3)]);
+        let mut friends: Vec<User> = expanded.get(&User::new(2)).unwrap().clone();
+        friends.sort();
+        assert_eq!(friends, vec![User::new(0), User::new(1)]);
+        let mut friends: Vec<User> = expanded.get(&User::new(3)).unwrap().clone();
+        friends.sort();
+        assert_eq!(friends, vec![User::new(0), User::new(2)]);
+        let mut friends: Vec<User> = expanded.get(&User::new(4)).unwrap().clone();
+        friends.sort();
+        assert_eq!(friends, vec![User::new(0), User::new(2)]);
+
+        // Capped expansion.
+        let mut capped = sg.clone();
+        assert_eq!(capped.expand_friends(1, 2), 3);
+        assert_eq!(capped.get(&User::new(0)).unwrap().len(), 2);
+        assert_eq!(capped.get(&User::new(1)).unwrap().len(), 3);
+        assert_eq!(capped.get(&User::new(2)).unwrap().len(), 2);
+        assert_eq!(capped.get(&User::new(3)).unwrap().len(), 2);
+        assert_eq!(capped.get(&User::new(4)).unwrap().len(), 2);
+    }
+
     #[test]
     fn get() {
         let user = User::new(1);
@@ -16,7 +16,10 @@ use twitter::User;
 ///
 /// The influence flows from the `influencer` to the `influencee` and is valid only for the cascade given by
 /// `cascade_id`. The influence occurs at time `timestamp`.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+///
+/// When serialized, e.g. to JSON, all fields are included under their own names. The `influencer_activation` is
+/// omitted if it is not known.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
 pub struct InfluenceEdge<T>
     where T: Abomonation {
     /// The user influencing some other user.
@@ -40,6 +43,11 @@ pub struct InfluenceEdge<T>
 
     /// The user who posted the original tweet.
     pub original_user: T,
+
+    /// The time at which the influencer has been activated in the cascade by their own Retweet. `None` if the
+    /// influencer is the poster of the original Tweet, or if the activation is not known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub influencer_activation: Option<u64>,
 }
 
 impl<T> InfluenceEdge<T>
@@ -55,8 +63,21 @@ impl<T> InfluenceEdge<T>
             retweet_id: retweet_id,
             cascade_id: cascade_id,
             original_user: original_user,
+            influencer_activation: None,
         }
     }
+
+    /// Set the time at which the influencer has been activated in the cascade.
+    pub fn influencer_activation(mut self, activation: Option<u64>) -> InfluenceEdge<T> {
+        self.influencer_activation = activation;
+        self
+    }
+
+    /// Get the time between the activation of the influencer and the influence, i.e. `timestamp -
+    /// influencer_activation`, or `None` if the influencer's activation is not known.
+    pub fn time_delta(&self) -> Option<u64> {
+        self.influencer_activation.map(|activation| self.timestamp.saturating_sub(activation))
+    }
 }
 
 impl<T: Abomonation + fmt::Display> fmt::Display for InfluenceEdge<T> {
@@ -71,6 +92,8 @@ unsafe_abomonate!(InfluenceEdge<User> : influencer, influencee, timestamp, casca
 
 #[cfg(test)]
 mod tests {
+    use serde_json;
+    use twitter::User;
     use super::*;
 
     #[test]
@@ -82,6 +105,17 @@ mod tests {
         assert_eq!(edge.retweet_id, 456);
         assert_eq!(edge.cascade_id, 789);
         assert_eq!(edge.original_user, 0.42);
+        assert_eq!(edge.influencer_activation, None);
+    }
+
+    #[test]
+    fn time_delta() {
+        let edge: InfluenceEdge<User> = InfluenceEdge::new(User::new(1), User::new(2), 123, 456, 789, User::new(0));
+        assert_eq!(edge.time_delta(), None);
+
+        let edge: InfluenceEdge<User> = edge.influencer_activation(Some(100));
+        assert_eq!(edge.influencer_activation, Some(100));
+        assert_eq!(edge.time_delta(), Some(edge.timestamp - 100));
     }
 
     #[test]
@@ -89,4 +123,17 @@ mod tests {
         let edge: InfluenceEdge<f64> = InfluenceEdge::new(42.0, 13.37, 123, 456, 789, 0.42);
         assert_eq!(format!("{}", edge), String::from("789;456;13.37;42;123;-1"));
     }
+
+    #[test]
+    fn serialize_json() {
+        let edge: InfluenceEdge<User> = InfluenceEdge::new(User::new(1), User::new(2), 123, 456, 789, User::new(0));
+        let json: String = serde_json::to_string(&edge).expect("Failed to serialize the edge");
+        assert_eq!(json, String::from("{\"influencer\":{\"id\":1},\"influencee\":{\"id\":2},\"timestamp\":123,\
+                                       \"retweet_id\":456,\"cascade_id\":789,\"original_user\":{\"id\":0}}"));
+
+        // The activation of the influencer is included if known.
+        let edge: InfluenceEdge<User> = edge.influencer_activation(Some(100));
+        let json: String = serde_json::to_string(&edge).expect("Failed to serialize the edge");
+        assert!(json.ends_with(",\"original_user\":{\"id\":0},\"influencer_activation\":100}"));
+    }
 }
@@ -8,9 +8,17 @@
 //!
 //! A social graph is a collection of directed edges.
 
+pub use self::abomonated_edges::ABOMONATED_EDGES_FILENAME;
+pub use self::abomonated_edges::AbomonatedEdges;
+pub use self::abomonated_edges::create_abomonated_edges;
+pub use self::abomonated_edges::read_edges;
+pub use self::abomonated_edges::write_abomonated_edge;
 pub use self::graph::SocialGraph;
 pub use self::influence_edge::InfluenceEdge;
+pub use self::owners::GraphOwners;
 
+mod abomonated_edges;
 mod graph;
 mod influence_edge;
+mod owners;
 pub mod source;
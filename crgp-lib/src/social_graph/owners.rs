@@ -0,0 +1,65 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Assignment of users to the workers storing their friendships.
+
+use twitter::User;
+
+/// The workers storing the friendships of the social graph.
+///
+/// Each user is assigned to exactly one owner, determined by the user's ID. If no owners are designated, the
+/// friendships are distributed across all workers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphOwners {
+    /// The indices of the designated workers. If `None`, all workers are owners.
+    workers: Option<Vec<usize>>,
+}
+
+impl GraphOwners {
+    /// Distribute the friendships across the given `workers`, or across all workers if `None`. The list of `workers`
+    /// must not be empty, and all indices must be valid worker indices.
+    pub fn new(workers: Option<Vec<usize>>) -> GraphOwners {
+        GraphOwners {
+            workers: workers,
+        }
+    }
+
+    /// Get the routing key for exchanging data to the worker owning the friendships of the given `user`.
+    ///
+    /// If owners are designated, the key is the index of the owning worker, i.e. it is always routed to that worker
+    /// by `timely`'s exchange pacts.
+    pub fn route(&self, user: &User) -> u64 {
+        match self.workers {
+            Some(ref workers) => {
+                let index: usize = (i64::from(user.id) as u64 % workers.len() as u64) as usize;
+                workers[index] as u64
+            },
+            None => i64::from(user.id) as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn route_all_workers() {
+        let owners = GraphOwners::new(None);
+        assert_eq!(owners.route(&User::new(0)), 0);
+        assert_eq!(owners.route(&User::new(42)), 42);
+    }
+
+    #[test]
+    fn route_designated_workers() {
+        let owners = GraphOwners::new(Some(vec![3, 5]));
+        assert_eq!(owners.route(&User::new(0)), 3);
+        assert_eq!(owners.route(&User::new(1)), 5);
+        assert_eq!(owners.route(&User::new(42)), 3);
+        assert_eq!(owners.route(&User::new(43)), 5);
+    }
+}
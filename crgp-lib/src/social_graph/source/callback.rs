@@ -0,0 +1,138 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Load the social graph by querying the friend lists from a callback, e.g. for a graph generated on the fly.
+
+use std::collections::HashSet;
+
+use Result;
+use UserId;
+use configuration::FriendListCallback;
+use configuration::InputSource;
+use reconstruction::algorithms::GraphHandle;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar::get_selected_users;
+use twitter::User;
+
+/// Load the social graph from the given `callback` into the computation using the `graph_input`. If a
+/// `selected_users_file` or `selected_user_ids` are given, only the users selected by them are queried. The callback
+/// does not provide the users' claimed numbers of friends, thus, no dummy users can be created.
+pub fn load(callback: &FriendListCallback,
+            selected_users_file: Option<InputSource>,
+            selected_user_ids: &[UserId],
+            graph_input: &mut GraphHandle
+    ) -> Result<LoadStatistics>
+{
+    load_with(callback, selected_users_file, selected_user_ids,
+              &mut |user: User, friends: Vec<User>| graph_input.send((user, friends)))
+}
+
+/// Load the social graph from the given `callback` like `load`, but pass each user and their friends to `send` instead
+/// of directly into the computation. Users listed more than once are only queried the first time.
+pub fn load_with<F>(callback: &FriendListCallback,
+                    selected_users_file: Option<InputSource>,
+                    selected_user_ids: &[UserId],
+                    send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>)
+{
+    // Get a set of selected users to query. If `None`, all users of the callback will be queried.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
+
+    let mut queried_users: HashSet<UserId> = HashSet::new();
+    let mut statistics = LoadStatistics::default();
+    for &user in callback.users() {
+        if let Some(ref selected_users) = selected_users {
+            if !selected_users.contains(&user) {
+                continue;
+            }
+        }
+
+        if !queried_users.insert(user) {
+            statistics.duplicate_users += 1;
+            continue;
+        }
+
+        let friends: Vec<User> = match callback.friends(user) {
+            Some(friends) => friends.into_iter().map(User::from).collect(),
+            None => continue
+        };
+
+        statistics.users += 1;
+        statistics.given_friendships += friends.len() as u64;
+        statistics.expected_friendships += friends.len() as u64;
+        send(User::from(user), friends);
+    }
+
+    if statistics.duplicate_users > 0 {
+        warn!("The friend list callback lists {number} users more than once, only querying them once",
+              number = statistics.duplicate_users);
+    }
+
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use UserId;
+    use configuration::FriendListCallback;
+    use configuration::InputSource;
+    use social_graph::source::LoadStatistics;
+    use twitter::User;
+
+    /// A callback giving each user with an even ID all users with smaller IDs as friends.
+    fn generator(users: &[i64]) -> FriendListCallback {
+        let users: Vec<UserId> = users.iter().map(|&user| UserId::from(user)).collect();
+        FriendListCallback::new(users, |user: UserId| {
+            let user: i64 = i64::from(user);
+            if user % 2 == 0 {
+                Some((0..user).map(UserId::from).collect())
+            } else {
+                None
+            }
+        })
+    }
+
+    #[test]
+    fn load_with() {
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_with(&generator(&[0, 1, 2, 3, 4, 2]), None, &[],
+                                                          &mut |user: User, friends: Vec<User>| {
+                                                              let _ = graph.insert(user, friends);
+                                                          })
+            .expect("Could not load the social graph");
+
+        assert_eq!(statistics.users, 3);
+        assert_eq!(statistics.given_friendships, 6);
+        assert_eq!(statistics.expected_friendships, 6);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(statistics.duplicate_users, 1);
+
+        assert_eq!(graph.len(), 3);
+        assert!(graph[&User::new(0)].is_empty());
+        assert_eq!(graph[&User::new(2)], vec![User::new(0), User::new(1)]);
+        assert_eq!(graph[&User::new(4)], vec![User::new(0), User::new(1), User::new(2), User::new(3)]);
+    }
+
+    #[test]
+    fn load_with_selected_users() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let selected_users = InputSource::new(data_path.join("retweeting_users.txt"));
+
+        // The selection contains the users 0, 1, 2, and 3.
+        let mut users: Vec<User> = Vec::new();
+        let statistics: LoadStatistics = super::load_with(&generator(&[0, 1, 2, 3, 4]), Some(selected_users), &[],
+                                                          &mut |user: User, _friends: Vec<User>| users.push(user))
+            .expect("Could not load the social graph");
+
+        assert_eq!(statistics.users, 2);
+        assert_eq!(statistics.given_friendships, 2);
+        assert_eq!(users, vec![User::new(0), User::new(2)]);
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Load the social graph from a single file listing one friendship per line, e.g. `1\t2` if user `1` follows user
+//! `2`.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Lines;
+use std::path::PathBuf;
+
+use Result;
+use UserId;
+use configuration::EdgeListFormat;
+use configuration::InputSource;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar::get_selected_users;
+use twitter::User;
+
+/// The prefix of lines which are ignored.
+const COMMENT_PREFIX: &'static str = "#";
+
+/// Load the social graph from the edge list at the given local `path`, passing each user and their friends to `send`.
+/// The lines are parsed in the given `format`. The friendships of a user are only grouped if they are given on
+/// consecutive lines; if the file is not sorted by the users, later groups of a user are passed on as additional
+/// friend lists of that user.
+pub fn load<F>(path: &PathBuf,
+               format: EdgeListFormat,
+               selected_users_file: Option<InputSource>,
+               selected_user_ids: &[UserId],
+               send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
+
+    let mut statistics = LoadStatistics::default();
+    let mut edges: EdgeList<BufReader<File>> = EdgeList::new(BufReader::new(File::open(path)?), format);
+    for (user, friends) in edges.by_ref() {
+        if let Some(ref selected_users) = selected_users {
+            if !selected_users.contains(&user) {
+                continue;
+            }
+        }
+
+        let number_of_friends: u64 = friends.len() as u64;
+        let friends: Vec<User> = friends.into_iter()
+            .map(User::from)
+            .collect();
+        if send(User::from(user), friends)? {
+            statistics.users += 1;
+            statistics.given_friendships += number_of_friends;
+            statistics.expected_friendships += number_of_friends;
+        }
+    }
+
+    if edges.skipped_lines() > 0 {
+        warn!("Skipped {number} malformed lines in {file}", number = edges.skipped_lines(), file = path.display());
+    }
+
+    Ok(statistics)
+}
+
+/// An iterator over the friend lists in an edge list, parsing each line in the given format. Consecutive lines of the
+/// same user are grouped into a single friend list. Blank lines and comments (starting with `#`) are ignored.
+/// Malformed lines, i.e. lines that cannot be parsed or that are not valid UTF-8, are skipped with a warning including
+/// their line number, and counted. The iteration stops at the first other IO error.
+pub struct EdgeList<R: BufRead> {
+    /// The lines of the edge list.
+    lines: Lines<R>,
+
+    /// The format of each line.
+    format: EdgeListFormat,
+
+    /// The number of lines read so far, including ignored and malformed ones.
+    line_number: usize,
+
+    /// The first friendship of the next friend list, already read while completing the previous one.
+    next_friendship: Option<(UserId, UserId)>,
+
+    /// The number of malformed lines.
+    skipped_lines: u64,
+}
+
+impl<R: BufRead> EdgeList<R> {
+    /// Iterate over the friend lists in the edge list `reader`, whose lines are given in the `format`.
+    pub fn new(reader: R, format: EdgeListFormat) -> EdgeList<R> {
+        EdgeList {
+            lines: reader.lines(),
+            format: format,
+            line_number: 0,
+            next_friendship: None,
+            skipped_lines: 0,
+        }
+    }
+
+    /// Get the number of malformed lines skipped so far.
+    pub fn skipped_lines(&self) -> u64 {
+        self.skipped_lines
+    }
+
+    /// Read the next valid friendship, skipping all ignored and malformed lines.
+    fn read_friendship(&mut self) -> Option<(UserId, UserId)> {
+        for line in &mut self.lines {
+            self.line_number += 1;
+            let line: String = match line {
+                Ok(line) => line,
+                Err(ref message) if message.kind() == IOErrorKind::InvalidData => {
+                    warn!("Line {number}: invalid UTF-8, skipping the line", number = self.line_number);
+                    self.skipped_lines += 1;
+                    continue;
+                },
+                Err(message) => {
+                    warn!("Line {number}: could not read the edge list: {error}", number = self.line_number,
+                          error = message);
+                    return None;
+                }
+            };
+
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+                continue;
+            }
+
+            match self.format.parse_line(line, self.line_number) {
+                Ok(friendship) => return Some(friendship),
+                Err(message) => {
+                    warn!("{error}, skipping the line", error = message);
+                    self.skipped_lines += 1;
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for EdgeList<R> {
+    type Item = (UserId, Vec<UserId>);
+
+    fn next(&mut self) -> Option<(UserId, Vec<UserId>)> {
+        let (user, friend) = match self.next_friendship.take().or_else(|| self.read_friendship()) {
+            Some(friendship) => friendship,
+            None => return None
+        };
+
+        let mut friends: Vec<UserId> = vec![friend];
+        while let Some((next_user, next_friend)) = self.read_friendship() {
+            if next_user != user {
+                self.next_friendship = Some((next_user, next_friend));
+                break;
+            }
+            friends.push(next_friend);
+        }
+
+        Some((user, friends))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use Result;
+    use configuration::IdBase;
+    use social_graph::source::LoadStatistics;
+    use twitter::User;
+    use super::*;
+
+    /// Create the friend list of the `user`, given by raw IDs.
+    fn friend_list(user: i64, friends: &[i64]) -> (UserId, Vec<UserId>) {
+        (UserId::from(user), friends.iter().map(|&friend| UserId::from(friend)).collect())
+    }
+
+    #[test]
+    fn edge_list() {
+        let contents: &[u8] = b"# user\tfriend\n\
+                                0\t1\n\
+                                0\t2\n\
+                                \n\
+                                1\tzero\n\
+                                1\t0\n\
+                                1\t2\t3\n\
+                                \xff\t0\n\
+                                2\t0\n\
+                                0\t3\n\
+                                \n\
+                                \n";
+        let mut edges = EdgeList::new(Cursor::new(contents), EdgeListFormat::default());
+        assert_eq!(edges.next(), Some(friend_list(0, &[1, 2])));
+        assert_eq!(edges.next(), Some(friend_list(1, &[0])));
+        assert_eq!(edges.next(), Some(friend_list(2, &[0])));
+
+        // Friendships of a user on non-consecutive lines are not grouped.
+        assert_eq!(edges.next(), Some(friend_list(0, &[3])));
+        assert_eq!(edges.next(), None);
+        assert_eq!(edges.skipped_lines(), 3);
+
+        // Other formats.
+        let contents: &[u8] = b"a, 0xb\nA,c\r\n";
+        let edges = EdgeList::new(Cursor::new(contents), EdgeListFormat::new(',', IdBase::Hexadecimal));
+        assert_eq!(edges.collect::<Vec<(UserId, Vec<UserId>)>>(), vec![friend_list(10, &[11, 12])]);
+
+        // Empty files.
+        let mut edges = EdgeList::new(Cursor::new(&b"# no friendships\n"[..]), EdgeListFormat::default());
+        assert_eq!(edges.next(), None);
+        assert_eq!(edges.skipped_lines(), 0);
+    }
+
+    #[test]
+    fn load() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let path: PathBuf = data_path.join("social_graph_edge_list.tsv");
+
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load(&path, EdgeListFormat::default(), None, &[],
+                                                     &mut |user: User, friends: Vec<User>| -> Result<bool> {
+                                                         let _ = graph.insert(user, friends);
+                                                         Ok(true)
+                                                     })
+            .expect("Failed to load the social graph");
+
+        // The edge list contains the same friendships as the TAR archives in `data/social_graph`.
+        assert_eq!(statistics.users, 5);
+        assert_eq!(statistics.given_friendships, 8);
+        assert_eq!(statistics.expected_friendships, 8);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph[&User::new(0)], vec![User::new(1), User::new(2)]);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+        assert_eq!(graph[&User::new(2)], vec![User::new(0)]);
+        assert_eq!(graph[&User::new(3)], vec![User::new(2)]);
+        assert_eq!(graph[&User::new(4)], vec![User::new(2)]);
+
+        // Only the selected users 0 to 3.
+        let selected_users = InputSource::new(data_path.join("retweeting_users.txt"));
+        let mut users: Vec<User> = Vec::new();
+        let _ = super::load(&path, EdgeListFormat::default(), Some(selected_users), &[],
+                            &mut |user: User, _friends: Vec<User>| -> Result<bool> {
+                                users.push(user);
+                                Ok(true)
+                            })
+            .expect("Failed to load the social graph");
+        assert_eq!(users, vec![User::new(0), User::new(1), User::new(2), User::new(3)]);
+    }
+}
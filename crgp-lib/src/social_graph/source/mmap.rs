@@ -0,0 +1,208 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Load the social graph from a memory-mapped file containing an abomonated `Vec<(UserId, Vec<UserId>)>`.
+//!
+//! The file is created by [`dump_abomonated_graph`](fn.dump_abomonated_graph.html) and starts with the eight bytes
+//! `CRGPABOM`, followed by the graph as encoded by `abomonation::encode`. Instead of deserializing the graph, the file
+//! is mapped into memory (copy-on-write) and the graph is used in place via `abomonation::decode`, which only has to
+//! correct the pointers of the vectors. Thus, loading the graph is almost instantaneous even for huge graphs; only the
+//! friend lists actually passed into the computation are copied.
+//!
+//! # Safety
+//!
+//! Abomonation reinterprets the bytes of the file as the in-memory representation of the graph. The length of each
+//! vector is checked against the size of the file, but the layout is not. Thus, the following invariants must hold:
+//!
+//!  * The file must have been produced by the same version of this crate, compiled for the same target architecture
+//!    (i.e. with the same size and endianness of `UserId` and `Vec`). Files from other versions must be dumped again.
+//!  * The file must not be modified while it is mapped, neither by this process nor by any other.
+//!
+//! The magic bytes at the start of the file only protect against accidentally loading an entirely different file.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Write;
+use std::path::Path;
+
+use abomonation::decode;
+use abomonation::encode;
+use memmap::MmapMut;
+use memmap::MmapOptions;
+
+use Error;
+use Result;
+use UserId;
+use configuration::InputSource;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar::get_selected_users;
+use twitter::User;
+
+/// The extension of files containing an abomonated social graph.
+pub const ABOMONATED_GRAPH_EXTENSION: &'static str = "abom";
+
+/// The magic bytes at the start of each file containing an abomonated social graph. Their length is a multiple of
+/// eight bytes so that the encoded graph following them is aligned within the (page-aligned) mapping.
+const MAGIC_BYTES: &'static [u8] = b"CRGPABOM";
+
+/// Write the social `graph`, given as pairs of a user and their friends, to the file at `path` so that it can be loaded
+/// by memory-mapping it. By convention, the file should have the extension `.abom`. Errors on any I/O error.
+///
+/// See the [module documentation](index.html) for the conditions under which the file can be loaded again.
+#[cfg_attr(feature = "cargo-clippy", allow(ptr_arg))]
+pub fn dump_abomonated_graph(graph: &Vec<(UserId, Vec<UserId>)>, path: &Path) -> Result<()> {
+    let mut bytes: Vec<u8> = Vec::from(MAGIC_BYTES);
+
+    // Encoding only copies the graph's memory into the vector, which is always safe.
+    unsafe {
+        encode(graph, &mut bytes);
+    }
+
+    let mut file: File = File::create(path)?;
+    file.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Load the social graph from the abomonated file at the given local `path`, passing each user and their friends to
+/// `send`. If `selected_users_file` is given, only the users listed in it are loaded. The file does not contain the
+/// users' claimed numbers of friends, thus, no dummy users can be created. Errors if the file cannot be mapped or does
+/// not contain an abomonated social graph.
+///
+/// See the [module documentation](index.html) for the safety invariants the file must satisfy.
+pub fn load<F>(path: &Path,
+               selected_users_file: Option<InputSource>,
+               selected_user_ids: &[UserId],
+               send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
+
+    // Map the file copy-on-write, since decoding corrects the pointers within the mapped memory. Only the pages
+    // containing the vectors' headers will thus be copied, the file itself remains unchanged. Mapping the file is
+    // safe as long as it is not modified concurrently (see the module documentation).
+    let file: File = File::open(path)?;
+    let mut mapping: MmapMut = unsafe { MmapOptions::new().map_copy(&file)? };
+
+    if mapping.len() < MAGIC_BYTES.len() || &mapping[..MAGIC_BYTES.len()] != MAGIC_BYTES {
+        let message: String = format!("{file} does not contain an abomonated social graph", file = path.display());
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+    }
+
+    // Decoding is safe if the file has been dumped by this version of the crate (see the module documentation). The
+    // lengths of all vectors are validated against the size of the file, and the graph must span the entire file.
+    let graph: &Vec<(UserId, Vec<UserId>)> = match unsafe { decode(&mut mapping[MAGIC_BYTES.len()..]) } {
+        Some((graph, remaining)) if remaining.is_empty() => graph,
+        _ => {
+            let message: String = format!("Could not decode the abomonated social graph in {file}",
+                                          file = path.display());
+            error!("{}", message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let mut statistics = LoadStatistics::default();
+    for &(user, ref friends) in graph {
+        if let Some(ref selected_users) = selected_users {
+            if !selected_users.contains(&user) {
+                continue;
+            }
+        }
+
+        // The computation owns the friend lists it receives, thus, they must be copied out of the mapping.
+        let number_of_friends: u64 = friends.len() as u64;
+        let friends: Vec<User> = friends.iter()
+            .map(|&friend| User::from(friend))
+            .collect();
+        if send(User::from(user), friends)? {
+            statistics.users += 1;
+            statistics.given_friendships += number_of_friends;
+            statistics.expected_friendships += number_of_friends;
+        }
+    }
+
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::env;
+    use std::fs::File;
+    use std::fs::OpenOptions;
+    use std::fs::remove_file;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use Result;
+    use UserId;
+    use social_graph::source::LoadStatistics;
+    use twitter::User;
+    use super::*;
+
+    /// Create the friend list of the `user`, given by raw IDs.
+    fn friend_list(user: i64, friends: &[i64]) -> (UserId, Vec<UserId>) {
+        (UserId::from(user), friends.iter().map(|&friend| UserId::from(friend)).collect())
+    }
+
+    #[test]
+    fn dump_and_load() {
+        let path: PathBuf = env::temp_dir().join("crgp_dump_and_load.abom");
+        let graph: Vec<(UserId, Vec<UserId>)> = vec![
+            friend_list(0, &[1, 2]),
+            friend_list(1, &[0, 2, 3]),
+            friend_list(2, &[]),
+            friend_list(42, &[-1, 1 << 40]),
+        ];
+        dump_abomonated_graph(&graph, &path).expect("Failed to dump the social graph");
+
+        let mut loaded: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = load(&path, None, &[],
+                                              &mut |user: User, friends: Vec<User>| -> Result<bool> {
+                                                  let _ = loaded.insert(user, friends);
+                                                  Ok(true)
+                                              })
+            .expect("Failed to load the social graph");
+
+        assert_eq!(statistics.users, 4);
+        assert_eq!(statistics.given_friendships, 7);
+        assert_eq!(statistics.expected_friendships, 7);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(loaded[&User::new(0)], vec![User::new(1), User::new(2)]);
+        assert_eq!(loaded[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+        assert_eq!(loaded[&User::new(2)], vec![]);
+        assert_eq!(loaded[&User::new(42)], vec![User::new(-1), User::new(1 << 40)]);
+
+        let _ = remove_file(&path);
+    }
+
+    #[test]
+    fn load_invalid() {
+        // A file without the magic bytes.
+        let path: PathBuf = env::temp_dir().join("crgp_load_invalid.abom");
+        {
+            let mut file = File::create(&path).expect("Could not create the file");
+            file.write_all(b"0\t1\n1\t2\n").expect("Could not write the file");
+        }
+        assert!(load(&path, None, &[], &mut |_user: User, _friends: Vec<User>| -> Result<bool> { Ok(true) }).is_err());
+
+        // A truncated graph.
+        let graph: Vec<(UserId, Vec<UserId>)> = vec![friend_list(0, &[1, 2, 3])];
+        dump_abomonated_graph(&graph, &path).expect("Failed to dump the social graph");
+        {
+            let file = OpenOptions::new().write(true).open(&path).expect("Could not open the file");
+            let length: u64 = file.metadata().expect("Could not get the file's length").len();
+            file.set_len(length - 8).expect("Could not truncate the file");
+        }
+        assert!(load(&path, None, &[], &mut |_user: User, _friends: Vec<User>| -> Result<bool> { Ok(true) }).is_err());
+
+        let _ = remove_file(&path);
+    }
+}
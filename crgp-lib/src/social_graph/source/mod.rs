@@ -6,4 +6,48 @@
 
 //! Sources where the social graph can be loaded from.
 
+pub use self::mmap::dump_abomonated_graph;
+pub use self::s3_cache::cache_s3_locally;
+
+pub mod callback;
+pub mod edge_list;
+pub mod mmap;
+mod s3_cache;
 pub mod tar;
+mod zip;
+
+/// Counts gathered while loading the social graph.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LoadStatistics {
+    /// The number of users for whom friendships were loaded.
+    pub users: u64,
+
+    /// The total number of explicitly given friendships.
+    pub given_friendships: u64,
+
+    /// The total number of friendships as claimed in the meta data.
+    pub expected_friendships: u64,
+
+    /// The total number of friendships with dummy users.
+    pub dummy_friendships: u64,
+
+    /// The total number of friendships added by expanding the friend lists.
+    pub expanded_friendships: u64,
+
+    /// The number of friend files found for users who already had a friend file.
+    pub duplicate_users: u64,
+}
+
+impl LoadStatistics {
+    /// Get the share of friendships with dummy users among the given and the dummy friendships, from `0.0` to `1.0`.
+    ///
+    /// If there are no such friendships at all, the share will be `0.0`.
+    pub fn dummy_friendship_ratio(&self) -> f64 {
+        let total_friendships: u64 = self.given_friendships + self.dummy_friendships;
+        if total_friendships == 0 {
+            0.0
+        } else {
+            self.dummy_friendships as f64 / total_friendships as f64
+        }
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Cache a social graph stored on AWS S3 on the local disk.
+
+use std::fs::File;
+use std::fs::create_dir_all;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use s3::bucket::Bucket;
+use s3::error::ErrorKind as S3ErrorKind;
+use s3::error::S3Error;
+use s3::serde_types::ListBucketResult;
+
+use Error;
+use Result;
+use configuration::S3;
+use social_graph::source::tar::DIRECTORY_NAME_TEMPLATE;
+use social_graph::source::tar::TAR_NAME_TEMPLATE;
+
+/// Download the TAR archives of the social graph at `path` in the AWS S3 bucket given by `s3_config` to the local
+/// `destination` directory, and return the `destination`.
+///
+/// The archives are stored in the layout expected when loading the social graph locally, i.e. `destination/NNN/NN.tar`.
+/// Archives whose key does not contain a valid directory will be stored in `destination/000`. Archives already present
+/// in the `destination` with the same size as on AWS S3 will not be downloaded again.
+pub fn cache_s3_locally(path: &str, s3_config: &S3, destination: &PathBuf) -> Result<PathBuf> {
+    let bucket: Bucket = s3_config.get_bucket()?;
+
+    // Get all objects in the given path.
+    let (list, code): (ListBucketResult, u32) = bucket.list(path, None)?;
+    if code != 200 {
+        let message: String = format!("Could not get contents of AWS S3 bucket \"{bucket} (region {region})\": \
+                                       HTTP error {code}",
+                                      bucket = bucket.name, region = bucket.region, code = code);
+        error!("{}", message);
+        return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
+    }
+
+    for entry in list.contents {
+        let target: PathBuf = match get_local_path(&entry.key, destination) {
+            Some(target) => target,
+            None => {
+                trace!("Invalid filename: {name}", name = entry.key);
+                continue;
+            }
+        };
+
+        // Skip archives that have already been downloaded.
+        if let Ok(metadata) = target.metadata() {
+            if metadata.len() == entry.size {
+                trace!("Skipping {file}, already cached at {target}", file = entry.key, target = target.display());
+                continue;
+            }
+        }
+
+        // Load the actual file.
+        let (contents, code): (Vec<u8>, u32) = bucket.get(&entry.key)?;
+        if code != 200 {
+            let message: String = format!("Could not get file \"{file}\" from AWS S3 bucket \"{bucket} (region \
+                                           {region})\": HTTP error {code}",
+                                          file = entry.key, bucket = bucket.name, region = bucket.region, code = code);
+            error!("{}", message);
+            return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
+        }
+
+        // Save the file.
+        if let Some(directory) = target.parent() {
+            create_dir_all(directory)?;
+        }
+        let mut file: File = File::create(&target)?;
+        file.write_all(&contents)?;
+        info!("Cached {file} at {target}", file = entry.key, target = target.display());
+    }
+
+    Ok(destination.clone())
+}
+
+/// Determine where the archive with the given AWS S3 `key` will be stored within `destination`. Return `None` if the
+/// key does not name a TAR archive.
+fn get_local_path(key: &str, destination: &PathBuf) -> Option<PathBuf> {
+    let key_path: &Path = Path::new(key);
+    let filename: &str = match key_path.file_name().and_then(|name| name.to_str()) {
+        Some(filename) => filename,
+        None => return None
+    };
+    if !TAR_NAME_TEMPLATE.is_match(filename) {
+        return None;
+    }
+
+    let directory: &str = match key_path.parent()
+        .and_then(|parent| parent.file_name())
+        .and_then(|parent| parent.to_str()) {
+        Some(directory) if DIRECTORY_NAME_TEMPLATE.is_match(directory) => directory,
+        _ => "000"
+    };
+
+    Some(destination.join(directory).join(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    #[test]
+    fn get_local_path() {
+        let destination = PathBuf::from("cache");
+
+        assert_eq!(super::get_local_path("00.tar", &destination), Some(PathBuf::from("cache/000/00.tar")));
+        assert_eq!(super::get_local_path("graph/001/02.tar", &destination), Some(PathBuf::from("cache/001/02.tar")));
+        assert_eq!(super::get_local_path("graph/ab/02.tar", &destination), Some(PathBuf::from("cache/000/02.tar")));
+        assert_eq!(super::get_local_path("graph/001/invalid.tar", &destination), None);
+        assert_eq!(super::get_local_path("graph/001", &destination), None);
+    }
+}
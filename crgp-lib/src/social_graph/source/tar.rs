@@ -6,14 +6,20 @@
 
 //! Load the social graph from TAR files.
 
+use std::cmp;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs::read_dir;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
 use std::io::Read;
-use std::io::Result as IOResult;
 use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+use std::u64;
 
 use regex::Regex;
 use s3::bucket::Bucket;
@@ -24,24 +30,45 @@ use tar::Archive;
 
 use Error;
 use Result;
-use UserID;
+use UserId;
+use configuration::DuplicateUserPolicy;
+use configuration::EdgeListFormat;
 use configuration::InputSource;
+use configuration::read_id_set;
+use gcs::Bucket as GCSBucket;
 use reconstruction::algorithms::GraphHandle;
+use social_graph::SocialGraph;
+use social_graph::source::LoadStatistics;
+use social_graph::source::edge_list;
+use social_graph::source::mmap;
+use social_graph::source::mmap::ABOMONATED_GRAPH_EXTENSION;
+use social_graph::source::zip;
 use twitter::User;
 
+/// The prefix of each friend file's name, followed by the user's ID.
+const FRIEND_FILE_PREFIX: &'static str = "friends";
+
+/// The ratio of lines in a friend file that are not valid UTF-8 above which the file is reported as probably using
+/// another encoding (e.g. Latin-1).
+const INVALID_ENCODING_WARNING_RATIO: f64 = 0.1;
+
+/// The time to wait before retrying a request to AWS S3 for the first time (in `ms`). Each further retry waits twice as
+/// long as the previous one.
+const S3_INITIAL_BACKOFF: u64 = 100;
+
 lazy_static! {
     /// A regular expression to validate directory names. The name must consist of exactly three digits.
     // The initialization of the Regex will fail if the expression is invalid. Since the expression is known to be
     // correct, it is safe to simply expect a valid result.
     #[derive(Debug)]
-    static ref DIRECTORY_NAME_TEMPLATE: Regex = Regex::new(r"^\d{3}$").expect("Failed to compile the REGEX.");
+    pub static ref DIRECTORY_NAME_TEMPLATE: Regex = Regex::new(r"^\d{3}$").expect("Failed to compile the REGEX.");
 
     /// A regular expression to validate TAR file names. The name must consist of exactly two digits followed by the
     /// extension `.tar`.
     // The initialization of the Regex will fail if the expression is invalid. Since the expression is known to be
     // correct, it is safe to simply expect a valid result.
     #[derive(Debug)]
-    static ref TAR_NAME_TEMPLATE: Regex = Regex::new(r"^\d{2}\.tar$").expect("Failed to compile the REGEX.");
+    pub static ref TAR_NAME_TEMPLATE: Regex = Regex::new(r"^\d{2}\.tar$").expect("Failed to compile the REGEX.");
 
     /// A regular expression to validate file names. The name must be of the form `friends[ID].csv` where `[ID]`
     /// consists of one or more digits.
@@ -53,66 +80,365 @@ lazy_static! {
 }
 
 /// Load the social graph from the given `input` into the computation using the `graph_input`. If required, dummy users
-/// will be created. The function returns three counts in the following order: the number of users for whom friendships
-/// where loaded, the total number of explicitly given friendships, the total number of all friendships, and the total
-/// number of dummy friends.
+/// will be created. Claimed numbers of friends larger than `max_expected_friends` will be clamped to this value. If the
+/// `input` is a local file other than a ZIP archive or an abomonated social graph, it is loaded as an edge list whose
+/// lines are given in the `edge_list_format`. Users with more than one friend file are handled according to
+/// `duplicate_users`. If `friend_expansion_hops` is greater than `0`, each user's friend list will be expanded with the
+/// friends reachable within that many additional hops (up to `max_expanded_friends` friends per user) before passing
+/// the social graph into the computation. If `strict`, fail if none of the users selected in the `selected_users_file`
+/// or by their `selected_user_ids` is found in the social graph. If `sort_friends`, each friend list is sorted before
+/// it is passed into the computation, placing dummy users first. If the `input` is a local directory, the
+/// `additional_directories` are loaded after it. If the `input` is on AWS S3, requests failing with a transient error
+/// are retried up to `s3_max_retries` times. The function returns the counts gathered while loading. Errors if the
+/// `input` is configured for both AWS S3 and Google Cloud Storage.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
 pub fn load(input: InputSource,
+            additional_directories: &[PathBuf],
             pad_with_dummy_users: bool,
-            selected_users_file: Option<PathBuf>,
+            max_expected_friends: u64,
+            s3_max_retries: u32,
+            edge_list_format: EdgeListFormat,
+            selected_users_file: Option<InputSource>,
+            selected_user_ids: &[UserId],
+            duplicate_users: DuplicateUserPolicy,
+            friend_expansion_hops: u8,
+            max_expanded_friends: usize,
+            strict: bool,
+            sort_friends: bool,
             graph_input: &mut GraphHandle
-    ) -> Result<(u64, u64, u64, u64)>
+    ) -> Result<LoadStatistics>
 {
-    let path = input.path.clone();
-    match input.s3 {
-        Some(s3_config) => {
-            load_from_s3(&path, &s3_config.get_bucket()?, pad_with_dummy_users, selected_users_file, graph_input)
-        },
-        None => {
-            load_locally(&PathBuf::from(path), pad_with_dummy_users, selected_users_file, graph_input)
+    load_with(input, additional_directories, pad_with_dummy_users, max_expected_friends, s3_max_retries,
+              edge_list_format, selected_users_file, selected_user_ids, duplicate_users, friend_expansion_hops,
+              max_expanded_friends, strict, sort_friends,
+              &mut |user: User, friends: Vec<User>| graph_input.send((user, friends)))
+}
+
+/// Load the social graph from the given `input` like `load`, but pass each user and their friends to `send` instead of
+/// directly into the computation.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+pub fn load_with<F>(input: InputSource,
+                    additional_directories: &[PathBuf],
+                    pad_with_dummy_users: bool,
+                    max_expected_friends: u64,
+                    s3_max_retries: u32,
+                    edge_list_format: EdgeListFormat,
+                    selected_users_file: Option<InputSource>,
+                    selected_user_ids: &[UserId],
+                    duplicate_users: DuplicateUserPolicy,
+                    friend_expansion_hops: u8,
+                    max_expanded_friends: usize,
+                    strict: bool,
+                    sort_friends: bool,
+                    send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>)
+{
+    let mut send_friends = |user: User, mut friends: Vec<User>| {
+        if sort_friends {
+            friends.sort();
         }
+        send(user, friends);
+    };
+
+    // Without expansion, the friendships can be passed into the computation immediately.
+    if friend_expansion_hops == 0 {
+        return load_from_source(input, additional_directories, pad_with_dummy_users, max_expected_friends,
+                                s3_max_retries, edge_list_format, selected_users_file, selected_user_ids,
+                                duplicate_users, strict, &mut send_friends);
+    }
+
+    // Otherwise, the entire social graph is required for the expansion.
+    let mut graph = SocialGraph::new();
+    let mut statistics: LoadStatistics = load_from_source(input, additional_directories, pad_with_dummy_users,
+                                                          max_expected_friends, s3_max_retries, edge_list_format,
+                                                          selected_users_file, selected_user_ids, duplicate_users,
+                                                          strict,
+                                                          &mut |user: User, friends: Vec<User>| {
+                                                              graph.entry(user)
+                                                                  .or_insert_with(Vec::new)
+                                                                  .extend(friends);
+                                                          })?;
+    statistics.expanded_friendships = graph.expand_friends(friend_expansion_hops, max_expanded_friends);
+    info!("Added {number} friendships by expanding the friend lists by {hops} hops",
+          number = statistics.expanded_friendships, hops = friend_expansion_hops);
+
+    for (user, friends) in graph.drain() {
+        send_friends(user, friends);
     }
+
+    Ok(statistics)
 }
 
-/// Load the social graph from the given local `path`.
-fn load_locally(path: &PathBuf,
-                pad_with_dummy_users: bool,
-                selected_users_file: Option<PathBuf>,
-                graph_input: &mut GraphHandle
-    ) -> Result<(u64, u64, u64, u64)>
+/// Load a sample of at most `max_users` users from the social graph in the given `input` without passing them anywhere,
+/// e.g. to check that the social graph can be read. Loading stops as soon as the sample is complete; for social graphs
+/// on AWS S3 or Google Cloud Storage, the objects are listed, but only the archives up to the sample's last user are
+/// downloaded. The function returns the counts of the sampled users, which do not include dummy friendships.
+pub fn load_sample(input: InputSource,
+                   max_expected_friends: u64,
+                   s3_max_retries: u32,
+                   edge_list_format: EdgeListFormat,
+                   selected_users_file: Option<InputSource>,
+                   selected_user_ids: &[UserId],
+                   max_users: u64
+    ) -> Result<LoadStatistics>
 {
-    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
-    let selected_users: Option<HashSet<UserID>> = match selected_users_file {
-        Some(file) => {
-            let mut selected_users: HashSet<UserID> = HashSet::new();
-            get_selected_friends(&file, &mut selected_users)?;
-            Some(selected_users)
-        },
-        None => None
+    if max_users == 0 {
+        return Ok(LoadStatistics::default());
+    }
+
+    let mut sample = LoadStatistics::default();
+    let result: Result<LoadStatistics> = {
+        // Stop loading with an error once the sample is complete.
+        let mut count = |_user: User, friends: Vec<User>| -> Result<bool> {
+            sample.users += 1;
+            sample.given_friendships += friends.len() as u64;
+            sample.expected_friendships += friends.len() as u64;
+            if sample.users >= max_users {
+                return Err(Error::from(IOError::new(IOErrorKind::Interrupted, "The sample is complete")));
+            }
+            Ok(true)
+        };
+
+        load_from_any(input, &[], false, max_expected_friends, s3_max_retries, edge_list_format, selected_users_file,
+                      selected_user_ids, &mut count)
     };
 
-    let mut total_expected_friendships: u64 = 0;
-    let mut total_given_friendships: u64 = 0;
-    let mut total_dummy_friendships: u64 = 0;
-    let mut users: u64 = 0;
+    match result {
+        Ok(statistics) => Ok(statistics),
+        Err(_) if sample.users >= max_users => Ok(sample),
+        Err(error) => Err(error)
+    }
+}
+
+/// Load the social graph from the given `input`, passing each user and their friends to `send`. Users with more than
+/// one friend file (including users in more than one of the `additional_directories`, which are only loaded if the
+/// `input` is a local directory, and users with non-consecutive lines in an edge list) are handled according to
+/// `duplicate_users`. If the friend lists from the `additional_directories` are merged and padded with dummy users,
+/// the dummy users are only created once all friend files have been loaded, such that each user is padded to the
+/// largest number of friends claimed in any of their friend files exactly once. The dummy friends are then passed to
+/// `send` separately. If none of the users selected in the `selected_users_file` or by their `selected_user_ids` is
+/// found, a warning is logged, or, if `strict`, an error is returned.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+fn load_from_source<F>(input: InputSource,
+                       additional_directories: &[PathBuf],
+                       pad_with_dummy_users: bool,
+                       max_expected_friends: u64,
+                       s3_max_retries: u32,
+                       edge_list_format: EdgeListFormat,
+                       selected_users_file: Option<InputSource>,
+                       selected_user_ids: &[UserId],
+                       duplicate_users: DuplicateUserPolicy,
+                       strict: bool,
+                       send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>)
+{
+    if input.s3.is_some() && input.gcs.is_some() {
+        let message: String = format!("The social graph {input} cannot be loaded from both AWS S3 and Google Cloud \
+                                       Storage",
+                                      input = input);
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidInput, message)));
+    }
 
-    // Top level.
-    for root_entry in read_dir(path)? {
-        let directory_path: PathBuf = match root_entry {
-            Ok(entry) => entry.path(),
-            Err(_) => continue
+    let selection: Option<InputSource> = selected_users_file.clone();
+
+    // The IDs of all users for whom a friend file has been loaded, and the number of additional friend files found.
+    let mut loaded_users: HashSet<UserId> = HashSet::new();
+    let mut number_of_duplicates: u64 = 0;
+
+    // If the friend lists from several directories are merged, each friend file would be padded on its own. Instead,
+    // the number of real friends loaded for each user and the largest number of friends claimed for them are kept to
+    // pad the merged friend lists afterwards.
+    let is_padded_after_merging: bool = pad_with_dummy_users && duplicate_users == DuplicateUserPolicy::Merge &&
+        !additional_directories.is_empty();
+    let mut claimed_friends: HashMap<UserId, (u64, u64)> = HashMap::new();
+
+    let mut statistics: LoadStatistics = {
+        // Pass on the friendships of each user, unless they are a skipped duplicate.
+        let mut send_unique = |user: User, mut friends: Vec<User>| -> Result<bool> {
+            if !loaded_users.insert(user.id) {
+                number_of_duplicates += 1;
+                match duplicate_users {
+                    DuplicateUserPolicy::Merge => {
+                        warn!("Found another friend file for user {user}, merging the friends", user = user);
+                    },
+                    DuplicateUserPolicy::KeepFirst => {
+                        warn!("Found another friend file for user {user}, skipping it", user = user);
+                        return Ok(false);
+                    },
+                    DuplicateUserPolicy::Fail => {
+                        let message: String = format!("Found another friend file for user {user}", user = user);
+                        error!("{}", message);
+                        return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+                    }
+                }
+            }
+
+            if is_padded_after_merging {
+                let number_of_claimed_friends: u64 = friends.len() as u64;
+                friends.retain(User::is_real);
+                let counts: &mut (u64, u64) = claimed_friends.entry(user.id).or_insert((0, 0));
+                counts.0 += friends.len() as u64;
+                counts.1 = cmp::max(counts.1, number_of_claimed_friends);
+                if friends.is_empty() {
+                    return Ok(true);
+                }
+            }
+
+            send(user, friends);
+            Ok(true)
         };
 
+        load_from_any(input, additional_directories, pad_with_dummy_users, max_expected_friends, s3_max_retries,
+                      edge_list_format, selected_users_file, selected_user_ids, &mut send_unique)?
+    };
+
+    // Pad the merged friend lists, in a well-defined order. Dummy friends already created for the single friend files
+    // have not been passed on and must not be counted.
+    if is_padded_after_merging {
+        let mut padded_users: Vec<(UserId, u64)> = claimed_friends.into_iter()
+            .filter(|&(_, (real_friends, claimed))| claimed > real_friends)
+            .map(|(user_id, (real_friends, claimed))| (user_id, claimed - real_friends))
+            .collect();
+        padded_users.sort();
+        statistics.dummy_friendships = 0;
+        for (user_id, number_of_missing_friends) in padded_users {
+            send(User::from(user_id), create_dummy_friends(number_of_missing_friends));
+            statistics.dummy_friendships += number_of_missing_friends;
+        }
+    }
+
+    // Merged users must only be counted once.
+    statistics.users = loaded_users.len() as u64;
+    statistics.duplicate_users = number_of_duplicates;
+    if number_of_duplicates > 0 {
+        warn!("Found {number} additional friend files for users who already had one", number = number_of_duplicates);
+    }
+
+    // If users have been selected, but none of them has been found, the selection most likely is the wrong file or
+    // uses other IDs than the social graph. The selection is only loaded again in this case.
+    if statistics.users == 0 && (selection.is_some() || !selected_user_ids.is_empty()) {
+        let number_of_selected_users: usize = get_selected_users(selection.clone(), selected_user_ids)?
+            .map_or(0, |selected_users| selected_users.len());
+        if number_of_selected_users > 0 {
+            let origin: String = match selection {
+                Some(ref file) if selected_user_ids.is_empty() => format!("in {file}", file = file),
+                Some(ref file) => format!("in {file} or by ID", file = file),
+                None => String::from("by ID")
+            };
+            let message: String = format!("None of the {number} users selected {origin} is in the social graph",
+                                          number = number_of_selected_users, origin = origin);
+            if strict {
+                error!("{}", message);
+                return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+            }
+            warn!("{}", message);
+        }
+    }
+
+    Ok(statistics)
+}
+
+/// Load the social graph from the given `input` in the format it is stored in, passing each user and their friends to
+/// `send`. The `additional_directories` are only loaded if the `input` is a local directory. Requests to AWS S3
+/// failing with a transient error are retried up to `s3_max_retries` times.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+fn load_from_any<F>(input: InputSource,
+                    additional_directories: &[PathBuf],
+                    pad_with_dummy_users: bool,
+                    max_expected_friends: u64,
+                    s3_max_retries: u32,
+                    edge_list_format: EdgeListFormat,
+                    selected_users_file: Option<InputSource>,
+                    selected_user_ids: &[UserId],
+                    send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    let is_zip_archive: bool = input.path.extension().map_or(false, |extension| extension == "zip");
+    let is_abomonated_graph: bool = input.path.extension()
+        .map_or(false, |extension| extension == ABOMONATED_GRAPH_EXTENSION);
+    let is_remote: bool = input.s3.is_some() || input.gcs.is_some();
+    let is_edge_list: bool = !is_remote && !is_zip_archive && !is_abomonated_graph && input.path.is_file();
+    if !additional_directories.is_empty() && (is_remote || is_zip_archive || is_abomonated_graph || is_edge_list) {
+        warn!("Additional social graph directories can only be loaded with a local social graph directory, \
+               ignoring them");
+    }
+    match (input.s3, input.gcs) {
+        (Some(s3_config), _) => {
+            load_from_s3(&input.path.to_string_lossy(), &s3_config.get_bucket()?, pad_with_dummy_users,
+                         max_expected_friends, s3_max_retries, selected_users_file, selected_user_ids, send)
+        },
+        (None, Some(gcs_config)) => {
+            load_from_gcs(&input.path.to_string_lossy(), &gcs_config.get_bucket()?, pad_with_dummy_users,
+                          max_expected_friends, selected_users_file, selected_user_ids, send)
+        },
+        (None, None) if is_zip_archive => {
+            zip::load(&input.path, pad_with_dummy_users, max_expected_friends, selected_users_file, selected_user_ids,
+                      send)
+        },
+        (None, None) if is_abomonated_graph => {
+            if pad_with_dummy_users {
+                warn!("Abomonated social graphs do not contain the claimed numbers of friends, no dummy users \
+                       will be created");
+            }
+            mmap::load(&input.path, selected_users_file, selected_user_ids, send)
+        },
+        (None, None) if is_edge_list => {
+            if pad_with_dummy_users {
+                warn!("Edge lists do not contain the claimed numbers of friends, no dummy users will be created");
+            }
+            edge_list::load(&input.path, edge_list_format, selected_users_file, selected_user_ids, send)
+        },
+        (None, None) => {
+            let mut roots: Vec<PathBuf> = vec![input.path.clone()];
+            roots.extend_from_slice(additional_directories);
+            load_locally(&roots, pad_with_dummy_users, max_expected_friends, selected_users_file, selected_user_ids,
+                         send)
+        }
+    }
+}
+
+/// Load the social graph from the given local `roots`, one after the other.
+fn load_locally<F>(roots: &[PathBuf],
+                   pad_with_dummy_users: bool,
+                   max_expected_friends: u64,
+                   selected_users_file: Option<InputSource>,
+                   selected_user_ids: &[UserId],
+                   send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
+
+    let mut statistics = LoadStatistics::default();
+
+    // Top level. The paths are sorted within each root so that the order in which friend files are found is
+    // well-defined.
+    let mut directory_paths: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        let mut root_directory_paths: Vec<PathBuf> = read_dir(root)?
+            .filter_map(|root_entry| root_entry.ok())
+            .map(|root_entry| root_entry.path())
+            .collect();
+        root_directory_paths.sort();
+        directory_paths.extend(root_directory_paths);
+    }
+    for directory_path in directory_paths {
         if !is_valid_directory(&directory_path) {
             continue;
         }
 
         // TAR archives.
-        for archive_entry in read_dir(directory_path)? {
-            let tar_path: PathBuf = match archive_entry {
-                Ok(entry) => entry.path(),
-                Err(_) => continue
-            };
-
+        let mut tar_paths: Vec<PathBuf> = read_dir(directory_path)?
+            .filter_map(|archive_entry| archive_entry.ok())
+            .map(|archive_entry| archive_entry.path())
+            .collect();
+        tar_paths.sort();
+        for tar_path in tar_paths {
             if !is_valid_tar_archive(&tar_path) {
                 continue;
             }
@@ -151,112 +477,188 @@ fn load_locally(path: &PathBuf,
                     Err(_) => continue
                 };
 
-                if !is_valid_friend_file(&friends_path) {
-                    continue;
-                }
+                load_friend_file(file, &friends_path, &selected_users, pad_with_dummy_users, max_expected_friends,
+                                 &mut statistics, send)?;
+            }
+        }
+    }
 
-                // Get the user ID.
-                let user_id: UserID = match get_user_id(&friends_path) {
-                    Some(id) => id,
-                    None => continue
-                };
+    Ok(statistics)
+}
 
-                // If only selected users are requested: skip this user if they are not on the VIP list.
-                if let Some(ref selected_users) = selected_users {
-                    if !selected_users.contains(&user_id) {
-                        continue;
-                    }
-                }
+/// A store of objects given by their names, e.g. a bucket on AWS S3 or on Google Cloud Storage.
+trait ObjectStore {
+    /// Get the names of all objects whose names start with `path`.
+    fn list_objects(&self, path: &str) -> Result<Vec<String>>;
 
-                // Parse the file.
-                let reader = BufReader::new(file);
-                let (expected_friendships, mut friendships) = parse_friend_file(reader, &friends_path, user_id);
-                let user = User::new(user_id);
-                let given_friendships: u64 = friendships.len() as u64;
-
-                // Introduce dummy friends if required. To avoid any overflows, we must first ensure that there are less
-                // given friends than expected ones.
-                let user_has_missing_friends: bool = given_friendships < expected_friendships;
-                let number_of_dummy_users: u64 = if pad_with_dummy_users && user_has_missing_friends {
-                    let number_of_missing_friends: u64 = expected_friendships - given_friendships;
-                    friendships.extend(create_dummy_friends(number_of_missing_friends));
-                    trace!("User {user}: created {number} dummy friends",
-                           user = user, number = number_of_missing_friends);
-                    number_of_missing_friends
-                } else {
-                    0
-                };
+    /// Get the contents of the object `name`.
+    fn get_object(&self, name: &str) -> Result<Vec<u8>>;
+}
 
-                // If the user still has no friends, continue.
-                if friendships.is_empty() {
-                    warn!("User {user} does not have any friends", user = user);
-                    continue;
-                }
+/// The requests to a bucket on AWS S3, returning the HTTP status code of the response together with its contents.
+trait S3Requests {
+    /// Get the name and region of the bucket, used in log messages.
+    fn description(&self) -> String;
 
-                // Update social graph statistics.
-                total_given_friendships += given_friendships;
-                total_expected_friendships += expected_friendships;
-                total_dummy_friendships += number_of_dummy_users;
-                users += 1;
+    /// Get the names of all objects whose names start with `path`.
+    fn list_names(&self, path: &str) -> Result<(Vec<String>, u32)>;
 
-                graph_input.send((user, friendships));
-            }
+    /// Get the contents of the object `name`.
+    fn get_contents(&self, name: &str) -> Result<(Vec<u8>, u32)>;
+}
+
+impl S3Requests for Bucket {
+    fn description(&self) -> String {
+        format!("{bucket} (region {region})", bucket = self.name, region = self.region)
+    }
+
+    fn list_names(&self, path: &str) -> Result<(Vec<String>, u32)> {
+        let (list, code): (ListBucketResult, u32) = self.list(path, None)?;
+        Ok((list.contents.into_iter().map(|entry| entry.key).collect(), code))
+    }
+
+    fn get_contents(&self, name: &str) -> Result<(Vec<u8>, u32)> {
+        Ok(self.get(name)?)
+    }
+}
+
+/// A bucket on AWS S3 whose requests are retried up to `max_retries` times if they fail with a transient error (see
+/// `retry_s3_request`). The first retry waits for `initial_backoff` milliseconds, each further retry twice as long.
+struct RetryingBucket<'a, B: 'a + S3Requests> {
+    /// The bucket to which the requests are sent.
+    bucket: &'a B,
+
+    /// The maximum number of retries of each request.
+    max_retries: u32,
+
+    /// The time to wait before the first retry (in `ms`).
+    initial_backoff: u64,
+}
+
+impl<'a, B: 'a + S3Requests> ObjectStore for RetryingBucket<'a, B> {
+    fn list_objects(&self, path: &str) -> Result<Vec<String>> {
+        let description: String = format!("Could not get contents of AWS S3 bucket \"{bucket}\"",
+                                          bucket = self.bucket.description());
+        retry_s3_request(self.max_retries, self.initial_backoff, &description, || self.bucket.list_names(path))
+    }
+
+    fn get_object(&self, name: &str) -> Result<Vec<u8>> {
+        let description: String = format!("Could not get file \"{file}\" from AWS S3 bucket \"{bucket}\"", file = name,
+                                          bucket = self.bucket.description());
+        retry_s3_request(self.max_retries, self.initial_backoff, &description, || self.bucket.get_contents(name))
+    }
+}
+
+/// Send the `request` to AWS S3 until it succeeds, retrying it up to `max_retries` times if it fails with a transient
+/// error, i.e. a server error or throttling. The first retry waits for `initial_backoff` milliseconds, each further
+/// retry twice as long. Other HTTP errors, e.g. a missing object or denied access, as well as errors sending the
+/// request fail immediately. The `description` of the failure is used in log messages.
+fn retry_s3_request<T, F>(max_retries: u32, initial_backoff: u64, description: &str, mut request: F) -> Result<T>
+    where F: FnMut() -> Result<(T, u32)>
+{
+    let mut retries: u32 = 0;
+    loop {
+        let (response, code): (T, u32) = request()?;
+        if code == 200 {
+            return Ok(response);
+        }
+
+        let message: String = format!("{description}: HTTP error {code}", description = description, code = code);
+        if !is_transient_s3_error(code) || retries >= max_retries {
+            error!("{}", message);
+            return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
         }
+
+        let backoff: u64 = initial_backoff.saturating_mul(1u64.checked_shl(retries).unwrap_or(u64::MAX));
+        retries += 1;
+        warn!("{message}, retrying in {backoff}ms (attempt {attempt} of {max_retries})", message = message,
+              backoff = backoff, attempt = retries, max_retries = max_retries);
+        sleep(Duration::from_millis(backoff));
     }
+}
 
-    Ok((users, total_given_friendships, total_expected_friendships, total_dummy_friendships))
+/// Determine if the HTTP status `code` of a response from AWS S3 indicates a transient error, i.e. a server error
+/// (e.g. `503 Slow Down`) or throttling (`429 Too Many Requests`), after which the request may succeed if retried.
+fn is_transient_s3_error(code: u32) -> bool {
+    code == 429 || (code >= 500 && code < 600)
 }
 
-/// Load the social graph from the given AWS S3 `bucket`.
-fn load_from_s3(path: &str,
-                bucket: &Bucket,
-                pad_with_dummy_users: bool,
-                selected_users_file: Option<PathBuf>,
-                graph_input: &mut GraphHandle
-    ) -> Result<(u64, u64, u64, u64)>
+impl ObjectStore for GCSBucket {
+    fn list_objects(&self, path: &str) -> Result<Vec<String>> {
+        self.list(path)
+    }
+
+    fn get_object(&self, name: &str) -> Result<Vec<u8>> {
+        self.get(name)
+    }
+}
+
+/// Load the social graph from the given AWS S3 `bucket`. Requests failing with a transient error are retried up to
+/// `s3_max_retries` times.
+fn load_from_s3<F>(path: &str,
+                   bucket: &Bucket,
+                   pad_with_dummy_users: bool,
+                   max_expected_friends: u64,
+                   s3_max_retries: u32,
+                   selected_users_file: Option<InputSource>,
+                   selected_user_ids: &[UserId],
+                   send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
 {
-    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
-    let selected_users: Option<HashSet<UserID>> = match selected_users_file {
-        Some(file) => {
-            let mut selected_users: HashSet<UserID> = HashSet::new();
-            get_selected_friends(&file, &mut selected_users)?;
-            Some(selected_users)
-        },
-        None => None
+    let store = RetryingBucket {
+        bucket: bucket,
+        max_retries: s3_max_retries,
+        initial_backoff: S3_INITIAL_BACKOFF,
     };
+    load_from_object_store(path, &store, pad_with_dummy_users, max_expected_friends, selected_users_file,
+                           selected_user_ids, send)
+}
+
+/// Load the social graph from the given Google Cloud Storage `bucket`.
+fn load_from_gcs<F>(path: &str,
+                    bucket: &GCSBucket,
+                    pad_with_dummy_users: bool,
+                    max_expected_friends: u64,
+                    selected_users_file: Option<InputSource>,
+                    selected_user_ids: &[UserId],
+                    send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    load_from_object_store(path, bucket, pad_with_dummy_users, max_expected_friends, selected_users_file,
+                           selected_user_ids, send)
+}
+
+/// Load the social graph from the TAR archives in the given `path` of the object `store`.
+fn load_from_object_store<S, F>(path: &str,
+                                store: &S,
+                                pad_with_dummy_users: bool,
+                                max_expected_friends: u64,
+                                selected_users_file: Option<InputSource>,
+                                selected_user_ids: &[UserId],
+                                send: &mut F
+    ) -> Result<LoadStatistics>
+    where S: ObjectStore, F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
 
-    let mut total_expected_friendships: u64 = 0;
-    let mut total_given_friendships: u64 = 0;
-    let mut total_dummy_friendships: u64 = 0;
-    let mut users: u64 = 0;
+    let mut statistics = LoadStatistics::default();
 
     // Get all objects in the given path.
-    let (list, code): (ListBucketResult, u32) = bucket.list(path, None)?;
-    if code != 200 {
-        let message: String = format!("Could not get contents of AWS S3 bucket \"{bucket} (region {region})\": \
-                                       HTTP error {code}",
-                                      bucket = bucket.name, region = bucket.region, code = code);
-        error!("{}", message);
-        return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
-    }
+    let names: Vec<String> = store.list_objects(path)?;
 
     // Load all TAR archives and parse them.
-    for entry in list.contents {
+    for name in names {
         // Validate the file name.
-        if !TAR_NAME_TEMPLATE.is_match(&entry.key) {
-            trace!("Invalid filename: {name}", name = entry.key);
+        if !TAR_NAME_TEMPLATE.is_match(&name) {
+            trace!("Invalid filename: {name}", name = name);
             continue;
         }
 
         // Load the actual file.
-        let (contents, code): (Vec<u8>, u32) = bucket.get(&entry.key)?;
-        if code != 200 {
-            let message: String = format!("Could not get file \"{file}\" from AWS S3 bucket \"{bucket} (region \
-                                           {region})\": HTTP error {code}",
-                                          file = entry.key, bucket = bucket.name, region = bucket.region, code = code);
-            error!("{}", message);
-            return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
-        }
+        let contents: Vec<u8> = store.get_object(&name)?;
 
         // The array of `u8`s is just the archive we want to read.
         let mut archive: Archive<&[u8]> = Archive::new(&contents);
@@ -264,7 +666,7 @@ fn load_from_s3(path: &str,
             Ok(entries) => entries,
             Err(message) => {
                 error!("Could not read contents of archive {archive}: {error}",
-                        archive = entry.key, error = message);
+                        archive = name, error = message);
                 continue;
             }
         };
@@ -276,7 +678,7 @@ fn load_from_s3(path: &str,
                 Ok(file) => file,
                 Err(message) => {
                     error!("Could not read archived file in archive {archive}: {error}",
-                            archive = entry.key, error = message);
+                            archive = name, error = message);
                     continue;
                 }
             };
@@ -286,108 +688,55 @@ fn load_from_s3(path: &str,
                 Err(_) => continue
             };
 
-            if !is_valid_friend_file(&friends_path) {
-                continue;
-            }
-
-            // Get the user ID.
-            let user_id: UserID = match get_user_id(&friends_path) {
-                Some(id) => id,
-                None => continue
-            };
-
-            // If only selected users are requested: skip this user if they are not on the VIP list.
-            if let Some(ref selected_users) = selected_users {
-                if !selected_users.contains(&user_id) {
-                    continue;
-                }
-            }
-
-            // Parse the file.
-            let reader = BufReader::new(file);
-            let (expected_friendships, mut friendships) = parse_friend_file(reader, &friends_path, user_id);
-            let user = User::new(user_id);
-            let given_friendships: u64 = friendships.len() as u64;
-
-            // Introduce dummy friends if required. To avoid any overflows, we must first ensure that there are less
-            // given friends than expected ones.
-            let user_has_missing_friends: bool = given_friendships < expected_friendships;
-            let number_of_dummy_users: u64 = if pad_with_dummy_users && user_has_missing_friends {
-                let number_of_missing_friends: u64 = expected_friendships - given_friendships;
-                friendships.extend(create_dummy_friends(number_of_missing_friends));
-                trace!("User {user}: created {number} dummy friends",
-                       user = user, number = number_of_missing_friends);
-                number_of_missing_friends
-            } else {
-                0
-            };
-
-            // If the user still has no friends, continue.
-            if friendships.is_empty() {
-                warn!("User {user} does not have any friends", user = user);
-                continue;
-            }
-
-            // Update social graph statistics.
-            total_given_friendships += given_friendships;
-            total_expected_friendships += expected_friendships;
-            total_dummy_friendships += number_of_dummy_users;
-            users += 1;
-
-            graph_input.send((user, friendships));
+            load_friend_file(file, &friends_path, &selected_users, pad_with_dummy_users, max_expected_friends,
+                             &mut statistics, send)?;
         }
     }
 
-    Ok((users, total_given_friendships, total_expected_friendships, total_dummy_friendships))
+    Ok(statistics)
 }
 
 /// Create the given `amount` of dummy friends.
 fn create_dummy_friends(amount: u64) -> Vec<User> {
     let mut dummies: Vec<User> = Vec::new();
     for dummy_id in 1..(amount + 1) {
-        let dummy = User::new(-(dummy_id as UserID));
-        dummies.push(dummy);
+        dummies.push(User::dummy(dummy_id));
     }
     dummies
 }
 
-/// Load the given file `path` and insert all user IDs into the `out` set of friends to load. Errors on any I/O error.
-fn get_selected_friends(path: &PathBuf, out: &mut HashSet<UserID>) -> Result<()> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        let id: String = match line {
-            Ok(line) => line,
-            Err(message) => {
-                warn!("Invalid line in file {file}: {error}", file = path.display(), error = message);
-                continue;
-            }
-        };
-
-        match id.parse::<UserID>() {
-            Ok(id) => {
-                let _ = out.insert(id);
-            },
-            Err(message) => {
-                warn!("Could not parse user ID '{user}' in file {file}: {error}",
-                      user = id, file = path.display(), error = message);
-                continue;
-            }
-        }
+/// Get the set of users listed in the `selected_users_file` together with the `selected_user_ids`. Return `None` if no
+/// file is given and no IDs are selected, i.e. if all users are selected.
+pub fn get_selected_users(selected_users_file: Option<InputSource>, selected_user_ids: &[UserId])
+    -> Result<Option<HashSet<UserId>>>
+{
+    if selected_users_file.is_none() && selected_user_ids.is_empty() {
+        return Ok(None);
     }
 
-    Ok(())
+    let mut selected_users: HashSet<UserId> = selected_user_ids.iter().cloned().collect();
+    if let Some(file) = selected_users_file {
+        read_id_set(file, &mut selected_users)?;
+    }
+    Ok(Some(selected_users))
 }
 
 /// Get the user ID encoded in the file `path`. Return `None` if any error occurred.
-fn get_user_id(path: &PathBuf) -> Option<UserID> {
+fn get_user_id(path: &PathBuf) -> Option<UserId> {
     if let Some(stem) = path.file_stem() {
         if let Some(stem) = stem.to_str() {
-            match stem[7..].parse::<UserID>() {
+            // Slicing off the prefix is only safe if the stem actually starts with it.
+            if !stem.starts_with(FRIEND_FILE_PREFIX) {
+                warn!("Could not parse user ID from file name '{name}': it does not start with '{prefix}'",
+                      name = path.display(), prefix = FRIEND_FILE_PREFIX);
+                return None;
+            }
+
+            let id: &str = &stem[FRIEND_FILE_PREFIX.len()..];
+            match id.parse::<UserId>() {
                 Ok(id) => return Some(id),
                 Err(message) => {
-                    warn!("Could not parse user ID '{id}': {error}", id = &stem[7..], error = message);
+                    warn!("Could not parse user ID '{id}': {error}", id = id, error = message);
                     return None
                 }
             }
@@ -445,24 +794,86 @@ fn is_valid_tar_archive(path: &PathBuf) -> bool {
     false
 }
 
+/// Load the friend `file` found at `friends_path` within an archive and pass the user and their friends to `send`,
+/// updating the `statistics` accordingly. Files that are not friend files and users that are not in `selected_users`
+/// are skipped. If required, dummy users will be created. Claimed numbers of friends larger than
+/// `max_expected_friends` will be clamped to this value.
+pub fn load_friend_file<R, F>(file: R,
+                              friends_path: &PathBuf,
+                              selected_users: &Option<HashSet<UserId>>,
+                              pad_with_dummy_users: bool,
+                              max_expected_friends: u64,
+                              statistics: &mut LoadStatistics,
+                              send: &mut F
+    ) -> Result<()>
+    where R: Read, F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    if !is_valid_friend_file(friends_path) {
+        return Ok(());
+    }
+
+    // Get the user ID.
+    let user_id: UserId = match get_user_id(friends_path) {
+        Some(id) => id,
+        None => return Ok(())
+    };
+
+    // If only selected users are requested: skip this user if they are not on the VIP list.
+    if let Some(ref selected_users) = *selected_users {
+        if !selected_users.contains(&user_id) {
+            return Ok(());
+        }
+    }
+
+    // Parse the file.
+    let reader = BufReader::new(file);
+    let (expected_friendships, mut friendships) = parse_friend_file(reader, friends_path, user_id,
+                                                                    max_expected_friends);
+    let user = User::from(user_id);
+    let given_friendships: u64 = friendships.len() as u64;
+
+    // Introduce dummy friends if required. To avoid any overflows, we must first ensure that there are less given
+    // friends than expected ones.
+    let user_has_missing_friends: bool = given_friendships < expected_friendships;
+    let number_of_dummy_users: u64 = if pad_with_dummy_users && user_has_missing_friends {
+        let number_of_missing_friends: u64 = expected_friendships - given_friendships;
+        friendships.extend(create_dummy_friends(number_of_missing_friends));
+        trace!("User {user}: created {number} dummy friends", user = user, number = number_of_missing_friends);
+        number_of_missing_friends
+    } else {
+        0
+    };
+
+    // If the user still has no friends, skip them.
+    if friendships.is_empty() {
+        warn!("User {user} does not have any friends", user = user);
+        return Ok(());
+    }
+
+    // Pass on the friendships, unless they have been skipped.
+    if !send(user, friendships)? {
+        return Ok(());
+    }
+
+    // Update social graph statistics.
+    statistics.given_friendships += given_friendships;
+    statistics.expected_friendships += expected_friendships;
+    statistics.dummy_friendships += number_of_dummy_users;
+    statistics.users += 1;
+
+    Ok(())
+}
+
 /// Read the given friend file `reader` and parse its content. The parameters `file_path` and `user` are used in log
 /// messages for more detailed information on possible failures. Return the number of expected friends (i.e. as
-/// specified in the meta data) and a list of friends actually found in the file.
-fn parse_friend_file<R: Read>(reader: BufReader<R>, file_path: &PathBuf, user: UserID) -> (u64, Vec<User>) {
+/// specified in the meta data, but at most `max_expected_friends`) and a list of friends actually found in the file.
+fn parse_friend_file<R: Read>(reader: BufReader<R>, file_path: &PathBuf, user: UserId, max_expected_friends: u64)
+    -> (u64, Vec<User>) {
     let mut is_first_line: bool = true;
     let mut expected_number_of_friends: u64 = 0;
 
-    let found_friendships: Vec<User> = reader.lines()
-        .filter_map(|line: IOResult<String>| -> Option<String> {
-            // Ensure correct encoding.
-            match line {
-                Ok(line) => Some(line),
-                Err(message) => {
-                    warn!("Invalid line in file {file}: {error}", file = file_path.display(), error = message);
-                    None
-                }
-            }
-        })
+    let mut lines = Utf8Lines::new(reader, file_path);
+    let found_friendships: Vec<User> = lines.by_ref()
         .filter_map(|line: String| -> Option<User> {
             // If this is the first line in the file, it may contain meta data.
             if is_first_line && line.contains(';') {
@@ -473,12 +884,19 @@ fn parse_friend_file<R: Read>(reader: BufReader<R>, file_path: &PathBuf, user: U
                     }
                 }
 
+                // Do not trust absurd claims, they might be caused by corrupt meta data.
+                if expected_number_of_friends > max_expected_friends {
+                    warn!("User {user} claims to have {claim} friends, clamping to {max}",
+                          user = user, claim = expected_number_of_friends, max = max_expected_friends);
+                    expected_number_of_friends = max_expected_friends;
+                }
+
                 // The line cannot be a valid friend ID at this point anymore.
                 return None;
             }
 
             // Otherwise, parse the line as a friend ID.
-            let id: UserID = match line.parse() {
+            let id: UserId = match line.parse() {
                 Ok(id) => id,
                 Err(message) => {
                     warn!("Could not parse friend ID '{friend}' of user {user}: {error}",
@@ -486,10 +904,21 @@ fn parse_friend_file<R: Read>(reader: BufReader<R>, file_path: &PathBuf, user: U
                     return None;
                 }
             };
-            Some(User::new(id))
+            Some(User::from(id))
         })
         .collect();
 
+    // Many undecodable lines hint at a file in another encoding, whose friendships are silently lost otherwise.
+    if lines.invalid_lines > 0 {
+        let ratio: f64 = lines.invalid_lines as f64 / lines.lines as f64;
+        if ratio > INVALID_ENCODING_WARNING_RATIO {
+            warn!("{invalid} of {lines} lines ({percentage:.1}%) in file {file} are not valid UTF-8 and have been \
+                   skipped, the file might use another encoding (e.g. Latin-1)",
+                  invalid = lines.invalid_lines, lines = lines.lines, percentage = ratio * 100.0,
+                  file = file_path.display());
+        }
+    }
+
     // Log how many friends were found.
     let given_friendships: u64 = found_friendships.len() as u64;
     trace!("User {user}: {given} of {expected} friends found",
@@ -504,11 +933,177 @@ fn parse_friend_file<R: Read>(reader: BufReader<R>, file_path: &PathBuf, user: U
     (expected_number_of_friends, found_friendships)
 }
 
+/// An iterator over the lines of a friend file without their line terminators. Lines that are not valid UTF-8 are
+/// skipped with a warning including their position in the file, and counted. The iteration stops at the first IO error.
+struct Utf8Lines<'a, R: Read> {
+    /// The reader of the friend file.
+    reader: BufReader<R>,
+
+    /// The path of the friend file, used in log messages.
+    file_path: &'a PathBuf,
+
+    /// The byte offset of the next line within the file.
+    offset: u64,
+
+    /// The number of lines read so far, including invalid ones.
+    lines: u64,
+
+    /// The number of lines that were not valid UTF-8.
+    invalid_lines: u64,
+}
+
+impl<'a, R: Read> Utf8Lines<'a, R> {
+    /// Iterate over the lines of the friend file `reader`, found at `file_path`.
+    fn new(reader: BufReader<R>, file_path: &'a PathBuf) -> Utf8Lines<'a, R> {
+        Utf8Lines {
+            reader: reader,
+            file_path: file_path,
+            offset: 0,
+            lines: 0,
+            invalid_lines: 0,
+        }
+    }
+}
+
+impl<'a, R: Read> Iterator for Utf8Lines<'a, R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let mut line: Vec<u8> = Vec::new();
+            let length: usize = match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => return None,
+                Ok(length) => length,
+                Err(message) => {
+                    warn!("Could not read file {file} at byte {offset}: {error}",
+                          file = self.file_path.display(), offset = self.offset, error = message);
+                    return None;
+                }
+            };
+            let offset: u64 = self.offset;
+            self.offset += length as u64;
+            self.lines += 1;
+
+            // Remove the line terminator.
+            if line.last() == Some(&b'\n') {
+                let _ = line.pop();
+                if line.last() == Some(&b'\r') {
+                    let _ = line.pop();
+                }
+            }
+
+            match String::from_utf8(line) {
+                Ok(line) => return Some(line),
+                Err(message) => {
+                    self.invalid_lines += 1;
+                    warn!("Invalid line {line} at byte {offset} in file {file}: {error}",
+                          line = self.lines, offset = offset, file = self.file_path.display(), error = message);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::io::Read;
     use std::path::PathBuf;
     use find_folder::Search;
+    use Error;
+    use Result;
+    use UserId;
+    use configuration::DuplicateUserPolicy;
+    use configuration::EdgeListFormat;
+    use configuration::GCS;
+    use configuration::InputSource;
+    use configuration::S3;
+    use schema;
+    use social_graph::source::LoadStatistics;
     use twitter::User;
+    use super::ObjectStore;
+    use super::RetryingBucket;
+    use super::S3Requests;
+
+    /// An object store keeping its objects in memory, given by their names and contents.
+    struct MemoryStore {
+        objects: Vec<(String, Vec<u8>)>,
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn list_objects(&self, path: &str) -> Result<Vec<String>> {
+            Ok(self.objects.iter()
+                .map(|&(ref name, _)| name.clone())
+                .filter(|name| name.starts_with(path))
+                .collect())
+        }
+
+        fn get_object(&self, name: &str) -> Result<Vec<u8>> {
+            self.objects.iter()
+                .find(|&&(ref object, _)| object.as_str() == name)
+                .map(|&(_, ref contents)| contents.clone())
+                .ok_or_else(|| Error::from(format!("Missing object {name}", name = name)))
+        }
+    }
+
+    /// A bucket on AWS S3 containing a single archive, whose listing and download each fail with the HTTP status
+    /// `code` the given number of times before they succeed. All requests are counted.
+    struct FlakyBucket {
+        store: MemoryStore,
+        code: u32,
+        list_failures: Cell<u32>,
+        get_failures: Cell<u32>,
+        requests: Cell<u32>,
+    }
+
+    impl FlakyBucket {
+        fn new(archive: Vec<u8>, code: u32, failures: u32) -> FlakyBucket {
+            FlakyBucket {
+                store: MemoryStore {
+                    objects: vec![(String::from("00.tar"), archive)],
+                },
+                code: code,
+                list_failures: Cell::new(failures),
+                get_failures: Cell::new(failures),
+                requests: Cell::new(0),
+            }
+        }
+
+        /// Count a request, and determine if it fails, given its remaining `failures`.
+        fn fails(&self, failures: &Cell<u32>) -> bool {
+            self.requests.set(self.requests.get() + 1);
+            if failures.get() == 0 {
+                return false;
+            }
+
+            failures.set(failures.get() - 1);
+            true
+        }
+    }
+
+    impl S3Requests for FlakyBucket {
+        fn description(&self) -> String {
+            String::from("flaky (region nowhere)")
+        }
+
+        fn list_names(&self, path: &str) -> Result<(Vec<String>, u32)> {
+            if self.fails(&self.list_failures) {
+                return Ok((Vec::new(), self.code));
+            }
+            Ok((self.store.list_objects(path)?, 200))
+        }
+
+        fn get_contents(&self, name: &str) -> Result<(Vec<u8>, u32)> {
+            if self.fails(&self.get_failures) {
+                return Ok((Vec::new(), self.code));
+            }
+            Ok((self.store.get_object(name)?, 200))
+        }
+    }
 
     #[test]
     fn create_dummy_friends() {
@@ -557,6 +1152,483 @@ mod tests {
 
         let invalid = PathBuf::from(String::from(".."));
         assert_eq!(super::get_user_id(&invalid), None);
+        // Stems shorter than the prefix or not starting with it must not cause a panic.
+        let invalid = PathBuf::from(String::from("000/111/fr.csv"));
+        assert_eq!(super::get_user_id(&invalid), None);
+
+        let invalid = PathBuf::from(String::from("fr"));
+        assert_eq!(super::get_user_id(&invalid), None);
+
+        // The byte at the length of the prefix is within a multibyte character.
+        let invalid = PathBuf::from(String::from("friend\u{e9}1.csv"));
+        assert_eq!(super::get_user_id(&invalid), None);
+
+        let invalid = PathBuf::from(String::from("frie\u{e9}\u{e9}1.csv"));
+        assert_eq!(super::get_user_id(&invalid), None);
+    }
+
+    #[test]
+    fn parse_friend_file() {
+        let path = PathBuf::from(String::from("000/000/friends1.csv"));
+
+        let contents: &[u8] = b"1;user;1;3;\n2\n3\n";
+        let (expected, friends) = super::parse_friend_file(BufReader::new(contents), &path, 1, 100);
+        assert_eq!(expected, 3);
+        assert_eq!(friends, vec![User::new(2), User::new(3)]);
+
+        // Absurd claims are clamped.
+        let contents: &[u8] = b"1;user;1;18446744073709551615;\n2\n3\n";
+        let (expected, friends) = super::parse_friend_file(BufReader::new(contents), &path, 1, 100);
+        assert_eq!(expected, 100);
+        assert_eq!(friends, vec![User::new(2), User::new(3)]);
+    }
+
+    #[test]
+    fn parse_friend_file_invalid_utf8() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let path: PathBuf = data_path.join("friends_invalid_utf8.csv");
+
+        // The lines that are not valid UTF-8 are skipped.
+        let file = File::open(&path).expect("Could not open the friend file");
+        let (expected, friends) = super::parse_friend_file(BufReader::new(file), &path, 1, 100);
+        assert_eq!(expected, 4);
+        assert_eq!(friends, vec![User::new(2), User::new(4)]);
+    }
+
+    #[test]
+    fn utf8_lines() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let path: PathBuf = data_path.join("friends_invalid_utf8.csv");
+
+        let file = File::open(&path).expect("Could not open the friend file");
+        let mut lines = super::Utf8Lines::new(BufReader::new(file), &path);
+        let valid_lines: Vec<String> = lines.by_ref().collect();
+        assert_eq!(valid_lines, vec![String::from("user1;1;1;4;5"), String::from("2"), String::from("4")]);
+        assert_eq!(lines.lines, 5);
+        assert_eq!(lines.invalid_lines, 2);
+        assert_eq!(lines.offset, 25);
+
+        // A missing final line terminator does not matter.
+        let contents: &[u8] = b"1\r\n2";
+        let mut lines = super::Utf8Lines::new(BufReader::new(contents), &path);
+        let valid_lines: Vec<String> = lines.by_ref().collect();
+        assert_eq!(valid_lines, vec![String::from("1"), String::from("2")]);
+        assert_eq!(lines.invalid_lines, 0);
+    }
+
+    #[test]
+    fn parse_friend_file_schema_example() {
+        let path = PathBuf::from(String::from(schema::FRIEND_FILE_PATH_EXAMPLE));
+        assert!(super::is_valid_friend_file(&path));
+
+        let contents: &[u8] = schema::FRIEND_FILE_EXAMPLE.as_bytes();
+        let (expected, friends) = super::parse_friend_file(BufReader::new(contents), &path, 1, 100);
+        assert_eq!(expected, 3);
+        assert_eq!(friends, vec![User::new(2), User::new(3)]);
+    }
+
+    #[test]
+    fn load_from_source_duplicate_users() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph_duplicates").to_str().unwrap());
+
+        // Merge the friend lists.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &[], false, 100, 3,
+                                                                 EdgeListFormat::default(), None, &[],
+                                                                 DuplicateUserPolicy::Merge, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     graph.entry(user)
+                                                                         .or_insert_with(Vec::new)
+                                                                         .extend(friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 2);
+        assert_eq!(statistics.given_friendships, 5);
+        assert_eq!(statistics.duplicate_users, 1);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(2), User::new(3)]);
+
+        // Keep the first friend list.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &[], false, 100, 3,
+                                                                 EdgeListFormat::default(), None, &[],
+                                                                 DuplicateUserPolicy::KeepFirst, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     graph.entry(user)
+                                                                         .or_insert_with(Vec::new)
+                                                                         .extend(friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 2);
+        assert_eq!(statistics.given_friendships, 3);
+        assert_eq!(statistics.duplicate_users, 1);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2)]);
+
+        // Fail.
+        let result = super::load_from_source(input, &[], false, 100, 3,
+                                             EdgeListFormat::default(), None, &[], DuplicateUserPolicy::Fail, false,
+                                             &mut |_user: User, _friends: Vec<User>| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_from_source_additional_directories() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph_split/volume_a").to_str().unwrap());
+        let additional_directories: Vec<PathBuf> = vec![data_path.join("social_graph_split/volume_b")];
+
+        // User 1 has friend files in both directories.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &additional_directories, false, 100, 3,
+                                                                 EdgeListFormat::default(),
+                                                                 None, &[], DuplicateUserPolicy::Merge, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     graph.entry(user)
+                                                                         .or_insert_with(Vec::new)
+                                                                         .extend(friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 3);
+        assert_eq!(statistics.given_friendships, 5);
+        assert_eq!(statistics.duplicate_users, 1);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph[&User::new(0)], vec![User::new(1)]);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+        assert_eq!(graph[&User::new(2)], vec![User::new(0)]);
+
+        // User 1 claims to have three friends in both directories. Together, all of them are given, thus, the merged
+        // friend list is not padded.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &additional_directories, true, 100, 3,
+                                                                 EdgeListFormat::default(),
+                                                                 None, &[], DuplicateUserPolicy::Merge, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     graph.entry(user)
+                                                                         .or_insert_with(Vec::new)
+                                                                         .extend(friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 3);
+        assert_eq!(statistics.given_friendships, 5);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+
+        // Without the additional directory, only the first part is loaded, and user 1 is padded with one dummy friend.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &[], true, 100, 3,
+                                                                 EdgeListFormat::default(), None, &[],
+                                                                 DuplicateUserPolicy::Merge, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     graph.entry(user)
+                                                                         .or_insert_with(Vec::new)
+                                                                         .extend(friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.dummy_friendships, 1);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(-1)]);
+
+        // Without the additional directory, only the first part is loaded.
+        let statistics: LoadStatistics = super::load_from_source(input, &[], false, 100, 3,
+                                                                 EdgeListFormat::default(), None, &[],
+                                                                 DuplicateUserPolicy::Merge, false,
+                                                                 &mut |_user: User, _friends: Vec<User>| {})
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 2);
+        assert_eq!(statistics.given_friendships, 3);
+        assert_eq!(statistics.duplicate_users, 0);
+    }
+
+    #[test]
+    fn load_from_source_absent_selected_users() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+        let absent_users = InputSource::new(data_path.join("absent_users.txt").to_str().unwrap());
+
+        // Without strict checking, the empty social graph is loaded.
+        let statistics: LoadStatistics = super::load_from_source(input.clone(), &[], false, 100, 3,
+                                                                 EdgeListFormat::default(),
+                                                                 Some(absent_users.clone()), &[],
+                                                                 DuplicateUserPolicy::Merge, false,
+                                                                 &mut |_user: User, _friends: Vec<User>| {})
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 0);
+        assert_eq!(statistics.given_friendships, 0);
+
+        // With strict checking, loading fails.
+        let result = super::load_from_source(input.clone(), &[], false, 100, 3,
+                                             EdgeListFormat::default(), Some(absent_users), &[],
+                                             DuplicateUserPolicy::Merge, true,
+                                             &mut |_user: User, _friends: Vec<User>| {});
+        assert!(result.is_err());
+
+        // Selected users that are found are fine.
+        let selected_users = InputSource::new(data_path.join("root_users.txt").to_str().unwrap());
+        let statistics: LoadStatistics = super::load_from_source(input, &[], false, 100, 3,
+                                                                 EdgeListFormat::default(), Some(selected_users), &[],
+                                                                 DuplicateUserPolicy::Merge, true,
+                                                                 &mut |_user: User, _friends: Vec<User>| {})
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 1);
+    }
+
+    #[test]
+    fn load_from_source_selected_user_ids() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+
+        // Only the users listed in the file.
+        let selected_users = InputSource::new(data_path.join("retweeting_users.txt").to_str().unwrap());
+        let mut users: Vec<User> = Vec::new();
+        let _ = super::load_from_source(input.clone(), &[], false, 100, 3, EdgeListFormat::default(),
+                                        Some(selected_users), &[], DuplicateUserPolicy::Merge, true,
+                                        &mut |user: User, _friends: Vec<User>| users.push(user))
+            .expect("Failed to load the social graph");
+        users.sort();
+        assert_eq!(users, vec![User::new(0), User::new(1), User::new(2), User::new(3)]);
+
+        // Only the users given by their IDs.
+        let mut users: Vec<User> = Vec::new();
+        let _ = super::load_from_source(input.clone(), &[], false, 100, 3, EdgeListFormat::default(), None,
+                                        &[UserId::from(4)], DuplicateUserPolicy::Merge, true,
+                                        &mut |user: User, _friends: Vec<User>| users.push(user))
+            .expect("Failed to load the social graph");
+        assert_eq!(users, vec![User::new(4)]);
+
+        // The users listed in the file and those given by their IDs.
+        let selected_users = InputSource::new(data_path.join("root_users.txt").to_str().unwrap());
+        let mut users: Vec<User> = Vec::new();
+        let _ = super::load_from_source(input.clone(), &[], false, 100, 3, EdgeListFormat::default(),
+                                        Some(selected_users), &[UserId::from(4)], DuplicateUserPolicy::Merge, true,
+                                        &mut |user: User, _friends: Vec<User>| users.push(user))
+            .expect("Failed to load the social graph");
+        users.sort();
+        assert_eq!(users, vec![User::new(1), User::new(4)]);
+
+        // With strict checking, loading fails if none of the given IDs is in the social graph.
+        let result = super::load_from_source(input, &[], false, 100, 3, EdgeListFormat::default(), None,
+                                             &[UserId::from(100), UserId::from(101)], DuplicateUserPolicy::Merge, true,
+                                             &mut |_user: User, _friends: Vec<User>| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn get_selected_users() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let selected_users = InputSource::new(data_path.join("root_users.txt").to_str().unwrap());
+
+        assert_eq!(super::get_selected_users(None, &[]).expect("Could not get the selected users"), None);
+
+        let selected_user_ids: Vec<UserId> = vec![UserId::from(4), UserId::from(4), UserId::from(0)];
+        let users: HashSet<UserId> = super::get_selected_users(None, &selected_user_ids)
+            .expect("Could not get the selected users")
+            .expect("No users selected");
+        assert_eq!(users, vec![UserId::from(0), UserId::from(4)].into_iter().collect());
+
+        let users: HashSet<UserId> = super::get_selected_users(Some(selected_users), &[UserId::from(4)])
+            .expect("Could not get the selected users")
+            .expect("No users selected");
+        assert_eq!(users, vec![UserId::from(1), UserId::from(4)].into_iter().collect());
+    }
+
+    #[test]
+    fn load_from_source_edge_list() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph_edge_list.tsv").to_str().unwrap());
+
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_source(input, &[], true, 100, 3, EdgeListFormat::default(),
+                                                                 None, &[], DuplicateUserPolicy::Fail, false,
+                                                                 &mut |user: User, friends: Vec<User>| {
+                                                                     let _ = graph.insert(user, friends);
+                                                                 })
+            .expect("Failed to load the social graph");
+
+        // No dummy users are created, since the claimed numbers of friends are not known.
+        assert_eq!(statistics.users, 5);
+        assert_eq!(statistics.given_friendships, 8);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+    }
+
+    #[test]
+    fn load_with_sort_friends() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+
+        // Dummy friends are appended to the given friends.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let _ = super::load_with(input.clone(), &[], true, 100, 3, EdgeListFormat::default(), None, &[],
+                                 DuplicateUserPolicy::Merge, 0, 0, false, false,
+                                 &mut |user: User, friends: Vec<User>| {
+                                     let _ = graph.insert(user, friends);
+                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(graph[&User::new(0)], vec![User::new(1), User::new(2), User::new(-1)]);
+        assert_eq!(graph[&User::new(2)].first(), Some(&User::new(0)));
+
+        // Sorted, the dummy friends come first.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let _ = super::load_with(input.clone(), &[], true, 100, 3, EdgeListFormat::default(), None, &[],
+                                 DuplicateUserPolicy::Merge, 0, 0, false, true,
+                                 &mut |user: User, friends: Vec<User>| {
+                                     let _ = graph.insert(user, friends);
+                                 })
+            .expect("Failed to load the social graph");
+        for friends in graph.values() {
+            assert!(friends.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        assert_eq!(graph[&User::new(0)], vec![User::new(-1), User::new(1), User::new(2)]);
+        assert_eq!(graph[&User::new(2)].len(), 42);
+        assert_eq!(graph[&User::new(2)].first(), Some(&User::new(-41)));
+        assert_eq!(graph[&User::new(2)].last(), Some(&User::new(0)));
+
+        // Expanded friend lists are sorted as well: user 3 reaches user 0 via their friend 2.
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let _ = super::load_with(input, &[], false, 100, 3, EdgeListFormat::default(), None, &[],
+                                 DuplicateUserPolicy::Merge, 1, 100, false, true,
+                                 &mut |user: User, friends: Vec<User>| {
+                                     let _ = graph.insert(user, friends);
+                                 })
+            .expect("Failed to load the social graph");
+        assert_eq!(graph[&User::new(3)], vec![User::new(0), User::new(2)]);
+    }
+
+    #[test]
+    fn load_sample() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let path: PathBuf = data_path.join("social_graph");
+
+        let sample: LoadStatistics = super::load_sample(InputSource::new(path.to_str().unwrap()), 100, 3,
+                                                        EdgeListFormat::default(), None, &[], 2)
+            .expect("Failed to load the sample");
+        assert_eq!(sample.users, 2);
+        assert!(sample.given_friendships > 0);
+        assert_eq!(sample.dummy_friendships, 0);
+
+        // Samples larger than the social graph contain the entire graph.
+        let input = InputSource::new(data_path.join("social_graph_edge_list.tsv").to_str().unwrap());
+        let sample: LoadStatistics = super::load_sample(input, 100, 3, EdgeListFormat::default(), None, &[], 100)
+            .expect("Failed to load the sample");
+        assert_eq!(sample.users, 5);
+        assert_eq!(sample.given_friendships, 8);
+
+        let sample: LoadStatistics = super::load_sample(InputSource::new(path.to_str().unwrap()), 100, 3,
+                                                        EdgeListFormat::default(), None, &[], 0)
+            .expect("Failed to load the sample");
+        assert_eq!(sample, LoadStatistics::default());
+
+        assert!(super::load_sample(InputSource::new("does_not_exist.tsv"), 100, 3, EdgeListFormat::default(), None, &[],
+                                   10)
+                    .is_err());
+    }
+
+    #[test]
+    fn load_from_source_s3_and_gcs() {
+        let input = InputSource::new("social_graph")
+            .s3(Some(S3::new("bucket", "region")))
+            .gcs(Some(GCS::new("bucket")));
+        let result = super::load_from_source(input, &[], false, 100, 3,
+                                             EdgeListFormat::default(), None, &[], DuplicateUserPolicy::Merge, false,
+                                             &mut |_user: User, _friends: Vec<User>| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_from_object_store() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let mut archive: Vec<u8> = Vec::new();
+        let _ = File::open(data_path.join("social_graph/000/00.tar")).expect("Could not open the archive")
+            .read_to_end(&mut archive)
+            .expect("Could not read the archive");
+
+        // Objects that are not TAR archives are skipped.
+        let store = MemoryStore {
+            objects: vec![(String::from("00.tar"), archive), (String::from("README.md"), b"Friends".to_vec())],
+        };
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_object_store("", &store, false, 100, None, &[],
+                                                                       &mut |user: User, friends: Vec<User>| {
+                                                                           let _ = graph.insert(user, friends);
+                                                                           Ok(true)
+                                                                       })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 5);
+        assert_eq!(statistics.given_friendships, 8);
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+
+        // Objects outside the path are not listed.
+        let statistics: LoadStatistics = super::load_from_object_store("social_graph/", &store, false, 100, None,
+                                                                       &[],
+                                                                       &mut |_user: User, _friends: Vec<User>| {
+                                                                           Ok(true)
+                                                                       })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 0);
+    }
+
+    #[test]
+    fn load_from_s3_with_retries() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let mut archive: Vec<u8> = Vec::new();
+        let _ = File::open(data_path.join("social_graph/000/00.tar")).expect("Could not open the archive")
+            .read_to_end(&mut archive)
+            .expect("Could not read the archive");
+
+        // The listing and the download both fail twice with a server error before they succeed.
+        let bucket = FlakyBucket::new(archive.clone(), 503, 2);
+        let store = RetryingBucket {
+            bucket: &bucket,
+            max_retries: 3,
+            initial_backoff: 1,
+        };
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load_from_object_store("", &store, false, 100, None, &[],
+                                                                       &mut |user: User, friends: Vec<User>| {
+                                                                           let _ = graph.insert(user, friends);
+                                                                           Ok(true)
+                                                                       })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 5);
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+        assert_eq!(bucket.requests.get(), 6);
+
+        // Throttled requests fail once all retries have been used up.
+        let bucket = FlakyBucket::new(archive.clone(), 429, 2);
+        let store = RetryingBucket {
+            bucket: &bucket,
+            max_retries: 1,
+            initial_backoff: 1,
+        };
+        assert!(super::load_from_object_store("", &store, false, 100, None, &[],
+                                              &mut |_user: User, _friends: Vec<User>| -> Result<bool> { Ok(true) })
+            .is_err());
+        assert_eq!(bucket.requests.get(), 2);
+
+        // Denied access and missing objects fail immediately.
+        for &code in &[403, 404] {
+            let bucket = FlakyBucket::new(archive.clone(), code, 1);
+            let store = RetryingBucket {
+                bucket: &bucket,
+                max_retries: 3,
+                initial_backoff: 1,
+            };
+            assert!(super::load_from_object_store("", &store, false, 100, None, &[],
+                                                  &mut |_user: User, _friends: Vec<User>| -> Result<bool> { Ok(true) })
+                .is_err());
+            assert_eq!(bucket.requests.get(), 1);
+        }
+    }
+
+    #[test]
+    fn is_transient_s3_error() {
+        assert!(super::is_transient_s3_error(429));
+        assert!(super::is_transient_s3_error(500));
+        assert!(super::is_transient_s3_error(503));
+        assert!(!super::is_transient_s3_error(200));
+        assert!(!super::is_transient_s3_error(403));
+        assert!(!super::is_transient_s3_error(404));
     }
 
     #[test]
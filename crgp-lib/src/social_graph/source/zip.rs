@@ -0,0 +1,117 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Load the social graph from a single ZIP archive.
+//!
+//! The archive contains the friend files in the same `NNN/NNN/friends[ID].csv` layout as the TAR archives.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Error as IOError;
+use std::path::PathBuf;
+
+use zip::ZipArchive;
+
+use Error;
+use Result;
+use UserId;
+use configuration::InputSource;
+use social_graph::source::LoadStatistics;
+use social_graph::source::tar::get_selected_users;
+use social_graph::source::tar::load_friend_file;
+use twitter::User;
+
+/// Load the social graph from the ZIP archive at the given local `path`, passing each user and their friends to
+/// `send`. If required, dummy users will be created. Claimed numbers of friends larger than `max_expected_friends`
+/// will be clamped to this value.
+pub fn load<F>(path: &PathBuf,
+               pad_with_dummy_users: bool,
+               max_expected_friends: u64,
+               selected_users_file: Option<InputSource>,
+               selected_user_ids: &[UserId],
+               send: &mut F
+    ) -> Result<LoadStatistics>
+    where F: FnMut(User, Vec<User>) -> Result<bool>
+{
+    // Get a set of selected users to load from the social graph. If `None`, the entire social graph will be loaded.
+    let selected_users: Option<HashSet<UserId>> = get_selected_users(selected_users_file, selected_user_ids)?;
+
+    let mut statistics = LoadStatistics::default();
+
+    // Open the archive.
+    let mut archive: ZipArchive<File> = match ZipArchive::new(File::open(path)?) {
+        Ok(archive) => archive,
+        Err(message) => {
+            error!("Could not read contents of archive {archive}: {error}",
+                   archive = path.display(), error = message);
+            return Err(Error::from(IOError::from(message)));
+        }
+    };
+
+    // Friend files.
+    for index in 0..archive.len() {
+        // Ensure correct reading.
+        let file = match archive.by_index(index) {
+            Ok(file) => file,
+            Err(message) => {
+                error!("Could not read archived file in archive {archive}: {error}",
+                       archive = path.display(), error = message);
+                continue;
+            }
+        };
+
+        let friends_path = PathBuf::from(file.name());
+        load_friend_file(file, &friends_path, &selected_users, pad_with_dummy_users, max_expected_friends,
+                         &mut statistics, send)?;
+    }
+
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use Result;
+    use social_graph::source::LoadStatistics;
+    use twitter::User;
+
+    #[test]
+    fn load() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let path: PathBuf = data_path.join("social_graph.zip");
+
+        let mut graph: HashMap<User, Vec<User>> = HashMap::new();
+        let statistics: LoadStatistics = super::load(&path, false, 100, None, &[],
+                                                     &mut |user: User, friends: Vec<User>| -> Result<bool> {
+                                                         let _ = graph.insert(user, friends);
+                                                         Ok(true)
+                                                     })
+            .expect("Failed to load the social graph");
+
+        // The archive contains the same friend files as the TAR archives in `data/social_graph`.
+        assert_eq!(statistics.users, 5);
+        assert_eq!(statistics.given_friendships, 8);
+        assert_eq!(statistics.expected_friendships, 50);
+        assert_eq!(statistics.dummy_friendships, 0);
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph[&User::new(0)], vec![User::new(1), User::new(2)]);
+        assert_eq!(graph[&User::new(1)], vec![User::new(0), User::new(2), User::new(3)]);
+        assert_eq!(graph[&User::new(2)], vec![User::new(0)]);
+        assert_eq!(graph[&User::new(3)], vec![User::new(2)]);
+        assert_eq!(graph[&User::new(4)], vec![User::new(2)]);
+
+        // Pad the friend lists with dummy users.
+        let statistics: LoadStatistics = super::load(&path, true, 100, None, &[],
+                                                     &mut |_user: User, _friends: Vec<User>| -> Result<bool> {
+                                                         Ok(true)
+                                                     })
+            .expect("Failed to load the social graph");
+        assert_eq!(statistics.users, 6);
+        assert_eq!(statistics.dummy_friendships, 43);
+    }
+}
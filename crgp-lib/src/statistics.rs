@@ -9,18 +9,141 @@
 use std::fmt;
 
 use Configuration;
+use UserId;
+use reconstruction::TopCascade;
 
 /// Collection of statistics about the execution of the algorithm.
 ///
 /// Times are given in nanoseconds.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Statistics {
-    /// Number of friendships in the social graph.
+    /// Number of friendships in the social graph, including friendships with dummy users.
     pub number_of_friendships: u64,
 
+    /// Number of friendships explicitly given in the social graph data set.
+    pub number_of_real_friendships: u64,
+
+    /// Number of friendships with dummy users created to pad the social graph.
+    pub number_of_dummy_friendships: u64,
+
+    /// Number of friend files found for users who already had a friend file in the social graph.
+    pub number_of_duplicate_users: u64,
+
     /// Number of retweets processed.
     pub number_of_retweets: u64,
 
+    /// Number of Retweets dropped while loading since they lack the ID of their original Tweet, i.e. of their cascade.
+    ///
+    /// Only the Retweets loaded in the first process are counted.
+    pub retweets_missing_cascade: u64,
+
+    /// Number of processed Retweets skipped since their original Tweet has not been posted by any of the
+    /// `Configuration::seed_authors`. These Retweets are included in the `number_of_retweets`.
+    ///
+    /// Only the Retweets loaded in the first process are counted.
+    pub retweets_not_seeded: u64,
+
+    /// Number of Tweets skipped while loading since they are not Retweets, e.g. original Tweets.
+    ///
+    /// Only the Tweets loaded in the first process are counted.
+    pub number_of_skipped_tweets: u64,
+
+    /// Number of influence edges produced by the reconstruction, i.e. passed to the output target.
+    ///
+    /// Only the first process counts the influence edges, since its first worker writes all of them.
+    pub number_of_influences: u64,
+
+    /// Number of influence edges produced by the reconstruction, but not written since the
+    /// `Configuration::max_output_edges` had been reached. These edges are included in the `number_of_influences`.
+    ///
+    /// Only the first process counts the skipped influence edges, since its first worker writes all of them.
+    pub number_of_skipped_influences: u64,
+
+    /// Number of influence edges from a user to themselves which have not been produced.
+    ///
+    /// Only the suppressed edges of the workers within this process are counted.
+    pub self_edges_suppressed: u64,
+
+    /// The maximum number of cascades whose activations have been kept in memory at the same time on any worker.
+    ///
+    /// Only the workers within this process are considered. All algorithms drop the activations of complete cascades
+    /// (see `Configuration::cascade_timeout`).
+    pub peak_live_cascades: u64,
+
+    /// Number of Retweets that have started a cascade anew, since they arrived after the cascade had timed out and its
+    /// activations had been dropped (see `Configuration::cascade_timeout`). Influences by users activated before the
+    /// timeout are not found for these Retweets.
+    ///
+    /// Only the Retweets of users owned by the workers within this process are counted. `LEAF` does not count them.
+    pub late_retweets: u64,
+
+    /// The widest fan-out of any user in any cascade, i.e. the largest number of influence edges from a single user
+    /// within a single cascade. `0` if there are no influence edges.
+    ///
+    /// Only the workers within this process are considered.
+    pub widest_fan_out: u64,
+
+    /// The user with the widest fan-out. `0` if there are no influence edges.
+    pub widest_fan_out_influencer: UserId,
+
+    /// The cascade in which the widest fan-out occurs. `0` if there are no influence edges.
+    pub widest_fan_out_cascade: u64,
+
+    /// Number of friendships in the social graph used to produce at least one influence edge. `0` unless tracked (see
+    /// `Configuration::track_friendship_utilization`).
+    pub used_friendships: u64,
+
+    /// Number of friendships in the social graph never used to produce an influence edge. `0` unless tracked.
+    pub unused_friendships: u64,
+
+    /// Share of the used friendships among all friendships in the social graph, from `0.0` to `1.0`. `0.0` unless
+    /// tracked.
+    pub friendship_utilization: f64,
+
+    /// The estimated median size of the completed cascades, i.e. of their numbers of influence edges. `0` unless
+    /// estimated (see `Configuration::cascade_size_quantiles`).
+    ///
+    /// The quantiles are estimated from the cascades reported as complete by `CompleteCascades`. Thus, with the
+    /// default `cascade_timeout` of `None`, all cascades are only complete, and their sizes only known, once all
+    /// Retweets have been processed. The sizes are only known in process `0`; all other processes report `0`.
+    pub cascade_size_p50: u64,
+
+    /// The estimated 90th percentile of the sizes of the completed cascades. `0` unless estimated.
+    pub cascade_size_p90: u64,
+
+    /// The estimated 99th percentile of the sizes of the completed cascades. `0` unless estimated.
+    pub cascade_size_p99: u64,
+
+    /// The number of Retweets for which `GALE` iterated over the retweeting user's friends to find the influencers,
+    /// since they were fewer than the activations of the cascade. `LEAF` always iterates over the friends, thus it
+    /// counts each Retweet of a user with a friend list.
+    ///
+    /// Only the workers within this process are considered.
+    pub friend_iterations: u64,
+
+    /// The mean number of friends iterated in each of the `friend_iterations`. `0.0` if there are none.
+    pub mean_iterated_friends: f64,
+
+    /// The number of Retweets for which `GALE` iterated over the activations of the cascade to find the influencers,
+    /// since they were fewer than the retweeting user's friends.
+    pub activation_iterations: u64,
+
+    /// The mean number of activations iterated in each of the `activation_iterations`. `0.0` if there are none.
+    pub mean_iterated_activations: f64,
+
+    /// The cascades with the most Retweets processed, largest first. Empty unless requested (see
+    /// `Configuration::report_top_cascades`).
+    pub top_cascades: Vec<TopCascade>,
+
+    /// The SHA-256 hash (in hexadecimal) of all bytes written to the result file in the first output directory. Empty
+    /// unless hashed (see `Configuration::hash_output`).
+    ///
+    /// Only the result file written by the first process is hashed.
+    pub output_sha256: String,
+
+    /// The number of bytes written to the result file in the first output directory. `0` unless hashed.
+    pub output_bytes: u64,
+
     /// Time to set up the computation (in `ns`).
     pub time_to_setup: u64,
 
@@ -33,17 +156,35 @@ pub struct Statistics {
     /// Time to process the retweets (in `ns`).
     pub time_to_process_retweets: u64,
 
+    /// Time to process the retweets in each repetition (in `ns`). The first entry equals `time_to_process_retweets`.
+    pub retweet_processing_times: Vec<u64>,
+
+    /// Time to process each batch of Retweets in the first run, i.e. to pass it into the computation and wait until it
+    /// has been processed (in `ns`). Only the first worker, which passes in the Retweets, measures the batches.
+    pub batch_latencies: Vec<u64>,
+
     /// Total time of the computation (in `ns`).
     pub total_time: u64,
 
     /// Average Retweet processing rate in Retweets per seconds (`RT/s`).
     ///
-    /// This field will automatically be set whenever `number_of_retweets` or `time_to_process_retweets` are set.
+    /// This field will automatically be set whenever `number_of_retweets` or `time_to_process_retweets` are set. If
+    /// processing the Retweets took no measurable time, i.e. if `time_to_process_retweets` is `0`, the rate is `0`.
     pub retweet_processing_rate: u64,
 
     /// The algorithm used for reconstruction.
+    ///
+    /// `Channel` output targets are replaced by `OutputTarget::None`, such that the statistics do not keep the channels
+    /// open.
     pub configuration: Configuration,
 
+    /// `true` if the computation has been cancelled before all Retweets were processed.
+    pub cancelled: bool,
+
+    /// `true` if the inputs have only been validated instead of running the computation (see
+    /// `Configuration::dry_run`). The counts then refer to the validated samples, all times are `0`.
+    pub was_dry_run: bool,
+
     /// Private field to prevent initialization without the provided methods.
     ///
     /// All other fields should be public for easy access without getter functions. However, adding more fields later
@@ -53,36 +194,225 @@ pub struct Statistics {
 }
 
 impl Statistics {
-    /// Initialize default statistics.
-    pub fn new(configuration: Configuration) -> Statistics {
+    /// Initialize default statistics for a computation with the given `configuration`.
+    ///
+    /// All counts and times are `0`. They are set using the builder methods of the same name, e.g.:
+    ///
+    /// ```
+    /// use crgp_lib::Configuration;
+    /// use crgp_lib::Statistics;
+    /// use crgp_lib::configuration::InputSource;
+    ///
+    /// let configuration = Configuration::default(InputSource::new("path/to/retweets.json"),
+    ///                                            InputSource::new("path/to/social/graph"));
+    /// let statistics = Statistics::new(configuration)
+    ///     .number_of_retweets(3)
+    ///     .time_to_setup(1_000_000_000)
+    ///     .time_to_process_retweets(2_000_000_000)
+    ///     .total_time(3_000_000_000);
+    ///
+    /// assert_eq!(statistics.retweet_processing_rate, 1);
+    /// assert!(statistics.is_consistent());
+    /// ```
+    ///
+    /// The Retweet processing rate is derived from the number of Retweets and the time to process them and never has
+    /// to be set explicitly. Once all values have been set, the statistics must be consistent (see `is_consistent`).
+    pub fn new(mut configuration: Configuration) -> Statistics {
+        configuration.output_target = configuration.output_target.without_channels();
         Statistics {
             configuration: configuration,
+            cancelled: false,
+            was_dry_run: false,
             number_of_friendships: 0,
+            number_of_real_friendships: 0,
+            number_of_dummy_friendships: 0,
+            number_of_duplicate_users: 0,
             number_of_retweets: 0,
+            retweets_missing_cascade: 0,
+            retweets_not_seeded: 0,
+            number_of_skipped_tweets: 0,
+            number_of_influences: 0,
+            number_of_skipped_influences: 0,
+            self_edges_suppressed: 0,
+            peak_live_cascades: 0,
+            late_retweets: 0,
+            widest_fan_out: 0,
+            widest_fan_out_influencer: UserId::from(0),
+            widest_fan_out_cascade: 0,
+            used_friendships: 0,
+            unused_friendships: 0,
+            friendship_utilization: 0.0,
+            cascade_size_p50: 0,
+            cascade_size_p90: 0,
+            cascade_size_p99: 0,
+            friend_iterations: 0,
+            mean_iterated_friends: 0.0,
+            activation_iterations: 0,
+            mean_iterated_activations: 0.0,
+            top_cascades: Vec::new(),
+            output_sha256: String::new(),
+            output_bytes: 0,
             time_to_setup: 0,
             time_to_process_social_graph: 0,
             time_to_load_retweets: 0,
             time_to_process_retweets: 0,
+            retweet_processing_times: Vec::new(),
+            batch_latencies: Vec::new(),
             total_time: 0,
             retweet_processing_rate: 0,
             _prevent_outside_initialization: true
         }
     }
 
+    /// Flag the computation as cancelled.
+    pub fn cancelled(mut self, cancelled: bool) -> Statistics {
+        self.cancelled = cancelled;
+        self
+    }
+
+    /// Flag the statistics as those of a dry run.
+    pub fn was_dry_run(mut self, was_dry_run: bool) -> Statistics {
+        self.was_dry_run = was_dry_run;
+        self
+    }
+
     /// Set the number of friendships in the social graph.
     pub fn number_of_friendships(mut self, number_of_friendships: u64) -> Statistics {
         self.number_of_friendships = number_of_friendships;
         self
     }
 
+    /// Set the number of friendships explicitly given in the social graph data set.
+    pub fn number_of_real_friendships(mut self, number_of_real_friendships: u64) -> Statistics {
+        self.number_of_real_friendships = number_of_real_friendships;
+        self
+    }
+
+    /// Set the number of friendships with dummy users created to pad the social graph.
+    pub fn number_of_dummy_friendships(mut self, number_of_dummy_friendships: u64) -> Statistics {
+        self.number_of_dummy_friendships = number_of_dummy_friendships;
+        self
+    }
+
+    /// Set the number of friend files found for users who already had a friend file in the social graph.
+    pub fn number_of_duplicate_users(mut self, number_of_duplicate_users: u64) -> Statistics {
+        self.number_of_duplicate_users = number_of_duplicate_users;
+        self
+    }
+
     /// Set the total number of retweets processed.
     ///
-    /// Also automatically sets the Retweet processing rate (if the Retweet processing rate is not `0`).
+    /// Also automatically sets the Retweet processing rate.
     pub fn number_of_retweets(mut self, number_of_retwets: u64) -> Statistics {
         self.number_of_retweets = number_of_retwets;
-        if self.retweet_processing_rate != 0 {
-            self.calculate_retweet_processing_rate();
-        }
+        self.calculate_retweet_processing_rate();
+        self
+    }
+
+    /// Set the number of Retweets dropped while loading since they lack the ID of their original Tweet.
+    pub fn retweets_missing_cascade(mut self, retweets_missing_cascade: u64) -> Statistics {
+        self.retweets_missing_cascade = retweets_missing_cascade;
+        self
+    }
+
+    /// Set the number of processed Retweets skipped since their original Tweet has not been posted by a seed author.
+    pub fn retweets_not_seeded(mut self, retweets_not_seeded: u64) -> Statistics {
+        self.retweets_not_seeded = retweets_not_seeded;
+        self
+    }
+
+    /// Set the number of Tweets skipped while loading since they are not Retweets.
+    pub fn number_of_skipped_tweets(mut self, number_of_skipped_tweets: u64) -> Statistics {
+        self.number_of_skipped_tweets = number_of_skipped_tweets;
+        self
+    }
+
+    /// Set the number of influence edges produced by the reconstruction.
+    pub fn number_of_influences(mut self, number_of_influences: u64) -> Statistics {
+        self.number_of_influences = number_of_influences;
+        self
+    }
+
+    /// Set the number of influence edges not written since the maximum number of output edges had been reached.
+    pub fn number_of_skipped_influences(mut self, number_of_skipped_influences: u64) -> Statistics {
+        self.number_of_skipped_influences = number_of_skipped_influences;
+        self
+    }
+
+    /// Set the number of influence edges from a user to themselves which have not been produced.
+    pub fn self_edges_suppressed(mut self, self_edges_suppressed: u64) -> Statistics {
+        self.self_edges_suppressed = self_edges_suppressed;
+        self
+    }
+
+    /// Set the maximum number of cascades whose activations have been kept in memory at the same time.
+    pub fn peak_live_cascades(mut self, peak_live_cascades: u64) -> Statistics {
+        self.peak_live_cascades = peak_live_cascades;
+        self
+    }
+
+    /// Set the number of Retweets that have started a cascade anew after it had timed out.
+    pub fn late_retweets(mut self, late_retweets: u64) -> Statistics {
+        self.late_retweets = late_retweets;
+        self
+    }
+
+    /// Set the widest fan-out of any user in any cascade: the `influencer` has `fan_out` influence edges in the cascade
+    /// `cascade_id`.
+    pub fn widest_fan_out(mut self, cascade_id: u64, influencer: UserId, fan_out: u64) -> Statistics {
+        self.widest_fan_out = fan_out;
+        self.widest_fan_out_influencer = influencer;
+        self.widest_fan_out_cascade = cascade_id;
+        self
+    }
+
+    /// Set the number of `friendships` in the social graph and how many of them have been used to produce influence
+    /// edges (`used_friendships`).
+    ///
+    /// Also sets the number of unused friendships and the friendship utilization.
+    pub fn friendship_utilization(mut self, friendships: u64, used_friendships: u64) -> Statistics {
+        self.used_friendships = used_friendships;
+        self.unused_friendships = friendships.saturating_sub(used_friendships);
+        self.friendship_utilization = if friendships == 0 {
+            0.0
+        } else {
+            used_friendships as f64 / friendships as f64
+        };
+        self
+    }
+
+    /// Set the estimated 50th (`p50`), 90th (`p90`), and 99th (`p99`) percentile of the cascade sizes.
+    pub fn cascade_size_quantiles(mut self, p50: u64, p90: u64, p99: u64) -> Statistics {
+        self.cascade_size_p50 = p50;
+        self.cascade_size_p90 = p90;
+        self.cascade_size_p99 = p99;
+        self
+    }
+
+    /// Set how often the friends have been iterated (`friend_iterations`) to find influencers, and the total number of
+    /// `iterated_friends`, as well as how often the activations have been iterated (`activation_iterations`), and the
+    /// total number of `iterated_activations`.
+    ///
+    /// Also sets the mean number of iterated friends and activations.
+    pub fn iterations(mut self, friend_iterations: u64, iterated_friends: u64, activation_iterations: u64,
+                      iterated_activations: u64) -> Statistics {
+        self.friend_iterations = friend_iterations;
+        self.mean_iterated_friends = mean(iterated_friends, friend_iterations);
+        self.activation_iterations = activation_iterations;
+        self.mean_iterated_activations = mean(iterated_activations, activation_iterations);
+        self
+    }
+
+    /// Set the cascades with the most Retweets, largest first.
+    pub fn top_cascades(mut self, top_cascades: Vec<TopCascade>) -> Statistics {
+        self.top_cascades = top_cascades;
+        self
+    }
+
+    /// Set the SHA-256 hash (`sha256`) of the result file and the number of `bytes` written to it.
+    pub fn output_hash(mut self, sha256: String, bytes: u64) -> Statistics {
+        self.output_sha256 = sha256;
+        self.output_bytes = bytes;
         self
     }
 
@@ -113,35 +443,153 @@ impl Statistics {
         self
     }
 
+    /// Set the times to process the retweets in each repetition (in nanoseconds).
+    pub fn retweet_processing_times(mut self, retweet_processing_times: Vec<u64>) -> Statistics {
+        self.retweet_processing_times = retweet_processing_times;
+        self
+    }
+
+    /// Set the times to process each batch of Retweets (in nanoseconds).
+    pub fn batch_latencies(mut self, batch_latencies: Vec<u64>) -> Statistics {
+        self.batch_latencies = batch_latencies;
+        self
+    }
+
     /// Set the total time it took the computation to finish (in nanoseconds).
     pub fn total_time(mut self, total_time: u64) -> Statistics {
         self.total_time = total_time;
         self
     }
 
+    /// Determine if the statistics are consistent: the total time must be at least the sum of the times of all
+    /// phases, i.e. setting up the computation, processing the social graph, and loading and processing the Retweets,
+    /// and the Retweet processing rate must match the number of Retweets and the time to process them.
+    pub fn is_consistent(&self) -> bool {
+        let time_of_phases: Option<u64> = self.time_to_setup.checked_add(self.time_to_process_social_graph)
+            .and_then(|time| time.checked_add(self.time_to_load_retweets))
+            .and_then(|time| time.checked_add(self.time_to_process_retweets));
+        let is_total_time_consistent: bool = match time_of_phases {
+            Some(time_of_phases) => self.total_time >= time_of_phases,
+            None => false
+        };
+
+        let is_rate_consistent: bool = self.retweet_processing_rate ==
+            retweet_processing_rate(self.number_of_retweets, self.time_to_process_retweets);
+
+        is_total_time_consistent && is_rate_consistent
+    }
+
+    /// Get the share of friendships with dummy users among all friendships, from `0.0` to `1.0`.
+    ///
+    /// If there are no friendships at all, the share will be `0.0`.
+    pub fn dummy_friendship_ratio(&self) -> f64 {
+        let total_friendships: u64 = self.number_of_real_friendships + self.number_of_dummy_friendships;
+        if total_friendships == 0 {
+            0.0
+        } else {
+            self.number_of_dummy_friendships as f64 / total_friendships as f64
+        }
+    }
+
+    /// Get the mean time to process the retweets over all repetitions (in nanoseconds).
+    ///
+    /// If there are no repetitions, the mean will be `0.0`.
+    pub fn mean_time_to_process_retweets(&self) -> f64 {
+        if self.retweet_processing_times.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f64 = self.retweet_processing_times.iter().map(|&time| time as f64).sum();
+        sum / self.retweet_processing_times.len() as f64
+    }
+
+    /// Get the median time to process the retweets over all repetitions (in nanoseconds).
+    ///
+    /// For an even number of repetitions, this is the mean of the two middle times. If there are no repetitions, the
+    /// median will be `0.0`.
+    pub fn median_time_to_process_retweets(&self) -> f64 {
+        let mut times: Vec<u64> = self.retweet_processing_times.clone();
+        times.sort();
+
+        let middle: usize = times.len() / 2;
+        if times.is_empty() {
+            0.0
+        } else if times.len() % 2 == 0 {
+            (times[middle - 1] as f64 + times[middle] as f64) / 2.0
+        } else {
+            times[middle] as f64
+        }
+    }
+
+    /// Get the (population) standard deviation of the times to process the retweets over all repetitions (in
+    /// nanoseconds).
+    ///
+    /// If there are no repetitions, the standard deviation will be `0.0`.
+    pub fn standard_deviation_of_time_to_process_retweets(&self) -> f64 {
+        if self.retweet_processing_times.is_empty() {
+            return 0.0;
+        }
+
+        let mean: f64 = self.mean_time_to_process_retweets();
+        let squared_deviations: f64 = self.retweet_processing_times.iter()
+            .map(|&time| (time as f64 - mean).powi(2))
+            .sum();
+        (squared_deviations / self.retweet_processing_times.len() as f64).sqrt()
+    }
+
+    /// Get the Retweet processing rate in Retweets per seconds (RT/s), based on the mean time to process the retweets
+    /// over all repetitions.
+    ///
+    /// If the mean time is `0`, the rate will be `0.0` as well.
+    pub fn mean_retweet_processing_rate(&self) -> f64 {
+        let mean: f64 = self.mean_time_to_process_retweets();
+        if mean == 0.0 {
+            0.0
+        } else {
+            self.number_of_retweets as f64 * 1_000_000_000.0 / mean
+        }
+    }
+
     /// Set the average Retweet processing rate in Retweets per seconds (RT/s).
     ///
     /// If the time it took to process the retweets is 0, the rate will be set to 0 as well.
     fn calculate_retweet_processing_rate(&mut self) {
-        self.retweet_processing_rate = if self.time_to_process_retweets == 0 {
-            0
-        } else {
-            (self.number_of_retweets * 1_000_000_000) / self.time_to_process_retweets
-        };
+        self.retweet_processing_rate = retweet_processing_rate(self.number_of_retweets, self.time_to_process_retweets);
+    }
+}
+
+/// Get the Retweet processing rate in Retweets per seconds (RT/s) for the `number_of_retweets` processed within
+/// `time_to_process_retweets` (in nanoseconds). If the time is 0, the rate will be 0 as well.
+fn retweet_processing_rate(number_of_retweets: u64, time_to_process_retweets: u64) -> u64 {
+    if time_to_process_retweets == 0 {
+        0
+    } else {
+        (number_of_retweets * 1_000_000_000) / time_to_process_retweets
+    }
+}
+
+/// Get the mean of `count` values summing up to `total`. If there are no values, the mean will be 0.
+fn mean(total: u64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total as f64 / count as f64
     }
 }
 
 impl fmt::Display for Statistics {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(formatter,
-               "(Number of Friendships: {friendships}, Number of Retweets: {retweets}, Time to Set Up: {setup}ns, \
+               "(Number of Friendships: {friendships}, Number of Retweets: {retweets}, \
+                Number of Influences: {influences}, Time to Set Up: {setup}ns, \
                 Time to Process Social Graph: {graph}ns, Time to Load Retweets: {retweet_loading}ns, \
                 Time to Process Retweets: {retweet_processing}ns, Total Time: {total}ns, \
-                Retweet Processing Rate: {rate}RT/s, Configuration: {configuration})",
-               friendships = self.number_of_friendships, retweets = self.number_of_retweets, setup = self.time_to_setup,
+                Retweet Processing Rate: {rate}RT/s, Cancelled: {cancelled}, Configuration: {configuration})",
+               friendships = self.number_of_friendships, retweets = self.number_of_retweets,
+               influences = self.number_of_influences, setup = self.time_to_setup,
                graph = self.time_to_process_social_graph, retweet_loading = self.time_to_load_retweets,
                retweet_processing = self.time_to_process_retweets, total = self.total_time,
-               rate = self.retweet_processing_rate, configuration = self.configuration)
+               rate = self.retweet_processing_rate, cancelled = self.cancelled, configuration = self.configuration)
     }
 }
 
@@ -159,17 +607,105 @@ mod tests {
 
         let statistics = Statistics::new(configuration.clone());
         assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.cancelled, false);
+        assert_eq!(statistics.was_dry_run, false);
         assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_real_friendships, 0);
+        assert_eq!(statistics.number_of_dummy_friendships, 0);
+        assert_eq!(statistics.number_of_duplicate_users, 0);
         assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.retweets_missing_cascade, 0);
+        assert_eq!(statistics.retweets_not_seeded, 0);
+        assert_eq!(statistics.number_of_skipped_tweets, 0);
+        assert_eq!(statistics.number_of_influences, 0);
+        assert_eq!(statistics.number_of_skipped_influences, 0);
+        assert_eq!(statistics.self_edges_suppressed, 0);
+        assert_eq!(statistics.peak_live_cascades, 0);
+        assert_eq!(statistics.late_retweets, 0);
+        assert_eq!(statistics.widest_fan_out, 0);
+        assert_eq!(statistics.widest_fan_out_influencer, UserId::from(0));
+        assert_eq!(statistics.widest_fan_out_cascade, 0);
+        assert_eq!(statistics.used_friendships, 0);
+        assert_eq!(statistics.unused_friendships, 0);
+        assert_eq!(statistics.friendship_utilization, 0.0);
+        assert_eq!(statistics.cascade_size_p50, 0);
+        assert_eq!(statistics.cascade_size_p90, 0);
+        assert_eq!(statistics.cascade_size_p99, 0);
+        assert_eq!(statistics.friend_iterations, 0);
+        assert_eq!(statistics.mean_iterated_friends, 0.0);
+        assert_eq!(statistics.activation_iterations, 0);
+        assert_eq!(statistics.mean_iterated_activations, 0.0);
+        assert!(statistics.top_cascades.is_empty());
+        assert!(statistics.output_sha256.is_empty());
+        assert_eq!(statistics.output_bytes, 0);
         assert_eq!(statistics.time_to_setup, 0);
         assert_eq!(statistics.time_to_process_social_graph, 0);
         assert_eq!(statistics.time_to_load_retweets, 0);
         assert_eq!(statistics.time_to_process_retweets, 0);
+        assert!(statistics.retweet_processing_times.is_empty());
+        assert!(statistics.batch_latencies.is_empty());
         assert_eq!(statistics.total_time, 0);
         assert_eq!(statistics.retweet_processing_rate, 0);
         assert!(statistics._prevent_outside_initialization);
     }
 
+    #[test]
+    fn new_without_channels() {
+        use std::sync::mpsc::channel;
+        use configuration::ChannelOutput;
+        use configuration::OutputTarget;
+        use social_graph::InfluenceEdge;
+
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let (sender, receiver) = channel::<InfluenceEdge<UserId>>();
+        let configuration = Configuration::default(retweets, social_graph)
+            .output_target(OutputTarget::Channel(ChannelOutput::new(sender)));
+
+        // The statistics do not keep the sender, thus the channel is closed once the configuration has been dropped.
+        let statistics = Statistics::new(configuration);
+        assert_eq!(statistics.configuration.output_target, OutputTarget::None);
+        assert!(receiver.iter().next().is_none());
+    }
+
+    #[test]
+    fn cancelled() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .cancelled(true);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.cancelled, true);
+        assert!(format!("{}", statistics).contains("Cancelled: true"));
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.time_to_setup, 0);
+        assert_eq!(statistics.time_to_process_social_graph, 0);
+        assert_eq!(statistics.time_to_load_retweets, 0);
+        assert_eq!(statistics.time_to_process_retweets, 0);
+        assert_eq!(statistics.total_time, 0);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn was_dry_run() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .was_dry_run(true);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.cancelled, false);
+        assert_eq!(statistics.was_dry_run, true);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.total_time, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
     #[test]
     fn number_of_friendships() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -190,6 +726,125 @@ mod tests {
         assert!(statistics._prevent_outside_initialization);
     }
 
+    #[test]
+    fn number_of_real_friendships() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_real_friendships(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_real_friendships, 42);
+        assert_eq!(statistics.number_of_dummy_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn number_of_dummy_friendships() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_dummy_friendships(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_real_friendships, 0);
+        assert_eq!(statistics.number_of_dummy_friendships, 42);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn number_of_duplicate_users() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_duplicate_users(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_duplicate_users, 42);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn retweets_missing_cascade() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .retweets_missing_cascade(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.retweets_missing_cascade, 42);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn retweets_not_seeded() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .retweets_not_seeded(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.retweets_not_seeded, 42);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn number_of_skipped_tweets() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_skipped_tweets(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.number_of_skipped_tweets, 42);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn output_hash() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let sha256: String = String::from("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        let statistics = Statistics::new(configuration.clone())
+            .output_hash(sha256.clone(), 42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.output_sha256, sha256);
+        assert_eq!(statistics.output_bytes, 42);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn dummy_friendship_ratio() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone());
+        assert_eq!(statistics.dummy_friendship_ratio(), 0.0);
+
+        let statistics = statistics
+            .number_of_real_friendships(3)
+            .number_of_dummy_friendships(1);
+        assert_eq!(statistics.dummy_friendship_ratio(), 0.25);
+    }
+
     #[test]
     fn number_of_retweets() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -216,6 +871,185 @@ mod tests {
         assert_eq!(statistics.retweet_processing_rate, 1_000_000_000);
     }
 
+    #[test]
+    fn number_of_influences() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_influences(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.number_of_influences, 42);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn number_of_skipped_influences() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_skipped_influences(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_influences, 0);
+        assert_eq!(statistics.number_of_skipped_influences, 42);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn self_edges_suppressed() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .self_edges_suppressed(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.self_edges_suppressed, 42);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn peak_live_cascades() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .peak_live_cascades(42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.self_edges_suppressed, 0);
+        assert_eq!(statistics.peak_live_cascades, 42);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn late_retweets() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .late_retweets(3);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.peak_live_cascades, 0);
+        assert_eq!(statistics.late_retweets, 3);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn friendship_utilization() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .friendship_utilization(8, 2);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.used_friendships, 2);
+        assert_eq!(statistics.unused_friendships, 6);
+        assert_eq!(statistics.friendship_utilization, 0.25);
+        assert!(statistics._prevent_outside_initialization);
+
+        // Without friendships, nothing is used.
+        let statistics = Statistics::new(configuration.clone())
+            .friendship_utilization(0, 0);
+        assert_eq!(statistics.unused_friendships, 0);
+        assert_eq!(statistics.friendship_utilization, 0.0);
+    }
+
+    #[test]
+    fn cascade_size_quantiles() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .cascade_size_quantiles(3, 17, 42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.cascade_size_p50, 3);
+        assert_eq!(statistics.cascade_size_p90, 17);
+        assert_eq!(statistics.cascade_size_p99, 42);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn iterations() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .iterations(4, 10, 2, 3);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.friend_iterations, 4);
+        assert_eq!(statistics.mean_iterated_friends, 2.5);
+        assert_eq!(statistics.activation_iterations, 2);
+        assert_eq!(statistics.mean_iterated_activations, 1.5);
+        assert!(statistics._prevent_outside_initialization);
+
+        // Without iterations, the means are zero.
+        let statistics = Statistics::new(configuration)
+            .iterations(0, 0, 0, 0);
+        assert_eq!(statistics.mean_iterated_friends, 0.0);
+        assert_eq!(statistics.mean_iterated_activations, 0.0);
+    }
+
+    #[test]
+    fn top_cascades() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let top_cascades = vec![
+            TopCascade { cascade_id: 2, root_user: UserId::from(1), retweets: 7 },
+            TopCascade { cascade_id: 1, root_user: UserId::from(0), retweets: 3 },
+        ];
+        let statistics = Statistics::new(configuration.clone())
+            .top_cascades(top_cascades.clone());
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.top_cascades, top_cascades);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn widest_fan_out() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .widest_fan_out(3, UserId::from(7), 42);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_friendships, 0);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.peak_live_cascades, 0);
+        assert_eq!(statistics.widest_fan_out, 42);
+        assert_eq!(statistics.widest_fan_out_influencer, UserId::from(7));
+        assert_eq!(statistics.widest_fan_out_cascade, 3);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
     #[test]
     fn time_to_setup() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -298,6 +1132,61 @@ mod tests {
         assert!(statistics._prevent_outside_initialization);
     }
 
+    #[test]
+    fn retweet_processing_times() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .retweet_processing_times(vec![42, 21]);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.number_of_retweets, 0);
+        assert_eq!(statistics.time_to_process_retweets, 0);
+        assert_eq!(statistics.retweet_processing_times, vec![42, 21]);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn batch_latencies() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone())
+            .batch_latencies(vec![42, 21, 84]);
+        assert_eq!(statistics.configuration, configuration);
+        assert_eq!(statistics.time_to_process_retweets, 0);
+        assert!(statistics.retweet_processing_times.is_empty());
+        assert_eq!(statistics.batch_latencies, vec![42, 21, 84]);
+        assert!(statistics._prevent_outside_initialization);
+    }
+
+    #[test]
+    fn retweet_processing_time_aggregates() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone());
+        assert_eq!(statistics.mean_time_to_process_retweets(), 0.0);
+        assert_eq!(statistics.median_time_to_process_retweets(), 0.0);
+        assert_eq!(statistics.standard_deviation_of_time_to_process_retweets(), 0.0);
+        assert_eq!(statistics.mean_retweet_processing_rate(), 0.0);
+
+        let statistics = statistics
+            .number_of_retweets(6)
+            .retweet_processing_times(vec![4_000_000_000, 1_000_000_000, 2_000_000_000, 1_000_000_000]);
+        assert_eq!(statistics.mean_time_to_process_retweets(), 2_000_000_000.0);
+        assert_eq!(statistics.median_time_to_process_retweets(), 1_500_000_000.0);
+        assert!((statistics.standard_deviation_of_time_to_process_retweets() - 1_224_744_871.391_589).abs() < 1.0);
+        assert_eq!(statistics.mean_retweet_processing_rate(), 3.0);
+
+        let statistics = statistics.retweet_processing_times(vec![3, 1, 2]);
+        assert_eq!(statistics.median_time_to_process_retweets(), 2.0);
+    }
+
     #[test]
     fn total_time() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -318,6 +1207,75 @@ mod tests {
         assert!(statistics._prevent_outside_initialization);
     }
 
+    #[test]
+    fn is_consistent() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        let statistics = Statistics::new(configuration.clone());
+        assert!(statistics.is_consistent());
+
+        let statistics = statistics
+            .time_to_setup(1)
+            .time_to_process_social_graph(2)
+            .time_to_load_retweets(3)
+            .number_of_retweets(8)
+            .time_to_process_retweets(4_000_000_000)
+            .total_time(4_000_000_006);
+        assert_eq!(statistics.retweet_processing_rate, 2);
+        assert!(statistics.is_consistent());
+
+        // The setting order does not matter for the Retweet processing rate.
+        let statistics = Statistics::new(configuration.clone())
+            .time_to_process_retweets(4_000_000_000)
+            .number_of_retweets(8)
+            .total_time(4_000_000_000);
+        assert_eq!(statistics.retweet_processing_rate, 2);
+        assert!(statistics.is_consistent());
+
+        // The total time is shorter than the phases.
+        let statistics = statistics.time_to_setup(1);
+        assert!(!statistics.is_consistent());
+
+        // The sum of the phases overflows.
+        let statistics = Statistics::new(configuration.clone())
+            .time_to_setup(u64::max_value())
+            .time_to_load_retweets(1)
+            .total_time(u64::max_value());
+        assert!(!statistics.is_consistent());
+
+        // A manually modified rate.
+        let mut statistics = Statistics::new(configuration.clone())
+            .number_of_retweets(8)
+            .time_to_process_retweets(4_000_000_000)
+            .total_time(4_000_000_000);
+        statistics.retweet_processing_rate = 3;
+        assert!(!statistics.is_consistent());
+    }
+
+    #[test]
+    fn is_consistent_without_retweets() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        // Processing zero Retweets takes time, but has a rate of zero.
+        let statistics = Statistics::new(configuration.clone())
+            .number_of_retweets(0)
+            .time_to_process_retweets(42)
+            .total_time(42);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics.is_consistent());
+
+        // Processing Retweets in no measurable time has a rate of zero as well.
+        let statistics = Statistics::new(configuration)
+            .number_of_retweets(42)
+            .time_to_process_retweets(0);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert!(statistics.is_consistent());
+    }
+
     #[test]
     fn retweet_processing_rate() {
         let retweets = InputSource::new("path/to/retweets.json");
@@ -332,6 +1290,22 @@ mod tests {
         assert_eq!(statistics.retweet_processing_rate, 1);
     }
 
+    #[test]
+    fn retweet_processing_rate_without_time() {
+        let retweets = InputSource::new("path/to/retweets.json");
+        let social_graph = InputSource::new("path/to/social/graph");
+        let configuration = Configuration::default(retweets, social_graph);
+
+        // Tiny datasets might be processed faster than the stopwatch can measure.
+        let statistics = Statistics::new(configuration)
+            .number_of_retweets(3)
+            .time_to_process_retweets(0)
+            .retweet_processing_times(vec![0, 0]);
+        assert_eq!(statistics.retweet_processing_rate, 0);
+        assert_eq!(statistics.mean_retweet_processing_rate(), 0.0);
+        assert!(statistics.mean_retweet_processing_rate().is_finite());
+    }
+
     /// Old way of computing the Retweet processing rate.
     fn retweet_processing_rate_using_float(number_of_retweets: u64, time_to_process_retweets: u64) -> u64 {
         if time_to_process_retweets == 0 {
@@ -373,9 +1347,9 @@ mod tests {
 
         let statistics = Statistics::new(configuration.clone());
 
-        let fmt = "(Number of Friendships: 0, Number of Retweets: 0, Time to Set Up: 0ns, \
+        let fmt = "(Number of Friendships: 0, Number of Retweets: 0, Number of Influences: 0, Time to Set Up: 0ns, \
                    Time to Process Social Graph: 0ns, Time to Load Retweets: 0ns, Time to Process Retweets: 0ns, \
-                   Total Time: 0ns, Retweet Processing Rate: 0RT/s, Configuration: \
+                   Total Time: 0ns, Retweet Processing Rate: 0RT/s, Cancelled: false, Configuration: \
                     (Algorithm: GALE, Batch Size: 50000, Hosts: [], Number of Processes: 1, \
                     Number of Workers: 1, Output Target: STDOUT, Insert Dummy Users: false, \
                     Process ID: 0, Report Connection Progress: false, Retweet Data Set: path/to/retweets.json, \
@@ -0,0 +1,210 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Read small auxiliary text files, e.g. host files or lists of selected users.
+
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Cursor;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use s3::error::ErrorKind as S3ErrorKind;
+use s3::error::S3Error;
+
+use Error;
+use Result;
+use configuration::InputSource;
+
+/// The first two bytes of every gzip-compressed file.
+const GZIP_MAGIC_NUMBER: [u8; 2] = [0x1f, 0x8b];
+
+/// The prefix of lines in a host file which are ignored.
+const COMMENT_PREFIX: &'static str = "#";
+
+/// Open the text file given by `input` for reading, either from a local file or from AWS S3.
+///
+/// If the file is gzip-compressed, it will be decompressed transparently. Compression is detected from the contents of
+/// the file, not from its name.
+pub fn read_text_source(input: InputSource) -> Result<BufReader<Box<Read>>> {
+    let reader: Box<Read> = match input.s3 {
+        Some(ref s3_config) => {
+            let bucket = s3_config.get_bucket()?;
+            let (contents, code): (Vec<u8>, u32) = bucket.get(&input.path.to_string_lossy())?;
+            if code != 200 {
+                let message: String = format!("Could not get file \"{file}\" from AWS S3 bucket \"{bucket} (region \
+                                               {region})\": HTTP error {code}",
+                                              file = input.path.display(), bucket = bucket.name, region = bucket.region,
+                                              code = code);
+                error!("{}", message);
+                return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
+            }
+            Box::new(Cursor::new(contents))
+        },
+        None => Box::new(File::open(&input.path)?)
+    };
+
+    decompress(reader)
+}
+
+/// Buffer the `reader`, decompressing its contents if they are gzip-compressed. Compression is detected from the
+/// first bytes of the contents.
+pub fn decompress(reader: Box<Read>) -> Result<BufReader<Box<Read>>> {
+    let mut reader: BufReader<Box<Read>> = BufReader::new(reader);
+    let is_compressed: bool = reader.fill_buf()?.starts_with(&GZIP_MAGIC_NUMBER);
+
+    if is_compressed {
+        Ok(BufReader::new(Box::new(GzDecoder::new(reader))))
+    } else {
+        Ok(reader)
+    }
+}
+
+/// Read the hosts of the computation from the host file given by `input`, one `host:port` entry per line.
+///
+/// Leading and trailing whitespace is removed from each line. Blank lines and comments (starting with `#`) are
+/// ignored. Errors if the file cannot be read, or if an entry is not of the form `host:port` with a numeric port; the
+/// error names the line of the invalid entry.
+pub fn read_hosts(input: InputSource) -> Result<Vec<String>> {
+    parse_hosts(read_text_source(input)?)
+}
+
+/// Parse the hosts from the lines of the host file `reader`.
+fn parse_hosts<R: BufRead>(reader: R) -> Result<Vec<String>> {
+    let mut hosts: Vec<String> = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line: String = line?;
+        let host: &str = line.trim();
+        if host.is_empty() || host.starts_with(COMMENT_PREFIX) {
+            continue;
+        }
+
+        if !is_valid_host(host) {
+            let message: String = format!("Line {number}: invalid host \"{host}\" in the host file, expected \
+                                           host:port", number = index + 1, host = host);
+            error!("{}", message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+        hosts.push(String::from(host));
+    }
+
+    Ok(hosts)
+}
+
+/// Determine if the `host` entry is of the form `host:port`, with a non-empty host and a numeric port.
+fn is_valid_host(host: &str) -> bool {
+    let mut parts = host.rsplitn(2, ':');
+    let port: &str = parts.next().unwrap_or("");
+    let name: &str = parts.next().unwrap_or("");
+    !name.is_empty() && port.bytes().all(|byte| b'0' <= byte && byte <= b'9') && port.parse::<u16>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::Cursor;
+    use std::io::Read;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use find_folder::Search;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use configuration::InputSource;
+    use configuration::S3;
+
+    /// Read all lines from the given `reader`.
+    fn lines<R: BufRead>(reader: R) -> Vec<String> {
+        reader.lines()
+            .map(|line| line.expect("Could not read line"))
+            .collect()
+    }
+
+    #[test]
+    fn decompress_plain() {
+        let contents: Box<Read> = Box::new(Cursor::new(b"1\n2\n3\n".to_vec()));
+        let reader = super::decompress(contents).expect("Could not read the contents");
+        assert_eq!(lines(reader), vec!["1", "2", "3"]);
+
+        // Empty contents.
+        let contents: Box<Read> = Box::new(Cursor::new(Vec::new()));
+        let reader = super::decompress(contents).expect("Could not read the contents");
+        assert!(lines(reader).is_empty());
+    }
+
+    #[test]
+    fn decompress_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"1\n2\n3\n").expect("Could not compress the contents");
+        let compressed: Vec<u8> = encoder.finish().expect("Could not compress the contents");
+
+        let contents: Box<Read> = Box::new(Cursor::new(compressed));
+        let reader = super::decompress(contents).expect("Could not read the contents");
+        assert_eq!(lines(reader), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn read_text_source_local_plain() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("retweeting_users.txt"));
+
+        let reader = super::read_text_source(input).expect("Could not open the file");
+        assert_eq!(lines(reader), vec!["2", "1", "0", "3", "2", "3"]);
+    }
+
+    #[test]
+    fn read_text_source_local_gzip() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("retweeting_users.txt.gz"));
+
+        let reader = super::read_text_source(input).expect("Could not open the file");
+        assert_eq!(lines(reader), vec!["2", "1", "0", "3", "2", "3"]);
+    }
+
+    #[test]
+    fn read_text_source_local_missing() {
+        let input = InputSource::new("path/to/missing/file.txt");
+        assert!(super::read_text_source(input).is_err());
+    }
+
+    #[test]
+    fn read_text_source_s3() {
+        // With an AWS S3 configuration, the file is requested from the bucket even if it exists locally.
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("retweeting_users.txt"))
+            .s3(Some(S3::new("bucket", "test-region-that-should-not-exist")));
+        assert!(input.s3.is_some());
+        assert!(super::read_text_source(input).is_err());
+    }
+
+    #[test]
+    fn read_hosts() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let input = InputSource::new(data_path.join("hostfile.txt"));
+
+        let hosts: Vec<String> = super::read_hosts(input).expect("Could not read the hosts");
+        assert_eq!(hosts, vec!["node1.example.com:2101", "node3.example.com:2101", "10.0.0.4:2101"]);
+    }
+
+    #[test]
+    fn parse_hosts() {
+        let hosts = super::parse_hosts(Cursor::new(&b"\n# comment\nhost1:2101\r\n"[..])).expect("Invalid hosts");
+        assert_eq!(hosts, vec!["host1:2101"]);
+
+        // Invalid entries.
+        let invalid: [&[u8]; 6] = [b"host1:2101\nhost2\n", b"host1:port", b":2101", b"host1:+2101", b"host1:65536",
+                                   b"host1:"];
+        for contents in &invalid {
+            assert!(super::parse_hosts(Cursor::new(*contents)).is_err());
+        }
+
+        let error: String = format!("{}", super::parse_hosts(Cursor::new(&b"# comment\n\nhost1\n"[..]))
+            .expect_err("Invalid host accepted"));
+        assert!(error.contains("Line 3"));
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Record the activations of users within Retweet cascades.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use Result;
+use twitter::User;
+
+/// The header line of the activation log file.
+const HEADER: &'static str = "cascade_id;user_id;activation_time";
+
+/// A log of user activations, written to a file as lines of `cascade_id;user_id;activation_time`, preceded by a header
+/// line. A user is activated in a cascade by their first Retweet within it.
+#[derive(Debug)]
+pub struct ActivationLog {
+    /// The writer for the activation log file.
+    writer: BufWriter<File>,
+}
+
+impl ActivationLog {
+    /// Create the activation log file at `path` and write its header.
+    pub fn create(path: &Path) -> Result<ActivationLog> {
+        let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+
+        Ok(ActivationLog {
+            writer: writer,
+        })
+    }
+
+    /// Record the activation of the `user` in the cascade `cascade_id` at `activation_time`.
+    pub fn record(&mut self, cascade_id: u64, user: User, activation_time: u64) -> Result<()> {
+        writeln!(self.writer, "{cascade};{user};{time}", cascade = cascade_id, user = user, time = activation_time)?;
+        Ok(())
+    }
+
+    /// Make sure all recorded activations have been written to the file.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Record the activation of the `user` in the cascade `cascade_id` at `activation_time` in the `activation_log`, if
+/// any. Errors are logged, but do not interrupt the computation.
+pub fn log_activation(activation_log: &Option<Arc<Mutex<ActivationLog>>>, cascade_id: u64, user: User,
+                      activation_time: u64) {
+    if let Some(ref activation_log) = *activation_log {
+        let mut activation_log = activation_log.lock().expect("The activation log has been poisoned");
+        if let Err(message) = activation_log.record(cascade_id, user, activation_time) {
+            error!("Could not write the activation log: {error}", error = message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn record() {
+        let path: PathBuf = env::temp_dir().join("crgp_activation_log_record.csv");
+        {
+            let mut log = ActivationLog::create(&path).expect("Could not create the activation log");
+            log.record(1, User::new(2), 3).expect("Could not record the activation");
+            log.record(1, User::new(4), 5).expect("Could not record the activation");
+
+            let shared_log = Some(Arc::new(Mutex::new(log)));
+            log_activation(&shared_log, 6, User::new(7), 8);
+            log_activation(&None, 9, User::new(10), 11);
+
+            let log = shared_log.expect("The activation log is missing");
+            log.lock().expect("The activation log has been poisoned")
+                .flush()
+                .expect("Could not flush the activation log");
+        }
+
+        let mut contents = String::new();
+        let _ = File::open(&path).expect("Could not open the activation log")
+            .read_to_string(&mut contents)
+            .expect("Could not read the activation log");
+        assert_eq!(contents, "cascade_id;user_id;activation_time\n1;2;3\n1;4;5\n6;7;8\n");
+
+        let _ = remove_file(&path);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Estimate the distribution of the sizes of completed Retweet cascades with bounded memory.
+//!
+//! The size of a cascade is its final number of influence edges. Instead of buffering the size of each cascade, the
+//! sizes are counted in a histogram with logarithmically growing buckets: sizes below `128` are counted exactly, larger
+//! sizes are grouped into `64` buckets per power of two. Quantiles are estimated as the midpoint of the bucket
+//! containing them (clamped to the smallest and largest size seen), thus their relative error is at most `1/128`,
+//! i.e. below `0.8%`. The histogram never contains more than about `3,800` buckets, regardless of the number of
+//! cascades.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use timely::dataflow::Scope;
+use timely::dataflow::Stream;
+use timely::dataflow::channels::pact::Exchange;
+use timely::dataflow::operators::unary::Unary;
+
+/// The number of bits of each size kept exactly in the histogram.
+const PRECISION_BITS: u32 = 6;
+
+/// The number of buckets per power of two for sizes that are not counted exactly.
+const SUB_BUCKETS: u64 = 1 << PRECISION_BITS;
+
+/// Feed the sizes of completed cascades into a quantile sketch.
+pub trait CascadeSizes<G: Scope> {
+    /// For a stream of completed cascades `(cascade_id, number_of_edges, number_of_retweets)` (as produced by
+    /// `CompleteCascades`), add each cascade's number of influence edges to the `sketch`. All sizes are sent to the
+    /// first worker, thus the sketch is only complete in process `0`; in all other processes, it remains empty. The
+    /// completed cascades are passed on unchanged.
+    fn cascade_sizes(&self, sketch: Arc<Mutex<QuantileSketch>>) -> Stream<G, (u64, u64, u64)>;
+}
+
+impl<G: Scope> CascadeSizes<G> for Stream<G, (u64, u64, u64)> {
+    fn cascade_sizes(&self, sketch: Arc<Mutex<QuantileSketch>>) -> Stream<G, (u64, u64, u64)> {
+        self.unary_stream(
+            Exchange::new(|_completion: &(u64, u64, u64)| 0),
+            "CascadeSizes",
+            move |completions, output| {
+                completions.for_each(|time, completion_data| {
+                    let mut sketch = sketch.lock().expect("The cascade size sketch has been poisoned");
+                    let mut session = output.session(&time);
+                    for (cascade_id, edges, retweets) in completion_data.drain(..) {
+                        sketch.insert(edges);
+                        session.give((cascade_id, edges, retweets));
+                    }
+                });
+            }
+        )
+    }
+}
+
+/// A histogram of values with logarithmically growing buckets, estimating quantiles with bounded relative error.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QuantileSketch {
+    /// For each non-empty bucket, given by its index, the number of values within it.
+    buckets: BTreeMap<u64, u64>,
+
+    /// The number of values in the sketch.
+    count: u64,
+
+    /// The smallest value in the sketch.
+    minimum: u64,
+
+    /// The largest value in the sketch.
+    maximum: u64,
+}
+
+impl QuantileSketch {
+    /// Create a new sketch without any values.
+    pub fn new() -> QuantileSketch {
+        QuantileSketch::default()
+    }
+
+    /// Add the `value` to the sketch.
+    pub fn insert(&mut self, value: u64) {
+        *self.buckets.entry(bucket_index(value)).or_insert(0) += 1;
+        if self.count == 0 || value < self.minimum {
+            self.minimum = value;
+        }
+        if self.count == 0 || value > self.maximum {
+            self.maximum = value;
+        }
+        self.count += 1;
+    }
+
+    /// The number of values in the sketch.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Determine if the sketch does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Estimate the `q`-quantile (from `0.0` to `1.0`) of the values in the sketch, using the nearest-rank method.
+    /// Return `None` if the sketch is empty.
+    pub fn quantile(&self, q: f64) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let rank: u64 = (q * self.count as f64).ceil() as u64;
+        let rank: u64 = if rank < 1 { 1 } else if rank > self.count { self.count } else { rank };
+
+        let mut seen: u64 = 0;
+        for (&index, &count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                let estimate: u64 = bucket_midpoint(index);
+                let estimate: u64 = if estimate < self.minimum {
+                    self.minimum
+                } else if estimate > self.maximum {
+                    self.maximum
+                } else {
+                    estimate
+                };
+                return Some(estimate);
+            }
+        }
+
+        Some(self.maximum)
+    }
+}
+
+/// Get the index of the bucket containing `value`. The indices grow monotonically with the values.
+fn bucket_index(value: u64) -> u64 {
+    if value < 2 * SUB_BUCKETS {
+        return value;
+    }
+
+    // Keep the `PRECISION_BITS + 1` most significant bits of the value.
+    let shift: u32 = 63 - value.leading_zeros() - PRECISION_BITS;
+    u64::from(shift) * SUB_BUCKETS + (value >> shift)
+}
+
+/// Get the midpoint of the values within the bucket given by its `index`.
+fn bucket_midpoint(index: u64) -> u64 {
+    if index < 2 * SUB_BUCKETS {
+        return index;
+    }
+
+    let shift: u64 = index / SUB_BUCKETS - 1;
+    let lower: u64 = (index - shift * SUB_BUCKETS) << shift;
+    lower + ((1 << shift) - 1) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_index_and_midpoint() {
+        // Small values are exact.
+        for value in 0..128 {
+            assert_eq!(bucket_index(value), value);
+            assert_eq!(bucket_midpoint(value), value);
+        }
+
+        // The indices grow monotonically.
+        assert_eq!(bucket_index(128), 128);
+        assert_eq!(bucket_index(129), 128);
+        assert_eq!(bucket_index(130), 129);
+        assert!(bucket_index(u64::max_value()) > bucket_index(u64::max_value() / 2));
+
+        // The relative error is at most 1/128.
+        for &value in &[128, 1_000, 123_456, 987_654_321, u64::max_value()] {
+            let midpoint: u64 = bucket_midpoint(bucket_index(value));
+            let error: f64 = (midpoint as f64 - value as f64).abs() / value as f64;
+            assert!(error <= 1.0 / 128.0, "error {} for value {}", error, value);
+        }
+    }
+
+    #[test]
+    fn quantile() {
+        let mut sketch = QuantileSketch::new();
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.quantile(0.5), None);
+
+        // Exact for small values.
+        for value in 1..101 {
+            sketch.insert(value);
+        }
+        assert_eq!(sketch.len(), 100);
+        assert_eq!(sketch.quantile(0.0), Some(1));
+        assert_eq!(sketch.quantile(0.5), Some(50));
+        assert_eq!(sketch.quantile(0.9), Some(90));
+        assert_eq!(sketch.quantile(0.99), Some(99));
+        assert_eq!(sketch.quantile(1.0), Some(100));
+
+        // Approximate for large values, but clamped to the largest value.
+        let mut sketch = QuantileSketch::new();
+        for value in 0..1_000 {
+            sketch.insert(value * 1_000);
+        }
+        let p90: u64 = sketch.quantile(0.9).expect("Empty sketch");
+        assert!((p90 as f64 - 899_000.0).abs() / 899_000.0 <= 1.0 / 128.0);
+        assert_eq!(sketch.quantile(1.0), Some(999_000));
+    }
+}
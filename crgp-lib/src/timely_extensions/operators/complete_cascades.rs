@@ -0,0 +1,369 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Detect the completion of Retweet cascades.
+//!
+//! Retweets arrive as a stream, thus it is never known for certain that a cascade will not receive any further
+//! Retweets. A cascade is considered complete in one of two cases:
+//!
+//! 1. **Timeout:** If a timeout is given and no Retweet of the cascade has been created within that many seconds before
+//!    the latest Retweet seen so far (of any cascade), the cascade is complete. This assumes that the Retweets are
+//!    sorted by their creation time. A Retweet arriving after its cascade has been completed starts tracking the
+//!    cascade anew, i.e. the cascade will be reported as complete a second time.
+//! 2. **End of input:** Once all Retweets have been processed, all cascades that have not been completed yet are
+//!    complete.
+//!
+//! In both cases, timely's progress tracking ensures that all influence edges produced for the cascade's Retweets
+//! have been counted before the cascade is reported: completions are only determined once all data for a batch of
+//! Retweets has been received.
+//!
+//! To detect the end of the input even if the last batches do not contain any data, each batch requests a notification
+//! for the next time the inputs may still reach. No capability is held for a batch once it has been notified, thus
+//! the output (and any probe downstream) advances with the inputs.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use timely::dataflow::Scope;
+use timely::dataflow::Stream;
+use timely::dataflow::channels::pact::Exchange;
+use timely::dataflow::operators::binary::Binary;
+
+use social_graph::InfluenceEdge;
+use twitter::Retweet;
+use twitter::User;
+
+/// Emit an event for each cascade once it is complete.
+pub trait CompleteCascades<G: Scope> {
+    /// For a stream of influence edges and the `retweets` they have been produced from, emit `(cascade_id,
+    /// number_of_edges, number_of_retweets)` once the cascade given by `cascade_id` is complete, where
+    /// `number_of_edges` is the final number of influence edges in the cascade and `number_of_retweets` its final
+    /// number of Retweets.
+    ///
+    /// If `timeout` is given, a cascade is complete once the latest Retweet is more than `timeout` seconds younger
+    /// than the cascade's latest Retweet. All remaining cascades are complete at the end of the input. Each cascade is
+    /// tracked on a single worker.
+    fn complete_cascades(&self, retweets: &Stream<G, Retweet>, timeout: Option<u64>) -> Stream<G, (u64, u64, u64)>;
+}
+
+impl<G: Scope> CompleteCascades<G> for Stream<G, InfluenceEdge<User>>
+where G::Timestamp: Hash {
+    fn complete_cascades(&self, retweets: &Stream<G, Retweet>, timeout: Option<u64>) -> Stream<G, (u64, u64, u64)> {
+        let mut tracker = CascadeTracker::new(timeout);
+
+        self.binary_notify(
+            retweets,
+            Exchange::new(|edge: &InfluenceEdge<User>| edge.cascade_id),
+            Exchange::new(|retweet: &Retweet| retweet.retweeted_status.id),
+            "CompleteCascades",
+            Vec::new(),
+            move |edges, retweets, output, notificator| {
+                // Count the influence edges of each cascade.
+                edges.for_each(|time, edge_data| {
+                    notificator.notify_at(time.clone());
+                    for edge in edge_data.iter() {
+                        tracker.add_edge(edge.cascade_id);
+                    }
+                });
+
+                // Track the latest Retweet of each cascade.
+                retweets.for_each(|time, retweet_data| {
+                    notificator.notify_at(time.clone());
+                    for retweet in retweet_data.iter() {
+                        let _ = tracker.add_retweet(retweet.retweeted_status.id, retweet.created_at);
+                    }
+                });
+
+                // The earliest time at which further data might arrive. If there is none, all cascades are complete.
+                let next_time: Option<G::Timestamp> = earliest(notificator.frontier(0), notificator.frontier(1));
+
+                // Once all data of a batch has been received, report the cascades that have timed out. Instead of
+                // retaining the batch's capability until the end of the input, request a notification for the next
+                // time, which is only delivered once the inputs have passed it as well.
+                notificator.for_each(|time, _num, notificator| {
+                    let mut completed: Vec<(u64, u64, u64)> = tracker.complete_timed_out();
+                    match next_time {
+                        Some(ref next_time) if *next_time > *time => notificator.notify_at(time.delayed(next_time)),
+                        Some(_) => {},
+                        None => completed.extend(tracker.complete_all())
+                    }
+
+                    if !completed.is_empty() {
+                        let mut session = output.session(&time);
+                        for completion in completed {
+                            session.give(completion);
+                        }
+                    }
+                });
+            }
+        )
+    }
+}
+
+/// Get the earliest time in the frontiers `first` and `second`, or `None` if both are empty.
+fn earliest<T: Clone + PartialOrd>(first: &[T], second: &[T]) -> Option<T> {
+    first.iter()
+        .chain(second.iter())
+        .fold(None, |earliest: Option<&T>, time: &T| {
+            match earliest {
+                Some(earliest) if earliest <= time => Some(earliest),
+                _ => Some(time)
+            }
+        })
+        .cloned()
+}
+
+/// The state of a single cascade that has not been completed yet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct CascadeState {
+    /// The number of influence edges in the cascade.
+    edges: u64,
+
+    /// The number of Retweets in the cascade.
+    retweets: u64,
+
+    /// The creation time of the cascade's latest Retweet.
+    latest_retweet: u64,
+}
+
+/// Keep track of all cascades that have not been completed yet.
+#[derive(Debug)]
+pub struct CascadeTracker {
+    /// The number of seconds without a Retweet after which a cascade is complete.
+    timeout: Option<u64>,
+
+    /// For each cascade, given by its ID, its current state.
+    cascades: HashMap<u64, CascadeState>,
+
+    /// The IDs of all cascades that have been completed after the timeout.
+    timed_out: HashSet<u64>,
+
+    /// The creation time of the latest Retweet seen in any cascade.
+    latest_retweet: u64,
+}
+
+impl CascadeTracker {
+    /// Create a new tracker, completing cascades after `timeout` seconds without a Retweet, if given.
+    pub fn new(timeout: Option<u64>) -> CascadeTracker {
+        CascadeTracker {
+            timeout: timeout,
+            cascades: HashMap::new(),
+            timed_out: HashSet::new(),
+            latest_retweet: 0,
+        }
+    }
+
+    /// Track a Retweet in the cascade `cascade_id`, created at time `created_at`. Return the creation time of the
+    /// cascade's latest Retweet before this one, if any Retweet of the cascade has been tracked.
+    pub fn add_retweet(&mut self, cascade_id: u64, created_at: u64) -> Option<u64> {
+        if created_at > self.latest_retweet {
+            self.latest_retweet = created_at;
+        }
+
+        let state: &mut CascadeState = self.cascades.entry(cascade_id).or_insert_with(CascadeState::default);
+        let previous_retweet: Option<u64> = if state.retweets > 0 {
+            Some(state.latest_retweet)
+        } else {
+            None
+        };
+        state.retweets += 1;
+        if created_at > state.latest_retweet {
+            state.latest_retweet = created_at;
+        }
+        previous_retweet
+    }
+
+    /// Count an influence edge in the cascade `cascade_id`.
+    pub fn add_edge(&mut self, cascade_id: u64) {
+        self.cascades.entry(cascade_id).or_insert_with(CascadeState::default).edges += 1;
+    }
+
+    /// Complete all cascades whose latest Retweet is more than the timeout older than the latest Retweet seen in any
+    /// cascade. Return the IDs of these cascades and their numbers of influence edges and Retweets, sorted by the IDs.
+    /// Without a timeout, no cascades are completed.
+    pub fn complete_timed_out(&mut self) -> Vec<(u64, u64, u64)> {
+        let timeout: u64 = match self.timeout {
+            Some(timeout) => timeout,
+            None => return Vec::new()
+        };
+
+        let latest_retweet: u64 = self.latest_retweet;
+        let timed_out: Vec<u64> = self.cascades.iter()
+            .filter(|&(_, state)| latest_retweet.saturating_sub(state.latest_retweet) > timeout)
+            .map(|(cascade_id, _)| *cascade_id)
+            .collect();
+
+        self.timed_out.extend(timed_out.iter().cloned());
+        self.complete(timed_out)
+    }
+
+    /// Determine if the cascade `cascade_id` has been completed after the timeout before, i.e. if a Retweet of it is
+    /// late. The cascade may have been started anew since.
+    pub fn has_timed_out(&self, cascade_id: u64) -> bool {
+        self.timed_out.contains(&cascade_id)
+    }
+
+    /// Complete all cascades. Return their IDs and their numbers of influence edges and Retweets, sorted by the IDs.
+    pub fn complete_all(&mut self) -> Vec<(u64, u64, u64)> {
+        let cascades: Vec<u64> = self.cascades.keys().cloned().collect();
+        self.complete(cascades)
+    }
+
+    /// The number of cascades that have not been completed yet.
+    pub fn len(&self) -> usize {
+        self.cascades.len()
+    }
+
+    /// Determine if all cascades have been completed.
+    pub fn is_empty(&self) -> bool {
+        self.cascades.is_empty()
+    }
+
+    /// Stop tracking the given `cascades`. Return their IDs and their numbers of influence edges and Retweets, sorted
+    /// by the IDs.
+    fn complete(&mut self, mut cascades: Vec<u64>) -> Vec<(u64, u64, u64)> {
+        cascades.sort();
+        cascades.into_iter()
+            .filter_map(|cascade_id| {
+                self.cascades.remove(&cascade_id).map(|state| (cascade_id, state.edges, state.retweets))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+    use timely::dataflow::operators::Input;
+    use timely::dataflow::operators::Inspect;
+    use timely::dataflow::operators::Probe;
+    use timely::execute::execute;
+    use timely_communication::initialize::Configuration as TimelyConfiguration;
+    use Result;
+    use timely_extensions::Sync;
+    use twitter::Tweet;
+    use super::*;
+
+    #[test]
+    fn complete_cascades() {
+        let guards = execute(TimelyConfiguration::Thread, |computation| {
+            let completions: Rc<RefCell<Vec<(u64, u64, u64)>>> = Rc::new(RefCell::new(Vec::new()));
+            let captured_completions = completions.clone();
+            let (mut edge_input, mut retweet_input, probe) = computation.dataflow::<u64, _, _>(move |scope| {
+                let (edge_input, edge_stream) = scope.new_input::<InfluenceEdge<User>>();
+                let (retweet_input, retweet_stream) = scope.new_input::<Retweet>();
+                let probe = edge_stream
+                    .complete_cascades(&retweet_stream, Some(10))
+                    .inspect(move |completion| captured_completions.borrow_mut().push(*completion))
+                    .probe();
+                (edge_input, retweet_input, probe)
+            });
+            let timeout = Duration::from_secs(10);
+            let mut syncs: Vec<Result<()>> = Vec::new();
+            let mut completions_per_batch: Vec<Vec<(u64, u64, u64)>> = Vec::new();
+
+            // Batch 1: Cascade 1 starts.
+            let original = Tweet::new(1, 0, 0);
+            retweet_input.send(Retweet::new(11, original, 1, 1));
+            edge_input.send(InfluenceEdge::new(User::new(0), User::new(1), 1, 11, 1, User::new(0)));
+            syncs.push(computation.sync_with_timeout(&probe, &mut retweet_input, &mut edge_input, timeout));
+            completions_per_batch.push(completions.borrow_mut().drain(..).collect());
+
+            // Batch 2: Cascade 2 starts more than ten seconds after the latest Retweet of cascade 1.
+            let original = Tweet::new(2, 1, 20);
+            retweet_input.send(Retweet::new(21, original, 2, 25));
+            syncs.push(computation.sync_with_timeout(&probe, &mut retweet_input, &mut edge_input, timeout));
+            completions_per_batch.push(completions.borrow_mut().drain(..).collect());
+
+            // Batch 3: Without any data, the batch must be processed nonetheless.
+            syncs.push(computation.sync_with_timeout(&probe, &mut retweet_input, &mut edge_input, timeout));
+            completions_per_batch.push(completions.borrow_mut().drain(..).collect());
+
+            // End of input: All remaining cascades are complete.
+            drop(retweet_input);
+            drop(edge_input);
+            while computation.step() {}
+            completions_per_batch.push(completions.borrow_mut().drain(..).collect());
+
+            let syncs: Vec<bool> = syncs.iter().map(|sync| sync.is_ok()).collect();
+            (syncs, completions_per_batch)
+        }).expect("Could not execute the computation");
+
+        for result in guards.join() {
+            let (syncs, completions_per_batch) = result.expect("The computation failed");
+            assert_eq!(syncs, vec![true, true, true]);
+            assert_eq!(completions_per_batch, vec![vec![], vec![(1, 1, 1)], vec![], vec![(2, 0, 1)]]);
+        }
+    }
+
+    #[test]
+    fn complete_all() {
+        let mut tracker = CascadeTracker::new(None);
+        assert!(tracker.is_empty());
+
+        assert_eq!(tracker.add_retweet(2, 1), None);
+        tracker.add_edge(2);
+        tracker.add_edge(2);
+        assert_eq!(tracker.add_retweet(1, 2), None);
+        assert_eq!(tracker.add_retweet(1, 2), Some(2));
+        assert_eq!(tracker.add_retweet(3, 3), None);
+        tracker.add_edge(3);
+        assert_eq!(tracker.len(), 3);
+
+        // Without a timeout, cascades are only complete at the end.
+        let _ = tracker.add_retweet(4, 1_000);
+        assert!(tracker.complete_timed_out().is_empty());
+        assert_eq!(tracker.len(), 4);
+
+        assert_eq!(tracker.complete_all(), vec![(1, 0, 2), (2, 2, 1), (3, 1, 1), (4, 0, 1)]);
+        assert!(tracker.is_empty());
+        assert!(tracker.complete_all().is_empty());
+    }
+
+    #[test]
+    fn earliest() {
+        let empty: &[u64] = &[];
+        assert_eq!(super::earliest(empty, empty), None);
+        assert_eq!(super::earliest(&[3], empty), Some(3));
+        assert_eq!(super::earliest(empty, &[2]), Some(2));
+        assert_eq!(super::earliest(&[3], &[2]), Some(2));
+        assert_eq!(super::earliest(&[1], &[2]), Some(1));
+    }
+
+    #[test]
+    fn complete_timed_out() {
+        let mut tracker = CascadeTracker::new(Some(10));
+        let _ = tracker.add_retweet(1, 0);
+        tracker.add_edge(1);
+        let _ = tracker.add_retweet(2, 5);
+        assert_eq!(tracker.add_retweet(1, 8), Some(0));
+        tracker.add_edge(1);
+
+        // Cascade 2 is exactly at the timeout.
+        let _ = tracker.add_retweet(3, 15);
+        assert!(tracker.complete_timed_out().is_empty());
+
+        let _ = tracker.add_retweet(3, 17);
+        assert_eq!(tracker.complete_timed_out(), vec![(2, 0, 1)]);
+        assert_eq!(tracker.len(), 2);
+        assert!(tracker.has_timed_out(2));
+        assert!(!tracker.has_timed_out(1));
+
+        // Edges may arrive before their Retweet.
+        tracker.add_edge(3);
+        let _ = tracker.add_retweet(4, 30);
+        assert_eq!(tracker.complete_timed_out(), vec![(1, 2, 2), (3, 1, 2)]);
+        assert_eq!(tracker.complete_all(), vec![(4, 0, 1)]);
+
+        // Only cascades completed after the timeout are remembered.
+        assert!(tracker.has_timed_out(1));
+        assert!(tracker.has_timed_out(3));
+        assert!(!tracker.has_timed_out(4));
+    }
+}
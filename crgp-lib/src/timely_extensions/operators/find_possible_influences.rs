@@ -8,16 +8,26 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::hash::*;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
 use timely::dataflow::Scope;
 use timely::dataflow::Stream;
 use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::operators::binary::Binary;
 
+use social_graph::GraphOwners;
 use social_graph::InfluenceEdge;
 use social_graph::SocialGraph;
+use timely_extensions::operators::ActivationLog;
+use timely_extensions::operators::IterationCounts;
+use timely_extensions::operators::log_activation;
+use timely_extensions::operators::update_maximum;
 use twitter::Retweet;
 use twitter::Tweet;
 use twitter::User;
@@ -27,24 +37,53 @@ pub trait FindPossibleInfluences<G: Scope> {
     /// Find all possible influence edges within a social graph, distinguishing between cascades.
     ///
     /// For a social graph, determine all possible influences for a retweet within that specific
-    /// retweet cascade. The `Stream` of retweets may contain multiple retweet cascades.
+    /// retweet cascade. The `Stream` of retweets may contain multiple retweet cascades. The friendships and retweets
+    /// of each user will be processed on the worker given by `owners`.
+    ///
+    /// Possible influence edges from a user to themselves are never produced. Those that would have become actual
+    /// influences (i.e. the user has been activated before or is the poster of the original Tweet) are counted in
+    /// `suppressed_self_edges`.
+    ///
+    /// The maximum number of cascades with `activated_users` on this worker is stored in `peak_live_cascades`. The
+    /// activations are not dropped here, since possible influences of a cascade may still be in flight to the filter;
+    /// they must be dropped once the cascade is complete.
+    ///
+    /// If an `activation_log` is given, each user's activation by a Retweet is recorded in it once per cascade.
+    ///
+    /// The friends of the retweeting user are always iterated, which is counted in `iteration_counts` for each Retweet
+    /// of a user with a friend list.
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
     fn find_possible_influences(&self, retweets: Stream<G, Retweet>,
-                                activated_users: Rc<RefCell<HashMap<u64, HashMap<User, u64>>>>)
+                                activated_users: Rc<RefCell<HashMap<u64, HashMap<User, u64>>>>,
+                                owners: GraphOwners,
+                                suppressed_self_edges: Arc<AtomicUsize>,
+                                peak_live_cascades: Arc<AtomicUsize>,
+                                activation_log: Option<Arc<Mutex<ActivationLog>>>,
+                                iteration_counts: IterationCounts)
                                 -> Stream<G, InfluenceEdge<User>>;
 }
 
 impl<G: Scope> FindPossibleInfluences<G> for Stream<G, (User, Vec<User>)>
     where G::Timestamp: Hash {
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
     fn find_possible_influences(&self, retweets: Stream<G, Retweet>,
-                                activated_users: Rc<RefCell<HashMap<u64, HashMap<User, u64>>>>)
+                                activated_users: Rc<RefCell<HashMap<u64, HashMap<User, u64>>>>,
+                                owners: GraphOwners,
+                                suppressed_self_edges: Arc<AtomicUsize>,
+                                peak_live_cascades: Arc<AtomicUsize>,
+                                activation_log: Option<Arc<Mutex<ActivationLog>>>,
+                                iteration_counts: IterationCounts)
                                 -> Stream<G, InfluenceEdge<User>> {
         // For each user, given by their ID, the set of their friends, given by their ID.
         let mut edges = SocialGraph::new();
 
+        // Route the retweets to the same workers as the retweeting users' friendships.
+        let retweet_owners: GraphOwners = owners.clone();
+
         self.binary_stream(
             &retweets,
-            Exchange::new(|edge: &(User, Vec<User>)| edge.0.id as u64),
-            Exchange::new(|retweet: &Retweet| retweet.user.id as u64),
+            Exchange::new(move |edge: &(User, Vec<User>)| owners.route(&edge.0)),
+            Exchange::new(move |retweet: &Retweet| retweet_owners.route(&retweet.user)),
             "FindPossibleInfluences",
             move |friendships, retweets, output| {
                 // Input 1: Capture all friends for each user.
@@ -56,8 +95,11 @@ impl<G: Scope> FindPossibleInfluences<G> for Stream<G, (User, Vec<User>)>
                         let friendship_set: &mut Vec<User> = edges.entry(user)
                             .or_insert_with(|| Vec::with_capacity(friends.len()));
                         friendship_set.extend(friends);
+                        friendship_set.sort();
+
+                        // Merged friend lists may contain a friend more than once.
+                        friendship_set.dedup();
                         friendship_set.shrink_to_fit();
-                        friendship_set.sort()
                     };
 
                     edges.shrink_to_fit();
@@ -70,11 +112,16 @@ impl<G: Scope> FindPossibleInfluences<G> for Stream<G, (User, Vec<User>)>
                         let original_tweet: &Tweet = &retweet.retweeted_status;
 
                         // Mark this user and the original user as active for this cascade.
-                        let _ = activated_users.borrow_mut()
+                        let activation_timestamp: u64 = match activated_users.borrow_mut()
                             .entry(original_tweet.id)
                             .or_insert_with(HashMap::new)
-                            .entry(retweet.user)
-                            .or_insert(retweet.created_at);
+                            .entry(retweet.user) {
+                            Entry::Occupied(activation) => *activation.get(),
+                            Entry::Vacant(activation) => {
+                                log_activation(&activation_log, original_tweet.id, retweet.user, retweet.created_at);
+                                *activation.insert(retweet.created_at)
+                            }
+                        };
 
                         // Get the user's friends.
                         let friends = match edges.get(&retweet.user) {
@@ -83,12 +130,24 @@ impl<G: Scope> FindPossibleInfluences<G> for Stream<G, (User, Vec<User>)>
                         };
 
                         // Pass on the possible influence edges.
+                        iteration_counts.add_friend_iteration(friends.len());
                         for &friend in friends {
+                            // Never influence oneself. Only count those self-edges that would have passed the filter.
+                            if friend == retweet.user {
+                                let is_activated_before: bool = activation_timestamp < retweet.created_at;
+                                if is_activated_before || friend == original_tweet.user {
+                                    let _ = suppressed_self_edges.fetch_add(1, Ordering::SeqCst);
+                                }
+                                continue;
+                            }
+
                             let influence = InfluenceEdge::new(friend, retweet.user, retweet.created_at, retweet.id,
                                                                original_tweet.id, original_tweet.user);
                             session.give(influence);
                         }
                     }
+
+                    update_maximum(&peak_live_cascades, activated_users.borrow().len());
                 });
             }
         )
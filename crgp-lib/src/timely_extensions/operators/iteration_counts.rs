@@ -0,0 +1,87 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Count which collection the reconstruction iterates to find the influencers of a Retweet.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Counts of how often the reconstruction iterated over the retweeting user's friends and how often over the
+/// activations of the cascade, i.e. over the smaller of both, and of the number of elements iterated in each case.
+/// Cloning the counts shares them.
+#[derive(Clone, Debug, Default)]
+pub struct IterationCounts {
+    /// The number of Retweets for which the friends have been iterated.
+    friend_iterations: Arc<AtomicUsize>,
+
+    /// The total number of friends iterated.
+    iterated_friends: Arc<AtomicUsize>,
+
+    /// The number of Retweets for which the activations have been iterated.
+    activation_iterations: Arc<AtomicUsize>,
+
+    /// The total number of activations iterated.
+    iterated_activations: Arc<AtomicUsize>,
+}
+
+impl IterationCounts {
+    /// Create new counts, all set to `0`.
+    pub fn new() -> IterationCounts {
+        IterationCounts::default()
+    }
+
+    /// Count an iteration over `friends` friends.
+    pub fn add_friend_iteration(&self, friends: usize) {
+        let _ = self.friend_iterations.fetch_add(1, Ordering::SeqCst);
+        let _ = self.iterated_friends.fetch_add(friends, Ordering::SeqCst);
+    }
+
+    /// Count an iteration over `activations` activations.
+    pub fn add_activation_iteration(&self, activations: usize) {
+        let _ = self.activation_iterations.fetch_add(1, Ordering::SeqCst);
+        let _ = self.iterated_activations.fetch_add(activations, Ordering::SeqCst);
+    }
+
+    /// Get the number of Retweets for which the friends have been iterated.
+    pub fn friend_iterations(&self) -> u64 {
+        self.friend_iterations.load(Ordering::SeqCst) as u64
+    }
+
+    /// Get the total number of friends iterated.
+    pub fn iterated_friends(&self) -> u64 {
+        self.iterated_friends.load(Ordering::SeqCst) as u64
+    }
+
+    /// Get the number of Retweets for which the activations have been iterated.
+    pub fn activation_iterations(&self) -> u64 {
+        self.activation_iterations.load(Ordering::SeqCst) as u64
+    }
+
+    /// Get the total number of activations iterated.
+    pub fn iterated_activations(&self) -> u64 {
+        self.iterated_activations.load(Ordering::SeqCst) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_iterations() {
+        let counts = IterationCounts::new();
+        let clone = counts.clone();
+        clone.add_friend_iteration(3);
+        clone.add_friend_iteration(2);
+        clone.add_activation_iteration(7);
+
+        assert_eq!(counts.friend_iterations(), 2);
+        assert_eq!(counts.iterated_friends(), 5);
+        assert_eq!(counts.activation_iterations(), 1);
+        assert_eq!(counts.iterated_activations(), 7);
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Produce influence edges to Apache Kafka.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use rdkafka::client::Context;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaError;
+use rdkafka::error::KafkaResult;
+use rdkafka::producer::BaseProducer;
+use rdkafka::producer::BaseRecord;
+use rdkafka::producer::DeliveryResult;
+use rdkafka::producer::ProducerContext;
+use rdkafka::types::RDKafkaError;
+use serde_json;
+
+use configuration::KafkaErrorPolicy;
+use social_graph::InfluenceEdge;
+use twitter::User;
+
+/// The time to wait for the producer's queue to free up space if it is full.
+const BACK_PRESSURE_WAIT_MS: u64 = 100;
+
+/// The maximum time to wait for all queued messages to be delivered when flushing.
+const FLUSH_TIMEOUT_S: u64 = 60;
+
+/// A producer context counting failed deliveries.
+struct DeliveryContext {
+    /// The number of messages that could not be delivered.
+    failures: Arc<AtomicUsize>,
+}
+
+impl Context for DeliveryContext {}
+
+impl ProducerContext for DeliveryContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, delivery_result: &DeliveryResult, _delivery_opaque: Self::DeliveryOpaque) {
+        if let Err((ref error, _)) = *delivery_result {
+            error!("Could not deliver influence edge to Kafka: {error}", error = error);
+            let _ = self.failures.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Produce influence edges as JSON messages to a Kafka topic, keyed by their cascade ID.
+pub struct KafkaProducer {
+    /// The actual Kafka producer.
+    producer: BaseProducer<DeliveryContext>,
+
+    /// The topic to which the messages are produced.
+    topic: String,
+
+    /// How to handle production and delivery errors.
+    error_policy: KafkaErrorPolicy,
+
+    /// The number of messages that could not be produced or delivered.
+    failures: Arc<AtomicUsize>,
+}
+
+impl KafkaProducer {
+    /// Connect a new producer to the given comma-separated list of `brokers`, producing to `topic`.
+    pub fn new(brokers: &str, topic: &str, error_policy: KafkaErrorPolicy) -> KafkaResult<KafkaProducer> {
+        let failures: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+        let context = DeliveryContext {
+            failures: failures.clone(),
+        };
+        let producer: BaseProducer<DeliveryContext> = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create_with_context(context)?;
+
+        Ok(KafkaProducer {
+            producer: producer,
+            topic: String::from(topic),
+            error_policy: error_policy,
+            failures: failures,
+        })
+    }
+
+    /// Queue the `influence` edge for production. If the producer's queue is full, wait until enough messages have
+    /// been delivered.
+    pub fn produce(&self, influence: &InfluenceEdge<User>) {
+        let payload: String = match serde_json::to_string(influence) {
+            Ok(payload) => payload,
+            Err(message) => {
+                error!("Could not serialize influence edge: {error}", error = message);
+                let _ = self.failures.fetch_add(1, Ordering::SeqCst);
+                return;
+            }
+        };
+        let key: String = influence.cascade_id.to_string();
+
+        loop {
+            match self.producer.send(BaseRecord::to(&self.topic).key(&key).payload(&payload)) {
+                Ok(()) => break,
+                Err((KafkaError::MessageProduction(RDKafkaError::QueueFull), _)) => {
+                    // Apply back-pressure: wait for the delivery of some queued messages before trying again.
+                    let _ = self.producer.poll(Duration::from_millis(BACK_PRESSURE_WAIT_MS));
+                },
+                Err((error, _)) => {
+                    error!("Could not produce influence edge to Kafka: {error}", error = error);
+                    let _ = self.failures.fetch_add(1, Ordering::SeqCst);
+                    break;
+                }
+            }
+        }
+
+        // Serve the delivery callbacks of previously produced messages.
+        let _ = self.producer.poll(Duration::from_millis(0));
+    }
+
+    /// Wait until all queued messages have been delivered.
+    ///
+    /// If any message could not be produced or delivered so far and the error policy is `Fail`, an error message is
+    /// returned such that the computation can fail.
+    pub fn flush(&self) -> Result<(), String> {
+        self.producer.flush(Duration::from_secs(FLUSH_TIMEOUT_S));
+
+        let failures: usize = self.failures.load(Ordering::SeqCst);
+        if failures > 0 && self.error_policy == KafkaErrorPolicy::Fail {
+            return Err(format!("Failed to produce {failures} influence edges to Kafka topic {topic}",
+                               failures = failures, topic = self.topic));
+        }
+        Ok(())
+    }
+}
@@ -9,10 +9,90 @@
 //! A collection of functions taking typed `Stream` objects from `timely` as input and producing new `Stream`
 //! objects as output. These custom operators are specialized for the use in `CRGP`.
 
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use twitter::User;
+
+pub use self::activation_log::ActivationLog;
+pub use self::activation_log::log_activation;
+pub use self::cascade_sizes::CascadeSizes;
+pub use self::cascade_sizes::QuantileSketch;
+pub use self::complete_cascades::CascadeTracker;
+pub use self::complete_cascades::CompleteCascades;
 pub use self::find_possible_influences::FindPossibleInfluences;
+pub use self::iteration_counts::IterationCounts;
+pub use self::output_hash::OutputHash;
 pub use self::reconstruct::Reconstruct;
+pub use self::widest_fan_out::WidestFanOut;
 pub use self::write::Write;
 
+mod activation_log;
+mod cascade_sizes;
+mod complete_cascades;
 mod find_possible_influences;
+mod iteration_counts;
+#[cfg(feature = "rdkafka")]
+mod kafka;
+mod output_hash;
 mod reconstruct;
+mod reservoir;
+mod widest_fan_out;
 mod write;
+
+/// Set the `maximum` to `value` if `value` is larger.
+fn update_maximum(maximum: &AtomicUsize, value: usize) {
+    let mut current: usize = maximum.load(Ordering::SeqCst);
+    while value > current {
+        let previous: usize = maximum.compare_and_swap(current, value, Ordering::SeqCst);
+        if previous == current {
+            return;
+        }
+        current = previous;
+    }
+}
+
+/// Get the time at which the `influencer` has been activated by their own Retweet, given their `activation` in the
+/// cascade. The poster of the original Tweet (`original_user`) has not been activated by a Retweet, thus `None` is
+/// returned for them.
+pub fn retweet_activation(influencer: User, original_user: User, activation: Option<u64>) -> Option<u64> {
+    if influencer == original_user {
+        None
+    } else {
+        activation
+    }
+}
+
+/// Determine if a user activated at `activation` has been activated before a Retweet created at `retweet_time`, and
+/// thus might have influenced it. The activation may be up to `grace_seconds` later than the Retweet to compensate
+/// for imprecise timestamps; a negative grace window instead requires the activation to be at least that much earlier
+/// than the Retweet. With a grace window of `0`, the activation must be strictly earlier.
+pub fn is_activated_before(activation: u64, retweet_time: u64, grace_seconds: i64) -> bool {
+    if grace_seconds >= 0 {
+        retweet_time.saturating_add(grace_seconds as u64) > activation
+    } else {
+        retweet_time > activation.saturating_add(grace_seconds.wrapping_neg() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn is_activated_before() {
+        // Without a grace window, the activation must be strictly earlier.
+        assert!(super::is_activated_before(4, 5, 0));
+        assert!(!super::is_activated_before(5, 5, 0));
+        assert!(!super::is_activated_before(6, 5, 0));
+
+        // A positive grace window allows later activations.
+        assert!(super::is_activated_before(5, 5, 1));
+        assert!(!super::is_activated_before(6, 5, 1));
+        assert!(super::is_activated_before(6, 5, 2));
+        assert!(super::is_activated_before(u64::max_value(), 5, i64::max_value()));
+
+        // A negative grace window requires earlier activations.
+        assert!(!super::is_activated_before(4, 5, -1));
+        assert!(super::is_activated_before(3, 5, -1));
+        assert!(!super::is_activated_before(0, 5, i64::min_value()));
+    }
+}
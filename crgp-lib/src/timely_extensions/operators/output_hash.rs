@@ -0,0 +1,163 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Hash the bytes written to the result files to allow verifying their integrity.
+
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use sha2::Digest;
+use sha2::Sha256;
+
+use Result;
+
+/// The extension appended to the name of a result file to get the name of the file containing its hash.
+pub const SIDECAR_EXTENSION: &'static str = "sha256";
+
+/// The hash of all bytes written to a result file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutputHash {
+    /// The path to the result file.
+    pub path: PathBuf,
+
+    /// The SHA-256 hash of the written bytes in hexadecimal.
+    pub sha256: String,
+
+    /// The number of written bytes.
+    pub bytes: u64,
+}
+
+impl OutputHash {
+    /// Write the hash next to the result file, i.e. to the file with the additional extension `sha256`, in the format
+    /// of `sha256sum`. Return the path to this file. Errors on any I/O error.
+    pub fn write_sidecar(&self) -> Result<PathBuf> {
+        let mut sidecar = self.path.clone().into_os_string();
+        sidecar.push(".");
+        sidecar.push(SIDECAR_EXTENSION);
+        let sidecar = PathBuf::from(sidecar);
+
+        let filename: String = self.path.file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let mut file = File::create(&sidecar)?;
+        writeln!(file, "{hash}  {file}", hash = self.sha256, file = filename)?;
+        Ok(sidecar)
+    }
+}
+
+/// A writer passing all bytes on to an inner writer while hashing the bytes the inner writer has accepted.
+pub struct HashingWriter<W: Write> {
+    /// The writer to which the bytes are passed on.
+    inner: W,
+
+    /// The hash of the bytes written so far, if the bytes are hashed.
+    hasher: Option<Sha256>,
+
+    /// The number of bytes written so far.
+    bytes: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Create a writer passing all bytes on to the `inner` writer. The bytes are only hashed if `hash` is set.
+    pub fn new(inner: W, hash: bool) -> HashingWriter<W> {
+        HashingWriter {
+            inner: inner,
+            hasher: if hash {
+                Some(Sha256::default())
+            } else {
+                None
+            },
+            bytes: 0,
+        }
+    }
+
+    /// Finish hashing and return the hash of all bytes written so far to the file at `path`. Return `None` if the bytes
+    /// are not hashed, or if the hash has already been finished.
+    pub fn finish(&mut self, path: &Path) -> Option<OutputHash> {
+        self.hasher.take().map(|hasher| {
+            let sha256: Vec<String> = hasher.result().iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            OutputHash {
+                path: path.to_path_buf(),
+                sha256: sha256.concat(),
+                bytes: self.bytes,
+            }
+        })
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let written: usize = self.inner.write(buffer)?;
+        if let Some(ref mut hasher) = self.hasher {
+            hasher.input(&buffer[..written]);
+        }
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Read;
+    use std::io::Write;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use super::*;
+
+    #[test]
+    fn finish() {
+        let mut writer = HashingWriter::new(Vec::new(), true);
+        writer.write_all(b"abc").expect("Could not write the bytes");
+
+        let hash: OutputHash = writer.finish(Path::new("cascs.csv")).expect("The bytes have not been hashed");
+        assert_eq!(hash.path, PathBuf::from("cascs.csv"));
+        assert_eq!(hash.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hash.bytes, 3);
+        assert_eq!(writer.inner, b"abc".to_vec());
+
+        // The hash can only be finished once.
+        assert!(writer.finish(Path::new("cascs.csv")).is_none());
+    }
+
+    #[test]
+    fn finish_without_hash() {
+        let mut writer = HashingWriter::new(Vec::new(), false);
+        writer.write_all(b"abc").expect("Could not write the bytes");
+        assert!(writer.finish(Path::new("cascs.csv")).is_none());
+        assert_eq!(writer.inner, b"abc".to_vec());
+    }
+
+    #[test]
+    fn write_sidecar() {
+        let hash = OutputHash {
+            path: env::temp_dir().join("crgp_output_hash.csv"),
+            sha256: String::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            bytes: 3,
+        };
+
+        let sidecar: PathBuf = hash.write_sidecar().expect("Could not write the hash");
+        assert_eq!(sidecar, env::temp_dir().join("crgp_output_hash.csv.sha256"));
+
+        let mut contents = String::new();
+        let _ = File::open(&sidecar).expect("Could not open the hash")
+            .read_to_string(&mut contents)
+            .expect("Could not read the hash");
+        assert_eq!(contents, format!("{hash}  crgp_output_hash.csv\n", hash = hash.sha256));
+
+        let _ = remove_file(&sidecar);
+    }
+}
@@ -7,16 +7,32 @@
 //! Reconstruct retweet cascades.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::Entry;
 use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
 use timely::dataflow::Stream;
 use timely::dataflow::Scope;
 use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::binary::Binary;
+use timely_communication::allocator::Allocate;
 
+use UserId;
+use social_graph::GraphOwners;
 use social_graph::InfluenceEdge;
 use social_graph::SocialGraph;
+use timely_extensions::operators::ActivationLog;
+use timely_extensions::operators::IterationCounts;
+use timely_extensions::operators::complete_cascades::CascadeTracker;
+use timely_extensions::operators::is_activated_before;
+use timely_extensions::operators::log_activation;
+use timely_extensions::operators::retweet_activation;
+use timely_extensions::operators::update_maximum;
 use twitter::Retweet;
 use twitter::Tweet;
 use twitter::User;
@@ -28,13 +44,68 @@ pub trait Reconstruct<G: Scope> {
     ///
     /// For a social graph, determine all influences for a retweet within that specific retweet cascade. The `Stream`
     /// of retweets may contain multiple retweet cascades. Each retweet in the retweet stream is expected to be
-    /// broadcast to all workers before calling this operator.
-    fn reconstruct(&self, graph: Stream<G, (User, Vec<User>)>) -> Stream<G, InfluenceEdge<User>>;
+    /// broadcast to all workers before calling this operator. The friendships of each user will be stored on the
+    /// worker given by `owners`.
+    ///
+    /// If `owners` is `None`, the social graph is instead expected to be broadcast to all workers, and all retweets of
+    /// a cascade to be sent to the same single worker before calling this operator. Each worker then stores the entire
+    /// social graph and reconstructs the cascades of the retweets it has received.
+    ///
+    /// If `seed_authors` are given, only the cascades whose original Tweet has been posted by one of these users are
+    /// reconstructed. All other retweets are skipped before activating their users, thus, they neither produce
+    /// influence edges nor keep activations in memory. Since the original Tweet of a retweet is always the very first
+    /// Tweet of its cascade, a retweet of a retweet is matched by the ultimate original author as well.
+    ///
+    /// Influence edges from a user to themselves (e.g. if a user follows themselves and retweets their own Tweet) are
+    /// never produced. Instead, they are counted in `suppressed_self_edges`.
+    ///
+    /// The activations of a cascade are dropped once it is complete, i.e. if `cascade_timeout` is given and the cascade
+    /// has timed out at the end of a batch of Retweets (see `CompleteCascades`). A later Retweet of the cascade starts
+    /// it anew without the earlier activations; such late Retweets are logged and counted in `late_retweets` by the
+    /// worker owning the retweeting user. The maximum number of cascades whose activations have been kept at the same
+    /// time is stored in `peak_live_cascades`.
+    ///
+    /// If `cascades_time_ordered`, the Retweets within each cascade must be ordered by their creation time. If the
+    /// original Tweet and the cascade's previous Retweet have been created before the current Retweet, all users
+    /// activated so far in the cascade have been activated before it, thus, their activation times do not have to be
+    /// compared. Retweets created in the same second as the previous one are compared as usual. Debug builds assert
+    /// the ordering; unordered input yields wrong results otherwise.
+    ///
+    /// A friend might have influenced the Retweet if they have been activated before it, or at most
+    /// `activation_grace_seconds` after it (see `is_activated_before`). Only activations by Retweets processed so far
+    /// are known. A negative grace window disables the shortcut for time-ordered cascades. Self-edges are always
+    /// determined without a grace window.
+    ///
+    /// If `ignore_activation_times`, any friend activated by a Retweet processed so far (or having posted the original
+    /// Tweet) might have influenced the Retweet, regardless of the activation times. The influence edges are then a
+    /// superset of those found when comparing the times. Self-edges are still determined as described above.
+    ///
+    /// When a cascade is started, its activations reserve memory for `activation_capacity` users, avoiding repeated
+    /// reallocations as large cascades grow.
+    ///
+    /// If an `activation_log` is given, each user's activation by a Retweet is recorded in it once per cascade (or
+    /// again, if the cascade has been started anew), by the worker owning the user.
+    ///
+    /// For each Retweet, either the retweeting user's friends or the cascade's activations are iterated, whichever are
+    /// fewer. Both cases and the number of iterated elements are counted in `iteration_counts`.
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+    fn reconstruct(&self, graph: Stream<G, (User, Vec<User>)>, owners: Option<GraphOwners>,
+                   seed_authors: Option<HashSet<UserId>>, suppressed_self_edges: Arc<AtomicUsize>,
+                   cascade_timeout: Option<u64>, cascades_time_ordered: bool, activation_grace_seconds: i64,
+                   ignore_activation_times: bool, activation_capacity: usize, peak_live_cascades: Arc<AtomicUsize>,
+                   late_retweets: Arc<AtomicUsize>, activation_log: Option<Arc<Mutex<ActivationLog>>>,
+                   iteration_counts: IterationCounts) -> Stream<G, InfluenceEdge<User>>;
 }
 
 impl<G: Scope> Reconstruct<G> for Stream<G, Retweet>
 where G::Timestamp: Hash {
-    fn reconstruct(&self, graph: Stream<G, (User, Vec<User>)>) -> Stream<G, InfluenceEdge<User>> {
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+    fn reconstruct(&self, graph: Stream<G, (User, Vec<User>)>, owners: Option<GraphOwners>,
+                   seed_authors: Option<HashSet<UserId>>, suppressed_self_edges: Arc<AtomicUsize>,
+                   cascade_timeout: Option<u64>, cascades_time_ordered: bool, activation_grace_seconds: i64,
+                   ignore_activation_times: bool, activation_capacity: usize, peak_live_cascades: Arc<AtomicUsize>,
+                   late_retweets: Arc<AtomicUsize>, activation_log: Option<Arc<Mutex<ActivationLog>>>,
+                   iteration_counts: IterationCounts) -> Stream<G, InfluenceEdge<User>> {
         // For each user, given by their ID, the set of their friends, given by their ID.
         let mut edges = SocialGraph::new();
 
@@ -43,10 +114,25 @@ where G::Timestamp: Hash {
         // retweeted within a cascade.
         let mut activations: HashMap<u64, HashMap<User, u64>> = HashMap::new();
 
+        // Track the cascades with activations to determine when they are complete.
+        let mut live_cascades = CascadeTracker::new(cascade_timeout);
+
+        // Each Retweet is seen by all workers, but its activation is only logged by the worker owning the user. If the
+        // social graph is stored on all workers, each Retweet is only seen by a single worker.
+        let worker_index: u64 = self.scope().index() as u64;
+        let peers: u64 = self.scope().peers() as u64;
+        let activation_owners: Option<GraphOwners> = owners.clone();
+
         self.binary_stream(
             &graph,
             Pipeline,
-            Exchange::new(|friendships: &(User, Vec<User>)| friendships.0.id as u64),
+            Exchange::new(move |friendships: &(User, Vec<User>)| {
+                // Routing the broadcast friendships to the current worker keeps them on each worker.
+                match owners {
+                    Some(ref owners) => owners.route(&friendships.0),
+                    None => worker_index
+                }
+            }),
             "Reconstruct",
             move |retweets, friendships, output| {
                 // Input 1: Process the retweets.
@@ -54,17 +140,65 @@ where G::Timestamp: Hash {
                     let mut session = output.session(&time);
                     for retweet in retweet_data.take().iter() {
                         let original_tweet: &Tweet = &retweet.retweeted_status;
+                        if let Some(ref seed_authors) = seed_authors {
+                            if !seed_authors.contains(&original_tweet.user.id) {
+                                continue;
+                            }
+                        }
+
+                        // Each Retweet is counted and logged only by the worker owning the retweeting user.
+                        let is_owner: bool = match activation_owners {
+                            Some(ref owners) => owners.route(&retweet.user) % peers == worker_index,
+                            None => true
+                        };
+
+                        // The activations of a timed out cascade have been dropped, thus, a late Retweet starts it
+                        // anew.
+                        let is_late: bool = !activations.contains_key(&original_tweet.id) &&
+                            live_cascades.has_timed_out(original_tweet.id);
+                        if is_late && is_owner {
+                            let _ = late_retweets.fetch_add(1, Ordering::SeqCst);
+                            warn!("Retweet {retweet} arrived after cascade {cascade} has timed out, starting the \
+                                   cascade anew", retweet = retweet.id, cascade = original_tweet.id);
+                        }
+
+                        let previous_retweet: Option<u64> = live_cascades.add_retweet(original_tweet.id,
+                                                                                      retweet.created_at);
+                        if cascades_time_ordered {
+                            debug_assert!(previous_retweet.map_or(true, |previous| previous <= retweet.created_at),
+                                          "Retweet {retweet} is not ordered by time within cascade {cascade}",
+                                          retweet = retweet.id, cascade = original_tweet.id);
+                        }
+
+                        // In time-ordered cascades, all activations so far precede the current Retweet if the original
+                        // Tweet and the previous Retweet do. Then, they only have to be compared if the grace window
+                        // requires them to be earlier by some margin.
+                        let compare_activations: bool = !cascades_time_ordered || activation_grace_seconds < 0 ||
+                            original_tweet.created_at >= retweet.created_at ||
+                            previous_retweet.map_or(false, |previous| previous >= retweet.created_at);
 
                         // Mark this user as active for this cascade.
                         let cascade_activations: &mut HashMap<User, u64> = &mut (*activations.entry(original_tweet.id)
                             .or_insert_with(|| {
                                 // Create a new map for the activations of this cascade and insert the original tweeter.
-                                let mut cascade_activations = HashMap::new();
+                                let mut cascade_activations = HashMap::with_capacity(activation_capacity);
                                 let _ = cascade_activations.insert(original_tweet.user, original_tweet.created_at);
                                 cascade_activations
                             }));
-                        let _ = cascade_activations.entry(retweet.user)
-                            .or_insert(retweet.created_at);
+
+                        // For time-ordered cascades, the retweeting user is the only one who might not have been
+                        // activated before this Retweet.
+                        let is_retweeter_activated_before: bool = match cascade_activations.entry(retweet.user) {
+                            Entry::Occupied(_) => true,
+                            Entry::Vacant(activation) => {
+                                let _ = activation.insert(retweet.created_at);
+                                if is_owner {
+                                    log_activation(&activation_log, original_tweet.id, retweet.user,
+                                                   retweet.created_at);
+                                }
+                                false
+                            }
+                        };
 
                         // If this is the worker storing the retweeting user's friends, find
                         // all influences. Otherwise, move on.
@@ -78,20 +212,39 @@ where G::Timestamp: Hash {
                         // activations.
                         if friends.len() <= cascade_activations.len() {
                             // Iterate over the friends.
+                            iteration_counts.add_friend_iteration(friends.len());
                             for &friend in friends {
-                                let is_influencer_activated: bool = match cascade_activations.get(&friend) {
-                                    Some(activation_timestamp) => &retweet.created_at > activation_timestamp,
+                                let activation: Option<u64> = cascade_activations.get(&friend).cloned();
+                                let is_influencer_activated: bool = match activation {
+                                    Some(_) if ignore_activation_times && friend != retweet.user => true,
+                                    Some(_) if !compare_activations => {
+                                        friend != retweet.user || is_retweeter_activated_before
+                                    },
+                                    Some(activation_timestamp) if friend == retweet.user => {
+                                        retweet.created_at > activation_timestamp
+                                    },
+                                    Some(activation_timestamp) => {
+                                        is_activated_before(activation_timestamp, retweet.created_at,
+                                                            activation_grace_seconds)
+                                    },
                                     None => false
                                 };
+                                if is_influencer_activated && friend == retweet.user {
+                                    let _ = suppressed_self_edges.fetch_add(1, Ordering::SeqCst);
+                                    continue;
+                                }
                                 if is_influencer_activated {
                                     let influence = InfluenceEdge::new(friend, retweet.user, retweet.created_at,
                                                                        retweet.id, original_tweet.id,
-                                                                       original_tweet.user);
+                                                                       original_tweet.user)
+                                        .influencer_activation(retweet_activation(friend, original_tweet.user,
+                                                                                  activation));
                                     session.give(influence);
                                 }
                             }
                         } else {
                             // Iterate over the activations.
+                            iteration_counts.add_activation_iteration(cascade_activations.len());
                             for (user, activation_timestamp) in cascade_activations {
                                 // If the current activation is not a friend, move on.
                                 let friend: User;
@@ -102,16 +255,39 @@ where G::Timestamp: Hash {
                                 }
 
                                 // Ensure the influence is possible.
-                                let is_influencer_activated: bool = &retweet.created_at > activation_timestamp;
+                                let is_influencer_activated: bool = if ignore_activation_times &&
+                                                                       friend != retweet.user {
+                                    true
+                                } else if !compare_activations {
+                                    friend != retweet.user || is_retweeter_activated_before
+                                } else if friend == retweet.user {
+                                    &retweet.created_at > activation_timestamp
+                                } else {
+                                    is_activated_before(*activation_timestamp, retweet.created_at,
+                                                        activation_grace_seconds)
+                                };
+                                if is_influencer_activated && friend == retweet.user {
+                                    let _ = suppressed_self_edges.fetch_add(1, Ordering::SeqCst);
+                                    continue;
+                                }
                                 if is_influencer_activated {
                                     let influence = InfluenceEdge::new(friend, retweet.user, retweet.created_at,
                                                                        retweet.id, original_tweet.id,
-                                                                       original_tweet.user);
+                                                                       original_tweet.user)
+                                        .influencer_activation(retweet_activation(friend, original_tweet.user,
+                                                                                  Some(*activation_timestamp)));
                                     session.give(influence);
                                 }
                             }
                         }
                     };
+
+                    // Drop the activations of all cascades that have been completed by this batch.
+                    debug_assert_eq!(activations.len(), live_cascades.len());
+                    update_maximum(&peak_live_cascades, activations.len());
+                    for (cascade_id, _edges, _retweets) in live_cascades.complete_timed_out() {
+                        let _ = activations.remove(&cascade_id);
+                    }
                 });
 
                 // Input 2: Capture all friends for each user.
@@ -123,8 +299,16 @@ where G::Timestamp: Hash {
                         let friendship_set: &mut Vec<User> = edges.entry(user)
                             .or_insert_with(|| Vec::with_capacity(friends.len()));
                         friendship_set.extend(friends);
+
+                        // Friend lists sorted while loading (see `Configuration::sort_friends`) need not be sorted
+                        // again, unless they have been merged with earlier ones.
+                        if !friendship_set.windows(2).all(|pair| pair[0] <= pair[1]) {
+                            friendship_set.sort();
+                        }
+
+                        // Merged friend lists may contain a friend more than once.
+                        friendship_set.dedup();
                         friendship_set.shrink_to_fit();
-                        friendship_set.sort();
                     };
 
                     edges.shrink_to_fit();
@@ -0,0 +1,124 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Uniform random samples of streams of unknown length.
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand::XorShiftRng;
+
+/// A uniform random sample of fixed size over all items offered to it, using reservoir sampling.
+///
+/// Only the sampled items are kept in memory. For a given seed and order of offered items, the sample is
+/// deterministic.
+#[derive(Clone, Debug)]
+pub struct Reservoir<T> {
+    /// The maximum number of items in the sample.
+    capacity: usize,
+
+    /// The number of items offered so far.
+    seen: u64,
+
+    /// The currently sampled items.
+    items: Vec<T>,
+
+    /// The random number generator deciding which items are kept.
+    rng: XorShiftRng,
+}
+
+impl<T> Reservoir<T> {
+    /// Create an empty reservoir keeping at most `capacity` items, using the given `seed` for its random decisions.
+    pub fn new(capacity: usize, seed: u64) -> Reservoir<T> {
+        // The generator must not be seeded with zeros only, thus the fixed last two parts.
+        let seed: [u32; 4] = [seed as u32, (seed >> 32) as u32, 0x9E37_79B9, 0x243F_6A88];
+
+        Reservoir {
+            capacity: capacity,
+            seen: 0,
+            items: Vec::with_capacity(capacity),
+            rng: XorShiftRng::from_seed(seed),
+        }
+    }
+
+    /// Offer the `item` to the sample. The `n`-th offered item replaces a random item in the sample with a probability
+    /// of `capacity / n`.
+    pub fn offer(&mut self, item: T) {
+        self.seen += 1;
+
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+            return;
+        }
+
+        let index: u64 = self.rng.gen_range(0, self.seen);
+        if index < self.capacity as u64 {
+            self.items[index as usize] = item;
+        }
+    }
+
+    /// Get the sampled items, emptying the reservoir.
+    pub fn take(&mut self) -> Vec<T> {
+        self.seen = 0;
+        ::std::mem::replace(&mut self.items, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offer() {
+        // Less items than the capacity.
+        let mut reservoir: Reservoir<u64> = Reservoir::new(10, 42);
+        for item in 0..5 {
+            reservoir.offer(item);
+        }
+        assert_eq!(reservoir.take(), vec![0, 1, 2, 3, 4]);
+
+        // More items than the capacity.
+        let mut reservoir: Reservoir<u64> = Reservoir::new(10, 42);
+        for item in 0..1000 {
+            reservoir.offer(item);
+        }
+        let mut sample: Vec<u64> = reservoir.take();
+        assert_eq!(sample.len(), 10);
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|item| *item < 1000));
+
+        // Empty samples.
+        let mut reservoir: Reservoir<u64> = Reservoir::new(0, 42);
+        for item in 0..10 {
+            reservoir.offer(item);
+        }
+        assert!(reservoir.take().is_empty());
+    }
+
+    #[test]
+    fn offer_deterministic() {
+        let sample = |seed: u64| -> Vec<u64> {
+            let mut reservoir: Reservoir<u64> = Reservoir::new(10, seed);
+            for item in 0..1000 {
+                reservoir.offer(item);
+            }
+            reservoir.take()
+        };
+
+        assert_eq!(sample(42), sample(42));
+        assert_eq!(sample(0), sample(0));
+        assert_ne!(sample(0), sample(42));
+    }
+
+    #[test]
+    fn take() {
+        let mut reservoir: Reservoir<u64> = Reservoir::new(2, 42);
+        reservoir.offer(1);
+        assert_eq!(reservoir.take(), vec![1]);
+        assert!(reservoir.take().is_empty());
+    }
+}
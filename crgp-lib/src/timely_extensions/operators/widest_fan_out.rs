@@ -0,0 +1,201 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Find the user with the widest fan-out within each Retweet cascade.
+//!
+//! The fan-out of a user within a cascade is the number of influence edges from that user in the cascade, i.e. their
+//! out-degree in the cascade's influence graph. Users with a wide fan-out are super-spreaders of the cascade.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use timely::dataflow::Scope;
+use timely::dataflow::Stream;
+use timely::dataflow::channels::pact::Exchange;
+use timely::dataflow::operators::binary::Binary;
+
+use social_graph::InfluenceEdge;
+use twitter::User;
+
+/// Emit the user with the widest fan-out for each cascade once it is complete.
+pub trait WidestFanOut<G: Scope> {
+    /// For a stream of influence edges and the `completed_cascades` (as produced by `CompleteCascades`), emit
+    /// `(cascade_id, influencer, fan_out)` for each completed cascade, where `influencer` is the user with the most
+    /// influence edges in the cascade and `fan_out` is their number of influence edges. Ties are broken in favor of
+    /// the user with the smallest ID. Cascades without influence edges are not reported.
+    ///
+    /// Independently of the completion of the cascades, the widest fan-out across all cascades seen so far is kept in
+    /// `widest_fan_out`. Each cascade is tracked on a single worker.
+    fn widest_fan_out(&self, completed_cascades: &Stream<G, (u64, u64, u64)>,
+                      widest_fan_out: Arc<Mutex<Option<(u64, User, u64)>>>) -> Stream<G, (u64, User, u64)>;
+}
+
+impl<G: Scope> WidestFanOut<G> for Stream<G, InfluenceEdge<User>>
+where G::Timestamp: Hash {
+    fn widest_fan_out(&self, completed_cascades: &Stream<G, (u64, u64, u64)>,
+                      widest_fan_out: Arc<Mutex<Option<(u64, User, u64)>>>) -> Stream<G, (u64, User, u64)> {
+        let mut tracker = FanOutTracker::new();
+
+        // For each timely time, a list of the IDs of the cascades completed at that time.
+        let mut completions_at_time: HashMap<G::Timestamp, Vec<u64>> = HashMap::new();
+
+        self.binary_notify(
+            completed_cascades,
+            Exchange::new(|edge: &InfluenceEdge<User>| edge.cascade_id),
+            Exchange::new(|&(cascade_id, _edges, _retweets): &(u64, u64, u64)| cascade_id),
+            "WidestFanOut",
+            Vec::new(),
+            move |edges, completions, output, notificator| {
+                // Count the influence edges per cascade and influencer, updating the overall widest fan-out once per
+                // batch of edges.
+                edges.for_each(|_time, edge_data| {
+                    let mut widest_in_batch: Option<(u64, User, u64)> = None;
+                    for edge in edge_data.iter() {
+                        let fan_out: u64 = tracker.add_edge(edge.cascade_id, edge.influencer);
+                        let candidate: (u64, User, u64) = (edge.cascade_id, edge.influencer, fan_out);
+                        if is_wider(&candidate, &widest_in_batch) {
+                            widest_in_batch = Some(candidate);
+                        }
+                    }
+
+                    if let Some(candidate) = widest_in_batch {
+                        let mut widest = widest_fan_out.lock().expect("The widest fan-out has been poisoned");
+                        if is_wider(&candidate, &widest) {
+                            *widest = Some(candidate);
+                        }
+                    }
+                });
+
+                // Save the completed cascades to report them once all influence edges of their time have arrived.
+                completions.for_each(|time, completion_data| {
+                    notificator.notify_at(time.clone());
+
+                    let mut completions_now = completions_at_time.entry(time.time().clone())
+                        .or_insert_with(Vec::new);
+                    for &(cascade_id, _edges, _retweets) in completion_data.iter() {
+                        completions_now.push(cascade_id);
+                    }
+                });
+
+                notificator.for_each(|time, _num, _notify| {
+                    if let Some(mut completions_now) = completions_at_time.remove(&time) {
+                        completions_now.sort();
+                        let mut session = output.session(&time);
+                        for cascade_id in completions_now {
+                            if let Some((influencer, fan_out)) = tracker.complete(cascade_id) {
+                                session.give((cascade_id, influencer, fan_out));
+                            }
+                        }
+                    }
+                });
+            }
+        )
+    }
+}
+
+/// Determine if the fan-out `candidate` is wider than the `current` widest fan-out. Ties are broken in favor of the
+/// smaller cascade ID, then of the smaller user ID.
+fn is_wider(candidate: &(u64, User, u64), current: &Option<(u64, User, u64)>) -> bool {
+    match *current {
+        Some((cascade_id, influencer, fan_out)) => {
+            (candidate.2, cascade_id, influencer) > (fan_out, candidate.0, candidate.1)
+        },
+        None => true
+    }
+}
+
+/// Count the influence edges per influencer of all cascades that have not been completed yet.
+#[derive(Debug, Default)]
+struct FanOutTracker {
+    /// For each cascade, given by its ID, the number of influence edges per influencer.
+    cascades: HashMap<u64, HashMap<User, u64>>,
+}
+
+impl FanOutTracker {
+    /// Create a new tracker without any cascades.
+    fn new() -> FanOutTracker {
+        FanOutTracker::default()
+    }
+
+    /// Count an influence edge from `influencer` in the cascade `cascade_id`. Return the influencer's fan-out in the
+    /// cascade so far.
+    fn add_edge(&mut self, cascade_id: u64, influencer: User) -> u64 {
+        let fan_out: &mut u64 = self.cascades.entry(cascade_id)
+            .or_insert_with(HashMap::new)
+            .entry(influencer)
+            .or_insert(0);
+        *fan_out += 1;
+        *fan_out
+    }
+
+    /// Stop tracking the cascade `cascade_id`. Return the user with the widest fan-out in the cascade and their
+    /// fan-out, preferring the user with the smallest ID on ties, or `None` if the cascade has no influence edges.
+    fn complete(&mut self, cascade_id: u64) -> Option<(User, u64)> {
+        let fan_outs: HashMap<User, u64> = match self.cascades.remove(&cascade_id) {
+            Some(fan_outs) => fan_outs,
+            None => return None
+        };
+
+        let mut widest: Option<(User, u64)> = None;
+        for (influencer, fan_out) in fan_outs {
+            let is_new_widest: bool = match widest {
+                Some((widest_influencer, widest_fan_out)) => {
+                    fan_out > widest_fan_out || (fan_out == widest_fan_out && influencer < widest_influencer)
+                },
+                None => true
+            };
+            if is_new_widest {
+                widest = Some((influencer, fan_out));
+            }
+        }
+
+        widest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn complete_super_spreader() {
+        let mut tracker = FanOutTracker::new();
+        assert!(tracker.cascades.is_empty());
+
+        // User 7 influences five users in cascade 1, all other users only one.
+        for _ in 0..5 {
+            let _ = tracker.add_edge(1, User::new(7));
+        }
+        let _ = tracker.add_edge(1, User::new(2));
+        let _ = tracker.add_edge(1, User::new(3));
+        assert_eq!(tracker.add_edge(2, User::new(7)), 1);
+        assert_eq!(tracker.cascades.len(), 2);
+
+        assert_eq!(tracker.complete(1), Some((User::new(7), 5)));
+        assert_eq!(tracker.cascades.len(), 1);
+        assert_eq!(tracker.complete(1), None);
+
+        // Ties are broken in favor of the smallest user ID.
+        let _ = tracker.add_edge(2, User::new(4));
+        assert_eq!(tracker.complete(2), Some((User::new(4), 1)));
+        assert!(tracker.cascades.is_empty());
+    }
+
+    #[test]
+    fn is_wider_fan_out() {
+        assert!(is_wider(&(1, User::new(7), 5), &None));
+        assert!(is_wider(&(1, User::new(7), 5), &Some((2, User::new(3), 4))));
+        assert!(!is_wider(&(1, User::new(7), 4), &Some((2, User::new(3), 5))));
+
+        // Ties are broken in favor of the smallest cascade ID, then the smallest user ID.
+        assert!(is_wider(&(1, User::new(7), 5), &Some((2, User::new(3), 5))));
+        assert!(is_wider(&(1, User::new(3), 5), &Some((1, User::new(7), 5))));
+        assert!(!is_wider(&(1, User::new(7), 5), &Some((1, User::new(7), 5))));
+    }
+}
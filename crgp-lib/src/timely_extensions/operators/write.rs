@@ -6,46 +6,158 @@
 
 //! Write a stream to a file.
 
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs::File;
 use std::hash::Hash;
+use std::io;
 use std::io::Write as IOWrite;
 use std::io::BufWriter;
+use std::mem;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::mpsc::Sender;
+use std::sync::atomic::Ordering;
 
+use serde_json;
 use timely::dataflow::Stream;
 use timely::dataflow::Scope;
 use timely::dataflow::channels::pact::Exchange;
-use timely::dataflow::operators::unary::Unary;
+use timely::dataflow::operators::binary::Binary;
 
+use configuration::InputSource;
+use Configuration;
+use Error;
+use Result;
+#[cfg(feature = "rdkafka")]
+use configuration::KafkaErrorPolicy;
+use configuration::MAX_MATRIX_USERS;
+use configuration::OutputFormat;
 use configuration::OutputTarget;
+use configuration::TimeUnit;
+use social_graph::ABOMONATED_EDGES_FILENAME;
 use social_graph::InfluenceEdge;
+use social_graph::create_abomonated_edges;
+use social_graph::source::tar::get_selected_users;
+use social_graph::write_abomonated_edge;
+#[cfg(feature = "rdkafka")]
+use timely_extensions::operators::kafka::KafkaProducer;
+use timely_extensions::operators::output_hash::HashingWriter;
+use timely_extensions::operators::output_hash::OutputHash;
+use timely_extensions::operators::reservoir::Reservoir;
+use twitter::ScreenNames;
 use twitter::User;
+use twitter::UserId;
+
+/// The name of the file within the output directory to which the influence edges are written as text.
+const RESULT_FILENAME: &'static str = "cascs.csv";
+
+/// The name of the file within the output directory to which the users are written for the import into Neo4j.
+const NEO4J_NODES_FILENAME: &'static str = "users.csv";
+
+/// The header of the nodes file for the import into Neo4j.
+const NEO4J_NODES_HEADER: &'static str = "userId:ID,:LABEL";
+
+/// The header of the result file for the import into Neo4j.
+const NEO4J_RELATIONSHIPS_HEADER: &'static str = ":START_ID,:END_ID,cascade:long,:TYPE";
+
+/// The attributes of the nodes of dummy users in a DOT file.
+const DOT_DUMMY_NODE_STYLE: &'static str = "shape=box,color=gray";
 
 /// Write a stream to a file, passing on all seen messages.
 pub trait Write<G: Scope> {
-    /// Write all input messages to the given `output_target` without producing any output. If `output_target` is
-    /// `None`, the messages will be passed on without any further operations.
+    /// Write all input messages to the `output_target` given in the `configuration` in its `output_format` without
+    /// producing any output. If the `output_target` is `None`, the messages will be passed on without any further
+    /// operations. If it is `OutputTarget::Multi`, the messages are written to each target within it; a target to which
+    /// writing fails is skipped without affecting the others.
+    ///
+    /// If `sample_output` is given, only a uniform random sample of at most that many influence edges will be written
+    /// once the computation has finished, using the `seed` to draw the sample. The sample is drawn over all edges
+    /// written by this operator, not over cascades, i.e. large cascades will be represented by more edges than small
+    /// ones. Only the sampled edges are kept in memory.
+    ///
+    /// If the `output_format` is `OutputFormat::Matrix`, the adjacency matrices of all cascades are written once the
+    /// computation has finished. If it is `OutputFormat::EdgesWithScreenNames`, the `screen_names` file is loaded
+    /// before the first edge is written. If it is `OutputFormat::Abomonation`, the edges are appended to the result
+    /// file in a directory in binary form. If it is `OutputFormat::Neo4j`, the edges are written to a directory as
+    /// relationships for Neo4j, and the users occurring in them are written once the computation has finished. If it
+    /// is `OutputFormat::CascadeSummary`, the edges of each cascade are buffered and a summary is written once the
+    /// cascade is reported in the `completed_cascades` stream.
+    ///
+    /// If `output_root_users` is given, the users listed in it are loaded before the first edge is written. Each edge
+    /// is then written only if its `original_user`, i.e. the poster of its cascade's original Tweet, is one of them;
+    /// all other edges are dropped. If the users cannot be loaded, no edges are written and the error is stored in
+    /// `output_error`, such that the computation fails once it has finished.
+    ///
+    /// If `flush_on_cascade_complete` is set, the edges of each cascade are buffered until the cascade is reported in
+    /// the `completed_cascades` stream (as produced by `CompleteCascades`), and then written sorted by their time.
+    ///
+    /// If `deduplicate_edges` is set, only the first influence edge from each influencer to each influencee is written
+    /// per cascade. The edges of each timely time are sorted by their time and Retweet before checking them, thus, the
+    /// earliest of duplicate edges is kept. The pairs written for a cascade are forgotten once it is reported in the
+    /// `completed_cascades` stream.
+    ///
+    /// If the `output_target` is `OutputTarget::Dot`, the edges are buffered regardless of the `output_format` and
+    /// written as a single Graphviz graph once the computation has finished.
+    ///
+    /// All influence edges are written by the first worker. If the `output_target` is `OutputTarget::Channel`, each
+    /// worker clones the channel's sender, and the first worker sends the edges in the order in which it writes them.
+    /// The clones are dropped once the computation has finished. If `buffer_output` is set, the lines written to text
+    /// targets are buffered on this worker and written at once when all influence edges of a timely time have been
+    /// processed, such that the output never contains partial or interleaved lines.
+    ///
+    /// Once all influence edges of a timely time have been written, their number is added to `emitted_edges`. Once the
+    /// computation has finished, the number of edges not written since `max_output_edges` had been reached is added to
+    /// `skipped_edges`. If `track_friendship_utilization` is set, the friendships the written edges have used are added
+    /// to `used_friendships` as pairs of influencee and influencer.
+    ///
+    /// If `hash_output` is set, the bytes written to the result file in each output directory are hashed. Once the
+    /// computation has finished, the hash is written next to each result file, and the hash of the result file in the
+    /// first output directory is stored in `output_hash`.
     ///
     /// On any IO error, an error log message will be generated using the
-    /// [`log`](https://doc.rust-lang.org/log/log/index.html) crate.
-    fn write(&self, output_target: OutputTarget) -> Stream<G, InfluenceEdge<User>>;
+    /// [`log`](https://doc.rust-lang.org/log/log/index.html) crate. If producing to Kafka fails and its error policy is
+    /// `KafkaErrorPolicy::Fail`, the target is skipped and the first such error is stored in `output_error`, such that
+    /// the computation can fail once it has finished.
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+    fn write(&self, configuration: &Configuration, completed_cascades: &Stream<G, (u64, u64, u64)>,
+             emitted_edges: Arc<AtomicUsize>, skipped_edges: Arc<AtomicUsize>,
+             used_friendships: Arc<Mutex<HashSet<(User, User)>>>, output_hash: Arc<Mutex<Option<OutputHash>>>,
+             output_error: Arc<Mutex<Option<String>>>) -> Stream<G, InfluenceEdge<User>>;
 }
 
 impl<G: Scope> Write<G> for Stream<G, InfluenceEdge<User>>
 where G::Timestamp: Hash {
-    #[cfg_attr(feature = "cargo-clippy", allow(print_stdout))]
-    fn write(&self, output_target: OutputTarget) -> Stream<G, InfluenceEdge<User>> {
-        let mut file_writer: Option<BufWriter<File>> = None;
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+    fn write(&self, configuration: &Configuration, completed_cascades: &Stream<G, (u64, u64, u64)>,
+             emitted_edges: Arc<AtomicUsize>, skipped_edges: Arc<AtomicUsize>,
+             used_friendships: Arc<Mutex<HashSet<(User, User)>>>, output_hash: Arc<Mutex<Option<OutputHash>>>,
+             output_error: Arc<Mutex<Option<String>>>) -> Stream<G, InfluenceEdge<User>> {
+        let mut writer = EdgeWriter::new(configuration, skipped_edges, output_hash, output_error);
+        let track_friendship_utilization: bool = configuration.track_friendship_utilization;
+        let mut written_pairs: Option<CascadePairs> = if configuration.deduplicate_edges {
+            Some(CascadePairs::new())
+        } else {
+            None
+        };
 
         // For each timely time, a list of the influences seen at that time.
         let mut influences_at_time: HashMap<G::Timestamp, Vec<InfluenceEdge<User>>> = HashMap::new();
 
-        self.unary_notify(
+        // For each timely time, a list of the IDs of the cascades completed at that time, and their number of Retweets.
+        let mut completions_at_time: HashMap<G::Timestamp, Vec<(u64, u64)>> = HashMap::new();
+
+        self.binary_notify(
+            completed_cascades,
             Exchange::new(|_: &InfluenceEdge<User>| 0),
+            Exchange::new(|_: &(u64, u64, u64)| 0),
             "Write",
             Vec::new(),
-            move |influences, _output, notificator| {
+            move |influences, completions, _output, notificator| {
                 // Process the influence edges: immediately pass them on and save them for batched writing.
                 influences.for_each(|time, influence_data| {
                     notificator.notify_at(time.clone());
@@ -57,59 +169,1382 @@ where G::Timestamp: Hash {
                     }
                 });
 
-                // If a timely time is done, write all associated edges.
+                // Save the completed cascades to flush them after the influence edges of the same time.
+                completions.for_each(|time, completion_data| {
+                    notificator.notify_at(time.clone());
+
+                    let mut completions_now = completions_at_time.entry(time.time().clone())
+                        .or_insert_with(Vec::new);
+                    for &(cascade_id, _edges, retweets) in completion_data.iter() {
+                        completions_now.push((cascade_id, retweets));
+                    }
+                });
+
+                // If a timely time is done, write all associated edges, then the buffered edges of the cascades
+                // completed at that time.
                 notificator.for_each(|time, _num, _notify| {
-                    // Introduce this sub-scope to unborrow `influences_at_time` so old entries can be removed from it
-                    // at the end.
-                    {
-                        let influences_now: &Vec<InfluenceEdge<User>> = match influences_at_time.get(&time) {
-                            Some(influences_now) => influences_now,
-                            None => return
-                        };
-
-                        for influence in influences_now {
-                            // Tell the compiler the influence edge is of type 'InfluenceEdge<u64>'.
-                            let influence: &InfluenceEdge<User> = influence;
-
-                            match output_target {
-                                OutputTarget::Directory(ref directory) => {
-                                    if file_writer.is_none() {
-                                        let filename: String = String::from("cascs.csv");
-                                        let path: PathBuf = directory.join(filename);
-                                        let file: File = match File::create(&path) {
-                                            Ok(file) => file,
-                                            Err(message) => {
-                                                error!("Could not create {file}: {error}",
-                                                       file = path.display(), error = message);
-                                                continue;
-                                            }
-                                        };
-
-                                        trace!("Created result file {file}", file = path.display());
-                                        file_writer = Some(BufWriter::new(file));
-                                    }
-
-                                    // Get the writer. Failing is impossible since the writer has just been created.
-                                    let writer: &mut BufWriter<File> = match file_writer {
-                                        Some(ref mut writer) => writer,
-                                        None => continue,
-                                    };
-
-                                    // Write the edge.
-                                    let _ = writeln!(writer, "{}", influence);
-                                },
-                                OutputTarget::StdOut => {
-                                    println!("{}", influence);
-                                },
-                                OutputTarget::None => {}
+                    if let Some(mut influences_now) = influences_at_time.remove(&time) {
+                        if let Some(ref mut written_pairs) = written_pairs {
+                            influences_now.sort_by_key(|influence| {
+                                (influence.timestamp, influence.retweet_id, influence.influencee, influence.influencer)
+                            });
+                            influences_now.retain(|influence| written_pairs.insert(influence));
+                        }
+                        for influence in &influences_now {
+                            writer.write(influence);
+                        }
+                        let _ = emitted_edges.fetch_add(influences_now.len(), Ordering::SeqCst);
+
+                        if track_friendship_utilization {
+                            let mut used_friendships = used_friendships.lock()
+                                .expect("The used friendships have been poisoned");
+                            for influence in &influences_now {
+                                let _ = used_friendships.insert((influence.influencee, influence.influencer));
                             }
                         }
                     }
 
-                    // Finally, remove the influence edges for this time.
-                    let _ = influences_at_time.remove(&time);
+                    if let Some(mut completions_now) = completions_at_time.remove(&time) {
+                        completions_now.sort();
+                        for (cascade_id, retweets) in completions_now {
+                            if let Some(ref mut written_pairs) = written_pairs {
+                                written_pairs.complete(cascade_id);
+                            }
+                            writer.complete_cascade(cascade_id, retweets);
+                        }
+                    }
+
+                    // Make sure all influences of this time have been delivered.
+                    writer.flush();
                 });
             }
         )
     }
 }
+
+/// Write influence edges to an output target, or sample them and write the sample when dropped.
+struct EdgeWriter {
+    /// The targets to which the influence edges are written.
+    sinks: Vec<Sink>,
+
+    /// Format in which the influence edges are written.
+    output_format: OutputFormat,
+
+    /// A sample of the influence edges, if only a sample is written.
+    sample: Option<Reservoir<InfluenceEdge<User>>>,
+
+    /// All pairs of influencer and influencee that have already been written (only used for distinct pairs).
+    written_pairs: HashSet<(User, User)>,
+
+    /// For each cascade, given by its ID, the buffered adjacency matrix (only used for matrices). Cascades with too
+    /// many users are set to `None`.
+    matrices: BTreeMap<u64, Option<AdjacencyMatrix>>,
+
+    /// The file mapping user IDs to screen names, until it has been loaded.
+    screen_names_file: Option<InputSource>,
+
+    /// The screen names of the users, loaded on the first write (only used for edges with screen names).
+    screen_names: ScreenNames,
+
+    /// The file listing the users whose cascades will be written, until it has been loaded.
+    root_users_file: Option<InputSource>,
+
+    /// The users whose cascades will be written, loaded on the first write. If `None`, all cascades are written.
+    root_users: Option<HashSet<UserId>>,
+
+    /// The influence edges of all incomplete cascades, if the edges are only written once their cascade is complete.
+    incomplete_cascades: Option<CascadeBuffer>,
+
+    /// The unit in which the time between the activation of the influencer and the influence is appended to each
+    /// written edge. If `None`, the time is omitted.
+    time_delta_unit: Option<TimeUnit>,
+
+    /// The maximum number of influence edges written. If `None`, all edges are written.
+    max_output_edges: Option<usize>,
+
+    /// The number of influence edges written so far.
+    written_edges: usize,
+
+    /// The number of influence edges not written because the maximum has been reached.
+    skipped_edges: usize,
+
+    /// The shared number of influence edges not written because the maximum has been reached, to which the
+    /// `skipped_edges` are added once the writer is dropped.
+    total_skipped_edges: Arc<AtomicUsize>,
+
+    /// The first error which must fail the computation, shared with all sinks.
+    output_error: Arc<Mutex<Option<String>>>,
+
+    /// The hash of the result file in the first output directory, set once the writer is dropped (only used if the
+    /// result files are hashed).
+    output_hash: Arc<Mutex<Option<OutputHash>>>,
+}
+
+impl EdgeWriter {
+    /// Create a writer for the output target and format given in the `configuration`. If `sample_output` is given, a
+    /// sample of at most that many edges will be drawn using the `seed`. The users' screen names are loaded from the
+    /// `screen_names` file, if given and required by the output format. If `flush_on_cascade_complete` is set, the
+    /// edges are buffered per cascade. If `emit_time_delta` is set, the time between the activation of the influencer
+    /// and the influence is appended to each edge. At most `max_output_edges` edges are written, if given; the number
+    /// of further edges is added to `skipped_edges` once the writer is dropped. If `hash_output` is set, the hash of
+    /// the first result file is stored in `output_hash` once the writer is dropped. Cascades are summarized instead of
+    /// sampled, and their edges are buffered until they are complete. Errors which must fail the computation are stored
+    /// in `output_error`.
+    fn new(configuration: &Configuration, skipped_edges: Arc<AtomicUsize>,
+           output_hash: Arc<Mutex<Option<OutputHash>>>, output_error: Arc<Mutex<Option<String>>>) -> EdgeWriter {
+        let seed: u64 = configuration.seed;
+        let output_format: OutputFormat = configuration.output_format;
+        let buffer_output: bool = configuration.buffer_output;
+        let hash_output: bool = configuration.hash_output;
+        let is_summarized: bool = output_format == OutputFormat::CascadeSummary;
+        let sinks: Vec<Sink> = configuration.output_target.targets()
+            .into_iter()
+            .filter(|target| **target != OutputTarget::None)
+            .map(|target| Sink::new(target.clone(), output_format, buffer_output, hash_output, output_error.clone()))
+            .collect();
+        EdgeWriter {
+            sinks: sinks,
+            output_format: configuration.output_format,
+            sample: if is_summarized {
+                None
+            } else {
+                configuration.sample_output.map(|size| Reservoir::new(size, seed))
+            },
+            written_pairs: HashSet::new(),
+            matrices: BTreeMap::new(),
+            screen_names_file: configuration.screen_names.clone(),
+            screen_names: ScreenNames::new(),
+            root_users_file: configuration.output_root_users.clone(),
+            root_users: None,
+            incomplete_cascades: if configuration.flush_on_cascade_complete || is_summarized {
+                Some(CascadeBuffer::new())
+            } else {
+                None
+            },
+            time_delta_unit: configuration.emit_time_delta,
+            max_output_edges: configuration.max_output_edges,
+            written_edges: 0,
+            skipped_edges: 0,
+            total_skipped_edges: skipped_edges,
+            output_error: output_error,
+            output_hash: output_hash,
+        }
+    }
+
+    /// Write the `influence` edge, or offer it to the sample if only a sample is written, or buffer it until its
+    /// cascade is complete. Edges of cascades whose root user is not selected are skipped.
+    fn write(&mut self, influence: &InfluenceEdge<User>) {
+        if !self.is_root_user_selected(influence.original_user) {
+            return;
+        }
+
+        if let Some(ref mut sample) = self.sample {
+            sample.offer(influence.clone());
+            return;
+        }
+
+        if let Some(ref mut incomplete_cascades) = self.incomplete_cascades {
+            incomplete_cascades.add(influence.clone());
+            return;
+        }
+
+        self.write_unsampled(influence);
+    }
+
+    /// Write the buffered influence edges of the completed cascade `cascade_id` with `retweets` Retweets and free them,
+    /// if edges are buffered per cascade. If cascades are summarized, the summary is written to all text targets.
+    fn complete_cascade(&mut self, cascade_id: u64, retweets: u64) {
+        let influences: Vec<InfluenceEdge<User>> = match self.incomplete_cascades {
+            Some(ref mut incomplete_cascades) => incomplete_cascades.take(cascade_id),
+            None => return
+        };
+
+        if self.output_format == OutputFormat::CascadeSummary {
+            self.write_summary(cascade_id, retweets, &influences);
+        }
+
+        for influence in &influences {
+            self.write_unsampled(influence);
+        }
+    }
+
+    /// Write the `influence` edge to all targets, or add it to its cascade's adjacency matrix for text targets if
+    /// matrices are written. If cascades are summarized, the edge is not written to text targets. Once the maximum
+    /// number of edges has been written, the edge is only counted as skipped.
+    fn write_unsampled(&mut self, influence: &InfluenceEdge<User>) {
+        if self.is_output_full() {
+            self.skipped_edges += 1;
+            return;
+        }
+
+        // Skip pairs that have already been written if requested.
+        if self.output_format == OutputFormat::DistinctPairs &&
+            !self.written_pairs.insert((influence.influencer, influence.influencee)) {
+            return;
+        }
+        self.written_edges += 1;
+
+        // Matrices are only written to text targets, all other targets receive the plain edges.
+        let has_text_sink: bool = self.sinks.iter().any(Sink::is_text);
+        let line: Option<String> = if self.output_format == OutputFormat::Matrix {
+            if has_text_sink {
+                self.add_to_matrix(influence);
+            }
+            None
+        } else if self.output_format == OutputFormat::CascadeSummary {
+            None
+        } else if has_text_sink {
+            Some(self.format_edge(influence))
+        } else {
+            None
+        };
+
+        for sink in &mut self.sinks {
+            if !sink.is_text() {
+                sink.write_edge(influence);
+            } else if sink.is_neo4j() {
+                sink.write_relationship(influence);
+            } else if let Some(ref line) = line {
+                sink.write_line(line);
+            }
+        }
+    }
+
+    /// Add the `influence` edge to the adjacency matrix of its cascade. If the cascade grows too large, its matrix is
+    /// discarded.
+    fn add_to_matrix(&mut self, influence: &InfluenceEdge<User>) {
+        let entry: &mut Option<AdjacencyMatrix> = self.matrices.entry(influence.cascade_id)
+            .or_insert_with(|| Some(AdjacencyMatrix::new()));
+
+        let is_too_large: bool = match *entry {
+            Some(ref mut matrix) => {
+                matrix.add(influence.influencer, influence.influencee);
+                matrix.users.len() > MAX_MATRIX_USERS
+            },
+            None => false
+        };
+
+        if is_too_large {
+            warn!("Cascade {cascade} has more than {max} users, skipping its adjacency matrix",
+                  cascade = influence.cascade_id, max = MAX_MATRIX_USERS);
+            *entry = None;
+        }
+    }
+
+    /// Write the adjacency matrices of all cascades.
+    fn write_matrices(&mut self) {
+        let matrices: BTreeMap<u64, Option<AdjacencyMatrix>> = mem::replace(&mut self.matrices, BTreeMap::new());
+        for (cascade_id, matrix) in matrices {
+            if let Some(matrix) = matrix {
+                for line in matrix.render(cascade_id) {
+                    self.write_line(&line);
+                }
+            }
+        }
+    }
+
+    /// Write the summary of the cascade `cascade_id` with `retweets` Retweets and the given `influences`, sorted by
+    /// their time, to all text targets. Cascades without influence edges are skipped.
+    fn write_summary(&mut self, cascade_id: u64, retweets: u64, influences: &[InfluenceEdge<User>]) {
+        if let Some(summary) = CascadeSummary::new(cascade_id, retweets, influences) {
+            self.write_line(&summary.render());
+        }
+    }
+
+    /// Summarize the cascades of the `influences` that have not been reported as complete, sorted by their cascade and
+    /// within each cascade by their time. Since their number of Retweets is not known, only the Retweets with influence
+    /// edges are counted.
+    fn write_remaining_summaries(&mut self, influences: &[InfluenceEdge<User>]) {
+        let mut start: usize = 0;
+        while start < influences.len() {
+            let cascade_id: u64 = influences[start].cascade_id;
+            let end: usize = influences[start..].iter()
+                .position(|influence| influence.cascade_id != cascade_id)
+                .map_or(influences.len(), |length| start + length);
+
+            let retweets: HashSet<u64> = influences[start..end].iter()
+                .map(|influence| influence.retweet_id)
+                .collect();
+            self.write_summary(cascade_id, retweets.len() as u64, &influences[start..end]);
+            start = end;
+        }
+    }
+
+    /// Determine if the maximum number of influence edges has been written.
+    fn is_output_full(&self) -> bool {
+        match self.max_output_edges {
+            Some(max_output_edges) => self.written_edges >= max_output_edges,
+            None => false
+        }
+    }
+
+    /// Make sure all written influence edges have been delivered.
+    fn flush(&mut self) {
+        for sink in &mut self.sinks {
+            sink.flush();
+        }
+    }
+
+    /// Format the `influence` edge as a line of text in the output format. Unless screen names or distinct pairs are
+    /// written, the edge is formatted like a plain edge.
+    fn format_edge(&mut self, influence: &InfluenceEdge<User>) -> String {
+        match self.output_format {
+            OutputFormat::Edges | OutputFormat::Matrix | OutputFormat::Abomonation | OutputFormat::Neo4j |
+            OutputFormat::CascadeSummary => {
+                with_time_delta(format!("{}", influence), influence, self.time_delta_unit)
+            },
+            OutputFormat::EdgesWithScreenNames => {
+                self.load_screen_names();
+                with_time_delta(format_with_screen_names(influence, &self.screen_names), influence,
+                                self.time_delta_unit)
+            },
+            OutputFormat::DistinctPairs => {
+                format!("{influencer};{influencee}", influencer = influence.influencer,
+                        influencee = influence.influencee)
+            }
+        }
+    }
+
+    /// Determine if the cascades posted by the `root_user` are written. On the first call, the selected root users are
+    /// loaded. If they cannot be loaded, no cascades are written and the computation fails.
+    fn is_root_user_selected(&mut self, root_user: User) -> bool {
+        if let Some(file) = self.root_users_file.take() {
+            self.root_users = match get_selected_users(Some(file.clone()), &[]) {
+                Ok(root_users) => root_users,
+                Err(message) => {
+                    let message: String = format!("Could not load the root users from {file}: {error}", file = file,
+                                                  error = message);
+                    error!("{}", message);
+                    store_output_error(&self.output_error, message);
+                    Some(HashSet::new())
+                }
+            };
+        }
+
+        match self.root_users {
+            Some(ref root_users) => root_users.contains(&root_user.id),
+            None => true
+        }
+    }
+
+    /// Load the screen names from the screen name file, if it has not been loaded yet. On any error, all users are
+    /// rendered by their bare ID.
+    fn load_screen_names(&mut self) {
+        if let Some(file) = self.screen_names_file.take() {
+            match ScreenNames::from_source(file.clone()) {
+                Ok(screen_names) => {
+                    trace!("Loaded {number} screen names from {file}", number = screen_names.len(), file = file);
+                    self.screen_names = screen_names;
+                },
+                Err(message) => {
+                    error!("Could not load the screen names from {file}: {error}", file = file, error = message);
+                }
+            }
+        }
+    }
+
+    /// Write a single `line` to all text output targets, i.e. directories and STDOUT.
+    fn write_line(&mut self, line: &str) {
+        for sink in self.sinks.iter_mut().filter(|sink| sink.is_text()) {
+            sink.write_line(line);
+        }
+    }
+}
+
+/// A single output target of the `EdgeWriter` and its connection. Once writing to a sink has failed, it is disabled
+/// without affecting any other sinks.
+struct Sink {
+    /// The target to which the influence edges are written.
+    target: OutputTarget,
+
+    /// The writer for the result file, created on the first write to a directory or a JSON lines file.
+    file_writer: Option<BufWriter<HashingWriter<File>>>,
+
+    /// The sender to the channel, cloned when the sink is created if the target is a channel.
+    channel_sender: Option<Sender<InfluenceEdge<UserId>>>,
+
+    /// The Kafka producer, connected on the first write to Kafka.
+    #[cfg(feature = "rdkafka")]
+    kafka_producer: Option<KafkaProducer>,
+
+    /// The first error which must fail the computation, shared by all sinks.
+    #[cfg(feature = "rdkafka")]
+    output_error: Arc<Mutex<Option<String>>>,
+
+    /// Whether the influence edges are written to a directory in binary form instead of as text.
+    is_abomonated: bool,
+
+    /// Whether writing to the target has failed.
+    has_failed: bool,
+
+    /// The lines written to a text target since the last flush, if the lines are buffered.
+    buffer: Option<String>,
+
+    /// Whether the bytes written to the result file are hashed.
+    hash_output: bool,
+
+    /// The IDs of all users in the relationships written so far, if the influence edges are written to a directory for
+    /// the import into Neo4j.
+    neo4j_users: Option<BTreeSet<UserId>>,
+
+    /// The influence edges written so far, if the target is a DOT file.
+    dot_edges: Option<Vec<InfluenceEdge<UserId>>>,
+}
+
+impl Sink {
+    /// Create a sink for the single `target`, to which the influence edges are written in the `output_format`. If
+    /// `buffer_output` is set, the lines written to a text target are buffered until the sink is flushed. If
+    /// `hash_output` is set, the bytes written to the result file of a directory are hashed. Errors which must fail the
+    /// computation are stored in `output_error`.
+    #[cfg_attr(not(feature = "rdkafka"), allow(unused_variables))]
+    fn new(target: OutputTarget, output_format: OutputFormat, buffer_output: bool, hash_output: bool,
+           output_error: Arc<Mutex<Option<String>>>) -> Sink {
+        let is_directory: bool = match target {
+            OutputTarget::Directory(_) => true,
+            _ => false
+        };
+        let is_abomonated: bool = is_directory && output_format == OutputFormat::Abomonation;
+        let channel_sender: Option<Sender<InfluenceEdge<UserId>>> = match target {
+            OutputTarget::Channel(ref channel) => Some(channel.sender()),
+            _ => None
+        };
+        let dot_edges: Option<Vec<InfluenceEdge<UserId>>> = match target {
+            OutputTarget::Dot(_) => Some(Vec::new()),
+            _ => None
+        };
+        Sink {
+            target: target,
+            file_writer: None,
+            channel_sender: channel_sender,
+            #[cfg(feature = "rdkafka")]
+            kafka_producer: None,
+            #[cfg(feature = "rdkafka")]
+            output_error: output_error,
+            is_abomonated: is_abomonated,
+            has_failed: false,
+            buffer: if buffer_output {
+                Some(String::new())
+            } else {
+                None
+            },
+            hash_output: hash_output,
+            neo4j_users: if is_directory && output_format == OutputFormat::Neo4j {
+                Some(BTreeSet::new())
+            } else {
+                None
+            },
+            dot_edges: dot_edges,
+        }
+    }
+
+    /// Get the path to the result file if the target is a directory.
+    fn result_file(&self) -> Option<PathBuf> {
+        match self.target {
+            OutputTarget::Directory(ref directory) => {
+                let filename: &str = if self.is_abomonated {
+                    ABOMONATED_EDGES_FILENAME
+                } else {
+                    RESULT_FILENAME
+                };
+                Some(directory.join(filename))
+            },
+            _ => None
+        }
+    }
+
+    /// Determine if the target is written to as text, i.e. if it is a directory (unless the edges are abomonated) or
+    /// STDOUT.
+    fn is_text(&self) -> bool {
+        match self.target {
+            OutputTarget::Directory(_) => !self.is_abomonated,
+            OutputTarget::StdOut => true,
+            _ => false
+        }
+    }
+
+    /// Determine if the influence edges are written as relationships for the import into Neo4j.
+    fn is_neo4j(&self) -> bool {
+        self.neo4j_users.is_some()
+    }
+
+    /// Write the `influence` edge as a relationship for the import into Neo4j, preceded by the header if it is the
+    /// first one, and remember its users.
+    fn write_relationship(&mut self, influence: &InfluenceEdge<User>) {
+        let is_first: bool = match self.neo4j_users {
+            Some(ref mut users) => {
+                let is_first: bool = users.is_empty();
+                let _ = users.insert(influence.influencer.id);
+                let _ = users.insert(influence.influencee.id);
+                is_first
+            },
+            None => return
+        };
+
+        if is_first {
+            self.write_line(NEO4J_RELATIONSHIPS_HEADER);
+        }
+        self.write_line(&format!("{influencer},{influencee},{cascade},INFLUENCED",
+                                 influencer = influence.influencer.id, influencee = influence.influencee.id,
+                                 cascade = influence.cascade_id));
+    }
+
+    /// Write all users of the written relationships to the nodes file for the import into Neo4j, and free them.
+    fn write_nodes(&mut self) {
+        let users: BTreeSet<UserId> = match self.neo4j_users.take() {
+            Some(users) => users,
+            None => return
+        };
+        if self.has_failed || users.is_empty() {
+            return;
+        }
+
+        let path: PathBuf = match self.target {
+            OutputTarget::Directory(ref directory) => directory.join(NEO4J_NODES_FILENAME),
+            _ => return
+        };
+        let result: io::Result<()> = File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            writeln!(writer, "{}", NEO4J_NODES_HEADER)?;
+            for user in &users {
+                writeln!(writer, "{user},User", user = user)?;
+            }
+            writer.flush()
+        });
+
+        match result {
+            Ok(()) => trace!("Wrote {number} users to {file}", number = users.len(), file = path.display()),
+            Err(message) => error!("Could not write the users to {file}: {error}", file = path.display(),
+                                   error = message)
+        }
+    }
+
+    /// Write all influence edges written so far to the DOT file as a single directed graph, and free them. Nothing is
+    /// written if there are no edges, e.g. on all workers but the first one.
+    fn write_dot(&mut self) {
+        let edges: Vec<InfluenceEdge<UserId>> = match self.dot_edges.take() {
+            Some(edges) => edges,
+            None => return
+        };
+        if self.has_failed || edges.is_empty() {
+            return;
+        }
+
+        let path: PathBuf = match self.target {
+            OutputTarget::Dot(ref path) => path.clone(),
+            _ => return
+        };
+        let result: io::Result<()> = File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            writer.write_all(render_dot(&edges).as_bytes())?;
+            writer.flush()
+        });
+
+        match result {
+            Ok(()) => trace!("Wrote {number} influence edges to {file}", number = edges.len(), file = path.display()),
+            Err(message) => error!("Could not write the influence edges to {file}: {error}", file = path.display(),
+                                   error = message)
+        }
+    }
+
+    /// Write a single `line` to a text output target, i.e. a directory or STDOUT, or append it to the buffer if the
+    /// lines are buffered.
+    fn write_line(&mut self, line: &str) {
+        if self.has_failed {
+            return;
+        }
+
+        if let Some(ref mut buffer) = self.buffer {
+            buffer.push_str(line);
+            buffer.push('\n');
+            return;
+        }
+
+        self.write_text(&format!("{}\n", line));
+    }
+
+    /// Write the `text`, consisting of complete lines, to a text output target at once.
+    #[cfg_attr(feature = "cargo-clippy", allow(print_stdout))]
+    fn write_text(&mut self, text: &str) {
+        if self.has_failed {
+            return;
+        }
+
+        match self.target {
+            OutputTarget::Directory(ref directory) => {
+                if self.file_writer.is_none() {
+                    let path: PathBuf = directory.join(RESULT_FILENAME);
+                    let file: File = match File::create(&path) {
+                        Ok(file) => file,
+                        Err(message) => {
+                            error!("Could not create {file}, skipping this output target: {error}",
+                                   file = path.display(), error = message);
+                            self.has_failed = true;
+                            return;
+                        }
+                    };
+
+                    trace!("Created result file {file}", file = path.display());
+                    self.file_writer = Some(BufWriter::new(HashingWriter::new(file, self.hash_output)));
+                }
+
+                // Get the writer. Failing is impossible since the writer has just been created.
+                let writer: &mut BufWriter<HashingWriter<File>> = match self.file_writer {
+                    Some(ref mut writer) => writer,
+                    None => return,
+                };
+
+                // Write the lines.
+                if let Err(message) = writer.write_all(text.as_bytes()) {
+                    error!("Could not write to the result file in {directory}, skipping this output target: {error}",
+                           directory = directory.display(), error = message);
+                    self.has_failed = true;
+                }
+            },
+            OutputTarget::StdOut => {
+                print!("{}", text);
+            },
+            _ => {}
+        }
+    }
+
+    /// Deliver the bytes written to the result file or the lines written to STDOUT.
+    fn flush_text(&mut self) {
+        let result: io::Result<()> = match self.target {
+            OutputTarget::Directory(_) | OutputTarget::JsonLines(_) => match self.file_writer {
+                Some(ref mut writer) => writer.flush(),
+                None => Ok(())
+            },
+            OutputTarget::StdOut => io::stdout().flush(),
+            _ => Ok(())
+        };
+
+        if let Err(message) = result {
+            error!("Could not flush the output to {target:?}: {error}", target = self.target, error = message);
+        }
+    }
+
+    /// Deliver all bytes written to the result file and finish hashing them, if they are hashed. The hash is written
+    /// next to the result file. Return `None` if no result file has been written or if it is not hashed.
+    fn finish_hash(&mut self) -> Option<OutputHash> {
+        let path: PathBuf = match self.result_file() {
+            Some(path) => path,
+            None => return None
+        };
+        let writer: &mut BufWriter<HashingWriter<File>> = match self.file_writer {
+            Some(ref mut writer) => writer,
+            None => return None
+        };
+
+        // Only the bytes that have actually been delivered to the file are hashed.
+        if let Err(message) = writer.flush() {
+            error!("Could not write to the result file {file}: {error}", file = path.display(), error = message);
+        }
+        let hash: OutputHash = match writer.get_mut().finish(&path) {
+            Some(hash) => hash,
+            None => return None
+        };
+
+        match hash.write_sidecar() {
+            Ok(sidecar) => trace!("Wrote the hash of {file} to {sidecar}", file = path.display(),
+                                  sidecar = sidecar.display()),
+            Err(message) => error!("Could not write the hash of {file}: {error}", file = path.display(),
+                                   error = message)
+        }
+        Some(hash)
+    }
+
+    /// Write the `influence` edge to a target that is not written to as text.
+    fn write_edge(&mut self, influence: &InfluenceEdge<User>) {
+        if self.has_failed {
+            return;
+        }
+
+        match self.target {
+            OutputTarget::Directory(ref directory) if self.is_abomonated => {
+                if self.file_writer.is_none() {
+                    let path: PathBuf = directory.join(ABOMONATED_EDGES_FILENAME);
+                    let hash_output: bool = self.hash_output;
+                    let file: Result<File> = File::create(&path).map_err(Error::from);
+                    match file.and_then(|file| create_abomonated_edges(HashingWriter::new(file, hash_output))) {
+                        Ok(writer) => {
+                            trace!("Created result file {file}", file = path.display());
+                            self.file_writer = Some(writer);
+                        },
+                        Err(message) => {
+                            error!("Could not create {file}, skipping this output target: {error}",
+                                   file = path.display(), error = message);
+                            self.has_failed = true;
+                            return;
+                        }
+                    }
+                }
+
+                if let Some(ref mut writer) = self.file_writer {
+                    if let Err(message) = write_abomonated_edge(writer, influence) {
+                        error!("Could not write to the result file in {directory}, skipping this output target: \
+                                {error}", directory = directory.display(), error = message);
+                        self.has_failed = true;
+                    }
+                }
+            },
+            #[cfg(feature = "petgraph")]
+            OutputTarget::PetGraph(ref graph) => {
+                graph.add_influence(influence);
+            },
+            OutputTarget::Channel(_) => {
+                let is_closed: bool = match self.channel_sender {
+                    Some(ref sender) => sender.send(with_user_ids(influence)).is_err(),
+                    None => true
+                };
+                if is_closed {
+                    error!("The receiver of the channel has been dropped, skipping this output target");
+                    self.has_failed = true;
+                }
+            },
+            OutputTarget::Dot(_) => {
+                if let Some(ref mut edges) = self.dot_edges {
+                    edges.push(with_user_ids(influence));
+                }
+            },
+            OutputTarget::JsonLines(ref path) => {
+                if self.file_writer.is_none() {
+                    let file: File = match File::create(path) {
+                        Ok(file) => file,
+                        Err(message) => {
+                            error!("Could not create {file}, skipping this output target: {error}",
+                                   file = path.display(), error = message);
+                            self.has_failed = true;
+                            return;
+                        }
+                    };
+
+                    trace!("Created result file {file}", file = path.display());
+                    self.file_writer = Some(BufWriter::new(HashingWriter::new(file, false)));
+                }
+
+                let line: String = match serde_json::to_string(&JsonLinesEdge::new(influence)) {
+                    Ok(line) => line,
+                    Err(message) => {
+                        error!("Could not serialize influence edge: {error}", error = message);
+                        return;
+                    }
+                };
+                if let Some(ref mut writer) = self.file_writer {
+                    if let Err(message) = writeln!(writer, "{}", line) {
+                        error!("Could not write to {file}, skipping this output target: {error}",
+                               file = path.display(), error = message);
+                        self.has_failed = true;
+                    }
+                }
+            },
+            #[cfg(feature = "rdkafka")]
+            OutputTarget::Kafka { ref brokers, ref topic, error_policy } => {
+                if self.kafka_producer.is_none() {
+                    match KafkaProducer::new(brokers, topic, error_policy) {
+                        Ok(producer) => {
+                            trace!("Connected to Kafka brokers {brokers}", brokers = brokers);
+                            self.kafka_producer = Some(producer);
+                        },
+                        Err(message) => {
+                            let message: String = format!("Could not connect to Kafka brokers {brokers}: {error}",
+                                                          brokers = brokers, error = message);
+                            error!("{}", message);
+                            if error_policy == KafkaErrorPolicy::Fail {
+                                self.fail_computation(message);
+                            }
+                            return;
+                        }
+                    }
+                }
+
+                if let Some(ref producer) = self.kafka_producer {
+                    producer.produce(influence);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Make sure all written influence edges have been delivered, i.e. are on disk if written to a directory. Buffered
+    /// lines are written at once.
+    fn flush(&mut self) {
+        let buffered_text: Option<String> = self.buffer.as_mut().map(|buffer| mem::replace(buffer, String::new()));
+        if let Some(text) = buffered_text {
+            if !text.is_empty() {
+                self.write_text(&text);
+            }
+        }
+        self.flush_text();
+
+        match self.target {
+            #[cfg(feature = "rdkafka")]
+            OutputTarget::Kafka { .. } => {
+                let failure: Option<String> = match self.kafka_producer {
+                    Some(ref producer) => producer.flush().err(),
+                    None => None
+                };
+                if let Some(message) = failure {
+                    error!("{}", message);
+                    self.kafka_producer = None;
+                    self.fail_computation(message);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Skip this output target and store the error `message` to fail the computation, unless another error has
+    /// already been stored.
+    #[cfg(feature = "rdkafka")]
+    fn fail_computation(&mut self, message: String) {
+        self.has_failed = true;
+        store_output_error(&self.output_error, message);
+    }
+}
+
+/// Store the error `message` in `output_error` to fail the computation, unless another error has already been stored.
+fn store_output_error(output_error: &Mutex<Option<String>>, message: String) {
+    let mut output_error = output_error.lock().expect("The output error has been poisoned");
+    if output_error.is_none() {
+        *output_error = Some(message);
+    }
+}
+
+impl Drop for Sink {
+    /// Deliver everything written so far, even if the computation has ended with an error. Unlike dropping the result
+    /// file's writer on its own, failing to do so is logged.
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl Drop for EdgeWriter {
+    /// Write the sample, the cascades that have not been reported as complete, the adjacency matrices, and the users
+    /// for Neo4j, if any. The writer is dropped once the computation has finished.
+    fn drop(&mut self) {
+        if let Some(mut incomplete_cascades) = self.incomplete_cascades.take() {
+            if !incomplete_cascades.is_empty() {
+                info!("Writing {number} cascades completed at the end of the computation",
+                      number = incomplete_cascades.len());
+            }
+            let influences: Vec<InfluenceEdge<User>> = incomplete_cascades.take_all();
+            if self.output_format == OutputFormat::CascadeSummary {
+                self.write_remaining_summaries(&influences);
+            }
+            for influence in &influences {
+                self.write_unsampled(influence);
+            }
+        }
+
+        if let Some(mut sample) = self.sample.take() {
+            for influence in sample.take() {
+                self.write_unsampled(&influence);
+            }
+        }
+
+        self.write_matrices();
+        for sink in &mut self.sinks {
+            sink.write_nodes();
+            sink.write_dot();
+        }
+        self.flush();
+
+        let hashes: Vec<OutputHash> = self.sinks.iter_mut()
+            .filter_map(Sink::finish_hash)
+            .collect();
+        if let Some(hash) = hashes.into_iter().next() {
+            info!("Wrote {bytes} bytes with SHA-256 hash {sha256} to {file}", bytes = hash.bytes,
+                  sha256 = hash.sha256, file = hash.path.display());
+            *self.output_hash.lock().expect("The output hash has been poisoned") = Some(hash);
+        }
+
+        if self.skipped_edges > 0 {
+            info!("Wrote the maximum of {written} influence edges, skipped {skipped} further edges",
+                  written = self.written_edges, skipped = self.skipped_edges);
+            let _ = self.total_skipped_edges.fetch_add(self.skipped_edges, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Format the `influence` edge like `OutputFormat::Edges`, but render the influencee and the influencer including their
+/// `screen_names`, if known.
+fn format_with_screen_names(influence: &InfluenceEdge<User>, screen_names: &ScreenNames) -> String {
+    format!("{cascade};{retweet};{user};{influencer};{time};-1",
+            cascade = influence.cascade_id, retweet = influence.retweet_id,
+            user = screen_names.render(influence.influencee), influencer = screen_names.render(influence.influencer),
+            time = influence.timestamp)
+}
+
+/// Convert the `influence` edge to one between the IDs of its users.
+fn with_user_ids(influence: &InfluenceEdge<User>) -> InfluenceEdge<UserId> {
+    InfluenceEdge::new(influence.influencer.id, influence.influencee.id, influence.timestamp, influence.retweet_id,
+                       influence.cascade_id, influence.original_user.id)
+        .influencer_activation(influence.influencer_activation)
+}
+
+/// An influence edge as written to a JSON lines file, with the users given by their IDs.
+#[derive(Debug, Serialize)]
+struct JsonLinesEdge {
+    /// The ID of the user influencing the influencee.
+    influencer: UserId,
+
+    /// The ID of the user being influenced.
+    influencee: UserId,
+
+    /// The ID of the Retweet cascade.
+    cascade: u64,
+
+    /// The ID of the Retweet.
+    retweet: u64,
+
+    /// The time at which the influence is established.
+    timestamp: u64,
+}
+
+impl JsonLinesEdge {
+    /// Get the JSON lines representation of the `influence` edge.
+    fn new(influence: &InfluenceEdge<User>) -> JsonLinesEdge {
+        JsonLinesEdge {
+            influencer: influence.influencer.id,
+            influencee: influence.influencee.id,
+            cascade: influence.cascade_id,
+            retweet: influence.retweet_id,
+            timestamp: influence.timestamp,
+        }
+    }
+}
+
+/// Render the influence `edges` as a directed graph in the DOT language of Graphviz. Each edge points from the
+/// influencer to the influencee and is labelled with the ID of its Retweet. The nodes of dummy users are styled
+/// distinctly.
+fn render_dot(edges: &[InfluenceEdge<UserId>]) -> String {
+    let dummy_users: BTreeSet<UserId> = edges.iter()
+        .flat_map(|edge| vec![edge.influencer, edge.influencee])
+        .filter(|user| user.is_dummy())
+        .collect();
+
+    let mut dot = String::from("digraph {\n");
+    for user in dummy_users {
+        dot.push_str(&format!("    {user} [{style}];\n", user = user, style = DOT_DUMMY_NODE_STYLE));
+    }
+    for edge in edges {
+        dot.push_str(&format!("    {influencer} -> {influencee} [label=\"{retweet}\"];\n",
+                              influencer = edge.influencer, influencee = edge.influencee, retweet = edge.retweet_id));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Append the time between the activation of the influencer and the `influence` to the formatted `line` in the given
+/// `unit`, or `-1` if the influencer's activation is not known. Without a `unit`, the `line` is returned unchanged.
+fn with_time_delta(line: String, influence: &InfluenceEdge<User>, unit: Option<TimeUnit>) -> String {
+    let unit: TimeUnit = match unit {
+        Some(unit) => unit,
+        None => return line
+    };
+
+    match influence.time_delta() {
+        Some(delta) => format!("{line};{delta}", line = line, delta = unit.convert_seconds(delta)),
+        None => format!("{line};-1", line = line)
+    }
+}
+
+/// The influence edges of all cascades that have not been completed yet.
+struct CascadeBuffer {
+    /// For each cascade, given by its ID, its influence edges.
+    cascades: HashMap<u64, Vec<InfluenceEdge<User>>>,
+}
+
+impl CascadeBuffer {
+    /// Create an empty buffer.
+    fn new() -> CascadeBuffer {
+        CascadeBuffer {
+            cascades: HashMap::new(),
+        }
+    }
+
+    /// Buffer the `influence` edge until its cascade is complete.
+    fn add(&mut self, influence: InfluenceEdge<User>) {
+        self.cascades.entry(influence.cascade_id)
+            .or_insert_with(Vec::new)
+            .push(influence);
+    }
+
+    /// Remove the influence edges of the cascade `cascade_id` from the buffer. Return them sorted by their time, then
+    /// by their Retweet, influencee, and influencer.
+    fn take(&mut self, cascade_id: u64) -> Vec<InfluenceEdge<User>> {
+        let mut influences: Vec<InfluenceEdge<User>> = self.cascades.remove(&cascade_id).unwrap_or_else(Vec::new);
+        influences.sort_by_key(|influence| {
+            (influence.timestamp, influence.retweet_id, influence.influencee, influence.influencer)
+        });
+        influences
+    }
+
+    /// Remove the influence edges of all cascades from the buffer. Return them sorted by their cascade, and within each
+    /// cascade like `take`.
+    fn take_all(&mut self) -> Vec<InfluenceEdge<User>> {
+        let mut cascade_ids: Vec<u64> = self.cascades.keys().cloned().collect();
+        cascade_ids.sort();
+
+        let mut influences: Vec<InfluenceEdge<User>> = Vec::new();
+        for cascade_id in cascade_ids {
+            influences.extend(self.take(cascade_id));
+        }
+        influences
+    }
+
+    /// The number of buffered cascades.
+    fn len(&self) -> usize {
+        self.cascades.len()
+    }
+
+    /// Determine if no cascades are buffered.
+    fn is_empty(&self) -> bool {
+        self.cascades.is_empty()
+    }
+}
+
+/// The pairs of influencer and influencee of all influence edges written for each cascade that has not been completed
+/// yet.
+struct CascadePairs {
+    /// For each cascade, given by its ID, the pairs of influencer and influencee.
+    cascades: HashMap<u64, HashSet<(User, User)>>,
+}
+
+impl CascadePairs {
+    /// Create an empty set of pairs.
+    fn new() -> CascadePairs {
+        CascadePairs {
+            cascades: HashMap::new(),
+        }
+    }
+
+    /// Add the pair of influencer and influencee of the `influence` edge to its cascade. Return `false` if the cascade
+    /// already contains the pair, i.e. if the `influence` edge is a duplicate.
+    fn insert(&mut self, influence: &InfluenceEdge<User>) -> bool {
+        self.cascades.entry(influence.cascade_id)
+            .or_insert_with(HashSet::new)
+            .insert((influence.influencer, influence.influencee))
+    }
+
+    /// Forget the pairs of the completed cascade `cascade_id`.
+    fn complete(&mut self, cascade_id: u64) {
+        let _ = self.cascades.remove(&cascade_id);
+    }
+}
+
+/// The dense adjacency matrix of the influences within a single cascade.
+struct AdjacencyMatrix {
+    /// All users in the cascade, ordered by their ID.
+    users: BTreeSet<User>,
+
+    /// All pairs of influencer and influencee in the cascade.
+    influences: HashSet<(User, User)>,
+}
+
+impl AdjacencyMatrix {
+    /// Create an empty matrix.
+    fn new() -> AdjacencyMatrix {
+        AdjacencyMatrix {
+            users: BTreeSet::new(),
+            influences: HashSet::new(),
+        }
+    }
+
+    /// Add an influence from `influencer` to `influencee` to the matrix.
+    fn add(&mut self, influencer: User, influencee: User) {
+        let _ = self.users.insert(influencer);
+        let _ = self.users.insert(influencee);
+        let _ = self.influences.insert((influencer, influencee));
+    }
+
+    /// Render the matrix for the cascade `cascade_id` as lines of text, including a title line and a header row.
+    fn render(&self, cascade_id: u64) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::with_capacity(self.users.len() + 2);
+        lines.push(format!("# Cascade {cascade}", cascade = cascade_id));
+
+        let header: Vec<String> = self.users.iter()
+            .map(|user| format!("{}", user))
+            .collect();
+        lines.push(format!(";{users}", users = header.join(";")));
+
+        for influencer in &self.users {
+            let row: Vec<&str> = self.users.iter()
+                .map(|influencee| if self.influences.contains(&(*influencer, *influencee)) { "1" } else { "0" })
+                .collect();
+            lines.push(format!("{influencer};{row}", influencer = influencer, row = row.join(";")));
+        }
+
+        lines
+    }
+}
+
+/// The summary of a single cascade (see `OutputFormat::CascadeSummary`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct CascadeSummary {
+    /// The ID of the cascade.
+    cascade_id: u64,
+
+    /// The user who posted the cascade's original Tweet.
+    root_user: User,
+
+    /// The number of Retweets in the cascade.
+    retweets: u64,
+
+    /// The number of influence edges in the cascade.
+    edges: u64,
+
+    /// The largest number of influence edges on the shortest path from the root user to any user in the cascade.
+    max_depth: u64,
+
+    /// The number of influence edges from or to a dummy user.
+    dummy_edges: u64,
+}
+
+impl CascadeSummary {
+    /// Summarize the cascade `cascade_id` with `retweets` Retweets from its `influences`, sorted by their time. An
+    /// influence edge whose influencer has not been reached from the root user by any earlier edge does not count
+    /// towards the depth. Return `None` if there are no influence edges, since the root user is not known then.
+    fn new(cascade_id: u64, retweets: u64, influences: &[InfluenceEdge<User>]) -> Option<CascadeSummary> {
+        let root_user: User = match influences.first() {
+            Some(influence) => influence.original_user,
+            None => return None
+        };
+
+        // For each user reached so far, the length of the shortest path from the root user.
+        let mut depths: HashMap<User, u64> = HashMap::new();
+        let _ = depths.insert(root_user, 0);
+
+        let mut dummy_edges: u64 = 0;
+        for influence in influences {
+            if influence.influencer.is_dummy() || influence.influencee.is_dummy() {
+                dummy_edges += 1;
+            }
+
+            let depth: Option<u64> = depths.get(&influence.influencer).map(|depth| depth + 1);
+            if let Some(depth) = depth {
+                let influencee_depth: &mut u64 = depths.entry(influence.influencee).or_insert(depth);
+                if depth < *influencee_depth {
+                    *influencee_depth = depth;
+                }
+            }
+        }
+
+        Some(CascadeSummary {
+            cascade_id: cascade_id,
+            root_user: root_user,
+            retweets: retweets,
+            edges: influences.len() as u64,
+            max_depth: depths.values().cloned().max().unwrap_or(0),
+            dummy_edges: dummy_edges,
+        })
+    }
+
+    /// Render the summary as a line of text.
+    fn render(&self) -> String {
+        format!("{cascade};{root};{retweets};{edges};{depth};{dummies}", cascade = self.cascade_id,
+                root = self.root_user, retweets = self.retweets, edges = self.edges, depth = self.max_depth,
+                dummies = self.dummy_edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::create_dir_all;
+    use std::fs::remove_file;
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn format_with_screen_names() {
+        let mut screen_names = ScreenNames::new();
+        screen_names.insert(UserId::from(1), "alice");
+        screen_names.insert(UserId::dummy(3), "dummy");
+
+        // Mapped influencee, unmapped influencer.
+        let influence = InfluenceEdge::new(User::new(2), User::new(1), 42, 3, 4, User::new(0));
+        assert_eq!(super::format_with_screen_names(&influence, &screen_names), String::from("4;3;1:@alice;2;42;-1"));
+
+        // Mapped influencer, dummy influencee.
+        let influence = InfluenceEdge::new(User::new(1), User::new(-3), 42, 3, 4, User::new(0));
+        assert_eq!(super::format_with_screen_names(&influence, &screen_names), String::from("4;3;-3;1:@alice;42;-1"));
+
+        // Without any screen names, the edge is formatted as usual.
+        let influence = InfluenceEdge::new(User::new(2), User::new(1), 42, 3, 4, User::new(0));
+        assert_eq!(super::format_with_screen_names(&influence, &ScreenNames::new()), format!("{}", influence));
+    }
+
+    #[test]
+    fn json_lines_edge() {
+        let influence: InfluenceEdge<User> = InfluenceEdge::new(User::new(1), User::new(-2), 42, 7, 3, User::new(1));
+        let line: String = serde_json::to_string(&JsonLinesEdge::new(&influence)).expect("Could not serialize");
+        assert_eq!(line, "{\"influencer\":1,\"influencee\":-2,\"cascade\":3,\"retweet\":7,\"timestamp\":42}");
+    }
+
+    #[test]
+    fn cascade_pairs() {
+        let mut pairs = CascadePairs::new();
+        assert!(pairs.insert(&InfluenceEdge::new(User::new(0), User::new(2), 1, 2, 1, User::new(0))));
+        assert!(pairs.insert(&InfluenceEdge::new(User::new(0), User::new(1), 2, 3, 1, User::new(0))));
+
+        // Another Retweet by the same user in the same cascade, and the same pair in another cascade.
+        assert!(!pairs.insert(&InfluenceEdge::new(User::new(0), User::new(2), 3, 4, 1, User::new(0))));
+        assert!(pairs.insert(&InfluenceEdge::new(User::new(0), User::new(2), 3, 6, 5, User::new(0))));
+
+        // Once the cascade is complete, its pairs are forgotten.
+        pairs.complete(1);
+        assert!(pairs.insert(&InfluenceEdge::new(User::new(0), User::new(2), 7, 8, 1, User::new(0))));
+        assert!(!pairs.insert(&InfluenceEdge::new(User::new(0), User::new(2), 8, 9, 5, User::new(0))));
+    }
+
+    #[test]
+    fn render_dot() {
+        let edges: Vec<InfluenceEdge<UserId>> = vec![
+            InfluenceEdge::new(UserId::from(0), UserId::from(1), 1, 3, 2, UserId::from(0)),
+            InfluenceEdge::new(UserId::from(1), UserId::dummy(1), 2, 4, 2, UserId::from(0)),
+        ];
+        assert_eq!(super::render_dot(&edges), String::from("digraph {\n    \
+                                                                  -1 [shape=box,color=gray];\n    \
+                                                                  0 -> 1 [label=\"3\"];\n    \
+                                                                  1 -> -1 [label=\"4\"];\n\
+                                                            }\n"));
+
+        assert_eq!(super::render_dot(&[]), String::from("digraph {\n}\n"));
+    }
+
+    #[test]
+    fn with_time_delta() {
+        let influence = InfluenceEdge::new(User::new(2), User::new(1), 42, 3, 4, User::new(0));
+        let line: String = format!("{}", influence);
+
+        // Without a unit, the line is unchanged.
+        assert_eq!(super::with_time_delta(line.clone(), &influence, None), line);
+
+        // Unknown activations are written as `-1`.
+        assert_eq!(super::with_time_delta(line.clone(), &influence, Some(TimeUnit::Seconds)), "4;3;1;2;42;-1;-1");
+
+        // The delta is the difference of the timestamps.
+        let influence = influence.influencer_activation(Some(40));
+        assert_eq!(super::with_time_delta(line.clone(), &influence, Some(TimeUnit::Seconds)), "4;3;1;2;42;-1;2");
+        assert_eq!(super::with_time_delta(line.clone(), &influence, Some(TimeUnit::Microseconds)),
+                   "4;3;1;2;42;-1;2000000");
+    }
+
+    #[test]
+    fn cascade_buffer_take() {
+        let mut buffer = CascadeBuffer::new();
+        buffer.add(InfluenceEdge::new(User::new(2), User::new(3), 5, 8, 2, User::new(1)));
+        buffer.add(InfluenceEdge::new(User::new(2), User::new(1), 2, 4, 1, User::new(0)));
+        buffer.add(InfluenceEdge::new(User::new(0), User::new(2), 1, 3, 1, User::new(0)));
+        buffer.add(InfluenceEdge::new(User::new(0), User::new(1), 2, 4, 1, User::new(0)));
+        assert_eq!(buffer.len(), 2);
+
+        // The edges of the completed cascade are sorted and freed.
+        let influences: Vec<String> = buffer.take(1).iter()
+            .map(|influence| format!("{}", influence))
+            .collect();
+        assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1"]);
+        assert_eq!(buffer.len(), 1);
+        assert!(buffer.take(1).is_empty());
+
+        assert_eq!(buffer.take_all().len(), 1);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn cascade_buffer_bounded() {
+        // Completing each cascade once its successor starts keeps at most two cascades in memory.
+        let mut buffer = CascadeBuffer::new();
+        let mut maximum_cascades: usize = 0;
+        let mut written_edges: usize = 0;
+        for cascade_id in 0..1_000u64 {
+            for retweet in 0..10u64 {
+                buffer.add(InfluenceEdge::new(User::new(0), User::new(1), retweet, retweet, cascade_id, User::new(0)));
+            }
+            if buffer.len() > maximum_cascades {
+                maximum_cascades = buffer.len();
+            }
+            if cascade_id > 0 {
+                written_edges += buffer.take(cascade_id - 1).len();
+            }
+        }
+        written_edges += buffer.take_all().len();
+
+        assert_eq!(maximum_cascades, 2);
+        assert_eq!(written_edges, 10_000);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn adjacency_matrix_render() {
+        let mut matrix = AdjacencyMatrix::new();
+        matrix.add(User::new(2), User::new(1));
+        matrix.add(User::new(0), User::new(1));
+        matrix.add(User::new(0), User::new(2));
+        matrix.add(User::new(0), User::new(2));
+
+        let lines: Vec<String> = matrix.render(42);
+        assert_eq!(lines, vec![
+            String::from("# Cascade 42"),
+            String::from(";0;1;2"),
+            String::from("0;0;1;1"),
+            String::from("1;0;0;0"),
+            String::from("2;0;1;0"),
+        ]);
+    }
+
+    #[test]
+    fn cascade_summary() {
+        // User 1 is influenced directly by the root user 0 and indirectly via user 2; user 3 only via the dummy user.
+        let influences: Vec<InfluenceEdge<User>> = vec![
+            InfluenceEdge::new(User::new(0), User::new(2), 1, 11, 42, User::new(0)),
+            InfluenceEdge::new(User::new(0), User::new(1), 2, 12, 42, User::new(0)),
+            InfluenceEdge::new(User::new(2), User::new(1), 2, 12, 42, User::new(0)),
+            InfluenceEdge::new(User::new(1), User::new(-1), 3, 13, 42, User::new(0)),
+            InfluenceEdge::new(User::new(-1), User::new(3), 4, 14, 42, User::new(0)),
+        ];
+
+        let summary: CascadeSummary = CascadeSummary::new(42, 5, &influences).expect("The cascade is not summarized");
+        assert_eq!(summary.root_user, User::new(0));
+        assert_eq!(summary.edges, 5);
+        assert_eq!(summary.max_depth, 3);
+        assert_eq!(summary.dummy_edges, 2);
+        assert_eq!(summary.render(), String::from("42;0;5;5;3;2"));
+
+        assert!(CascadeSummary::new(42, 1, &[]).is_none());
+    }
+
+    #[test]
+    fn sink_buffer_output() {
+        let directory: PathBuf = env::temp_dir().join("crgp_sink_buffer_output");
+        create_dir_all(&directory).expect("Could not create the output directory");
+        let path: PathBuf = directory.join(RESULT_FILENAME);
+        let _ = remove_file(&path);
+
+        // Read the result file, which is empty if it has not been created yet.
+        let read_result_file = || {
+            let mut contents = String::new();
+            if let Ok(mut file) = File::open(&path) {
+                let _ = file.read_to_string(&mut contents).expect("Could not read the result file");
+            }
+            contents
+        };
+
+        let mut sink = Sink::new(OutputTarget::Directory(directory), OutputFormat::Edges, true, false,
+                                 Arc::new(Mutex::new(None)));
+        sink.write_line("1;3;2;0;1;-1");
+        sink.write_line("1;4;1;0;2;-1");
+
+        // Before the flush, the lines are only buffered.
+        assert_eq!(read_result_file(), "");
+
+        // After the flush, all lines have been written at once.
+        sink.flush();
+        assert_eq!(read_result_file(), "1;3;2;0;1;-1\n1;4;1;0;2;-1\n");
+
+        drop(sink);
+        let _ = remove_file(&path);
+    }
+}
@@ -6,6 +6,11 @@
 
 //! An extension to timely dataflow `Scope`s allowing to wait for the computation to finish the current batch of data.
 
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::time::Duration;
+use std::time::Instant;
+
 use timely::Data;
 use timely::dataflow::operators::input::Handle as InputHandle;
 use timely::dataflow::operators::probe::Handle as ProbeHandle;
@@ -14,6 +19,9 @@ use timely::progress::timestamp::RootTimestamp;
 use timely::dataflow::scopes::root::Root;
 use timely_communication::allocator::Allocate;
 
+use Error;
+use Result;
+
 /// An extension to timely dataflow `Scope`s allowing to wait for the computation to finish the current batch of data.
 pub trait Sync<D1: Data, D2: Data> {
     /// Wait for the computation to finish the current batch of data.
@@ -22,6 +30,15 @@ pub trait Sync<D1: Data, D2: Data> {
     /// reached the time of `input1`.
     fn sync(&mut self, probe: &ProbeHandle<Product<RootTimestamp, u64>>, input1: &mut InputHandle<u64, D1>,
             input2: &mut InputHandle<u64, D2>);
+
+    /// Wait for the computation to finish the current batch of data as `sync` does, but for at most `timeout`.
+    ///
+    /// The elapsed time is checked after each step of the computation `self`. If the time of `probe` has not reached
+    /// the time of `input1` once the `timeout` has passed, e.g. because a worker in another process has died, an error
+    /// is returned instead of waiting any longer.
+    fn sync_with_timeout(&mut self, probe: &ProbeHandle<Product<RootTimestamp, u64>>,
+                         input1: &mut InputHandle<u64, D1>, input2: &mut InputHandle<u64, D2>, timeout: Duration)
+                         -> Result<()>;
 }
 
 impl<A: Allocate, D1: Data, D2: Data> Sync<D1, D2> for Root<A> {
@@ -38,4 +55,85 @@ impl<A: Allocate, D1: Data, D2: Data> Sync<D1, D2> for Root<A> {
             self.step();
         }
     }
+
+    fn sync_with_timeout(&mut self, probe: &ProbeHandle<Product<RootTimestamp, u64>>,
+                         input1: &mut InputHandle<u64, D1>, input2: &mut InputHandle<u64, D2>, timeout: Duration)
+                         -> Result<()> {
+        let batch: u64 = input1.epoch();
+        let input1_next = input1.epoch() + 1;
+        let input2_next = input2.epoch() + 1;
+
+        input1.advance_to(input1_next);
+        input2.advance_to(input2_next);
+
+        let start: Instant = Instant::now();
+        while probe.less_than(input1.time()) {
+            self.step();
+
+            if start.elapsed() > timeout {
+                let message: String = format!("The computation has not finished batch {batch} within {timeout}ms, \
+                                               a worker might have failed",
+                                              batch = batch, timeout = milliseconds(timeout));
+                error!("{}", message);
+                return Err(Error::from(IOError::new(IOErrorKind::TimedOut, message)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the given `duration` in milliseconds.
+fn milliseconds(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000 + u64::from(duration.subsec_nanos()) / 1_000_000
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use timely::dataflow::operators::Input;
+    use timely::dataflow::operators::Probe;
+    use timely::execute::execute;
+    use timely_communication::initialize::Configuration as TimelyConfiguration;
+    use Result;
+    use super::*;
+
+    #[test]
+    fn sync_with_timeout() {
+        let guards = execute(TimelyConfiguration::Thread, |computation| {
+            let (mut input1, mut input2, mut unreachable_input, probe, unreachable_probe) =
+                computation.dataflow::<u64, _, _>(|scope| {
+                    let (input1, stream1) = scope.new_input::<u64>();
+                    let (input2, _) = scope.new_input::<u64>();
+                    let (unreachable_input, unreachable_stream) = scope.new_input::<u64>();
+                    (input1, input2, unreachable_input, stream1.probe(), unreachable_stream.probe())
+                });
+
+            // The batch is processed well within the timeout.
+            input1.send(42);
+            let reachable: Result<()> = computation.sync_with_timeout(&probe, &mut input1, &mut input2,
+                                                                      Duration::from_secs(10));
+
+            // The third input is never advanced, thus its probe never reaches the time of the first input.
+            let unreachable: Result<()> = computation.sync_with_timeout(&unreachable_probe, &mut input1,
+                                                                        &mut input2, Duration::from_millis(50));
+            unreachable_input.send(42);
+
+            (reachable.is_ok(), unreachable.map_err(|error| error.to_string()))
+        }).expect("Could not execute the computation");
+
+        for result in guards.join() {
+            let (reachable, unreachable) = result.expect("The computation failed");
+            assert!(reachable);
+            let message: String = unreachable.expect_err("The timeout did not fire");
+            assert!(message.contains("has not finished batch 1 within 50ms"));
+        }
+    }
+
+    #[test]
+    fn milliseconds() {
+        assert_eq!(super::milliseconds(Duration::from_millis(50)), 50);
+        assert_eq!(super::milliseconds(Duration::new(2, 5_000_000)), 2_005);
+        assert_eq!(super::milliseconds(Duration::from_secs(0)), 0);
+    }
 }
@@ -6,36 +6,202 @@
 
 //! Functions for getting Tweets.
 
+use std::fmt;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::io::Cursor;
 use std::io::Error as IOError;
 use std::io::ErrorKind as IOErrorKind;
-use std::io::Result as IOResult;
+use std::io::Lines;
+use std::io::Read;
+use std::iter::Enumerate;
+use std::path::Path;
 use std::path::PathBuf;
 
 use s3::bucket::Bucket;
 use s3::error::ErrorKind as S3ErrorKind;
 use s3::error::S3Error;
+use s3::serde_types::ListBucketResult;
+use serde::Deserialize;
+use serde::de::Deserializer;
+use serde::de::IgnoredAny;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
 use serde_json;
+use serde_json::Value;
 
 use Error;
 use Result;
 use configuration::InputSource;
+use configuration::TimestampFormat;
+use text_source::decompress;
 use twitter::Retweet;
+use twitter::Timestamps;
+use twitter::Tweet;
 
-/// Load the Retweets from the given input.
-pub fn from_source(input: InputSource) -> Result<Vec<Retweet>> {
+/// Counts gathered while loading the Retweets.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RetweetLoadStatistics {
+    /// The number of Retweets dropped since they lack the ID of their original Tweet, i.e. of their cascade.
+    pub missing_cascade: u64,
+
+    /// The number of quote Tweets loaded as Retweets of the quoted Tweet.
+    pub quotes: u64,
+
+    /// The number of Tweets skipped since they are not Retweets, i.e. original Tweets (and quote Tweets, unless they
+    /// are included).
+    pub skipped_tweets: u64,
+}
+
+/// Load the Retweets from the given input. If `assert_sorted` is `true`, fail on the first Retweet that was created
+/// before its predecessor. If `include_quotes` is `true`, quote Tweets are loaded as Retweets of the quoted Tweet (see
+/// `Configuration::include_quotes`). Retweets whose creation times are not given in the `timestamp_format` are skipped
+/// with a warning. The file may be gzip-compressed, which is detected from its contents.
+pub fn from_source(input: InputSource, assert_sorted: bool, include_quotes: bool,
+                   timestamp_format: TimestampFormat) -> Result<Vec<Retweet>> {
+    from_source_with_statistics(input, assert_sorted, include_quotes, timestamp_format,
+                                &mut RetweetLoadStatistics::default())
+}
+
+/// Load the Retweets from the given input as `from_source` does, updating the `statistics` with the counts gathered
+/// while loading.
+pub fn from_source_with_statistics(input: InputSource, assert_sorted: bool, include_quotes: bool,
+                                   timestamp_format: TimestampFormat,
+                                   statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
     info!("Loading Retweets");
-    let path: String = input.path.clone();
-    match input.s3 {
-        Some(s3_config) => from_aws_s3(&path, &s3_config.get_bucket()?),
-        None => from_file(&PathBuf::from(path))
+    let retweets: Vec<Retweet> = match input.s3 {
+        Some(s3_config) => {
+            from_aws_s3(&input.path.to_string_lossy(), &s3_config.get_bucket()?, assert_sorted, include_quotes,
+                        timestamp_format, statistics)?
+        },
+        None => from_file(&input.path, assert_sorted, include_quotes, timestamp_format, statistics)?
+    };
+
+    if statistics.missing_cascade > 0 {
+        warn!("Dropped {number} Retweets without the ID of their original Tweet", number = statistics.missing_cascade);
     }
+    if statistics.quotes > 0 {
+        info!("Loaded {number} quote Tweets as Retweets of the quoted Tweets", number = statistics.quotes);
+    }
+    if statistics.skipped_tweets > 0 {
+        warn!("Skipped {number} Tweets which are not Retweets", number = statistics.skipped_tweets);
+    }
+
+    Ok(retweets)
 }
 
-/// Load the Retweets from the given `path`.
-fn from_file(path: &PathBuf) -> Result<Vec<Retweet>> {
+/// Load the Retweets from the given input as `from_source_with_statistics` does, and override their timestamps with
+/// those from the `timestamp_join_file`, if given (see `Timestamps::from_file`). Retweets without a timestamp in the
+/// file keep their own; they are reported in a warning. If `assert_sorted` is `true`, fail on the first Retweet that
+/// was created before its predecessor according to the joined timestamps.
+pub fn from_source_joined(input: InputSource, timestamp_join_file: Option<&Path>, assert_sorted: bool,
+                          include_quotes: bool, timestamp_format: TimestampFormat,
+                          statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
+    let path: &Path = match timestamp_join_file {
+        Some(path) => path,
+        None => return from_source_with_statistics(input, assert_sorted, include_quotes, timestamp_format, statistics)
+    };
+
+    let mut retweets: Vec<Retweet> = from_source_with_statistics(input, false, include_quotes, timestamp_format,
+                                                                 statistics)?;
+    info!("Joining timestamps from {file}", file = path.display());
+    let timestamps: Timestamps = Timestamps::from_file(path)?;
+    let missing: Vec<u64> = timestamps.join(&mut retweets);
+    if let Some(first_missing) = missing.first() {
+        warn!("{number} of {total} Retweets have no timestamp in {file} and keep their own, e.g. Retweet {id}",
+              number = missing.len(), total = retweets.len(), file = path.display(), id = first_missing);
+    }
+
+    if assert_sorted {
+        for (index, pair) in retweets.windows(2).enumerate() {
+            if pair[1].created_at < pair[0].created_at {
+                let message: String = format!("Retweet {id} at index {index} was created before its predecessor \
+                                               according to the timestamps in {file}",
+                                              id = pair[1].id, index = index + 1, file = path.display());
+                error!("{}", message);
+                return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+            }
+        }
+    }
+
+    Ok(retweets)
+}
+
+/// Load at most `max_retweets` Retweets from the given input, e.g. to check that the Retweets can be read. Local files
+/// are only read as far as necessary, unless they contain a single JSON array. Retweets on AWS S3 are not downloaded;
+/// instead, the bucket prefix of their path is listed to check that the file exists, and no Retweets are returned.
+/// If `assert_sorted` is `true`, fail on the first sampled Retweet that was created before its predecessor. If
+/// `include_quotes` is `true`, quote Tweets are sampled as Retweets of the quoted Tweet.
+pub fn sample_from_source(input: InputSource, assert_sorted: bool, include_quotes: bool,
+                          timestamp_format: TimestampFormat, max_retweets: usize)
+    -> Result<Vec<Retweet>> {
+    if let Some(s3_config) = input.s3 {
+        let path: String = input.path.to_string_lossy().into_owned();
+        let bucket: Bucket = s3_config.get_bucket()?;
+        let (list, code): (ListBucketResult, u32) = bucket.list(&path, None)?;
+        if code != 200 {
+            let message: String = format!("Could not get contents of AWS S3 bucket \"{bucket} (region {region})\": \
+                                           HTTP error {code}",
+                                          bucket = bucket.name, region = bucket.region, code = code);
+            error!("{}", message);
+            return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
+        }
+        if !list.contents.iter().any(|entry| entry.key == path) {
+            let message: String = format!("Could not find file \"{file}\" in AWS S3 bucket \"{bucket} (region \
+                                           {region})\"",
+                                          file = path, bucket = bucket.name, region = bucket.region);
+            error!("{}", message);
+            return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
+        }
+        return Ok(Vec::new());
+    }
+
+    match stream_from_file(&input.path, assert_sorted, include_quotes, timestamp_format)? {
+        Some(retweets) => retweets.take(max_retweets).collect(),
+        None => {
+            let mut retweets: Vec<Retweet> = from_file(&input.path, assert_sorted, include_quotes, timestamp_format,
+                                                       &mut RetweetLoadStatistics::default())?;
+            retweets.truncate(max_retweets);
+            Ok(retweets)
+        }
+    }
+}
+
+/// Open the Retweets (one per line) in the local file at `path` to read them lazily, i.e. only while iterating over
+/// them. If `assert_sorted` is `true`, the iteration fails on the first Retweet that was created before its
+/// predecessor. If `include_quotes` is `true`, quote Tweets are returned as Retweets of the quoted Tweet. Retweets
+/// whose creation times are not given in the `timestamp_format` are skipped. The file may be gzip-compressed. Return
+/// `None` if the file contains a single JSON array instead, whose elements are only available once it has been read
+/// completely. Errors if the file cannot be opened.
+pub fn stream_from_file(path: &Path, assert_sorted: bool, include_quotes: bool, timestamp_format: TimestampFormat)
+    -> Result<Option<RetweetLines<BufReader<Box<Read>>>>> {
+    if !path.is_file() {
+        error!("Retweet data set is a not a file: {path}", path = path.display());
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidInput,
+                                            format!("Retweet data set is not a file: {path}", path = path.display()))));
+    }
+
+    let retweet_file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            error!("Could not open Retweet data set: {error}", error = error);
+            return Err(Error::from(error));
+        }
+    };
+    let mut retweet_file: BufReader<Box<Read>> = decompress(Box::new(retweet_file))?;
+    if starts_with_array(&mut retweet_file)? {
+        return Ok(None);
+    }
+
+    Ok(Some(RetweetLines::new(retweet_file, &format!("{}", path.display()), assert_sorted, include_quotes,
+                              timestamp_format)))
+}
+
+/// Load the Retweets from the given `path`, counting those without the ID of their original Tweet in the `statistics`.
+fn from_file(path: &PathBuf, assert_sorted: bool, include_quotes: bool, timestamp_format: TimestampFormat,
+             statistics: &mut RetweetLoadStatistics)
+    -> Result<Vec<Retweet>> {
     if !path.is_file() {
         #[cfg(not(test))]
         error!("Retweet data set is a not a file: {path}", path = path.display());
@@ -51,33 +217,16 @@ fn from_file(path: &PathBuf) -> Result<Vec<Retweet>> {
             return Err(Error::from(error));
         }
     };
-    let retweet_file: BufReader<File> = BufReader::new(retweet_file);
-
-    // Parse the lines while discarding those that are invalid.
-    let retweets: Vec<Retweet> = retweet_file.lines()
-        .filter_map(|line: IOResult<String>| -> Option<Retweet> {
-            match line {
-                Ok(line) => {
-                    match serde_json::from_str::<Retweet>(&line) {
-                        Ok(tweet) => Some(tweet),
-                        Err(message) => {
-                            warn!("Failed to parse Retweet: {error}", error = message);
-                            None
-                        }
-                    }
-                },
-                Err(message) => {
-                    warn!("Invalid line in file {file}: {error}", file = path.display(), error = message);
-                    None
-                }
-            }
-        })
-        .collect();
-    Ok(retweets)
+    let retweet_file: BufReader<Box<Read>> = decompress(Box::new(retweet_file))?;
+
+    parse(retweet_file, &format!("{}", path.display()), assert_sorted, include_quotes, timestamp_format, statistics)
 }
 
-/// Load the Retweets from the given AWS S3 `bucket`.
-fn from_aws_s3(path: &str, bucket: &Bucket) -> Result<Vec<Retweet>> {
+/// Load the Retweets from the given AWS S3 `bucket`, counting those without the ID of their original Tweet in the
+/// `statistics`.
+fn from_aws_s3(path: &str, bucket: &Bucket, assert_sorted: bool, include_quotes: bool,
+               timestamp_format: TimestampFormat,
+               statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
     // Load the file from S3.
     let (contents, code): (Vec<u8>, u32) = bucket.get(path)?;
     if code != 200 {
@@ -87,31 +236,389 @@ fn from_aws_s3(path: &str, bucket: &Bucket) -> Result<Vec<Retweet>> {
         error!("{}", message);
         return Err(Error::from(S3Error::from_kind(S3ErrorKind::Msg(message))));
     }
-    let retweet_file: BufReader<&[u8]> = BufReader::new(&contents);
-
-    // Parse the lines while discarding those that are invalid.
-    let retweets: Vec<Retweet> = retweet_file.lines()
-        .filter_map(|line: IOResult<String>| -> Option<Retweet> {
-            match line {
-                Ok(line) => {
-                    match serde_json::from_str::<Retweet>(&line) {
-                        Ok(tweet) => Some(tweet),
-                        Err(message) => {
-                            warn!("Failed to parse Tweet: {error}", error = message);
-                            None
-                        }
-                    }
-                },
+    let retweet_file: BufReader<Box<Read>> = decompress(Box::new(Cursor::new(contents)))?;
+
+    parse(retweet_file, path, assert_sorted, include_quotes, timestamp_format, statistics)
+}
+
+/// Parse the Retweets from the given `reader` while discarding those that are invalid. The `file` name is used in log
+/// messages. If `assert_sorted` is `true`, fail on the first Retweet that was created before its predecessor.
+///
+/// The Tweets are given either as JSON lines (one per line), or as a single JSON array if the first non-whitespace
+/// character is `[`. Retweets without the ID of their original Tweet cannot be assigned to a cascade; they are
+/// discarded and counted in the `statistics`. If `include_quotes` is `true`, quote Tweets are parsed as Retweets of
+/// the quoted Tweet and counted in the `statistics` as well. Retweets whose creation times are not given in the
+/// `timestamp_format` are skipped with a warning.
+fn parse<R: BufRead>(mut reader: R, file: &str, assert_sorted: bool, include_quotes: bool,
+                     timestamp_format: TimestampFormat,
+                     statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
+    if starts_with_array(&mut reader)? {
+        parse_array(reader, file, assert_sorted, include_quotes, timestamp_format, statistics)
+    } else {
+        parse_lines(reader, file, assert_sorted, include_quotes, timestamp_format, statistics)
+    }
+}
+
+/// Determine if the first non-whitespace character in the `reader` opens a JSON array. Only whitespace is consumed.
+fn starts_with_array<R: BufRead>(reader: &mut R) -> Result<bool> {
+    loop {
+        let whitespace: usize = {
+            let buffer: &[u8] = reader.fill_buf()?;
+            if buffer.is_empty() {
+                return Ok(false);
+            }
+
+            match buffer.iter().position(|byte| !(*byte as char).is_whitespace()) {
+                Some(position) => return Ok(buffer[position] == b'['),
+                None => buffer.len()
+            }
+        };
+        reader.consume(whitespace);
+    }
+}
+
+/// Parse the Retweets (one per line) from the given `reader` while discarding those that are invalid, counting those
+/// without the ID of their original Tweet in the `statistics`. The `file` name is used in log messages. If
+/// `assert_sorted` is `true`, fail on the first Retweet that was created before its predecessor. If `include_quotes`
+/// is `true`, quote Tweets are parsed as Retweets of the quoted Tweet. Retweets whose creation times are not given in
+/// the `timestamp_format` are skipped.
+fn parse_lines<R: BufRead>(reader: R, file: &str, assert_sorted: bool, include_quotes: bool,
+                           timestamp_format: TimestampFormat,
+                           statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
+    let mut lines: RetweetLines<R> = RetweetLines::new(reader, file, assert_sorted, include_quotes, timestamp_format);
+    let retweets: Result<Vec<Retweet>> = lines.by_ref().collect();
+    statistics.missing_cascade += lines.statistics().missing_cascade;
+    statistics.quotes += lines.statistics().quotes;
+    statistics.skipped_tweets += lines.statistics().skipped_tweets;
+    retweets
+}
+
+/// An iterator parsing the Retweets (one per line) from a reader only when they are requested, discarding those that
+/// are invalid. Once a Retweet created before its predecessor has been found (if the order is asserted), an error is
+/// returned and the iteration ends.
+pub struct RetweetLines<R: BufRead> {
+    /// The lines of the reader, together with their index.
+    lines: Enumerate<Lines<R>>,
+
+    /// The name of the file, used in log messages.
+    file: String,
+
+    /// Whether Retweets created before their predecessor are an error.
+    assert_sorted: bool,
+
+    /// Whether quote Tweets are parsed as Retweets of the quoted Tweet.
+    include_quotes: bool,
+
+    /// The format in which the creation times are accepted.
+    timestamp_format: TimestampFormat,
+
+    /// The creation time of the previous Retweet, if any.
+    previous_created_at: Option<u64>,
+
+    /// Whether an error has ended the iteration.
+    has_failed: bool,
+
+    /// The counts gathered while parsing so far.
+    statistics: RetweetLoadStatistics,
+}
+
+impl<R: BufRead> RetweetLines<R> {
+    /// Parse the Retweets from the given `reader`. The `file` name is used in log messages. If `assert_sorted` is
+    /// `true`, fail on the first Retweet that was created before its predecessor. If `include_quotes` is `true`, quote
+    /// Tweets are parsed as Retweets of the quoted Tweet. Retweets whose creation times are not given in the
+    /// `timestamp_format` are skipped.
+    fn new(reader: R, file: &str, assert_sorted: bool, include_quotes: bool,
+           timestamp_format: TimestampFormat) -> RetweetLines<R> {
+        RetweetLines {
+            lines: reader.lines().enumerate(),
+            file: String::from(file),
+            assert_sorted: assert_sorted,
+            include_quotes: include_quotes,
+            timestamp_format: timestamp_format,
+            previous_created_at: None,
+            has_failed: false,
+            statistics: RetweetLoadStatistics::default(),
+        }
+    }
+
+    /// Get the counts gathered while parsing the Retweets so far, i.e. the number of Retweets without the ID of their
+    /// original Tweet which have been discarded, the number of quote Tweets parsed as Retweets, and the number of
+    /// skipped Tweets which are not Retweets.
+    pub fn statistics(&self) -> RetweetLoadStatistics {
+        self.statistics
+    }
+}
+
+impl<R: BufRead> Iterator for RetweetLines<R> {
+    type Item = Result<Retweet>;
+
+    fn next(&mut self) -> Option<Result<Retweet>> {
+        if self.has_failed {
+            return None;
+        }
+
+        for (index, line) in &mut self.lines {
+            let line: String = match line {
+                Ok(line) => line,
                 Err(message) => {
-                    warn!("Invalid line in file {file}: {error}", file = path, error = message);
-                    None
+                    warn!("Invalid line in file {file}: {error}", file = self.file, error = message);
+                    continue;
                 }
+            };
+
+            // Checking the timestamp format requires the line as a JSON value, from which the Retweet is then
+            // deserialized as well, thus each line is only parsed once.
+            let value: Option<Value> = if self.timestamp_format != TimestampFormat::Auto {
+                let format: TimestampFormat = self.timestamp_format;
+                let value: Option<Value> = serde_json::from_str::<Value>(&line).ok();
+                if !value.as_ref().map_or(true, |value| format.accepts(value)) {
+                    warn!("Skipping Retweet on line {line} of file {file} whose creation times are not given as \
+                           {format}",
+                          line = index + 1, file = self.file, format = format);
+                    continue;
+                }
+                value
+            } else {
+                None
+            };
+
+            let parsed_retweet: ::std::result::Result<Retweet, serde_json::Error> = match value {
+                Some(ref value) => Retweet::deserialize(value),
+                None => serde_json::from_str::<Retweet>(&line)
+            };
+            let retweet: Retweet = match parsed_retweet {
+                Ok(retweet) => retweet,
+                Err(message) => {
+                    // Only invalid Retweets are checked for quotes and a missing cascade ID, thus valid ones are only
+                    // parsed once.
+                    let value: Option<Value> = value.or_else(|| serde_json::from_str::<Value>(&line).ok());
+                    let quote: Option<Retweet> = match value {
+                        Some(ref value) if self.include_quotes => parse_quote(value, &self.file),
+                        _ => None
+                    };
+                    if let Some(quote) = quote {
+                        self.statistics.quotes += 1;
+                        quote
+                    } else if value.as_ref().map_or(false, is_cascade_missing) {
+                        warn!("Dropping Retweet on line {line} of file {file} without the ID of its original Tweet",
+                              line = index + 1, file = self.file);
+                        self.statistics.missing_cascade += 1;
+                        continue;
+                    } else if value.as_ref().map_or(false, is_original_tweet) {
+                        warn!("Skipping Tweet on line {line} of file {file} which is not a Retweet",
+                              line = index + 1, file = self.file);
+                        self.statistics.skipped_tweets += 1;
+                        continue;
+                    } else {
+                        warn!("Failed to parse Retweet: {error}", error = message);
+                        continue;
+                    }
+                }
+            };
+
+            let is_before_previous: bool = self.previous_created_at
+                .map_or(false, |previous_created_at| retweet.created_at < previous_created_at);
+            if self.assert_sorted && is_before_previous {
+                let message: String = format!("Retweet on line {line} of file {file} was created before its \
+                                               predecessor",
+                                              line = index + 1, file = self.file);
+                error!("{}", message);
+                self.has_failed = true;
+                return Some(Err(Error::from(IOError::new(IOErrorKind::InvalidData, message))));
+            }
+
+            self.previous_created_at = Some(retweet.created_at);
+            return Some(Ok(retweet));
+        }
+
+        None
+    }
+}
+
+/// Parse the Retweets from the JSON array in the given `reader` while discarding elements that are not Retweets. The
+/// array is read element by element, and each Retweet is pushed straight into the returned list, thus only a single
+/// element is held as a JSON value at any time. Syntax errors abort the parsing, since the remaining elements cannot
+/// be recovered. Retweets without the ID of their original Tweet are counted in the `statistics`. The `file` name is
+/// used in log messages. If `assert_sorted` is `true`, fail on the first Retweet that was created before its
+/// predecessor. If `include_quotes` is `true`, quote Tweets are parsed as Retweets of the quoted Tweet. Retweets whose
+/// creation times are not given in the `timestamp_format` are skipped.
+fn parse_array<R: BufRead>(reader: R, file: &str, assert_sorted: bool, include_quotes: bool,
+                           timestamp_format: TimestampFormat,
+                           statistics: &mut RetweetLoadStatistics) -> Result<Vec<Retweet>> {
+    let mut retweets: Vec<Retweet> = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let unsorted_index: Option<usize> = {
+        let visitor = RetweetArrayVisitor {
+            file: file,
+            assert_sorted: assert_sorted,
+            include_quotes: include_quotes,
+            timestamp_format: timestamp_format,
+            statistics: statistics,
+            retweets: &mut retweets,
+        };
+        match deserializer.deserialize_seq(visitor) {
+            Ok(unsorted_index) => unsorted_index,
+            Err(message) => {
+                let message: String = format!("Invalid JSON array in file {file}: {error}", file = file,
+                                              error = message);
+                error!("{}", message);
+                return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
             }
-        })
-        .collect();
+        }
+    };
+    if let Err(message) = deserializer.end() {
+        warn!("Ignoring trailing data after the JSON array in file {file}: {error}", file = file, error = message);
+    }
+
+    if let Some(index) = unsorted_index {
+        let message: String = format!("Retweet at index {index} of file {file} was created before its predecessor",
+                                      index = index, file = file);
+        error!("{}", message);
+        return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+    }
+
     Ok(retweets)
 }
 
+/// Determine if the JSON `value` of a Tweet has an original Tweet, but lacks the original Tweet's ID. Such a Retweet
+/// cannot be assigned to its cascade.
+fn is_cascade_missing(value: &Value) -> bool {
+    match value.get("retweeted_status") {
+        Some(original) if original.is_object() => original.get("id").map_or(true, Value::is_null),
+        _ => false
+    }
+}
+
+/// Determine if the JSON `value` is a valid Tweet without an original Tweet, i.e. if it is not a Retweet.
+fn is_original_tweet(value: &Value) -> bool {
+    Tweet::deserialize(value).map_or(false, |tweet| !tweet.is_retweet())
+}
+
+/// Parse the JSON `value` of a quote Tweet, i.e. of a Tweet without an original Tweet but with the ID of a quoted
+/// Tweet, as a Retweet of the quoted Tweet. Return `None` if the value is not a quote Tweet. Quote Tweets that do not
+/// include the quoted Tweet as `quoted_status` are skipped with a warning, since the root user of their cascade is
+/// unknown. The `file` name is used in log messages.
+fn parse_quote(value: &Value, file: &str) -> Option<Retweet> {
+    if value.get("retweeted_status").map_or(false, |original| !original.is_null()) {
+        return None;
+    }
+
+    let quote: Tweet = match serde_json::from_value::<Tweet>(value.clone()) {
+        Ok(quote) => quote,
+        Err(_) => return None
+    };
+    let quoted_status_id: u64 = match quote.quoted_status_id {
+        Some(quoted_status_id) => quoted_status_id,
+        None => return None
+    };
+
+    let quoted: Option<Tweet> = value.get("quoted_status")
+        .and_then(|quoted| serde_json::from_value::<Tweet>(quoted.clone()).ok())
+        .and_then(|quoted| if quoted.id == quoted_status_id { Some(quoted) } else { None });
+    match quoted {
+        Some(quoted) => Some(Retweet::from_quote(quote, quoted)),
+        None => {
+            warn!("Skipping quote Tweet {id} in file {file} without the quoted Tweet {quoted}", id = quote.id,
+                  file = file, quoted = quoted_status_id);
+            None
+        }
+    }
+}
+
+/// Determine if the `retweet` was created before the last of the `retweets`.
+fn is_before_last(retweet: &Retweet, retweets: &[Retweet]) -> bool {
+    match retweets.last() {
+        Some(previous) => retweet.created_at < previous.created_at,
+        None => false
+    }
+}
+
+/// A visitor pushing the Retweets from a JSON array into `retweets`. Elements that are not Retweets are skipped, those
+/// without the ID of their original Tweet and Tweets which are not Retweets are counted in the `statistics`. If
+/// `include_quotes` is set, quote Tweets are pushed as Retweets of the quoted Tweet and counted as well. Retweets whose
+/// creation times are not given in the `timestamp_format` are skipped. If `assert_sorted` is set, the visitor stops at
+/// the first Retweet created before its predecessor and returns its index in the array. The `file` name is used in
+/// log messages.
+struct RetweetArrayVisitor<'a> {
+    file: &'a str,
+    assert_sorted: bool,
+    include_quotes: bool,
+    timestamp_format: TimestampFormat,
+    statistics: &'a mut RetweetLoadStatistics,
+    retweets: &'a mut Vec<Retweet>,
+}
+
+impl<'a> RetweetArrayVisitor<'a> {
+    /// Parse the `element` at the given `index` in the array as a Retweet. Return `None` if it is skipped.
+    fn parse_element(&mut self, element: Value, index: usize) -> Option<Retweet> {
+        if !self.timestamp_format.accepts(&element) {
+            warn!("Skipping Retweet at index {index} of file {file} whose creation times are not given as {format}",
+                  index = index, file = self.file, format = self.timestamp_format);
+            return None;
+        }
+
+        if is_cascade_missing(&element) {
+            warn!("Dropping Retweet at index {index} of file {file} without the ID of its original Tweet",
+                  index = index, file = self.file);
+            self.statistics.missing_cascade += 1;
+            return None;
+        }
+
+        let quote: Option<Retweet> = if self.include_quotes {
+            parse_quote(&element, self.file)
+        } else {
+            None
+        };
+        if quote.is_some() {
+            self.statistics.quotes += 1;
+            return quote;
+        }
+
+        if is_original_tweet(&element) {
+            warn!("Skipping Tweet at index {index} of file {file} which is not a Retweet",
+                  index = index, file = self.file);
+            self.statistics.skipped_tweets += 1;
+            return None;
+        }
+
+        match serde_json::from_value::<Retweet>(element) {
+            Ok(retweet) => Some(retweet),
+            Err(message) => {
+                warn!("Failed to parse Retweet at index {index} of file {file}: {error}",
+                      index = index, file = self.file, error = message);
+                None
+            }
+        }
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for RetweetArrayVisitor<'a> {
+    type Value = Option<usize>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of Tweets")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> ::std::result::Result<Option<usize>, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut index: usize = 0;
+        while let Some(element) = seq.next_element::<Value>()? {
+            if let Some(retweet) = self.parse_element(element, index) {
+                if self.assert_sorted && is_before_last(&retweet, &self.retweets) {
+                    // The remaining elements must be consumed to reach the end of the array.
+                    while seq.next_element::<IgnoredAny>()?.is_some() {}
+                    return Ok(Some(index));
+                }
+
+                self.retweets.push(retweet);
+            }
+            index += 1;
+        }
+
+        Ok(None)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -119,14 +626,22 @@ mod tests {
     use std::path::PathBuf;
     use find_folder::Search;
     use Result;
+    use configuration::InputSource;
+    use configuration::TimestampFormat;
+    use schema;
     use twitter::Retweet;
+    use twitter::Tweet;
+    use super::RetweetLoadStatistics;
 
     #[test]
     fn from_file() {
+        let mut statistics = RetweetLoadStatistics::default();
+
         // Invalid file.
         let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
         let path: PathBuf = data_path.join("retweets.invalid.json");
-        let retweets: Result<Vec<Retweet>> = super::from_file(&path);
+        let retweets: Result<Vec<Retweet>> = super::from_file(&path, false, false, TimestampFormat::Auto,
+                                                              &mut statistics);
         assert!(retweets.is_err());
         if let Err(message) = retweets {
             assert!(message.description().starts_with("Retweet data set is not a file:"));
@@ -134,7 +649,8 @@ mod tests {
 
         // Valid file.
         let path: PathBuf = data_path.join("retweets.json");
-        let retweets: Result<Vec<Retweet>> = super::from_file(&path);
+        let retweets: Result<Vec<Retweet>> = super::from_file(&path, false, false, TimestampFormat::Auto,
+                                                              &mut statistics);
         assert!(retweets.is_ok());
         let retweets: Vec<Retweet> = retweets.expect("Retweet parsing failed, but previous assertion told otherwise.");
         assert_eq!(retweets.len(), 6);
@@ -146,4 +662,317 @@ mod tests {
             previous_timestamp = retweet.created_at;
         }
     }
+
+    #[test]
+    fn from_file_gzip() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+        // The compressed file contains the same Retweets as the uncompressed one.
+        let mut statistics = RetweetLoadStatistics::default();
+        let plain: Vec<Retweet> = super::from_file(&data_path.join("retweets.json"), true, false, TimestampFormat::Auto,
+                                                   &mut statistics)
+            .expect("Retweet parsing failed");
+        let mut compressed_statistics = RetweetLoadStatistics::default();
+        let compressed: Vec<Retweet> = super::from_file(&data_path.join("retweets.json.gz"), true, false,
+                                                        TimestampFormat::Auto, &mut compressed_statistics)
+            .expect("Retweet parsing failed");
+        assert_eq!(compressed.len(), 6);
+        assert_eq!(compressed, plain);
+        assert_eq!(compressed_statistics, statistics);
+    }
+
+    #[test]
+    fn from_file_assert_sorted() {
+        let mut statistics = RetweetLoadStatistics::default();
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+        // Sorted file.
+        let path: PathBuf = data_path.join("retweets.json");
+        let retweets: Result<Vec<Retweet>> = super::from_file(&path, true, false, TimestampFormat::Auto,
+                                                              &mut statistics);
+        assert!(retweets.is_ok());
+        assert_eq!(retweets.expect("Retweet parsing failed").len(), 6);
+
+        // Unsorted file, only failing if requested.
+        let path: PathBuf = data_path.join("retweets_unsorted.json");
+        let retweets: Result<Vec<Retweet>> = super::from_file(&path, false, false, TimestampFormat::Auto,
+                                                              &mut statistics);
+        assert!(retweets.is_ok());
+        assert_eq!(retweets.expect("Retweet parsing failed").len(), 3);
+
+        let retweets: Result<Vec<Retweet>> = super::from_file(&path, true, false, TimestampFormat::Auto,
+                                                              &mut statistics);
+        assert!(retweets.is_err());
+        if let Err(message) = retweets {
+            assert!(message.description().starts_with("Retweet on line 3 of file"));
+        }
+    }
+
+    #[test]
+    fn from_file_array() {
+        let mut statistics = RetweetLoadStatistics::default();
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+        // The array contains the same Tweets as the JSON lines.
+        let lines: Vec<Retweet> = super::from_file(&data_path.join("retweets.json"), true, false, TimestampFormat::Auto,
+                                                   &mut statistics)
+            .expect("Retweet parsing failed");
+        let array: Vec<Retweet> = super::from_file(&data_path.join("retweets_array.json"), true, false,
+                                                   TimestampFormat::Auto, &mut statistics)
+            .expect("Retweet parsing failed");
+        assert_eq!(array.len(), 6);
+        assert_eq!(array, lines);
+    }
+
+    #[test]
+    fn stream_from_file() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+        // The streamed Retweets are the same as the loaded ones.
+        let path: PathBuf = data_path.join("retweets.json");
+        let loaded: Vec<Retweet> = super::from_file(&path, true, false, TimestampFormat::Auto,
+                                                    &mut RetweetLoadStatistics::default())
+            .expect("Retweet parsing failed");
+        let streamed: Vec<Retweet> = super::stream_from_file(&path, true, false, TimestampFormat::Auto)
+            .expect("Could not open the Retweets")
+            .expect("The Retweets are not given as lines")
+            .collect::<Result<Vec<Retweet>>>()
+            .expect("Retweet parsing failed");
+        assert_eq!(streamed, loaded);
+
+        // Compressed files are streamed as well.
+        let streamed: Vec<Retweet> = super::stream_from_file(&data_path.join("retweets.json.gz"), true, false,
+                                                             TimestampFormat::Auto)
+            .expect("Could not open the Retweets")
+            .expect("The Retweets are not given as lines")
+            .collect::<Result<Vec<Retweet>>>()
+            .expect("Retweet parsing failed");
+        assert_eq!(streamed, loaded);
+
+        // Unsorted file: the Retweets before the unsorted one are still returned.
+        let mut streamed = super::stream_from_file(&data_path.join("retweets_unsorted.json"), true, false,
+                                                   TimestampFormat::Auto)
+            .expect("Could not open the Retweets")
+            .expect("The Retweets are not given as lines");
+        assert!(streamed.next().expect("Missing first Retweet").is_ok());
+        assert!(streamed.next().expect("Missing second Retweet").is_ok());
+        assert!(streamed.next().expect("Missing error").is_err());
+        assert!(streamed.next().is_none());
+
+        // Arrays cannot be streamed, invalid files cannot be opened.
+        assert!(super::stream_from_file(&data_path.join("retweets_array.json"), true, false, TimestampFormat::Auto)
+            .expect("Could not open the Retweets")
+            .is_none());
+        assert!(super::stream_from_file(&data_path.join("retweets.invalid.json"), true, false,
+                                        TimestampFormat::Auto).is_err());
+    }
+
+    #[test]
+    fn sample_from_source() {
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let loaded: Vec<Retweet> = super::from_file(&data_path.join("retweets.json"), true, false,
+                                                    TimestampFormat::Auto, &mut RetweetLoadStatistics::default())
+            .expect("Retweet parsing failed");
+
+        // The sample consists of the first Retweets, both from JSON lines and from arrays.
+        let input = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+        let sample: Vec<Retweet> = super::sample_from_source(input, true, false, TimestampFormat::Auto, 4)
+            .expect("Retweet sampling failed");
+        assert_eq!(sample, &loaded[..4]);
+        let input = InputSource::new(data_path.join("retweets_array.json").to_str().unwrap());
+        let sample: Vec<Retweet> = super::sample_from_source(input, true, false, TimestampFormat::Auto, 4)
+            .expect("Retweet sampling failed");
+        assert_eq!(sample, &loaded[..4]);
+
+        // Samples larger than the data set contain all Retweets.
+        let input = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+        let sample: Vec<Retweet> = super::sample_from_source(input, true, false, TimestampFormat::Auto, 100)
+            .expect("Retweet sampling failed");
+        assert_eq!(sample, loaded);
+
+        let input = InputSource::new(data_path.join("retweets.invalid.json").to_str().unwrap());
+        assert!(super::sample_from_source(input, true, false, TimestampFormat::Auto, 4).is_err());
+    }
+
+    #[test]
+    fn parse_array() {
+        let mut statistics = RetweetLoadStatistics::default();
+
+        // Leading whitespace, and elements that are not Retweets.
+        let array: String = format!(" \n[{retweet}, 42, {{}}]", retweet = schema::RETWEET_EXAMPLE);
+        let retweets: Vec<Retweet> = super::parse(array.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the array");
+        assert_eq!(retweets, vec![Retweet::new(3, Tweet::new(1, 0, 0), 2, 1)]);
+
+        // Empty array.
+        let retweets: Vec<Retweet> = super::parse("[]".as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the empty array");
+        assert!(retweets.is_empty());
+
+        // Unsorted array.
+        let unsorted: &str = "[{\"created_at\":2,\"id\":3,\"user\":{\"id\":2},\"retweeted_status\":\
+                              {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}},\
+                              {\"created_at\":1,\"id\":4,\"user\":{\"id\":3},\"retweeted_status\":\
+                              {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}]";
+        let retweets: Vec<Retweet> = super::parse(unsorted.as_bytes(), "example", false, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the array");
+        assert_eq!(retweets.len(), 2);
+        let retweets: Result<Vec<Retweet>> = super::parse(unsorted.as_bytes(), "example", true, false,
+                                                          TimestampFormat::Auto, &mut statistics);
+        assert!(retweets.is_err());
+        if let Err(message) = retweets {
+            assert!(message.description().starts_with("Retweet at index 1 of file example"));
+        }
+
+        // Syntax errors.
+        assert!(super::parse("[{}, ".as_bytes(), "example", false, false, TimestampFormat::Auto,
+                             &mut statistics).is_err());
+    }
+
+    #[test]
+    fn parse_missing_cascade() {
+        let valid: &str = "{\"created_at\":2,\"id\":3,\"user\":{\"id\":2},\"retweeted_status\":\
+                           {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let missing_id: &str = "{\"created_at\":3,\"id\":4,\"user\":{\"id\":3},\"retweeted_status\":\
+                                {\"created_at\":0,\"user\":{\"id\":0}}}";
+        let null_id: &str = "{\"created_at\":4,\"id\":5,\"user\":{\"id\":4},\"retweeted_status\":\
+                             {\"created_at\":0,\"id\":null,\"user\":{\"id\":0}}}";
+        let expected: Vec<Retweet> = vec![Retweet::new(3, Tweet::new(1, 0, 0), 2, 2)];
+
+        // JSON lines. The Retweets without a cascade ID are dropped and counted, other invalid lines are not counted.
+        let lines: String = format!("{}\n{}\n{}\n{{}}\n", valid, missing_id, null_id);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(lines.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the lines");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.missing_cascade, 2);
+
+        // JSON array.
+        let array: String = format!("[{}, {}, {}, {{}}]", valid, missing_id, null_id);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(array.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the array");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.missing_cascade, 2);
+    }
+
+    #[test]
+    fn parse_quotes() {
+        let retweet: &str = "{\"created_at\":2,\"id\":3,\"user\":{\"id\":2},\"retweeted_status\":\
+                             {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let quote: &str = "{\"created_at\":3,\"id\":4,\"user\":{\"id\":3},\"quoted_status_id\":1,\
+                           \"quoted_status\":{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let quote_without_quoted: &str = "{\"created_at\":4,\"id\":5,\"user\":{\"id\":4},\"quoted_status_id\":1}";
+        let tweet: &str = "{\"created_at\":5,\"id\":6,\"user\":{\"id\":5}}";
+        let expected: Vec<Retweet> = vec![Retweet::new(3, Tweet::new(1, 0, 0), 2, 2),
+                                          Retweet::new(4, Tweet::new(1, 0, 0), 3, 3)];
+
+        // JSON lines. Tweets neither retweeting nor quoting are skipped, as are quotes without the quoted Tweet.
+        let lines: String = format!("{}\n{}\n{}\n{}\n", retweet, quote, quote_without_quoted, tweet);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(lines.as_bytes(), "example", true, true, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the lines");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.quotes, 1);
+
+        // Quotes are only included if requested.
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(lines.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the lines");
+        assert_eq!(retweets, &expected[..1]);
+        assert_eq!(statistics.quotes, 0);
+
+        // JSON array.
+        let array: String = format!("[{}, {}, {}, {}]", retweet, quote, quote_without_quoted, tweet);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(array.as_bytes(), "example", true, true, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the array");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.quotes, 1);
+    }
+
+    #[test]
+    fn parse_skipped_tweets() {
+        let retweet: &str = "{\"created_at\":2,\"id\":3,\"user\":{\"id\":2},\"retweeted_status\":\
+                             {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let original: &str = "{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}";
+        let null_original: &str = "{\"created_at\":1,\"id\":2,\"user\":{\"id\":1},\"retweeted_status\":null}";
+        let invalid: &str = "{\"id\":4}";
+        let expected: Vec<Retweet> = vec![Retweet::new(3, Tweet::new(1, 0, 0), 2, 2)];
+
+        // JSON lines. Invalid Tweets are not counted.
+        let lines: String = format!("{}\n{}\n{}\n{}\n", original, null_original, retweet, invalid);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(lines.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the lines");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.skipped_tweets, 2);
+
+        // JSON array.
+        let array: String = format!("[{}, {}, {}, {}]", original, null_original, retweet, invalid);
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(array.as_bytes(), "example", true, false, TimestampFormat::Auto,
+                                                  &mut statistics)
+            .expect("Failed to parse the array");
+        assert_eq!(retweets, expected);
+        assert_eq!(statistics.skipped_tweets, 2);
+
+        // The data set contains two original Tweets.
+        let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::from_file(&data_path.join("retweets.json"), true, false,
+                                                      TimestampFormat::Auto, &mut statistics)
+            .expect("Failed to load the Retweets");
+        assert_eq!(retweets.len(), 6);
+        assert_eq!(statistics.skipped_tweets, 2);
+    }
+
+    #[test]
+    fn parse_timestamp_formats() {
+        let epoch: &str = "{\"created_at\":2,\"id\":3,\"user\":{\"id\":2},\"retweeted_status\":\
+                           {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let rfc3339: &str = "{\"created_at\":\"1970-01-01T00:00:03Z\",\"id\":4,\"user\":{\"id\":3},\
+                             \"retweeted_status\":{\"created_at\":\"1970-01-01T00:00:00Z\",\"id\":1,\
+                             \"user\":{\"id\":0}}}";
+        let malformed: &str = "{\"created_at\":\"yesterday\",\"id\":5,\"user\":{\"id\":4},\"retweeted_status\":\
+                               {\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let epoch_retweet = Retweet::new(3, Tweet::new(1, 0, 0), 2, 2);
+        let rfc3339_retweet = Retweet::new(4, Tweet::new(1, 0, 0), 3, 3);
+
+        for &(format, ref expected) in &[(TimestampFormat::Auto, vec![epoch_retweet.clone(), rfc3339_retweet.clone()]),
+                                         (TimestampFormat::Epoch, vec![epoch_retweet]),
+                                         (TimestampFormat::Rfc3339, vec![rfc3339_retweet])] {
+            // JSON lines. Malformed timestamps only skip their Tweet.
+            let lines: String = format!("{}\n{}\n{}\n", epoch, malformed, rfc3339);
+            let retweets: Vec<Retweet> = super::parse(lines.as_bytes(), "example", true, false, format,
+                                                      &mut RetweetLoadStatistics::default())
+                .expect("Failed to parse the lines");
+            assert_eq!(&retweets, expected);
+
+            // JSON array.
+            let array: String = format!("[{}, {}, {}]", epoch, malformed, rfc3339);
+            let retweets: Vec<Retweet> = super::parse(array.as_bytes(), "example", true, false, format,
+                                                      &mut RetweetLoadStatistics::default())
+                .expect("Failed to parse the array");
+            assert_eq!(&retweets, expected);
+        }
+    }
+
+    #[test]
+    fn parse_schema_example() {
+        let mut statistics = RetweetLoadStatistics::default();
+        let retweets: Vec<Retweet> = super::parse(schema::RETWEET_EXAMPLE.as_bytes(), "example", true, false,
+                                                  TimestampFormat::Auto, &mut statistics)
+            .expect("Failed to parse the example");
+        assert_eq!(retweets, vec![Retweet::new(3, Tweet::new(1, 0, 0), 2, 1)]);
+    }
 }
@@ -7,15 +7,17 @@
 //! Representations of data coming from Twitter and functions to work with those representations.
 
 pub use self::retweet::Retweet;
+pub use self::screen_names::ScreenNames;
+pub use self::timestamp_join::Timestamps;
 pub use self::tweet::Tweet;
 pub use self::user::User;
+pub use self::user_id::UserId;
 
 pub mod get;
 mod retweet;
+mod screen_names;
+mod timestamp;
+mod timestamp_join;
 mod tweet;
 mod user;
-
-/// An alias for user IDs to improve code legibility.
-///
-/// If the stored value is negative, the ID belongs to a dummy user who was created to pad the social graph.
-pub type UserID = i64;
+mod user_id;
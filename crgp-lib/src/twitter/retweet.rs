@@ -8,6 +8,7 @@
 
 use abomonation::Abomonation;
 
+use twitter::timestamp;
 use twitter::Tweet;
 use twitter::User;
 
@@ -21,6 +22,10 @@ use twitter::User;
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Retweet {
     /// UTC time when this tweet was created.
+    ///
+    /// Either given as an integer or as an ISO-8601 string (e.g. `2018-10-10T20:19:24Z`), which will be converted to
+    /// seconds since the epoch.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub created_at: u64,
 
     /// The integer representation of the unique identifier for this tweet.
@@ -33,4 +38,61 @@ pub struct Retweet {
     pub user: User
 }
 
+impl Retweet {
+    /// Initialize a new Retweet with the given `id` of the `original` tweet, posted by the user with ID `user_id` at
+    /// time `created_at`.
+    pub fn new(id: u64, original: Tweet, user_id: i64, created_at: u64) -> Retweet {
+        Retweet {
+            created_at: created_at,
+            id: id,
+            retweeted_status: original,
+            user: User::new(user_id),
+        }
+    }
+
+    /// Initialize a Retweet of the `quoted` tweet from the `quote` tweet, i.e. treat the quote as if it were a Retweet.
+    pub fn from_quote(quote: Tweet, quoted: Tweet) -> Retweet {
+        Retweet {
+            created_at: quote.created_at,
+            id: quote.id,
+            retweeted_status: quoted,
+            user: quote.user,
+        }
+    }
+}
+
 unsafe_abomonate!(Retweet : created_at, id, retweeted_status, user);
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use twitter::Tweet;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn new() {
+        let retweet = Retweet::new(2, Tweet::new(1, 13, 37), 42, 1337);
+        assert_eq!(retweet.created_at, 1337);
+        assert_eq!(retweet.id, 2);
+        assert_eq!(retweet.retweeted_status, Tweet::new(1, 13, 37));
+        assert_eq!(retweet.user, User::new(42));
+    }
+
+    #[test]
+    fn from_quote() {
+        let mut quote = Tweet::new(2, 42, 1337);
+        quote.quoted_status_id = Some(1);
+        let retweet = Retweet::from_quote(quote, Tweet::new(1, 13, 37));
+        assert_eq!(retweet, Retweet::new(2, Tweet::new(1, 13, 37), 42, 1337));
+    }
+
+    #[test]
+    fn deserialize_iso_8601_timestamps() {
+        let json = "{\"created_at\":\"2018-10-10T20:19:24Z\",\"id\":2,\
+                    \"retweeted_status\":{\"created_at\":\"2018-10-10T22:19:00+02:00\",\"id\":1,\"user\":{\"id\":13}},\
+                    \"user\":{\"id\":42}}";
+        let retweet: Retweet = serde_json::from_str(json).expect("Could not parse the Retweet");
+        assert_eq!(retweet, Retweet::new(2, Tweet::new(1, 13, 1_539_202_740), 42, 1_539_202_764));
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Map user IDs to their screen names.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use Result;
+use configuration::InputSource;
+use read_text_source;
+use twitter::User;
+use twitter::UserId;
+
+/// The separator between a user's ID and their screen name when rendering both.
+pub const SCREEN_NAME_SEPARATOR: &'static str = ":@";
+
+/// A map from user IDs to screen names.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ScreenNames {
+    /// The screen name of each user, without a leading `@`.
+    names: HashMap<UserId, String>,
+}
+
+impl ScreenNames {
+    /// Create an empty map.
+    pub fn new() -> ScreenNames {
+        ScreenNames {
+            names: HashMap::new(),
+        }
+    }
+
+    /// Load the map from the text file `input`. Each line of the file consists of a user ID and the user's screen name,
+    /// separated by `;`, e.g. `123;alice`. A leading `@` of the screen name is ignored. Invalid lines are skipped with
+    /// a warning. Errors on any I/O error opening the file.
+    pub fn from_source(input: InputSource) -> Result<ScreenNames> {
+        let reader = read_text_source(input.clone())?;
+
+        let mut screen_names = ScreenNames::new();
+        for line in reader.lines() {
+            let line: String = match line {
+                Ok(line) => line,
+                Err(message) => {
+                    warn!("Invalid line in file {file}: {error}", file = input, error = message);
+                    continue;
+                }
+            };
+
+            if !screen_names.add_line(&line) {
+                warn!("Could not parse screen name mapping '{line}' in file {file}", line = line, file = input);
+            }
+        }
+
+        Ok(screen_names)
+    }
+
+    /// Parse a single `line` of the form `id;screen_name` and add it to the map. Return `false` if the line is
+    /// invalid.
+    fn add_line(&mut self, line: &str) -> bool {
+        let mut parts = line.splitn(2, ';');
+        let id: UserId = match parts.next().map(|id| id.trim().parse::<UserId>()) {
+            Some(Ok(id)) => id,
+            _ => return false
+        };
+        let name: &str = match parts.next().map(|name| name.trim().trim_left_matches('@')) {
+            Some(name) if !name.is_empty() => name,
+            _ => return false
+        };
+
+        self.insert(id, name);
+        true
+    }
+
+    /// Set the screen name of the user with the given `id`. A leading `@` of the `name` is ignored.
+    pub fn insert(&mut self, id: UserId, name: &str) {
+        let _ = self.names.insert(id, String::from(name.trim_left_matches('@')));
+    }
+
+    /// Get the screen name (without a leading `@`) of the user with the given `id`, if known.
+    pub fn get(&self, id: UserId) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    /// Render the `user` as `id:@screen_name` if their screen name is known, or as the bare `id` otherwise. Dummy
+    /// users never have a screen name and are thus always rendered by their (negative) ID.
+    pub fn render(&self, user: User) -> String {
+        match self.get(user.id) {
+            Some(name) if user.is_real() => {
+                format!("{id}{separator}{name}", id = user.id, separator = SCREEN_NAME_SEPARATOR, name = name)
+            },
+            _ => format!("{}", user)
+        }
+    }
+
+    /// The number of users with a known screen name.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Determine if no screen names are known.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn add_line() {
+        let mut screen_names = ScreenNames::new();
+        assert!(screen_names.add_line("1;alice"));
+        assert!(screen_names.add_line("2;@bob"));
+        assert!(screen_names.add_line(" 3 ; carol "));
+        assert!(!screen_names.add_line("4"));
+        assert!(!screen_names.add_line("4;"));
+        assert!(!screen_names.add_line("a;dave"));
+        assert!(!screen_names.add_line(""));
+
+        assert_eq!(screen_names.len(), 3);
+        assert_eq!(screen_names.get(UserId::from(1)), Some("alice"));
+        assert_eq!(screen_names.get(UserId::from(2)), Some("bob"));
+        assert_eq!(screen_names.get(UserId::from(3)), Some("carol"));
+        assert_eq!(screen_names.get(UserId::from(4)), None);
+    }
+
+    #[test]
+    fn render() {
+        let mut screen_names = ScreenNames::new();
+        assert!(screen_names.is_empty());
+        screen_names.insert(UserId::from(123), "@alice");
+        screen_names.insert(UserId::dummy(1), "dummy");
+
+        // Mapped, unmapped, and dummy users.
+        assert_eq!(screen_names.render(User::new(123)), String::from("123:@alice"));
+        assert_eq!(screen_names.render(User::new(456)), String::from("456"));
+        assert_eq!(screen_names.render(User::new(-1)), String::from("-1"));
+    }
+}
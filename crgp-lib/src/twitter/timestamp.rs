@@ -0,0 +1,110 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Deserialization of Tweet timestamps.
+
+use std::fmt;
+
+use chrono::DateTime;
+use serde::de::Deserializer;
+use serde::de::Error as DeserializationError;
+use serde::de::Visitor;
+
+/// Deserialize a timestamp given either as an integer of seconds since the epoch, or as an ISO-8601 (RFC 3339) string,
+/// e.g. `2018-10-10T20:19:24Z`. Strings are normalized to seconds since the epoch (UTC).
+///
+/// Use this function with `#[serde(deserialize_with = "timestamp::deserialize")]`.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where D: Deserializer<'de>
+{
+    deserializer.deserialize_any(TimestampVisitor)
+}
+
+/// A visitor accepting integer and string timestamps.
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a non-negative integer timestamp or an ISO-8601 date string")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<u64, E>
+        where E: DeserializationError
+    {
+        Ok(value)
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<u64, E>
+        where E: DeserializationError
+    {
+        if value < 0 {
+            return Err(E::custom(format!("timestamp {value} is before the epoch", value = value)));
+        }
+
+        Ok(value as u64)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<u64, E>
+        where E: DeserializationError
+    {
+        let timestamp: i64 = match DateTime::parse_from_rfc3339(value) {
+            Ok(date) => date.timestamp(),
+            Err(message) => {
+                return Err(E::custom(format!("invalid ISO-8601 timestamp {value}: {error}",
+                                             value = value, error = message)));
+            }
+        };
+
+        self.visit_i64(timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    /// A wrapper to deserialize a single timestamp.
+    #[derive(Debug, Deserialize)]
+    struct Timestamp {
+        /// The deserialized timestamp.
+        #[serde(deserialize_with = "super::deserialize")]
+        created_at: u64,
+    }
+
+    /// Deserialize the JSON `value` as a timestamp.
+    fn parse(value: &str) -> Result<u64, serde_json::Error> {
+        serde_json::from_str::<Timestamp>(&format!("{{\"created_at\":{}}}", value))
+            .map(|timestamp| timestamp.created_at)
+    }
+
+    #[test]
+    fn deserialize_integer() {
+        assert_eq!(parse("0").expect("Could not parse the timestamp"), 0);
+        assert_eq!(parse("1539202764").expect("Could not parse the timestamp"), 1_539_202_764);
+        assert!(parse("-1").is_err());
+    }
+
+    #[test]
+    fn deserialize_rfc3339_utc() {
+        assert_eq!(parse("\"2018-10-10T20:19:24Z\"").expect("Could not parse the timestamp"), 1_539_202_764);
+        assert_eq!(parse("\"1970-01-01T00:00:00Z\"").expect("Could not parse the timestamp"), 0);
+        assert!(parse("\"1969-12-31T23:59:59Z\"").is_err());
+    }
+
+    #[test]
+    fn deserialize_rfc3339_offset() {
+        assert_eq!(parse("\"2018-10-10T22:19:24+02:00\"").expect("Could not parse the timestamp"), 1_539_202_764);
+        assert_eq!(parse("\"2018-10-10T15:19:24-05:00\"").expect("Could not parse the timestamp"), 1_539_202_764);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(parse("\"10/10/2018\"").is_err());
+        assert!(parse("true").is_err());
+    }
+}
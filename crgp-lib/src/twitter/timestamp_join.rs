@@ -0,0 +1,171 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Join precise timestamps from a separate file onto Tweets, given by their ID.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+use chrono::DateTime;
+
+use Result;
+use twitter::Retweet;
+
+/// A map from Tweet IDs to the times at which the Tweets were created (in seconds since the epoch).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Timestamps {
+    /// The creation time of each Tweet, given by its ID.
+    timestamps: HashMap<u64, u64>,
+}
+
+impl Timestamps {
+    /// Create an empty map.
+    pub fn new() -> Timestamps {
+        Timestamps::default()
+    }
+
+    /// Load the map from the text file at `path`. Each line of the file consists of a Tweet ID and its timestamp,
+    /// separated by `,`, e.g. `123,1507000000`. The timestamp is given either in seconds since the epoch, or as an
+    /// ISO-8601 (RFC 3339) string. Invalid lines (e.g. a header) are skipped with a warning. Errors on any I/O error
+    /// opening the file.
+    pub fn from_file(path: &Path) -> Result<Timestamps> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut timestamps = Timestamps::new();
+        for line in reader.lines() {
+            let line: String = match line {
+                Ok(line) => line,
+                Err(message) => {
+                    warn!("Invalid line in file {file}: {error}", file = path.display(), error = message);
+                    continue;
+                }
+            };
+
+            if !timestamps.add_line(&line) {
+                warn!("Could not parse timestamp '{line}' in file {file}", line = line, file = path.display());
+            }
+        }
+
+        Ok(timestamps)
+    }
+
+    /// Parse a single `line` of the form `tweet_id,timestamp` and add it to the map. Return `false` if the line is
+    /// invalid.
+    fn add_line(&mut self, line: &str) -> bool {
+        let mut parts = line.splitn(2, ',');
+        let id: u64 = match parts.next().map(|id| id.trim().parse::<u64>()) {
+            Some(Ok(id)) => id,
+            _ => return false
+        };
+        let timestamp: u64 = match parts.next().and_then(|timestamp| parse_timestamp(timestamp.trim())) {
+            Some(timestamp) => timestamp,
+            None => return false
+        };
+
+        self.insert(id, timestamp);
+        true
+    }
+
+    /// Set the `timestamp` of the Tweet with the given `id`.
+    pub fn insert(&mut self, id: u64, timestamp: u64) {
+        let _ = self.timestamps.insert(id, timestamp);
+    }
+
+    /// Get the timestamp of the Tweet with the given `id`, if known.
+    pub fn get(&self, id: u64) -> Option<u64> {
+        self.timestamps.get(&id).cloned()
+    }
+
+    /// Override the creation times of the `retweets` and of their original Tweets with the timestamps in this map,
+    /// where known. Return the IDs of the Retweets without a timestamp, which keep their creation time.
+    pub fn join(&self, retweets: &mut [Retweet]) -> Vec<u64> {
+        let mut missing: Vec<u64> = Vec::new();
+        for retweet in retweets {
+            match self.get(retweet.id) {
+                Some(timestamp) => retweet.created_at = timestamp,
+                None => missing.push(retweet.id)
+            }
+            if let Some(timestamp) = self.get(retweet.retweeted_status.id) {
+                retweet.retweeted_status.created_at = timestamp;
+            }
+        }
+        missing
+    }
+
+    /// The number of Tweets with a known timestamp.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Determine if no timestamps are known.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+}
+
+/// Parse a `timestamp` given either in seconds since the epoch, or as an ISO-8601 (RFC 3339) string. Return `None` if
+/// the timestamp is invalid or before the epoch.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    if let Ok(seconds) = timestamp.parse::<u64>() {
+        return Some(seconds);
+    }
+
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) if time.timestamp() >= 0 => Some(time.timestamp() as u64),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitter::Retweet;
+    use twitter::Tweet;
+    use super::*;
+
+    #[test]
+    fn add_line() {
+        let mut timestamps = Timestamps::new();
+        assert!(timestamps.add_line("1,10"));
+        assert!(timestamps.add_line(" 2 , 20 "));
+        assert!(timestamps.add_line("3,1970-01-01T00:00:30Z"));
+        assert!(!timestamps.add_line("retweet_id,timestamp"));
+        assert!(!timestamps.add_line("4"));
+        assert!(!timestamps.add_line("4,"));
+        assert!(!timestamps.add_line("4,-1"));
+        assert!(!timestamps.add_line(""));
+
+        assert_eq!(timestamps.len(), 3);
+        assert_eq!(timestamps.get(1), Some(10));
+        assert_eq!(timestamps.get(2), Some(20));
+        assert_eq!(timestamps.get(3), Some(30));
+        assert_eq!(timestamps.get(4), None);
+    }
+
+    #[test]
+    fn join() {
+        let mut timestamps = Timestamps::new();
+        assert!(timestamps.is_empty());
+        timestamps.insert(1, 100);
+        timestamps.insert(3, 103);
+
+        let original = Tweet::new(1, 0, 0);
+        let mut retweets: Vec<Retweet> = vec![
+            Retweet::new(3, original.clone(), 2, 1),
+            Retweet::new(4, original, 1, 2),
+        ];
+
+        // The Retweet without a timestamp keeps its creation time, but its original Tweet is updated.
+        let missing: Vec<u64> = timestamps.join(&mut retweets);
+        assert_eq!(missing, vec![4]);
+        assert_eq!(retweets[0].created_at, 103);
+        assert_eq!(retweets[0].retweeted_status.created_at, 100);
+        assert_eq!(retweets[1].created_at, 2);
+        assert_eq!(retweets[1].retweeted_status.created_at, 100);
+    }
+}
@@ -7,7 +7,11 @@
 //! Representations of tweets.
 
 use abomonation::Abomonation;
+use serde::Deserialize;
+use serde::de::Deserializer;
+use serde::de::IgnoredAny;
 
+use twitter::timestamp;
 use twitter::User;
 
 /// Tweets are the basic atomic building block of all things Twitter.
@@ -21,13 +25,103 @@ use twitter::User;
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Tweet {
     /// UTC time when this tweet was created.
+    ///
+    /// Either given as an integer or as an ISO-8601 string (e.g. `2018-10-10T20:19:24Z`), which will be converted to
+    /// seconds since the epoch.
+    #[serde(deserialize_with = "timestamp::deserialize")]
     pub created_at: u64,
 
     /// The integer representation of the unique identifier for this tweet.
     pub id: u64,
 
+    /// The integer representation of the unique identifier of the tweet quoted by this tweet, if it is a quote tweet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoted_status_id: Option<u64>,
+
     /// The user who posted this tweet.
-    pub user: User
+    pub user: User,
+
+    /// Whether this tweet retweets an original tweet, i.e. if it has a non-null `retweeted_status`. Only used while
+    /// deserializing, thus it is never serialized.
+    #[serde(default, rename = "retweeted_status", deserialize_with = "deserialize_has_original", skip_serializing)]
+    has_retweeted_status: bool,
+}
+
+impl Tweet {
+    /// Initialize a new tweet with the given `id`, posted by the user with ID `user_id` at time `created_at`.
+    pub fn new(id: u64, user_id: i64, created_at: u64) -> Tweet {
+        Tweet {
+            created_at: created_at,
+            id: id,
+            quoted_status_id: None,
+            user: User::new(user_id),
+            has_retweeted_status: false,
+        }
+    }
+
+    /// Determine if this tweet is a retweet, i.e. if it has been deserialized with an original tweet.
+    pub fn is_retweet(&self) -> bool {
+        self.has_retweeted_status
+    }
+}
+
+/// Deserialize whether a tweet has a non-null original tweet, ignoring the original tweet itself.
+fn deserialize_has_original<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where D: Deserializer<'de>
+{
+    Option::<IgnoredAny>::deserialize(deserializer).map(|original| original.is_some())
 }
 
 unsafe_abomonate!(Tweet : created_at, id, user);
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use twitter::User;
+    use super::*;
+
+    #[test]
+    fn new() {
+        let tweet = Tweet::new(1, 42, 1337);
+        assert_eq!(tweet.created_at, 1337);
+        assert_eq!(tweet.id, 1);
+        assert_eq!(tweet.quoted_status_id, None);
+        assert_eq!(tweet.user, User::new(42));
+        assert!(!tweet.is_retweet());
+    }
+
+    #[test]
+    fn is_retweet() {
+        let json = "{\"created_at\":1,\"id\":2,\"user\":{\"id\":1},\
+                    \"retweeted_status\":{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let tweet: Tweet = serde_json::from_str(json).expect("Could not parse the Retweet");
+        assert!(tweet.is_retweet());
+
+        // The original Tweet is not serialized.
+        assert_eq!(serde_json::to_string(&tweet).expect("Could not serialize the Retweet"),
+                   "{\"created_at\":1,\"id\":2,\"user\":{\"id\":1}}");
+
+        let tweet: Tweet = serde_json::from_str("{\"created_at\":0,\"id\":1,\"user\":{\"id\":0},\
+                                                  \"retweeted_status\":null}")
+            .expect("Could not parse the Tweet");
+        assert!(!tweet.is_retweet());
+    }
+
+    #[test]
+    fn deserialize_quote() {
+        let json = "{\"created_at\":2,\"id\":3,\"quoted_status_id\":1,\"user\":{\"id\":2},\
+                    \"quoted_status\":{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}}";
+        let tweet: Tweet = serde_json::from_str(json).expect("Could not parse the quote Tweet");
+        assert_eq!(tweet.created_at, 2);
+        assert_eq!(tweet.id, 3);
+        assert_eq!(tweet.quoted_status_id, Some(1));
+        assert_eq!(tweet.user, User::new(2));
+
+        // Other Tweets do not quote any Tweet, and the field is omitted when serializing them.
+        let tweet: Tweet = serde_json::from_str("{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}")
+            .expect("Could not parse the Tweet");
+        assert_eq!(tweet, Tweet::new(1, 0, 0));
+        assert_eq!(serde_json::to_string(&tweet).expect("Could not serialize the Tweet"),
+                   "{\"created_at\":0,\"id\":1,\"user\":{\"id\":0}}");
+    }
+}
@@ -10,7 +10,7 @@ use std::fmt;
 
 use abomonation::Abomonation;
 
-use UserID;
+use twitter::UserId;
 
 /// Users can be anyone or anything.
 ///
@@ -22,13 +22,34 @@ use UserID;
 /// https://dev.twitter.com/overview/api/users
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct User {
-    /// Integer representation of the unique identifier for this user.
-    pub id: UserID,
+    /// Integer representation of the unique identifier for this user. Dummy users have negative IDs.
+    pub id: UserId,
 }
 
 impl User {
-    /// Initialize a new user with the given ID.
-    pub fn new(id: UserID) -> User {
+    /// Initialize a new user with the given raw ID.
+    pub fn new(id: i64) -> User {
+        User::from(UserId::from(id))
+    }
+
+    /// Initialize the `n`-th dummy user, counting from `1`.
+    pub fn dummy(n: u64) -> User {
+        User::from(UserId::dummy(n))
+    }
+
+    /// Determine if this is a dummy user who was created to pad the social graph.
+    pub fn is_dummy(&self) -> bool {
+        self.id.is_dummy()
+    }
+
+    /// Determine if this is a real user.
+    pub fn is_real(&self) -> bool {
+        self.id.is_real()
+    }
+}
+
+impl From<UserId> for User {
+    fn from(id: UserId) -> User {
         User {
             id: id,
         }
@@ -50,7 +71,35 @@ mod tests {
     #[test]
     fn new() {
         let user = User::new(42);
-        assert_eq!(user.id, 42);
+        assert_eq!(user.id, UserId::from(42));
+    }
+
+    #[test]
+    fn from() {
+        assert_eq!(User::from(UserId::from(42)), User::new(42));
+        assert_eq!(User::from(UserId::dummy(3)), User::dummy(3));
+    }
+
+    #[test]
+    fn dummy() {
+        assert_eq!(User::dummy(1), User::new(-1));
+        assert_eq!(User::dummy(42), User::new(-42));
+    }
+
+    #[test]
+    fn is_dummy() {
+        assert!(User::dummy(1).is_dummy());
+        assert!(User::new(-7).is_dummy());
+        assert!(!User::new(0).is_dummy());
+        assert!(!User::new(42).is_dummy());
+    }
+
+    #[test]
+    fn is_real() {
+        assert!(User::new(0).is_real());
+        assert!(User::new(42).is_real());
+        assert!(!User::dummy(1).is_real());
+        assert!(!User::new(-7).is_real());
     }
 
     #[test]
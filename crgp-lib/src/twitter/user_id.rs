@@ -0,0 +1,149 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Typed IDs of Twitter users, distinguishing real users from dummy users.
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use abomonation::Abomonation;
+use serde::de::Deserialize;
+use serde::de::Deserializer;
+use serde::ser::Serialize;
+use serde::ser::Serializer;
+
+/// The ID of a user, either of a real user or of a dummy user who was created to pad the social graph.
+///
+/// Internally, dummy users have negative IDs. Converting from and to the raw `i64` is explicit, thus real and dummy
+/// IDs cannot be mixed up with other integers by accident. The ID is serialized as its raw value and has the same
+/// in-memory layout.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UserId(i64);
+
+impl UserId {
+    /// Get the ID of the `n`-th dummy user, counting from `1`.
+    pub fn dummy(n: u64) -> UserId {
+        debug_assert!(n > 0, "Dummy users are counted from 1");
+        UserId(-(n as i64))
+    }
+
+    /// Determine if the ID belongs to a dummy user.
+    pub fn is_dummy(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Determine if the ID belongs to a real user.
+    pub fn is_real(&self) -> bool {
+        !self.is_dummy()
+    }
+}
+
+impl From<i64> for UserId {
+    fn from(id: i64) -> UserId {
+        UserId(id)
+    }
+}
+
+impl From<UserId> for i64 {
+    fn from(id: UserId) -> i64 {
+        id.0
+    }
+}
+
+impl FromStr for UserId {
+    type Err = ParseIntError;
+
+    fn from_str(id: &str) -> Result<UserId, ParseIntError> {
+        id.parse::<i64>().map(UserId)
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, formatter)
+    }
+}
+
+impl Serialize for UserId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for UserId {
+    fn deserialize<D>(deserializer: D) -> Result<UserId, D::Error>
+        where D: Deserializer<'de>
+    {
+        i64::deserialize(deserializer).map(UserId)
+    }
+}
+
+unsafe_abomonate!(UserId);
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use super::*;
+
+    #[test]
+    fn dummy() {
+        assert_eq!(UserId::dummy(1), UserId::from(-1));
+        assert_eq!(UserId::dummy(42), UserId::from(-42));
+    }
+
+    #[test]
+    fn is_dummy() {
+        assert!(UserId::dummy(1).is_dummy());
+        assert!(UserId::from(-7).is_dummy());
+        assert!(!UserId::from(0).is_dummy());
+        assert!(!UserId::from(42).is_dummy());
+    }
+
+    #[test]
+    fn is_real() {
+        assert!(UserId::from(0).is_real());
+        assert!(UserId::from(42).is_real());
+        assert!(!UserId::dummy(1).is_real());
+        assert!(!UserId::from(-7).is_real());
+    }
+
+    #[test]
+    fn from() {
+        assert_eq!(i64::from(UserId::from(42)), 42);
+        assert_eq!(i64::from(UserId::dummy(3)), -3);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("42".parse::<UserId>(), Ok(UserId::from(42)));
+        assert_eq!("-3".parse::<UserId>(), Ok(UserId::dummy(3)));
+        assert!("U42".parse::<UserId>().is_err());
+    }
+
+    #[test]
+    fn fmt_display() {
+        assert_eq!(format!("{}", UserId::from(42)), String::from("42"));
+        assert_eq!(format!("{}", UserId::dummy(3)), String::from("-3"));
+        assert_eq!(format!("{:>4}", UserId::from(42)), String::from("  42"));
+    }
+
+    #[test]
+    fn serde() {
+        assert_eq!(serde_json::to_string(&UserId::from(42)).expect("Could not serialize the ID"), "42");
+        assert_eq!(serde_json::to_string(&UserId::dummy(3)).expect("Could not serialize the ID"), "-3");
+        assert_eq!(serde_json::from_str::<UserId>("-3").expect("Could not deserialize the ID"), UserId::dummy(3));
+    }
+
+    #[test]
+    fn sort() {
+        let mut ids: Vec<UserId> = vec![UserId::from(2), UserId::dummy(1), UserId::from(0), UserId::dummy(2)];
+        ids.sort();
+        assert_eq!(ids, vec![UserId::dummy(2), UserId::dummy(1), UserId::from(0), UserId::from(2)]);
+    }
+}
@@ -11,11 +11,17 @@ extern crate gag;
 #[cfg(unix)]
 #[macro_use]
 extern crate lazy_static;
+extern crate serde_json;
+extern crate sha2;
 
 #[cfg(unix)]
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread;
 
 use find_folder::Search;
 #[cfg(unix)]
@@ -24,8 +30,14 @@ use gag::BufferRedirect;
 use crgp_lib::Configuration;
 use crgp_lib::Result;
 use crgp_lib::Statistics;
+use crgp_lib::TopCascade;
+use crgp_lib::UserId;
 use crgp_lib::configuration::Algorithm;
+use crgp_lib::configuration::DuplicateUserPolicy;
 use crgp_lib::configuration::InputSource;
+use crgp_lib::configuration::OutputFormat;
+use crgp_lib::configuration::OutputTarget;
+use crgp_lib::configuration::TimeUnit;
 
 #[cfg(unix)]
 lazy_static! {
@@ -125,13 +137,66 @@ fn algorithm_execution_gale_2_workers() {
     }
 }
 
+#[test]
+fn algorithm_execution_gale_2_workers_buffer_output() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .buffer_output(true)
+        .workers(2);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+
+        // The output must consist of complete lines only, each of which is a single influence edge.
+        assert!(output.ends_with('\n'), "The output ends with a partial line");
+        let influences: Vec<&str> = output.lines().collect();
+        for influence in &influences {
+            let fields: Vec<&str> = influence.split(';').collect();
+            assert_eq!(fields.len(), 6, "Partial or interleaved influence: {}", influence);
+            assert!(fields.iter().all(|field| field.parse::<i64>().is_ok()),
+                    "Partial or interleaved influence: {}", influence);
+        }
+
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ];
+        for expected_line in &expected_lines {
+            assert!(influences.contains(expected_line), "Missing influence: {}", expected_line);
+        }
+        assert_eq!(influences.len(), 7);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
 #[test]
 fn algorithm_execution_gale_with_selected_users() {
     let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
 
     let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
     let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
-    let selected_users: PathBuf = data_path.join("retweeting_users.txt");
+    let selected_users = InputSource::new(data_path.join("retweeting_users.txt").to_str().unwrap());
 
     let configuration = Configuration::default(retweet_dataset, friendship_dataset)
         .batch_size(1)
@@ -226,7 +291,7 @@ fn algorithm_execution_gale_with_selected_users_and_dummy_friends() {
 
     let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
     let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
-    let selected_users: PathBuf = data_path.join("retweeting_users.txt");
+    let selected_users = InputSource::new(data_path.join("retweeting_users.txt").to_str().unwrap());
 
     let configuration = Configuration::default(retweet_dataset, friendship_dataset)
         .batch_size(1)
@@ -370,7 +435,7 @@ fn algorithm_execution_leaf_with_selected_users() {
 
     let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
     let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
-    let selected_users: PathBuf = data_path.join("retweeting_users.txt");
+    let selected_users = InputSource::new(data_path.join("retweeting_users.txt").to_str().unwrap());
 
     let configuration = Configuration::default(retweet_dataset, friendship_dataset)
         .algorithm(Algorithm::LEAF)
@@ -467,7 +532,7 @@ fn algorithm_execution_leaf_with_selected_users_and_dummy_friends() {
 
     let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
     let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
-    let selected_users: PathBuf = data_path.join("retweeting_users.txt");
+    let selected_users = InputSource::new(data_path.join("retweeting_users.txt").to_str().unwrap());
 
     let configuration = Configuration::default(retweet_dataset, friendship_dataset)
         .algorithm(Algorithm::LEAF)
@@ -510,3 +575,2387 @@ fn algorithm_execution_leaf_with_selected_users_and_dummy_friends() {
         assert!(result.is_ok());
     }
 }
+
+#[test]
+fn algorithm_execution_gale_graph_owner_workers() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .graph_owner_workers(vec![1])
+        .workers(2);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ];
+        for influence in &influences {
+            assert!(expected_lines.contains(influence), "Unexpected influence: {}", influence);
+        }
+        for expected_line in &expected_lines {
+            assert!(influences.contains(expected_line), "Missing influence: {}", expected_line);
+        }
+        assert_eq!(influences.len(), 7);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_leaf_graph_owner_workers() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .algorithm(Algorithm::LEAF)
+        .batch_size(1)
+        .graph_owner_workers(vec![1])
+        .workers(2);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ];
+        for influence in &influences {
+            assert!(expected_lines.contains(influence), "Unexpected influence: {}", influence);
+        }
+        for expected_line in &expected_lines {
+            assert!(influences.contains(expected_line), "Missing influence: {}", expected_line);
+        }
+        assert_eq!(influences.len(), 7);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_pace_matches_gale() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let gale = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+        .batch_size(1)
+        .workers(2);
+    let pace = Configuration::default(retweet_dataset, friendship_dataset)
+        .algorithm(Algorithm::PACE)
+        .batch_size(1)
+        .workers(2);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut outputs: Vec<Vec<String>> = Vec::new();
+        for configuration in vec![gale, pace] {
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            let statistics: Statistics = result.expect("The computation failed");
+            assert_eq!(statistics.number_of_retweets, 6);
+
+            let mut influences: Vec<String> = output.lines()
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            influences.sort();
+            outputs.push(influences);
+        }
+
+        assert_eq!(outputs[0].len(), 7);
+        assert_eq!(outputs[1], outputs[0]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(pace);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_distinct_pairs() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_format(OutputFormat::DistinctPairs);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let expected_lines: Vec<&str> = vec![
+            "0;1",
+            "0;2",
+            "1;0",
+            "2;1",
+            "2;3",
+        ];
+        for influence in &influences {
+            assert!(expected_lines.contains(influence), "Unexpected influence: {}", influence);
+        }
+        for expected_line in &expected_lines {
+            assert!(influences.contains(expected_line), "Missing influence: {}", expected_line);
+        }
+        assert_eq!(influences.len(), 5);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_matrix() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph"));
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json"));
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_format(OutputFormat::Matrix);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let lines: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let expected_lines: Vec<&str> = vec![
+            "# Cascade 1",
+            ";0;1;2;3",
+            "0;0;1;1;0",
+            "1;0;0;0;0",
+            "2;0;1;0;1",
+            "3;0;0;0;0",
+            "# Cascade 2",
+            ";0;1;2;3",
+            "0;0;0;1;0",
+            "1;1;0;0;0",
+            "2;0;0;0;1",
+            "3;0;0;0;0",
+        ];
+        assert_eq!(lines, expected_lines);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_duplicate_users() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph_duplicates").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .duplicate_users(DuplicateUserPolicy::KeepFirst)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_duplicate_users, 1);
+    assert_eq!(statistics.number_of_real_friendships, 3);
+}
+
+#[test]
+fn algorithm_execution_zero_batch_size() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    for &algorithm in &[Algorithm::GALE, Algorithm::LEAF] {
+        let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+        let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+        // An invalid batch size is replaced instead of panicking.
+        let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+            .algorithm(algorithm)
+            .batch_size(0)
+            .output_target(OutputTarget::None);
+
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.configuration.batch_size, 1);
+        assert_eq!(statistics.number_of_retweets, 6);
+    }
+}
+
+#[test]
+fn algorithm_execution_report_top_cascades() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_cascade_sizes.json").to_str().unwrap());
+
+    // The cascades 10, 20, 30, and 40 have 4, 1, 3, and 2 Retweets, respectively.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .report_top_cascades(2)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.top_cascades, vec![
+        TopCascade { cascade_id: 10, root_user: UserId::from(0), retweets: 4 },
+        TopCascade { cascade_id: 30, root_user: UserId::from(2), retweets: 3 },
+    ]);
+}
+
+#[test]
+fn algorithm_execution_gale_friend_list_callback() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    // The social graph is not loaded from this path, but generated by the callback.
+    let friendship_dataset = InputSource::new(data_path.join("missing_social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The callback generates the same friendships as given in the social graph data set. User 5 does not exist.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .friend_list_callback((0..6).map(UserId::from).collect(), |user| {
+            let friends: Option<Vec<i64>> = match i64::from(user) {
+                0 => Some(vec![1, 2]),
+                1 => Some(vec![0, 2, 3]),
+                2 => Some(vec![0]),
+                3 | 4 => Some(vec![2]),
+                _ => None
+            };
+            friends.map(|friends| friends.into_iter().map(UserId::from).collect())
+        });
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        let statistics: Statistics = result.expect("The computation failed");
+        assert_eq!(statistics.number_of_real_friendships, 8);
+
+        let mut influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_max_dummy_ratio() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // User 2 claims to have 42 friends, but only one is given. Thus, most friendships are with dummy users.
+    let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+        .max_dummy_ratio(Some(0.5))
+        .output_target(OutputTarget::None)
+        .pad_with_dummy_users(true)
+        .strict(true);
+    assert!(crgp_lib::run(configuration).is_err());
+
+    // Without strict checking, exceeding the ratio is only a warning.
+    let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+        .max_dummy_ratio(Some(0.5))
+        .output_target(OutputTarget::None)
+        .pad_with_dummy_users(true);
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert!(statistics.dummy_friendship_ratio() > 0.5);
+
+    // Below the maximum ratio, the computation succeeds even with strict checking.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .max_dummy_ratio(Some(0.9))
+        .output_target(OutputTarget::None)
+        .pad_with_dummy_users(true)
+        .strict(true);
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert!(statistics.dummy_friendship_ratio() <= 0.9);
+    assert_eq!(statistics.number_of_retweets, 6);
+}
+
+#[test]
+fn algorithm_execution_retweets_missing_cascade() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_missing_cascade.json").to_str().unwrap());
+
+    // Retweet 9 lacks the ID of its original Tweet. It is dropped instead of being assigned to any cascade.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .assert_sorted_retweets(true);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        let statistics: Statistics = result.expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 6);
+        assert_eq!(statistics.retweets_missing_cascade, 1);
+
+        let mut influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ]);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.retweets_missing_cascade, 1);
+    }
+}
+
+#[test]
+fn algorithm_execution_skipped_tweets() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The data set contains the two original Tweets of the cascades before their six Retweets. Only the Retweets are
+    // processed, regardless of whether they are loaded first or streamed.
+    for &stream_retweets in &[false, true] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .batch_size(1)
+            .stream_retweets(stream_retweets);
+
+        let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+            .expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 6);
+        assert_eq!(statistics.number_of_skipped_tweets, 2);
+        assert_eq!(statistics.retweets_missing_cascade, 0);
+        assert_eq!(edges.len(), 7);
+    }
+}
+
+#[test]
+fn algorithm_execution_minimal_stats() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .cascade_size_quantiles(true)
+        .output_target(OutputTarget::None)
+        .report_top_cascades(2)
+        .track_friendship_utilization(true);
+    let full: Statistics = crgp_lib::run(configuration.clone()).expect("The computation failed");
+    assert!(full.widest_fan_out > 0);
+    assert!(full.used_friendships > 0);
+    assert_eq!(full.top_cascades.len(), 2);
+
+    let minimal: Statistics = crgp_lib::run(configuration.minimal_stats(true)).expect("The computation failed");
+
+    // The derived statistics are left at their defaults.
+    assert!(minimal.top_cascades.is_empty());
+    assert_eq!(minimal.cascade_size_p50, 0);
+    assert_eq!(minimal.cascade_size_p90, 0);
+    assert_eq!(minimal.cascade_size_p99, 0);
+    assert_eq!(minimal.used_friendships, 0);
+    assert_eq!(minimal.unused_friendships, 0);
+    assert_eq!(minimal.friendship_utilization, 0.0);
+    assert_eq!(minimal.widest_fan_out, 0);
+    assert_eq!(minimal.widest_fan_out_influencer, UserId::from(0));
+    assert_eq!(minimal.widest_fan_out_cascade, 0);
+
+    // The raw counts are unaffected.
+    assert_eq!(minimal.number_of_retweets, full.number_of_retweets);
+    assert_eq!(minimal.number_of_friendships, full.number_of_friendships);
+    assert_eq!(minimal.self_edges_suppressed, full.self_edges_suppressed);
+    assert_eq!(minimal.friend_iterations, full.friend_iterations);
+    assert_eq!(minimal.activation_iterations, full.activation_iterations);
+    assert!(minimal.total_time > 0);
+    assert!(minimal.is_consistent());
+}
+
+#[test]
+fn algorithm_execution_gale_iterations() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Padded with dummy friends, user 0 has 3 friends and user 2 has 42 friends, thus the activations are iterated for
+    // their Retweets (3, 5, and 7), and the friends for all others (4, 6, and 8).
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .pad_with_dummy_users(true)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.friend_iterations, 3);
+    assert_eq!(statistics.mean_iterated_friends, 5.0 / 3.0);
+    assert_eq!(statistics.activation_iterations, 3);
+    assert_eq!(statistics.mean_iterated_activations, 7.0 / 3.0);
+}
+
+#[test]
+fn algorithm_execution_leaf_iterations() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // LEAF always iterates the friends: user 2 has 1 friend (Retweets 3 and 7), user 1 has 3 friends (4), user 0 has 2
+    // friends (5), and user 3 has 1 friend (6 and 8).
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .algorithm(Algorithm::LEAF)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.friend_iterations, 6);
+    assert_eq!(statistics.mean_iterated_friends, 9.0 / 6.0);
+    assert_eq!(statistics.activation_iterations, 0);
+    assert_eq!(statistics.mean_iterated_activations, 0.0);
+}
+
+#[test]
+fn algorithm_execution_gale_self_edges() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph_self_edges").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_self_edges.json").to_str().unwrap());
+
+    // User 0 follows themselves and retweets their own Tweet.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        let statistics: Statistics = result.expect("The computation failed");
+        assert_eq!(statistics.self_edges_suppressed, 1);
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(influences, vec!["1;3;1;0;2;-1"]);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.self_edges_suppressed, 1);
+    }
+}
+
+#[test]
+fn algorithm_execution_leaf_self_edges() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph_self_edges").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_self_edges.json").to_str().unwrap());
+
+    // User 0 follows themselves and retweets their own Tweet.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .algorithm(Algorithm::LEAF)
+        .batch_size(1);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        let statistics: Statistics = result.expect("The computation failed");
+        assert_eq!(statistics.self_edges_suppressed, 1);
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(influences, vec!["1;3;1;0;2;-1"]);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.self_edges_suppressed, 1);
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_sample_output() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .sample_output(Some(3))
+        .seed(42);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let run = |configuration: Configuration| -> String {
+            let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            assert!(result.is_ok());
+            output
+        };
+
+        let output: String = run(configuration.clone());
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ];
+        for influence in &influences {
+            assert!(expected_lines.contains(influence), "Unexpected influence: {}", influence);
+        }
+        assert_eq!(influences.len(), 3);
+
+        // The same seed draws the same sample.
+        assert_eq!(run(configuration), output);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_cancelled() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Cancel the computation from another thread once the first batch has been processed. The computation will stop
+    // before the second batch.
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_from_callback = cancel.clone();
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .cancellation_token(cancel)
+        .progress_callback(move |processed, _total| {
+            if processed == 1 {
+                let cancel_from_thread = cancel_from_callback.clone();
+                thread::spawn(move || cancel_from_thread.store(true, Ordering::SeqCst))
+                    .join()
+                    .expect("Could not cancel the computation");
+            }
+        })
+        .output_target(OutputTarget::None);
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+    let statistics: Statistics = result.unwrap();
+    assert!(statistics.cancelled);
+
+    // Only the first of the 6 Retweets has been processed.
+    assert_eq!(statistics.number_of_retweets, 1);
+}
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn algorithm_execution_gale_petgraph() {
+    use crgp_lib::configuration::PetGraphOutput;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output = PetGraphOutput::new();
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .output_target(OutputTarget::PetGraph(output.clone()));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    let graph = output.graph();
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.edge_count(), 7);
+}
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn algorithm_execution_gale_multiple_petgraphs() {
+    use crgp_lib::configuration::PetGraphOutput;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let first_output = PetGraphOutput::new();
+    let second_output = PetGraphOutput::new();
+
+    // The directory does not exist, but the graphs are collected nevertheless.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_target(OutputTarget::Multi(vec![
+            OutputTarget::PetGraph(first_output.clone()),
+            OutputTarget::Directory(data_path.join("does_not_exist")),
+            OutputTarget::PetGraph(second_output.clone()),
+        ]));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    for output in vec![first_output, second_output] {
+        let graph = output.graph();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 7);
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_channel() {
+    use std::sync::mpsc::channel;
+    use crgp_lib::InfluenceEdge;
+    use crgp_lib::configuration::ChannelOutput;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let (sender, receiver) = channel();
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .output_target(OutputTarget::Channel(ChannelOutput::new(sender)));
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+
+    // The channel is closed once the computation has finished, even though the statistics are still in scope.
+    let edges: Vec<InfluenceEdge<UserId>> = receiver.iter().collect();
+    assert_eq!(statistics.configuration.output_target, OutputTarget::None);
+    assert_eq!(statistics.number_of_retweets, 6);
+
+    // The original Tweets of the cascades 1 and 2 have been posted by the users 0 and 1, respectively.
+    for edge in &edges {
+        assert_eq!(edge.original_user, UserId::from(edge.cascade_id as i64 - 1));
+    }
+    let mut influences: Vec<String> = edges.iter()
+        .map(|edge| format!("{}", edge))
+        .collect();
+    influences.sort();
+    assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+}
+
+#[test]
+fn algorithm_execution_full_activation() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_grace.json").to_str().unwrap());
+
+    // User 1 retweets one second before their friend user 2, but user 2's Retweet is processed first. Only without
+    // comparing the activation times, an influence edge from user 2 to user 1 is produced.
+    let mut influences: Vec<Vec<String>> = Vec::new();
+    for algorithm in vec![Algorithm::GALE, Algorithm::FullActivation] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .algorithm(algorithm)
+            .batch_size(1)
+            .workers(2);
+
+        let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+            .expect("The computation failed");
+        assert_eq!(statistics.configuration.algorithm, algorithm);
+
+        let mut algorithm_influences: Vec<String> = edges.iter()
+            .map(|edge| format!("{}", edge))
+            .collect();
+        algorithm_influences.sort();
+        influences.push(algorithm_influences);
+    }
+    assert_eq!(influences[0], vec!["1;3;2;0;5;-1", "1;4;1;0;4;-1"]);
+    assert_eq!(influences[1], vec!["1;3;2;0;5;-1", "1;4;1;0;4;-1", "1;4;1;2;4;-1"]);
+    assert!(influences[1].len() > influences[0].len());
+    assert!(influences[0].iter().all(|influence| influences[1].contains(influence)));
+}
+
+#[test]
+fn algorithm_execution_deduplicate_edges() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_repeated.json").to_str().unwrap());
+
+    // User 2 retweets twice, each time influenced by user 0. Processed in separate batches, both Retweets produce an
+    // influence edge from user 0 to user 2, of which only the first one is written if the edges are deduplicated.
+    for &deduplicate_edges in &[false, true] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .batch_size(1)
+            .deduplicate_edges(deduplicate_edges)
+            .workers(2);
+
+        let (_statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+            .expect("The computation failed");
+        let mut influences: Vec<String> = edges.iter()
+            .map(|edge| format!("{}", edge))
+            .collect();
+        influences.sort();
+        if deduplicate_edges {
+            assert_eq!(influences, vec!["1;2;2;0;1;-1", "1;3;1;0;2;-1", "1;3;1;2;2;-1", "1;5;3;2;4;-1"]);
+        } else {
+            assert_eq!(influences, vec!["1;2;2;0;1;-1", "1;3;1;0;2;-1", "1;3;1;2;2;-1", "1;4;2;0;3;-1",
+                                        "1;5;3;2;4;-1"]);
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_include_quotes() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_quotes.json").to_str().unwrap());
+
+    // User 2 quotes the original Tweet of user 0 before user 1 retweets it. The Tweet of user 3 neither retweets nor
+    // quotes a Tweet and is always skipped.
+    for &include_quotes in &[false, true] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .batch_size(1)
+            .include_quotes(include_quotes)
+            .workers(2);
+
+        let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+            .expect("The computation failed");
+        let mut influences: Vec<String> = edges.iter()
+            .map(|edge| format!("{}", edge))
+            .collect();
+        influences.sort();
+        if include_quotes {
+            assert_eq!(statistics.number_of_retweets, 2);
+            assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1"]);
+        } else {
+            assert_eq!(statistics.number_of_retweets, 1);
+            assert_eq!(influences, vec!["1;4;1;0;2;-1"]);
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_dot() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let dot_path: PathBuf = env::temp_dir().join("crgp_gale.dot");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .output_target(OutputTarget::Dot(dot_path.clone()));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    let mut dot = String::new();
+    let _ = File::open(&dot_path).expect("Could not open the DOT file")
+        .read_to_string(&mut dot)
+        .expect("Could not read the DOT file");
+    let lines: Vec<&str> = dot.lines().collect();
+    assert_eq!(lines.first(), Some(&"digraph {"));
+    assert_eq!(lines.last(), Some(&"}"));
+
+    let mut edges: Vec<&str> = lines[1..lines.len() - 1].iter()
+        .map(|line| line.trim())
+        .collect();
+    edges.sort();
+    assert_eq!(edges, vec!["0 -> 1 [label=\"4\"];", "0 -> 2 [label=\"3\"];", "0 -> 2 [label=\"7\"];",
+                           "1 -> 0 [label=\"5\"];", "2 -> 1 [label=\"4\"];", "2 -> 3 [label=\"6\"];",
+                           "2 -> 3 [label=\"8\"];"]);
+
+    let _ = remove_file(&dot_path);
+}
+
+#[test]
+fn algorithm_execution_gale_json_lines() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use serde_json::Value;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_grace.json").to_str().unwrap());
+    let json_lines_path: PathBuf = env::temp_dir().join("crgp_gale.jsonl");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .output_target(OutputTarget::JsonLines(json_lines_path.clone()));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    let file = File::open(&json_lines_path).expect("Could not open the JSON lines file");
+    let mut edges: Vec<Value> = BufReader::new(file).lines()
+        .map(|line| serde_json::from_str(&line.expect("Could not read a line")).expect("Invalid JSON"))
+        .collect();
+    edges.sort_by_key(|edge| edge["retweet"].as_u64());
+    assert_eq!(edges.len(), 2);
+
+    assert_eq!(edges[0]["influencer"].as_i64(), Some(0));
+    assert_eq!(edges[0]["influencee"].as_i64(), Some(2));
+    assert_eq!(edges[0]["cascade"].as_u64(), Some(1));
+    assert_eq!(edges[0]["retweet"].as_u64(), Some(3));
+    assert_eq!(edges[0]["timestamp"].as_u64(), Some(5));
+
+    assert_eq!(edges[1]["influencer"].as_i64(), Some(0));
+    assert_eq!(edges[1]["influencee"].as_i64(), Some(1));
+    assert_eq!(edges[1]["cascade"].as_u64(), Some(1));
+    assert_eq!(edges[1]["retweet"].as_u64(), Some(4));
+    assert_eq!(edges[1]["timestamp"].as_u64(), Some(4));
+
+    let _ = remove_file(&json_lines_path);
+}
+
+#[test]
+fn algorithm_execution_gale_multiple_output_targets() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Writing to the directory fails since it does not exist, but the edges are still written to STDOUT.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_target(OutputTarget::Multi(vec![
+            OutputTarget::Directory(data_path.join("does_not_exist")),
+            OutputTarget::None,
+            OutputTarget::StdOut,
+        ]));
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let mut influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                    "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn algorithm_execution_gale_directory_and_stdout() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_directory_and_stdout");
+    let _ = remove_dir_all(&output_directory);
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .output_target(OutputTarget::Multi(vec![
+            OutputTarget::Directory(output_directory.clone()),
+            OutputTarget::StdOut,
+        ]));
+
+    let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+    let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    let mut output = String::new();
+    buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+    drop(buffer);
+    assert!(result.is_ok());
+
+    // Both targets have received the same edges in the same order.
+    let mut contents = String::new();
+    let _ = File::open(output_directory.join("cascs.csv")).expect("Could not open the result file")
+        .read_to_string(&mut contents)
+        .expect("Could not read the result file");
+    let written: Vec<&str> = contents.lines().collect();
+    let printed: Vec<&str> = output.lines()
+        .filter(|line| !line.is_empty())
+        .collect();
+    assert_eq!(written, printed);
+
+    let mut influences: Vec<&str> = written.clone();
+    influences.sort();
+    assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_repetitions() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph_self_edges").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_self_edges.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .repetitions(3);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        // Only the first repetition writes its results and counts the suppressed self-influences.
+        let statistics: Statistics = result.expect("The computation failed");
+        assert_eq!(statistics.retweet_processing_times.len(), 3);
+        assert_eq!(statistics.retweet_processing_times[0], statistics.time_to_process_retweets);
+        assert_eq!(statistics.self_edges_suppressed, 1);
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(influences, vec!["1;3;1;0;2;-1"]);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.retweet_processing_times.len(), 3);
+    }
+}
+
+#[test]
+fn algorithm_execution_bounded_live_cascades() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+    use std::io::Write;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+
+    // Many small cascades, each consisting of a single Retweet made long after the previous cascade's Retweet.
+    let retweets_path: PathBuf = env::temp_dir().join("crgp_bounded_live_cascades.json");
+    {
+        let mut file = File::create(&retweets_path).expect("Could not create the Retweet file");
+        for cascade in 0..100u64 {
+            let created_at: u64 = cascade * 100;
+            writeln!(file, "{{\"created_at\":{retweet_time},\"text\":\"RT @U0 Test\",\"id\":{retweet},\
+                            \"retweeted_status\":{{\"created_at\":{time},\"text\":\"Test\",\"id\":{cascade},\
+                            \"user\":{{\"id\":0,\"screen_name\":\"U0\"}},\"retweet_count\":1}},\
+                            \"user\":{{\"id\":1,\"screen_name\":\"U1\"}},\"retweet_count\":1}}",
+                     retweet_time = created_at + 1, retweet = 1_000 + cascade, time = created_at, cascade = cascade)
+                .expect("Could not write the Retweet file");
+        }
+    }
+
+    let retweet_dataset = InputSource::new(retweets_path.to_str().unwrap());
+    for &algorithm in &[Algorithm::GALE, Algorithm::LEAF] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .algorithm(algorithm)
+            .batch_size(1)
+            .output_target(OutputTarget::None);
+
+        // Without a timeout, all cascades are kept until the end.
+        let statistics: Statistics = crgp_lib::run(configuration.clone()).expect("The computation failed");
+        assert_eq!(statistics.peak_live_cascades, 100, "{:?}", algorithm);
+        assert_eq!(statistics.number_of_influences, 100, "{:?}", algorithm);
+
+        // With a timeout, the previous cascade is complete once the next one starts.
+        let statistics: Statistics = crgp_lib::run(configuration.cascade_timeout(Some(10)))
+            .expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 100, "{:?}", algorithm);
+        assert!(statistics.peak_live_cascades <= 2, "{:?}", algorithm);
+        assert_eq!(statistics.number_of_influences, 100, "{:?}", algorithm);
+    }
+
+    let _ = remove_file(&retweets_path);
+}
+
+#[test]
+fn algorithm_execution_gale_output_root_users() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let root_users = InputSource::new(data_path.join("root_users.txt").to_str().unwrap());
+
+    // Only the cascade of Tweet 2, posted by user 1, is written.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_root_users(Some(root_users));
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let mut influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec!["2;5;0;1;3;-1", "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_output_root_users_missing() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let root_users = InputSource::new(data_path.join("missing_root_users.txt").to_str().unwrap());
+
+    // Root users that cannot be loaded fail the computation instead of silently writing no edges.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .output_root_users(Some(root_users))
+        .output_target(OutputTarget::None);
+    assert!(crgp_lib::run(configuration).is_err());
+}
+
+#[test]
+fn algorithm_execution_seed_authors() {
+    use std::collections::HashSet;
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Tweet 1 has been posted by user 0, Tweet 2 by user 1. User 3 retweets Tweet 1 after their friend, user 2, did, so
+    // the Retweet of the Retweet still belongs to the cascade of user 0.
+    let seeded_influences: Vec<(i64, Vec<&str>)> = vec![
+        (0, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1"]),
+        (1, vec!["2;5;0;1;3;-1", "2;7;2;0;4;-1", "2;8;3;2;5;-1"]),
+    ];
+    for &algorithm in &[Algorithm::GALE, Algorithm::LEAF, Algorithm::PACE] {
+        for &(seed_author, ref expected_influences) in &seeded_influences {
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .algorithm(algorithm)
+                .batch_size(1)
+                .seed_authors(Some(vec![UserId::from(seed_author)].into_iter().collect()))
+                .workers(2);
+
+            let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+                .expect("The computation failed");
+            let mut influences: Vec<String> = edges.iter()
+                .map(|edge| format!("{}", edge))
+                .collect();
+            influences.sort();
+            assert_eq!(influences, *expected_influences, "{:?} seeded with user {}", algorithm, seed_author);
+
+            // Each cascade has three Retweets.
+            assert_eq!(statistics.number_of_retweets, 6);
+            assert_eq!(statistics.retweets_not_seeded, 3);
+        }
+    }
+
+    // An empty set of seed authors skips all Retweets.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .seed_authors(Some(HashSet::new()));
+    let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+        .expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+    assert_eq!(statistics.retweets_not_seeded, 6);
+    assert!(edges.is_empty());
+}
+
+#[test]
+fn algorithm_execution_gale_edge_timeseries() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let timeseries_path: PathBuf = env::temp_dir().join("crgp_edge_timeseries.csv");
+
+    // The six Retweets are processed in two batches of three Retweets each.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(3)
+        .edge_timeseries(Some(timeseries_path.clone()))
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+
+    let mut timeseries = String::new();
+    let _ = File::open(&timeseries_path).expect("Could not open the edge time series")
+        .read_to_string(&mut timeseries)
+        .expect("Could not read the edge time series");
+    let lines: Vec<&str> = timeseries.lines().collect();
+    assert_eq!(lines, vec!["batch_index;edges;cumulative_edges", "1;4;4", "2;3;7"]);
+
+    let _ = remove_file(&timeseries_path);
+}
+
+#[test]
+fn algorithm_execution_graph_load_statistics() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let statistics_path: PathBuf = env::temp_dir().join("crgp_graph_load_statistics_run.toml");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset.clone())
+        .graph_load_statistics(Some(statistics_path.clone()))
+        .output_target(OutputTarget::None)
+        .pad_with_dummy_users(true);
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+
+    let mut contents = String::new();
+    let _ = File::open(&statistics_path).expect("Could not open the graph load statistics")
+        .read_to_string(&mut contents)
+        .expect("Could not read the graph load statistics");
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("users = "));
+    assert_eq!(lines[1], format!("given_friendships = {}", statistics.number_of_real_friendships));
+    assert!(lines[2].starts_with("expected_friendships = "));
+    assert_eq!(lines[3], format!("dummy_friendships = {}", statistics.number_of_dummy_friendships));
+    assert_eq!(lines[4], format!("time_to_process_social_graph = {}", statistics.time_to_process_social_graph));
+    let _ = remove_file(&statistics_path);
+
+    // The statistics are written even if the Retweets cannot be loaded afterwards.
+    let missing_retweets = InputSource::new(data_path.join("missing_retweets.json").to_str().unwrap());
+    let configuration = Configuration::default(missing_retweets, friendship_dataset)
+        .graph_load_statistics(Some(statistics_path.clone()))
+        .output_target(OutputTarget::None);
+    assert!(crgp_lib::run(configuration).is_err());
+    assert!(statistics_path.exists());
+    let _ = remove_file(&statistics_path);
+}
+
+#[test]
+fn algorithm_execution_activation_log() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::remove_file;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_repeated.json").to_str().unwrap());
+    let log_path: PathBuf = env::temp_dir().join("crgp_activation_log.csv");
+
+    // User 2 retweets twice, but is only activated by their first Retweet. Even though each Retweet is seen by all
+    // workers in GALE, each activation is logged only once.
+    for algorithm in vec![Algorithm::GALE, Algorithm::LEAF] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .activation_log(Some(log_path.clone()))
+            .algorithm(algorithm)
+            .batch_size(1)
+            .output_target(OutputTarget::None)
+            .workers(2);
+
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 4);
+
+        let mut log = String::new();
+        let _ = File::open(&log_path).expect("Could not open the activation log")
+            .read_to_string(&mut log)
+            .expect("Could not read the activation log");
+        let mut lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.remove(0), "cascade_id;user_id;activation_time");
+        lines.sort();
+        assert_eq!(lines, vec!["1;1;2", "1;2;1", "1;3;4"]);
+
+        let _ = remove_file(&log_path);
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_cascades_time_ordered() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The Retweets within each cascade are strictly ordered by time.
+    let default_configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1);
+    let ordered_configuration = default_configuration.clone()
+        .cascades_time_ordered(true);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut outputs: Vec<Vec<String>> = Vec::new();
+        for configuration in vec![default_configuration, ordered_configuration] {
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            assert!(result.is_ok());
+            let mut influences: Vec<String> = output.split('\n')
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            influences.sort();
+            outputs.push(influences);
+        }
+
+        assert_eq!(outputs[0].len(), 7);
+        assert_eq!(outputs[0], outputs[1]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(ordered_configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_cascades_time_ordered_same_second() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_same_second.json").to_str().unwrap());
+
+    // Users 2 and 1 retweet within the same second. User 1's friend 2 has not been activated before user 1's Retweet,
+    // thus, there is no influence edge from user 2 to user 1, regardless of the guaranteed ordering.
+    for cascades_time_ordered in vec![false, true] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .batch_size(1)
+            .cascades_time_ordered(cascades_time_ordered);
+
+        let (_statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+            .expect("The computation failed");
+        let mut influences: Vec<String> = edges.iter()
+            .map(|edge| format!("{}", edge))
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;1;-1"]);
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_activation_set_initial_capacity() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Reserving memory for the activations must not change the result.
+    let default_configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1);
+    let reserved_configuration = default_configuration.clone()
+        .activation_set_initial_capacity(1000);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut outputs: Vec<Vec<String>> = Vec::new();
+        for configuration in vec![default_configuration, reserved_configuration] {
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            assert!(result.is_ok());
+            let mut influences: Vec<String> = output.split('\n')
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            influences.sort();
+            outputs.push(influences);
+        }
+
+        assert_eq!(outputs[0].len(), 7);
+        assert_eq!(outputs[0], outputs[1]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(reserved_configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_stream_retweets() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Reading the Retweets while processing them must not change the result.
+    let loaded_configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(2);
+    let streamed_configuration = loaded_configuration.clone()
+        .stream_retweets(true);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut outputs: Vec<Vec<String>> = Vec::new();
+        for configuration in vec![loaded_configuration, streamed_configuration] {
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            let statistics: Statistics = result.expect("The computation failed");
+            assert_eq!(statistics.number_of_retweets, 6);
+            let mut influences: Vec<String> = output.split('\n')
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            influences.sort();
+            outputs.push(influences);
+        }
+
+        assert_eq!(outputs[0].len(), 7);
+        assert_eq!(outputs[0], outputs[1]);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(streamed_configuration).expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 6);
+    }
+}
+
+#[test]
+fn algorithm_execution_stream_retweets_report_top_cascades() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_cascade_sizes.json").to_str().unwrap());
+
+    // The cascades are counted while the Retweets are read, since the streamed Retweets are not kept.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .report_top_cascades(2)
+        .stream_retweets(true)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 10);
+    assert_eq!(statistics.top_cascades, vec![
+        TopCascade { cascade_id: 10, root_user: UserId::from(0), retweets: 4 },
+        TopCascade { cascade_id: 30, root_user: UserId::from(2), retweets: 3 },
+    ]);
+}
+
+#[test]
+fn algorithm_execution_stream_retweets_without_top_cascades() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_cascade_sizes.json").to_str().unwrap());
+
+    // By default, no top cascades are reported, thus the streamed Retweets are not counted at all.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .stream_retweets(true)
+        .output_target(OutputTarget::None);
+    assert_eq!(configuration.report_top_cascades, 0);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 10);
+    assert!(statistics.top_cascades.is_empty());
+}
+
+#[test]
+fn algorithm_execution_max_retweets() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_max_retweets.json").to_str().unwrap());
+
+    // Only the first five of the twenty Retweets are processed, both if they are loaded first and if they are streamed.
+    for stream_retweets in vec![false, true] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .batch_size(2)
+            .max_retweets(Some(5))
+            .stream_retweets(stream_retweets)
+            .output_target(OutputTarget::None);
+
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.number_of_retweets, 5);
+    }
+
+    // A limit above the number of Retweets has no effect.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .max_retweets(Some(30))
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 20);
+}
+
+#[test]
+fn algorithm_execution_batch_latencies() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The six Retweets are processed in a complete batch of four Retweets and an incomplete one of two Retweets.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(4)
+        .workers(2)
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+    assert_eq!(statistics.batch_latencies.len(), 2);
+    assert!(statistics.batch_latencies.iter().sum::<u64>() <= statistics.time_to_process_retweets);
+}
+
+#[test]
+fn algorithm_execution_sync_timeout() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // All batches are processed well within the timeout.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(2)
+        .workers(2)
+        .sync_timeout(Some(60))
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+    assert_eq!(statistics.batch_latencies.len(), 3);
+}
+
+#[test]
+fn algorithm_execution_dry_run() {
+    use std::env;
+    use std::fs::create_dir_all;
+    use std::fs::read_dir;
+    use std::fs::remove_dir_all;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_dry_run");
+    let _ = remove_dir_all(&output_directory);
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    // Only the first batch of four Retweets is loaded, and the entire social graph fits into the sample.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset.clone())
+        .batch_size(4)
+        .dry_run(true)
+        .output_target(OutputTarget::Directory(output_directory.clone()));
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The dry run failed");
+    assert!(statistics.was_dry_run);
+    assert_eq!(statistics.number_of_retweets, 4);
+    assert_eq!(statistics.number_of_friendships, 8);
+    assert_eq!(statistics.number_of_real_friendships, 8);
+    assert_eq!(statistics.time_to_setup, 0);
+    assert_eq!(statistics.time_to_process_social_graph, 0);
+    assert_eq!(statistics.time_to_load_retweets, 0);
+    assert_eq!(statistics.time_to_process_retweets, 0);
+    assert_eq!(statistics.total_time, 0);
+    assert_eq!(statistics.retweet_processing_rate, 0);
+
+    // No output has been written.
+    assert_eq!(read_dir(&output_directory).expect("Could not read the output directory").count(), 0);
+    let _ = remove_dir_all(&output_directory);
+
+    // Invalid inputs are detected.
+    let retweet_dataset = InputSource::new(data_path.join("does_not_exist.json").to_str().unwrap());
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .dry_run(true)
+        .output_target(OutputTarget::None);
+    assert!(crgp_lib::run(configuration).is_err());
+}
+
+#[test]
+fn algorithm_execution_gale_track_friendship_utilization() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_track_friendship_utilization");
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_target(OutputTarget::Directory(output_directory.clone()))
+        .track_friendship_utilization(true);
+
+    // The seven influence edges use five distinct friendships: user 2 is influenced by user 0 in both cascades.
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.used_friendships, 5);
+    assert_eq!(statistics.unused_friendships, statistics.number_of_real_friendships - 5);
+    assert!(statistics.friendship_utilization > 0.0 && statistics.friendship_utilization < 1.0);
+
+    let mut unused = String::new();
+    let _ = File::open(output_directory.join("unused_friendships.csv")).expect("Could not open the unused friendships")
+        .read_to_string(&mut unused)
+        .expect("Could not read the unused friendships");
+    let unused: Vec<&str> = unused.lines().collect();
+    assert_eq!(unused.len() as u64, statistics.unused_friendships);
+    assert!(unused.contains(&"0;2"));
+    assert!(unused.contains(&"1;3"));
+    assert!(!unused.contains(&"2;0"));
+    assert!(!unused.contains(&"1;0"));
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_late_error_keeps_written_edges() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_late_error");
+    let _ = remove_dir_all(&output_directory);
+
+    // A directory in place of the unused friendships file fails the computation after all Retweets are processed.
+    create_dir_all(output_directory.join("unused_friendships.csv")).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_target(OutputTarget::Directory(output_directory.clone()))
+        .track_friendship_utilization(true);
+    assert!(crgp_lib::run(configuration).is_err());
+
+    // All influence edges written before the error have been delivered.
+    let mut output = String::new();
+    let _ = File::open(output_directory.join("cascs.csv")).expect("Could not open the result file")
+        .read_to_string(&mut output)
+        .expect("Could not read the result file");
+    let mut influences: Vec<&str> = output.lines().collect();
+    influences.sort();
+    assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_abomonated_edges() {
+    use std::env;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_abomonated_edges");
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_format(OutputFormat::Abomonation)
+        .output_target(OutputTarget::Directory(output_directory.clone()));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    let mut influences: Vec<String> = crgp_lib::read_edges(&output_directory.join("cascs.abom"))
+        .expect("Could not read the abomonated edges")
+        .map(|influence| format!("{}", influence))
+        .collect();
+    influences.sort();
+    assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+    assert!(!output_directory.join("cascs.csv").exists());
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_neo4j() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+    use std::io::Read;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_neo4j");
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_format(OutputFormat::Neo4j)
+        .output_target(OutputTarget::Directory(output_directory.clone()));
+
+    let result: Result<Statistics> = crgp_lib::run(configuration);
+    assert!(result.is_ok());
+
+    // The relationships: one per influence edge, from the influencer to the influencee.
+    let mut relationships = String::new();
+    let _ = File::open(output_directory.join("cascs.csv")).expect("Could not open the relationships")
+        .read_to_string(&mut relationships)
+        .expect("Could not read the relationships");
+    let relationships: Vec<&str> = relationships.lines().collect();
+    assert_eq!(relationships.len(), 8);
+    assert_eq!(relationships[0], ":START_ID,:END_ID,cascade:long,:TYPE");
+    assert!(relationships.contains(&"0,2,1,INFLUENCED"));
+
+    // The nodes: each user of the relationships exactly once.
+    let mut nodes = String::new();
+    let _ = File::open(output_directory.join("users.csv")).expect("Could not open the nodes")
+        .read_to_string(&mut nodes)
+        .expect("Could not read the nodes");
+    let nodes: Vec<&str> = nodes.lines().collect();
+    assert_eq!(nodes, vec!["userId:ID,:LABEL", "0,User", "1,User", "2,User", "3,User"]);
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_cascade_summary() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_format(OutputFormat::CascadeSummary);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+
+        // In cascade 1, user 3 is only influenced by user 2, who has been influenced by the root user 0. In cascade 2,
+        // the influences form a chain of length 3 starting at the root user 1.
+        let mut summaries: Vec<&str> = output.lines()
+            .filter(|line| !line.is_empty())
+            .collect();
+        summaries.sort();
+        assert_eq!(summaries, vec!["1;0;3;4;2;0", "2;1;3;3;3;0"]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_hash_output() {
+    use std::env;
+    use std::fs::File;
+    use std::fs::create_dir_all;
+    use std::fs::remove_dir_all;
+    use std::io::Read;
+    use sha2::Digest;
+    use sha2::Sha256;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let output_directory: PathBuf = env::temp_dir().join("crgp_hash_output");
+    create_dir_all(&output_directory).expect("Could not create the output directory");
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .hash_output(true)
+        .output_target(OutputTarget::Directory(output_directory.clone()));
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+
+    // Hash the result file independently.
+    let mut contents: Vec<u8> = Vec::new();
+    let _ = File::open(output_directory.join("cascs.csv")).expect("Could not open the result file")
+        .read_to_end(&mut contents)
+        .expect("Could not read the result file");
+    let mut hasher = Sha256::default();
+    hasher.input(&contents);
+    let expected_hash: String = hasher.result().iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<String>>()
+        .concat();
+
+    assert_eq!(contents.len(), 7 * "1;3;2;0;1;-1\n".len());
+    assert_eq!(statistics.output_sha256, expected_hash);
+    assert_eq!(statistics.output_bytes, contents.len() as u64);
+
+    let mut sidecar = String::new();
+    let _ = File::open(output_directory.join("cascs.csv.sha256")).expect("Could not open the hash file")
+        .read_to_string(&mut sidecar)
+        .expect("Could not read the hash file");
+    assert_eq!(sidecar, format!("{hash}  cascs.csv\n", hash = expected_hash));
+
+    let _ = remove_dir_all(&output_directory);
+}
+
+#[test]
+fn algorithm_execution_gale_widest_fan_out() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .output_target(OutputTarget::None);
+
+    // Users 0 and 2 both influence two users in cascade 1; the tie is broken in favor of user 0.
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.widest_fan_out, 2);
+    assert_eq!(statistics.widest_fan_out_influencer, UserId::from(0));
+    assert_eq!(statistics.widest_fan_out_cascade, 1);
+}
+
+#[test]
+fn algorithm_execution_gale_cascade_size_quantiles() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .cascade_size_quantiles(true)
+        .output_target(OutputTarget::None);
+
+    // Cascade 1 has four influence edges, cascade 2 three.
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.cascade_size_p50, 3);
+    assert_eq!(statistics.cascade_size_p90, 4);
+    assert_eq!(statistics.cascade_size_p99, 4);
+}
+
+#[test]
+fn algorithm_execution_emit_time_delta() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The delta is the difference between the time of the influence and the time of the influencer's Retweet in the
+    // cascade, or -1 for the poster of the original Tweet.
+    let mut expected_lines: Vec<&str> = vec![
+        "1;3;2;0;1;-1;-1",
+        "1;4;1;0;2;-1;-1",
+        "1;4;1;2;2;-1;1000",
+        "1;6;3;2;3;-1;2000",
+        "2;5;0;1;3;-1;-1",
+        "2;7;2;0;4;-1;1000",
+        "2;8;3;2;5;-1;1000",
+    ];
+    expected_lines.sort();
+
+    for algorithm in vec![Algorithm::GALE, Algorithm::LEAF] {
+        let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+            .algorithm(algorithm)
+            .batch_size(1)
+            .emit_time_delta(Some(TimeUnit::Milliseconds));
+
+        // Capturing STDOUT currently only works on Unix systems.
+        if cfg!(unix) {
+            let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+            let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            let mut output = String::new();
+            buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+            drop(buffer);
+
+            assert!(result.is_ok());
+            let mut influences: Vec<&str> = output.split('\n')
+                .filter(|line| !line.is_empty())
+                .collect();
+            influences.sort();
+            assert_eq!(influences, expected_lines);
+        }
+        else {
+            let result: Result<Statistics> = crgp_lib::run(configuration);
+            assert!(result.is_ok());
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_activation_grace_seconds() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_grace.json").to_str().unwrap());
+
+    // User 1 retweets one second before their friend user 2, but user 2's Retweet is processed first. The influence
+    // from user 2 to user 1 is only found if the grace window covers the second between the two Retweets.
+    let without_grace: Vec<&str> = vec![
+        "1;3;2;0;5;-1",
+        "1;4;1;0;4;-1",
+    ];
+    let with_grace: Vec<&str> = vec![
+        "1;3;2;0;5;-1",
+        "1;4;1;0;4;-1",
+        "1;4;1;2;4;-1",
+    ];
+
+    for algorithm in vec![Algorithm::GALE, Algorithm::LEAF] {
+        for &(grace, expected_lines) in &[(0, &without_grace), (1, &without_grace), (2, &with_grace)] {
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .activation_grace_seconds(grace)
+                .algorithm(algorithm)
+                .batch_size(1);
+
+            // Capturing STDOUT currently only works on Unix systems.
+            if cfg!(unix) {
+                let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+                let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+                let result: Result<Statistics> = crgp_lib::run(configuration);
+                let mut output = String::new();
+                buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+                drop(buffer);
+
+                assert!(result.is_ok());
+                let mut influences: Vec<&str> = output.split('\n')
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                influences.sort();
+                assert_eq!(&influences, expected_lines);
+            }
+            else {
+                let result: Result<Statistics> = crgp_lib::run(configuration);
+                assert!(result.is_ok());
+            }
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_timestamp_join_file() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let timestamps: PathBuf = data_path.join("retweet_timestamps.csv");
+
+    // Only Retweet 3 (by user 2) has a timestamp in the file, moving it after the Retweets 4 and 6 of its cascade.
+    // Thus, user 2 can no longer have influenced users 1 and 3 in cascade 1. All other Retweets keep their timestamps.
+    let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+        .batch_size(1)
+        .timestamp_join_file(Some(timestamps.clone()));
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let mut influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        influences.sort();
+        assert_eq!(influences, vec!["1;3;2;0;5;-1", "1;4;1;0;2;-1", "2;5;0;1;3;-1", "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+
+    // The joined timestamps are no longer sorted.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .assert_sorted_retweets(true)
+        .output_target(OutputTarget::None)
+        .timestamp_join_file(Some(timestamps));
+    assert!(crgp_lib::run(configuration).is_err());
+}
+
+#[test]
+fn algorithm_execution_gale_max_output_edges() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .cascade_timeout(Some(1))
+        .max_output_edges(Some(3))
+        .flush_on_cascade_complete(true);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        let statistics: Statistics = result.expect("The computation failed");
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        // With deterministic output, the first edges of the first cascade are written.
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+        ];
+        assert_eq!(influences, expected_lines);
+
+        // The remaining edges are counted.
+        assert_eq!(statistics.number_of_influences, 7);
+        assert_eq!(statistics.number_of_skipped_influences, 4);
+    }
+    else {
+        let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+        assert_eq!(statistics.number_of_influences, 7);
+        assert_eq!(statistics.number_of_skipped_influences, 4);
+    }
+}
+
+#[test]
+fn algorithm_execution_gale_flush_on_cascade_complete() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .cascade_timeout(Some(1))
+        .flush_on_cascade_complete(true);
+
+    // Capturing STDOUT currently only works on Unix systems.
+    if cfg!(unix) {
+        let _lock = STDOUT_MUTEX.lock().expect("Could not lock STDOUT");
+        let mut buffer = BufferRedirect::stdout().expect("Could not redirect STDOUT");
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        let mut output = String::new();
+        buffer.read_to_string(&mut output).expect("Could not read STDOUT buffer");
+        drop(buffer);
+
+        assert!(result.is_ok());
+        let influences: Vec<&str> = output.split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        // The edges are written per cascade, sorted by their time.
+        let expected_lines: Vec<&str> = vec![
+            "1;3;2;0;1;-1",
+            "1;4;1;0;2;-1",
+            "1;4;1;2;2;-1",
+            "1;6;3;2;3;-1",
+            "2;5;0;1;3;-1",
+            "2;7;2;0;4;-1",
+            "2;8;3;2;5;-1",
+        ];
+        assert_eq!(influences, expected_lines);
+    }
+    else {
+        let result: Result<Statistics> = crgp_lib::run(configuration);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn algorithm_execution_complete_cascades_batch_size_1() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Each Retweet is synced on its own. The completion of the cascades must not hold back any batch, otherwise the
+    // sync timeout fails the computation.
+    for &algorithm in &[Algorithm::GALE, Algorithm::LEAF, Algorithm::PACE] {
+        for &cascade_timeout in &[None, Some(1)] {
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .algorithm(algorithm)
+                .batch_size(1)
+                .cascade_timeout(cascade_timeout)
+                .output_target(OutputTarget::None)
+                .sync_timeout(Some(10));
+
+            let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+            assert_eq!(statistics.number_of_retweets, 6);
+            assert_eq!(statistics.number_of_influences, 7);
+
+            // The widest fan-out is only reported for completed cascades.
+            assert_eq!(statistics.widest_fan_out, 2, "{:?} with timeout {:?}", algorithm, cascade_timeout);
+            assert_eq!(statistics.widest_fan_out_cascade, 1, "{:?} with timeout {:?}", algorithm, cascade_timeout);
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_late_retweets() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets_late.json").to_str().unwrap());
+
+    // Cascade 1 times out once cascade 2 has been retweeted, thus, the last Retweet starts it anew without the
+    // activation of user 2, and is counted once, regardless of the number of workers.
+    let timeouts: Vec<(Option<u64>, u64, Vec<&str>)> = vec![
+        (None, 0, vec!["1;3;2;0;1;-1", "1;6;1;0;11;-1", "1;6;1;2;11;-1", "2;5;0;1;10;-1"]),
+        (Some(5), 1, vec!["1;3;2;0;1;-1", "1;6;1;0;11;-1", "2;5;0;1;10;-1"]),
+    ];
+    for &algorithm in &[Algorithm::GALE, Algorithm::PACE] {
+        for &(cascade_timeout, late_retweets, ref expected_influences) in &timeouts {
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .algorithm(algorithm)
+                .batch_size(1)
+                .cascade_timeout(cascade_timeout)
+                .workers(2);
+
+            let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+                .expect("The computation failed");
+            let mut influences: Vec<String> = edges.iter()
+                .map(|edge| format!("{}", edge))
+                .collect();
+            influences.sort();
+            assert_eq!(influences, *expected_influences, "{:?} with timeout {:?}", algorithm, cascade_timeout);
+            assert_eq!(statistics.late_retweets, late_retweets, "{:?} with timeout {:?}", algorithm,
+                       cascade_timeout);
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_progress_callback() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The six Retweets are processed in a complete batch of four and an incomplete batch of two.
+    let reports: Arc<Mutex<Vec<(u64, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+    let callback_reports = reports.clone();
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(4)
+        .progress_callback(move |processed, total| {
+            callback_reports.lock().expect("The reports have been poisoned").push((processed, total));
+        })
+        .output_target(OutputTarget::None);
+
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+
+    let reports = reports.lock().expect("The reports have been poisoned");
+    assert_eq!(*reports, vec![(4, 6), (6, 6)]);
+    assert_eq!(reports.last(), Some(&(6, 6)));
+}
+
+#[test]
+fn algorithm_execution_batch_sizes() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The six Retweets are processed in batches of a single Retweet, of a divisor of their number, and of a
+    // non-divisor, leaving an incomplete last batch. Each batch is reported once, and the edges do not depend on it.
+    let batches: Vec<(usize, Vec<(u64, u64)>)> = vec![
+        (1, vec![(1, 6), (2, 6), (3, 6), (4, 6), (5, 6), (6, 6)]),
+        (3, vec![(3, 6), (6, 6)]),
+        (4, vec![(4, 6), (6, 6)]),
+    ];
+    for &algorithm in &[Algorithm::GALE, Algorithm::PACE] {
+        let mut influences: Vec<Vec<String>> = Vec::new();
+        for &(batch_size, ref expected_reports) in &batches {
+            let reports: Arc<Mutex<Vec<(u64, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+            let callback_reports = reports.clone();
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .algorithm(algorithm)
+                .batch_size(batch_size)
+                .progress_callback(move |processed, total| {
+                    callback_reports.lock().expect("The reports have been poisoned").push((processed, total));
+                })
+                .workers(2);
+
+            let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration)
+                .expect("The computation failed");
+            assert_eq!(statistics.number_of_retweets, 6);
+            assert_eq!(*reports.lock().expect("The reports have been poisoned"), *expected_reports,
+                       "{:?} with batch size {}", algorithm, batch_size);
+
+            let mut edges: Vec<String> = edges.iter()
+                .map(|edge| format!("{}", edge))
+                .collect();
+            edges.sort();
+            influences.push(edges);
+        }
+
+        assert_eq!(influences[0], vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                       "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+        assert_eq!(influences[0], influences[1], "{:?} with batch size 3", algorithm);
+        assert_eq!(influences[0], influences[2], "{:?} with batch size 4", algorithm);
+    }
+}
+
+#[test]
+fn algorithm_execution_run_collect() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2);
+
+    let (statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) = crgp_lib::run_collect(configuration.clone())
+        .expect("The computation failed");
+    let mut influences: Vec<String> = edges.iter()
+        .map(|edge| format!("{}", edge))
+        .collect();
+    influences.sort();
+    assert_eq!(influences, vec!["1;3;2;0;1;-1", "1;4;1;0;2;-1", "1;4;1;2;2;-1", "1;6;3;2;3;-1", "2;5;0;1;3;-1",
+                                "2;7;2;0;4;-1", "2;8;3;2;5;-1"]);
+
+    // The statistics are the same as those of a computation without output.
+    let expected: Statistics = crgp_lib::run(configuration.clone().output_target(OutputTarget::None))
+        .expect("The computation failed");
+    assert_eq!(statistics.configuration, expected.configuration);
+    assert_eq!(statistics.number_of_friendships, 8);
+    assert_eq!(statistics.number_of_friendships, expected.number_of_friendships);
+    assert_eq!(statistics.number_of_retweets, 6);
+    assert_eq!(statistics.number_of_retweets, expected.number_of_retweets);
+    assert_eq!(statistics.number_of_influences, 7);
+    assert_eq!(statistics.number_of_influences, expected.number_of_influences);
+    assert_eq!(statistics.self_edges_suppressed, expected.self_edges_suppressed);
+    assert_eq!(statistics.friend_iterations, expected.friend_iterations);
+    assert_eq!(statistics.activation_iterations, expected.activation_iterations);
+    assert!(statistics.is_consistent());
+
+    // The edges of multiple processes cannot be collected.
+    match crgp_lib::run_collect(configuration.processes(2)) {
+        Ok(_) => panic!("Collected the edges of multiple processes"),
+        Err(error) => assert_eq!(format!("{}", error), "Cannot collect the influence edges from 2 processes")
+    }
+}
+
+#[test]
+fn algorithm_execution_sort_friends() {
+    use crgp_lib::InfluenceEdge;
+
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // Padding and expanding the friend lists yields unsorted friend lists including dummy users.
+    for &algorithm in &[Algorithm::GALE, Algorithm::PACE] {
+        for &friend_expansion_hops in &[0, 1] {
+            let configuration = Configuration::default(retweet_dataset.clone(), friendship_dataset.clone())
+                .algorithm(algorithm)
+                .batch_size(1)
+                .friend_expansion_hops(friend_expansion_hops)
+                .pad_with_dummy_users(true)
+                .workers(2);
+
+            let mut influences: Vec<Vec<String>> = Vec::new();
+            for &sort_friends in &[false, true] {
+                let (_statistics, edges): (Statistics, Vec<InfluenceEdge<UserId>>) =
+                    crgp_lib::run_collect(configuration.clone().sort_friends(sort_friends))
+                        .expect("The computation failed");
+                let mut edges: Vec<String> = edges.iter()
+                    .map(|edge| format!("{}", edge))
+                    .collect();
+                edges.sort();
+                influences.push(edges);
+            }
+
+            assert!(!influences[0].is_empty());
+            assert_eq!(influences[0], influences[1], "{:?} with {} hops", algorithm, friend_expansion_hops);
+        }
+    }
+}
+
+#[test]
+fn algorithm_execution_number_of_influences() {
+    let data_path: PathBuf = Search::ParentsThenKids(3, 3).for_folder("data").expect("Data folder not found.");
+
+    let friendship_dataset = InputSource::new(data_path.join("social_graph").to_str().unwrap());
+    let retweet_dataset = InputSource::new(data_path.join("retweets.json").to_str().unwrap());
+
+    // The two cascades consist of seven influence edges, which are counted once, regardless of the repetitions.
+    let configuration = Configuration::default(retweet_dataset, friendship_dataset)
+        .batch_size(1)
+        .workers(2)
+        .repetitions(2)
+        .output_target(OutputTarget::None);
+    let statistics: Statistics = crgp_lib::run(configuration).expect("The computation failed");
+    assert_eq!(statistics.number_of_retweets, 6);
+    assert_eq!(statistics.number_of_influences, 7);
+}
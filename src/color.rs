@@ -0,0 +1,156 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Color the log messages and the summary on terminals using ANSI escape codes.
+//!
+//! Colors are only used if the output is a terminal, unless they are explicitly requested. Following the
+//! [`NO_COLOR`](https://no-color.org/) convention, they are never used automatically if the environment variable
+//! `NO_COLOR` is set.
+
+use std::env;
+
+use atty;
+use atty::Stream;
+use flexi_logger::with_thread;
+use log::LogLevel;
+use log::LogRecord;
+
+/// The ANSI escape code for bold text.
+const BOLD: &'static str = "\x1b[1m";
+
+/// The ANSI escape code for red text.
+const RED: &'static str = "\x1b[31m";
+
+/// The ANSI escape code for yellow text.
+const YELLOW: &'static str = "\x1b[33m";
+
+/// The ANSI escape code resetting all attributes.
+const RESET: &'static str = "\x1b[0m";
+
+/// When to use colors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Use colors if the output is a terminal and `NO_COLOR` is not set.
+    Auto,
+
+    /// Always use colors.
+    Always,
+
+    /// Never use colors.
+    Never,
+}
+
+impl ColorChoice {
+    /// Get the choice from its command-line `name`. Unknown names fall back to `Auto`.
+    pub fn from_name(name: &str) -> ColorChoice {
+        match name {
+            "always" => ColorChoice::Always,
+            "never" => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+
+    /// Determine if colors are used for the given output `stream`.
+    pub fn is_enabled_for(&self, stream: Stream) -> bool {
+        match *self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => env::var_os("NO_COLOR").is_none() && atty::is(stream),
+        }
+    }
+}
+
+/// Apply colors to text, if enabled.
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    /// Whether colors are applied.
+    enabled: bool,
+}
+
+impl Palette {
+    /// Create a palette applying colors only if `enabled`.
+    pub fn new(enabled: bool) -> Palette {
+        Palette {
+            enabled: enabled,
+        }
+    }
+
+    /// Format `text` as an error, i.e. in red.
+    pub fn error(&self, text: &str) -> String {
+        self.paint(RED, text)
+    }
+
+    /// Format `text` as a warning, i.e. in yellow.
+    pub fn warning(&self, text: &str) -> String {
+        self.paint(YELLOW, text)
+    }
+
+    /// Format `text` as a key result, i.e. in bold.
+    pub fn bold(&self, text: &str) -> String {
+        self.paint(BOLD, text)
+    }
+
+    /// Wrap `text` into the escape `code` and a reset, if colors are enabled.
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("{code}{text}{reset}", code = code, text = text, reset = RESET)
+        } else {
+            String::from(text)
+        }
+    }
+}
+
+/// Get the log line format: `flexi_logger::with_thread`, with errors colored red and warnings colored yellow if
+/// `colored`.
+pub fn log_format(colored: bool) -> fn(&LogRecord) -> String {
+    if colored {
+        colored_with_thread
+    } else {
+        with_thread
+    }
+}
+
+/// A log line format like `flexi_logger::with_thread`, with errors colored red and warnings colored yellow.
+fn colored_with_thread(record: &LogRecord) -> String {
+    let line: String = with_thread(record);
+    let palette = Palette::new(true);
+    match record.level() {
+        LogLevel::Error => palette.error(&line),
+        LogLevel::Warn => palette.warning(&line),
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_choice_from_name() {
+        assert_eq!(ColorChoice::from_name("always"), ColorChoice::Always);
+        assert_eq!(ColorChoice::from_name("never"), ColorChoice::Never);
+        assert_eq!(ColorChoice::from_name("auto"), ColorChoice::Auto);
+    }
+
+    #[test]
+    fn color_choice_is_enabled_for() {
+        assert!(ColorChoice::Always.is_enabled_for(Stream::Stdout));
+        assert!(!ColorChoice::Never.is_enabled_for(Stream::Stdout));
+    }
+
+    #[test]
+    fn palette() {
+        let colored = Palette::new(true);
+        assert_eq!(colored.error("Error"), "\x1b[31mError\x1b[0m");
+        assert_eq!(colored.warning("Warning"), "\x1b[33mWarning\x1b[0m");
+        assert_eq!(colored.bold("42"), "\x1b[1m42\x1b[0m");
+
+        let plain = Palette::new(false);
+        assert_eq!(plain.error("Error"), "Error");
+        assert_eq!(plain.warning("Warning"), "Warning");
+        assert_eq!(plain.bold("42"), "42");
+    }
+}
@@ -25,21 +25,23 @@
                                            stutter, used_underscore_binding, use_debug, wrong_self_convention,
                                            wrong_pub_self_convention))]
 
+extern crate atty;
 #[macro_use]
 extern crate clap;
 extern crate crgp_lib;
 extern crate flexi_logger;
+extern crate log;
+extern crate sha2;
 extern crate time;
 extern crate toml;
 
+use std::collections::HashSet;
 use std::env::current_dir;
 use std::error::Error as StdError;
 use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::io::Write;
+use std::io::stderr;
 use std::io::BufWriter;
-use std::io::Error as IOError;
 use std::path::PathBuf;
 
 use clap::Arg;
@@ -48,15 +50,21 @@ use crgp_lib::Configuration;
 use crgp_lib::Error;
 use crgp_lib::aws_s3;
 use crgp_lib::configuration;
-use flexi_logger::with_thread;
 use flexi_logger::LogOptions;
 use time::Tm;
 use time::TmFmt;
 
 pub use quit::ExitCode;
 
+use color::ColorChoice;
+use color::Palette;
+
+mod color;
+mod manifest;
 mod validation;
 mod quit;
+mod statistics_diff;
+mod statistics_summary;
 
 /// Execute the program.
 fn main() {
@@ -72,13 +80,50 @@ fn main() {
                              variable \"{token}\".",
                             access = aws_s3::ACCESS_KEY_VAR_NAME, secret = aws_s3::SECRET_VAR_NAME,
                             token = aws_s3::TOKEN_VAR_NAME).as_str())
+        .arg(Arg::with_name("activation-grace-seconds")
+            .long("activation-grace-seconds")
+            .value_name("SECONDS")
+            .help("Consider friends as influencers if they have been activated at most this many seconds after a \
+                  Retweet. A negative value requires their activation to precede the Retweet by at least this many \
+                  seconds.")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .default_value("0")
+            .validator(validation::i64))
+        .arg(Arg::with_name("activation-log")
+            .long("activation-log")
+            .value_name("FILE")
+            .help("Write the activation of each user within each cascade to this file, one line \
+                  \"cascade_id;user_id;activation_time\" per activation.")
+            .takes_value(true))
+        .arg(Arg::with_name("activation-set-capacity")
+            .long("activation-set-capacity")
+            .value_name("USERS")
+            .help("Reserve memory for this many activations when a cascade is started, to avoid repeated \
+                  reallocations in large cascades. Only used by GALE and PACE.")
+            .takes_value(true)
+            .default_value("0")
+            .validator(validation::usize))
+        .arg(Arg::with_name("additional-social-graphs")
+            .long("additional-social-graphs")
+            .value_name("DIRECTORIES")
+            .help("A comma-separated list of further directories of TAR archives which are loaded after the \
+                  friendship dataset if it is a directory as well, e.g. if the social graph is split across several \
+                  volumes. Users with friend files in more than one directory are handled according to \
+                  \"--duplicate-users\".")
+            .takes_value(true)
+            .use_delimiter(true))
         .arg(Arg::with_name("algorithm")
             .short("a")
             .long("algorithm")
             .takes_value(true)
-            .possible_values(&["GALE", "LEAF"])
+            .possible_values(&["GALE", "LEAF", "PACE", "FULL"])
             .default_value("GALE")
-            .help("Use the specified algorithm."))
+            .help("Use the specified algorithm. FULL ignores the activation times, producing a superset of the \
+                  influence edges found by GALE."))
+        .arg(Arg::with_name("assert-sorted-retweets")
+            .long("assert-sorted-retweets")
+            .help("Fail if any Retweet in the data set was created before its predecessor."))
         .arg(Arg::with_name("batch-size")
             .short("b")
             .long("batch-size")
@@ -87,12 +132,120 @@ fn main() {
             .takes_value(true)
             .default_value("50000")
             .validator(validation::positive_usize))
+        .arg(Arg::with_name("benchmark")
+            .long("benchmark")
+            .value_name("N")
+            .help("Process the Retweets N times, keeping the social graph in memory, and report aggregate timings. \
+                  Only the first run writes results.")
+            .takes_value(true)
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("buffer-output")
+            .long("buffer-output")
+            .help("Buffer the written lines and write the lines of each batch at once, preventing partial or \
+                  interleaved lines in the output."))
+        .arg(Arg::with_name("cascade-size-quantiles")
+            .long("cascade-size-quantiles")
+            .help("Estimate the 50th, 90th, and 99th percentile of the cascade sizes (numbers of influence edges) \
+                  with a relative error below 0.8%, using bounded memory."))
+        .arg(Arg::with_name("cascade-timeout")
+            .long("cascade-timeout")
+            .value_name("SECONDS")
+            .help("Consider a cascade complete once no Retweet has been made in it for this many seconds (assuming \
+                  the Retweets are sorted by their creation time). By default, cascades are only complete once all \
+                  Retweets have been processed.")
+            .takes_value(true)
+            .validator(validation::u64))
+        .arg(Arg::with_name("cascades-time-ordered")
+            .long("cascades-time-ordered")
+            .help("Guarantee that the Retweets within each cascade are ordered by their creation time to speed up \
+                  GALE. Warning: unordered Retweets yield wrong results."))
+        .arg(Arg::with_name("color")
+            .long("color")
+            .value_name("WHEN")
+            .help("When to color errors, warnings, and the key results. \"auto\" only uses colors on terminals and if \
+                  the environment variable NO_COLOR is not set.")
+            .takes_value(true)
+            .possible_values(&["auto", "always", "never"])
+            .default_value("auto"))
+        .arg(Arg::with_name("diff-stats")
+            .long("diff-stats")
+            .value_names(&["BEFORE", "AFTER"])
+            .help("Compare the numeric fields of two statistics files, print the changes, then exit. Times and \
+                  rates worse by more than the regression threshold and any changed counts are flagged.")
+            .takes_value(true)
+            .number_of_values(2))
+        .arg(Arg::with_name("deduplicate-edges")
+            .long("deduplicate-edges")
+            .help("Only write the first influence edge between each pair of users within a cascade."))
+        .arg(Arg::with_name("dry-run")
+            .long("dry-run")
+            .help("Only validate the inputs: load a small sample of the social graph and the first batch of Retweets, \
+                  report their counts, and exit without running the computation or writing any output."))
+        .arg(Arg::with_name("duplicate-users")
+            .long("duplicate-users")
+            .value_name("POLICY")
+            .help("How to handle users with more than one friend file in the social graph.")
+            .takes_value(true)
+            .possible_values(&["merge", "keep-first", "fail"])
+            .default_value("merge"))
+        .arg(Arg::with_name("edge-timeseries")
+            .long("edge-timeseries")
+            .value_name("FILE")
+            .help("Write the number of influence edges emitted per batch of Retweets to this file, one line \
+                  \"batch_index;edges;cumulative_edges\" per batch.")
+            .takes_value(true))
+        .arg(Arg::with_name("emit-time-delta")
+            .long("emit-time-delta")
+            .value_name("UNIT")
+            .help("Append the time between the activation of the influencer and the influence to each influence edge \
+                  in the given unit, or -1 if the influencer is the poster of the original Tweet. Only applies to the \
+                  \"edges\" and \"edges-with-screen-names\" output formats.")
+            .takes_value(true)
+            .possible_values(&["s", "ms", "us"]))
+        .arg(Arg::with_name("flush-on-cascade-complete")
+            .long("flush-on-cascade-complete")
+            .help("Buffer the influence edges of each cascade and write them once the cascade is complete after \
+                  --cascade-timeout, sorted by their time.")
+            .requires("cascade-timeout"))
+        .arg(Arg::with_name("friend-expansion-hops")
+            .long("friend-expansion-hops")
+            .value_name("HOPS")
+            .help("Expand each user's friend list with the users reachable within this many additional hops in the \
+                  social graph. Warning: the social graph can grow exponentially with the number of hops.")
+            .takes_value(true)
+            .default_value("0")
+            .validator(validation::u8))
+        .arg(Arg::with_name("graph-load-statistics")
+            .long("graph-load-statistics")
+            .value_name("FILE")
+            .help("Write the number of users, the numbers of given, expected, and dummy friendships, and the time to \
+                  process the social graph to this TOML file (e.g. \"graph_load.toml\") as soon as the social graph \
+                  has been loaded.")
+            .takes_value(true))
+        .arg(Arg::with_name("graph-owners")
+            .long("graph-owners")
+            .value_name("WORKERS")
+            .help("A comma-separated list of the indices of the workers (across all processes) that will store the \
+                  social graph. By default, the social graph is distributed across all workers.")
+            .takes_value(true)
+            .use_delimiter(true)
+            .validator(validation::usize))
+        .arg(Arg::with_name("hash-output")
+            .long("hash-output")
+            .help("Compute the SHA-256 hash of the result file and write it next to the file (e.g. to \
+                  \"cascs.csv.sha256\") and to the statistics. Only used when writing the result to a directory."))
         .arg(Arg::with_name("hostfile")
             .short("f")
             .long("hostfile")
             .value_name("FILE")
-            .help("A text file specifying \"hostname:port\" per line in order of process identity")
+            .help("A text file (optionally gzip-compressed) specifying \"hostname:port\" per line in order of process \
+                  identity. Blank lines and lines starting with \"#\" are ignored. Unless --processes is given, one \
+                  process per host is used.")
             .takes_value(true))
+        .arg(Arg::with_name("include-quotes")
+            .long("include-quotes")
+            .help("Treat quote Tweets as Retweets of the Tweet they quote. The quoted Tweet must be included in the \
+                  quote Tweet as \"quoted_status\"."))
         .arg(Arg::with_name("log")
             .short("l")
             .long("log-directory")
@@ -100,18 +253,76 @@ fn main() {
             .help("The directory where log files will be created (if logging is enabled via '-v'). If this argument is \
                   not specified log messages will be written to STDERR.")
             .takes_value(true))
+        .arg(Arg::with_name("manifest-sha256")
+            .long("manifest-sha256")
+            .help("Include a SHA-256 hash of the contents of all input data sets in the run manifest. By default, only \
+                  their size and time of last modification are recorded. The manifest is only written if the result \
+                  is written to a directory."))
+        .arg(Arg::with_name("max-dummy-ratio")
+            .long("max-dummy-ratio")
+            .value_name("RATIO")
+            .help("The maximum share (from 0 to 1) of friendships with dummy users among all friendships when padding \
+                  the social graph with dummy users. A higher share is logged as a warning, or, with --strict, fails \
+                  the computation.")
+            .takes_value(true)
+            .validator(validation::ratio))
+        .arg(Arg::with_name("max-expanded-friends")
+            .long("max-expanded-friends")
+            .value_name("AMOUNT")
+            .help("The maximum number of friends per user when expanding friend lists.")
+            .takes_value(true)
+            .default_value("100000")
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("max-output-edges")
+            .long("max-output-edges")
+            .value_name("AMOUNT")
+            .help("Write at most this many influence edges. All further edges are still produced and counted, but not \
+                  written. Which edges are written depends on their emission order.")
+            .takes_value(true)
+            .validator(validation::usize))
+        .arg(Arg::with_name("max-retweets")
+            .long("max-retweets")
+            .value_name("AMOUNT")
+            .help("Process at most this many Retweets, i.e. only the first ones in the Retweet file.")
+            .takes_value(true)
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("max-expected-friends")
+            .long("max-expected-friends")
+            .value_name("AMOUNT")
+            .help("The maximum number of friends a user may claim to have in the meta data of their friend file. \
+                  Larger claims will be clamped to this value.")
+            .takes_value(true)
+            .default_value("100000000")
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("minimal-stats")
+            .long("minimal-stats")
+            .help("Skip all derived and aggregated statistics (e.g. the top cascades, the cascade size quantiles, and \
+                  the friendship utilization) to minimize the overhead of measuring the computation."))
         .arg(Arg::with_name("pad-users")
             .long("pad-users")
             .help("If the given friend list for each user is only a subset of their friends, create as many dummy \
                   users as needed to reach the user's actual number of friends."))
+        .arg(Arg::with_name("print-schema")
+            .long("print-schema")
+            .help("Print a description and an example of each supported input format, then exit."))
+        .arg(Arg::with_name("progress")
+            .long("progress")
+            .help("Print the number of processed Retweets to STDERR after each batch."))
+        .arg(Arg::with_name("progress-json")
+            .long("progress-json")
+            .help("Write the progress of the computation as JSON objects to STDERR, one per line."))
         .arg(Arg::with_name("processes")
             .short("n")
             .long("processes")
             .value_name("PROCESSES")
-            .help("Number of processes involved in the computation")
+            .help("Number of processes involved in the computation. Defaults to the number of hosts in the \
+                  --hostfile, if given, otherwise to 1.")
             .takes_value(true)
             .default_value("1")
             .validator(validation::positive_usize))
+        .arg(Arg::with_name("no-color")
+            .long("no-color")
+            .help("Never use colors. Overrides --color."))
         .arg(Arg::with_name("output-directory")
             .short("o")
             .long("output-directory")
@@ -119,6 +330,30 @@ fn main() {
             .help("The directory where the result and statistics files will be created. If this argument is not \
                   specified the current direcotry will be used.")
             .takes_value(true))
+        .arg(Arg::with_name("output-format")
+            .long("output-format")
+            .value_name("FORMAT")
+            .help("The format in which the result will be written. \"distinct-pairs\" writes each pair of influencer \
+                  and influencee only once across all cascades; all pairs must be kept in memory to do so. \
+                  \"matrix\" writes an adjacency matrix per cascade once the computation has finished; cascades \
+                  with more than 32 users are skipped. \"edges-with-screen-names\" writes each user as \
+                  \"id:@screen_name\" if their screen name is given in the file of \"--screen-names\". \
+                  \"abomonation\" writes the edges in binary form to \"cascs.abom\" in the output directory; the file \
+                  can only be read by the same version of CRGP. \"neo4j\" writes the edges as relationships to \
+                  \"cascs.csv\" and their users as nodes to \"users.csv\" in the output directory, for the import \
+                  with \"neo4j-admin import\"; all users must be kept in memory to do so. \"cascade-summary\" writes \
+                  one line \"cascade_id;root_user;retweets;edges;max_depth;dummy_edges\" per completed cascade \
+                  instead of its edges.")
+            .takes_value(true)
+            .possible_values(&["edges", "distinct-pairs", "matrix", "edges-with-screen-names", "abomonation", "neo4j",
+                               "cascade-summary"])
+            .default_value("edges"))
+        .arg(Arg::with_name("output-root-users")
+            .long("output-root-users")
+            .value_name("FILE")
+            .help("Only write the cascades of Tweets posted by the given users (one per line). All cascades are still \
+                  reconstructed. The file may be gzip-compressed.")
+            .takes_value(true))
         .arg(Arg::with_name("no-output")
             .long("no-output")
             .help("Do not write any results. This setting overwrites \"--output-directory\"."))
@@ -130,9 +365,23 @@ fn main() {
             .takes_value(true)
             .default_value("0")
             .validator(validation::usize))
+        .arg(Arg::with_name("regression-threshold")
+            .long("regression-threshold")
+            .value_name("PERCENT")
+            .help("The change in percent beyond which a worse time or rate is flagged as a regression when \
+                  comparing statistics files.")
+            .takes_value(true)
+            .default_value("10")
+            .validator(validation::usize))
         .arg(Arg::with_name("report-connection-progress")
             .long("connection-progress")
             .help("Print connection progress to STDOUT when using multiple processes."))
+        .arg(Arg::with_name("report-top-cascades")
+            .long("report-top-cascades")
+            .value_name("N")
+            .help("Print the N cascades with the most Retweets and their root users in the summary.")
+            .takes_value(true)
+            .validator(validation::positive_usize))
         .arg(Arg::with_name("s3-tweets-bucket")
             .long("s3-tweets-bucket")
             .help("The AWS S3 bucket for the Retweet cascade file.")
@@ -145,6 +394,34 @@ fn main() {
             .takes_value(true)
             .value_name("REGION")
             .requires("s3-tweets-bucket"))
+        .arg(Arg::with_name("s3-aux-bucket")
+            .long("s3-aux-bucket")
+            .help("The AWS S3 bucket for the auxiliary files, i.e. the host file, the screen names, and the files of \
+                  selected users and root users.")
+            .takes_value(true)
+            .value_name("BUCKET")
+            .requires("s3-aux-region"))
+        .arg(Arg::with_name("s3-aux-region")
+            .long("s3-aux-region")
+            .help("The AWS S3 region for the auxiliary files.")
+            .takes_value(true)
+            .value_name("REGION")
+            .requires("s3-aux-bucket"))
+        .arg(Arg::with_name("s3-cache-directory")
+            .long("s3-cache-dir")
+            .value_name("DIRECTORY")
+            .help("Download the social graph from AWS S3 to this directory once and load it from there. Archives \
+                  already present in the directory will not be downloaded again.")
+            .takes_value(true)
+            .requires("s3-sg-bucket"))
+        .arg(Arg::with_name("s3-max-retries")
+            .long("s3-max-retries")
+            .value_name("AMOUNT")
+            .help("Retry requests to AWS S3 failing with a server error or throttling up to this many times, waiting \
+                  twice as long before each retry.")
+            .takes_value(true)
+            .default_value("3")
+            .validator(validation::u8))
         .arg(Arg::with_name("s3-sg-bucket")
             .long("s3-sg-bucket")
             .help("The AWS S3 bucket for the social graph.")
@@ -157,11 +434,95 @@ fn main() {
             .takes_value(true)
             .value_name("REGION")
             .requires("s3-sg-bucket"))
+        .arg(Arg::with_name("sample-output")
+            .long("sample-output")
+            .value_name("EDGES")
+            .help("Only write a uniform random sample of at most this many influence edges once the computation has \
+                  finished. The sample is drawn over all edges, not over cascades.")
+            .takes_value(true)
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("screen-names")
+            .long("screen-names")
+            .value_name("FILE")
+            .help("Map user IDs to screen names for the output format \"edges-with-screen-names\", given as one \
+                  \"id;screen_name\" per line. The file may be gzip-compressed.")
+            .takes_value(true))
+        .arg(Arg::with_name("seed")
+            .long("seed")
+            .value_name("SEED")
+            .help("The seed for all random decisions, e.g. when sampling the output.")
+            .takes_value(true)
+            .default_value("0")
+            .validator(validation::u64))
+        .arg(Arg::with_name("seed-users")
+            .long("seed-users")
+            .value_name("IDS")
+            .help("A comma-separated list of the IDs of users whose cascades will be reconstructed. Retweets of Tweets \
+                  posted by any other user are skipped. By default, all cascades are reconstructed.")
+            .takes_value(true)
+            .use_delimiter(true)
+            .validator(validation::i64))
         .arg(Arg::with_name("selected-users")
             .long("selected-users")
             .value_name("FILE")
-            .help("Load only the given users (one per line) from the social graph.")
+            .help("Load only the given users (one per line) from the social graph. The file may be gzip-compressed.")
+            .takes_value(true))
+        .arg(Arg::with_name("sort-friends")
+            .long("sort-friends")
+            .help("Sort each user's friend list while loading the social graph."))
+        .arg(Arg::with_name("stdout")
+            .long("stdout")
+            .help("Also write the influence edges to STDOUT, e.g. to follow them while they are computed. Ignored if \
+                  \"--no-output\" is given."))
+        .arg(Arg::with_name("stream-retweets")
+            .long("stream-retweets")
+            .help("Read the Retweets from a local file of JSON lines while processing them instead of loading all of \
+                  them first. Disables repetitions."))
+        .arg(Arg::with_name("strict")
+            .long("strict")
+            .help("Fail instead of only warning on input data that almost certainly indicates a mistake, e.g. a list \
+                  of selected users none of whom is in the social graph."))
+        .arg(Arg::with_name("summarize")
+            .long("summarize")
+            .value_name("DIRECTORY")
+            .help("Print a table of the statistics files of all runs in the directory, one row per run, and the \
+                  minimum, maximum, and mean of the processing rate and the times over all runs, then exit.")
+            .takes_value(true))
+        .arg(Arg::with_name("sync-timeout")
+            .long("sync-timeout")
+            .value_name("SECONDS")
+            .help("Fail if a batch has not been processed within this many seconds, e.g. because a worker in another \
+                  process has died. By default, the workers wait for each batch indefinitely.")
+            .takes_value(true)
+            .validator(validation::positive_usize))
+        .arg(Arg::with_name("timestamp-format")
+            .long("timestamp-format")
+            .value_name("FORMAT")
+            .help("Only accept Retweets whose creation times are given in this format: \"epoch\" for integers of \
+                  seconds since the epoch, \"rfc3339\" for ISO-8601 strings, or \"auto\" for both. Other Retweets \
+                  are skipped with a warning.")
+            .takes_value(true)
+            .possible_values(&["auto", "epoch", "rfc3339"])
+            .default_value("auto"))
+        .arg(Arg::with_name("timestamp-join-file")
+            .long("timestamp-join-file")
+            .value_name("FILE")
+            .help("Override the timestamps of the Retweets with those given in this file, one line \
+                  \"retweet_id,timestamp\" per Retweet. Retweets missing from the file keep their own timestamps.")
             .takes_value(true))
+        .arg(Arg::with_name("track-friendship-utilization")
+            .long("track-friendship-utilization")
+            .help("Count the friendships used to produce influence edges and write the unused friendships to the \
+                  output directory. Keeps the entire social graph in memory on the first worker."))
+        .arg(Arg::with_name("user")
+            .long("user")
+            .value_name("ID")
+            .help("Load this user from the social graph in addition to those given in \"--selected-users\". The \
+                  argument can occur multiple times.")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .validator(validation::i64))
         .arg(Arg::with_name("verbosity")
             .short("v")
             .multiple(true)
@@ -176,39 +537,140 @@ fn main() {
             .default_value("1")
             .validator(validation::positive_usize))
         .arg(Arg::with_name("FRIENDS")
-            .help("Path to the friendship dataset")
-            .required(true)
+            .help("Path to the friendship dataset (a directory of TAR archives, a ZIP archive, an abomonated \
+                  graph file, or an edge list with one tab-separated friendship per line)")
+            .required_unless_one(&["diff-stats", "print-schema", "summarize"])
             .index(1))
         .arg(Arg::with_name("RETWEETS")
-            .help("Path to the Retweet dataset")
-            .required(true)
+            .help("Path to the Retweet dataset (optionally gzip-compressed)")
+            .required_unless_one(&["diff-stats", "print-schema", "summarize"])
             .index(2))
         .get_matches();
 
-    // Get the positional arguments. Since they are required the `unwrap()`s cannot fail.
+    // If only the input formats are requested, print them and quit.
+    if arguments.is_present("print-schema") {
+        println!("{}", crgp_lib::schema::describe());
+        quit::succeed();
+    }
+
+    // If only two statistics files are to be compared, print the changes and quit. Since exactly two files are
+    // required and the threshold has a default value and a validator, none of the `unwrap()`s can fail.
+    if let Some(mut files) = arguments.values_of("diff-stats") {
+        let before: PathBuf = PathBuf::from(files.next().unwrap());
+        let after: PathBuf = PathBuf::from(files.next().unwrap());
+        let threshold: usize = arguments.value_of("regression-threshold").unwrap().parse().unwrap();
+
+        let before = match statistics_diff::load(&before) {
+            Ok(before) => before,
+            Err(error) => quit::fail_from_error(error)
+        };
+        let after = match statistics_diff::load(&after) {
+            Ok(after) => after,
+            Err(error) => quit::fail_from_error(error)
+        };
+        let deltas: Vec<statistics_diff::Delta> = statistics_diff::diff(&before, &after);
+        print!("{}", statistics_diff::format_table(&deltas, threshold as f64));
+        quit::succeed();
+    }
+
+    // If only the statistics files in a directory are to be summarized, print the summary and quit.
+    if let Some(directory) = arguments.value_of("summarize") {
+        let runs: Vec<statistics_summary::Run> = match statistics_summary::load_directory(&PathBuf::from(directory)) {
+            Ok(runs) => runs,
+            Err(error) => quit::fail_from_error(error)
+        };
+        print!("{}", statistics_summary::format_table(&runs));
+        quit::succeed();
+    }
+
+    // Get the positional arguments. Since they are required unless printing the schema, comparing statistics, or
+    // summarizing statistics, the `unwrap()`s cannot fail.
     let mut social_graph_path = configuration::InputSource::new(arguments.value_of("FRIENDS").unwrap());
     let mut retweet_path = configuration::InputSource::new(arguments.value_of("RETWEETS").unwrap());
 
     // Get the arguments with default values. Since these arguments have default values and validators defined none
     // of the `unwrap()`s can fail.
     let given_algorithm: &str = arguments.value_of("algorithm").unwrap();
-    let algorithm: configuration::Algorithm = if given_algorithm == "LEAF" {
-        configuration::Algorithm::LEAF
-    } else {
-        configuration::Algorithm::GALE
+    let algorithm: configuration::Algorithm = match given_algorithm {
+        "LEAF" => configuration::Algorithm::LEAF,
+        "PACE" => configuration::Algorithm::PACE,
+        "FULL" => configuration::Algorithm::FullActivation,
+        _ => configuration::Algorithm::GALE,
     };
+    let activation_grace_seconds: i64 = arguments.value_of("activation-grace-seconds").unwrap().parse().unwrap();
+    let activation_set_initial_capacity: usize = arguments.value_of("activation-set-capacity").unwrap()
+        .parse()
+        .unwrap();
+    let assert_sorted_retweets: bool = arguments.is_present("assert-sorted-retweets");
     let batch_size: usize = arguments.value_of("batch-size").unwrap().parse().unwrap();
+    let duplicate_users: configuration::DuplicateUserPolicy = match arguments.value_of("duplicate-users").unwrap() {
+        "keep-first" => configuration::DuplicateUserPolicy::KeepFirst,
+        "fail" => configuration::DuplicateUserPolicy::Fail,
+        _ => configuration::DuplicateUserPolicy::Merge,
+    };
+    let friend_expansion_hops: u8 = arguments.value_of("friend-expansion-hops").unwrap().parse().unwrap();
+    let max_expanded_friends: usize = arguments.value_of("max-expanded-friends").unwrap().parse().unwrap();
+    let max_expected_friends: u64 = arguments.value_of("max-expected-friends").unwrap().parse().unwrap();
     let process_id: usize = arguments.value_of("process").unwrap().parse().unwrap();
     let processes: usize = arguments.value_of("processes").unwrap().parse().unwrap();
     let workers: usize = arguments.value_of("workers").unwrap().parse().unwrap();
+    let seed: u64 = arguments.value_of("seed").unwrap().parse().unwrap();
+    let progress_json: bool = arguments.is_present("progress-json");
     let report_connection_progess: bool = arguments.is_present("report-connection-progress");
     let pad_with_dummy_users: bool = arguments.is_present("pad-users");
+    let manifest_sha256: bool = arguments.is_present("manifest-sha256");
+    let minimal_stats: bool = arguments.is_present("minimal-stats");
+    let deduplicate_edges: bool = arguments.is_present("deduplicate-edges");
+    let dry_run: bool = arguments.is_present("dry-run");
+    let strict: bool = arguments.is_present("strict");
+    let cascades_time_ordered: bool = arguments.is_present("cascades-time-ordered");
+    let track_friendship_utilization: bool = arguments.is_present("track-friendship-utilization");
+    let flush_on_cascade_complete: bool = arguments.is_present("flush-on-cascade-complete");
+    let cascade_size_quantiles: bool = arguments.is_present("cascade-size-quantiles");
+    let buffer_output: bool = arguments.is_present("buffer-output");
+    let hash_output: bool = arguments.is_present("hash-output");
+    let include_quotes: bool = arguments.is_present("include-quotes");
+    let stream_retweets: bool = arguments.is_present("stream-retweets");
+    let sort_friends: bool = arguments.is_present("sort-friends");
+    let given_output_format: &str = arguments.value_of("output-format").unwrap();
+    let output_format: configuration::OutputFormat = match given_output_format {
+        "distinct-pairs" => configuration::OutputFormat::DistinctPairs,
+        "matrix" => configuration::OutputFormat::Matrix,
+        "edges-with-screen-names" => configuration::OutputFormat::EdgesWithScreenNames,
+        "abomonation" => configuration::OutputFormat::Abomonation,
+        "neo4j" => configuration::OutputFormat::Neo4j,
+        "cascade-summary" => configuration::OutputFormat::CascadeSummary,
+        _ => configuration::OutputFormat::Edges,
+    };
+
+    // Get the number of benchmark repetitions. Since the value has been validated, the `unwrap()` cannot fail.
+    let repetitions: usize = arguments.value_of("benchmark").map_or(1, |repetitions| repetitions.parse().unwrap());
+
+    // Get the cascade timeout. Since the value has been validated, the `unwrap()` cannot fail.
+    let cascade_timeout: Option<u64> = arguments.value_of("cascade-timeout").map(|timeout| timeout.parse().unwrap());
+    let sync_timeout: Option<u64> = arguments.value_of("sync-timeout").map(|timeout| timeout.parse().unwrap());
+
+    // Get the size of the output sample. Since the value has been validated, the `unwrap()` cannot fail.
+    let sample_output: Option<usize> = arguments.value_of("sample-output").map(|size| size.parse().unwrap());
+    let s3_max_retries: u32 = arguments.value_of("s3-max-retries").unwrap().parse().unwrap();
+    let report_top_cascades: usize = arguments.value_of("report-top-cascades").map_or(0, |n| n.parse().unwrap());
+    let max_output_edges: Option<usize> = arguments.value_of("max-output-edges").map(|max| max.parse().unwrap());
+    let max_retweets: Option<usize> = arguments.value_of("max-retweets").map(|max| max.parse().unwrap());
+    let max_dummy_ratio: Option<f64> = arguments.value_of("max-dummy-ratio").map(|ratio| ratio.parse().unwrap());
+
+    // Get the further directories of the social graph.
+    let additional_social_graphs: Vec<PathBuf> = arguments.values_of("additional-social-graphs")
+        .map_or_else(Vec::new, |directories| directories.map(PathBuf::from).collect());
+
+    // Get the workers storing the social graph. Since the values have been validated, the `unwrap()` cannot fail.
+    let graph_owner_workers: Option<Vec<usize>> = arguments.values_of("graph-owners")
+        .map(|owners| owners.map(|owner| owner.parse().unwrap()).collect());
 
     // Determine the output target.
     let output_target: configuration::OutputTarget = if arguments.is_present("no-output") {
         configuration::OutputTarget::None
     } else {
-        match arguments.value_of("output-directory") {
+        let directory: configuration::OutputTarget = match arguments.value_of("output-directory") {
             Some(directory) => configuration::OutputTarget::Directory(PathBuf::from(directory)),
             None => match current_dir() {
                 Ok(directory) => configuration::OutputTarget::Directory(directory),
@@ -216,6 +678,12 @@ fn main() {
                     quit::fail_from_error(Error::from(error));
                 }
             },
+        };
+
+        if arguments.is_present("stdout") {
+            configuration::OutputTarget::Multi(vec![directory, configuration::OutputTarget::StdOut])
+        } else {
+            directory
         }
     };
 
@@ -230,31 +698,82 @@ fn main() {
         let bucket: &str = arguments.value_of("s3-sg-bucket").unwrap();
         let region: &str = arguments.value_of("s3-sg-region").unwrap();
         let s3_config = configuration::S3::new(bucket, region);
-        social_graph_path.s3 = Some(s3_config);
+
+        // If requested, load the social graph from a local copy instead.
+        if let Some(cache_directory) = arguments.value_of("s3-cache-directory") {
+            let cache_directory = PathBuf::from(cache_directory);
+            match crgp_lib::cache_s3_locally(&social_graph_path.path.to_string_lossy(), &s3_config, &cache_directory) {
+                Ok(cache_directory) => {
+                    social_graph_path = configuration::InputSource::new(cache_directory);
+                },
+                Err(error) => {
+                    quit::fail_from_error(error);
+                }
+            }
+        } else {
+            social_graph_path.s3 = Some(s3_config);
+        }
     }
+    let auxiliary_s3: Option<configuration::S3> = if arguments.is_present("s3-aux-bucket") &&
+                                                     arguments.is_present("s3-aux-region") {
+        let bucket: &str = arguments.value_of("s3-aux-bucket").unwrap();
+        let region: &str = arguments.value_of("s3-aux-region").unwrap();
+        Some(configuration::S3::new(bucket, region))
+    } else {
+        None
+    };
 
     // Get the hosts.
     let hosts: Option<Vec<String>> = match arguments.value_of("hostfile") {
         Some(file) => {
-            let file = match File::open(file) {
-                Ok(file) => file,
-                Err(error) => {
-                    quit::fail_from_error(Error::from(error));
-                }
-            };
-            let reader = BufReader::new(file);
-            match reader.lines().collect::<Result<Vec<String>, IOError>>() {
+            match crgp_lib::read_hosts(configuration::InputSource::new(file).s3(auxiliary_s3.clone())) {
                 Ok(hosts) => Some(hosts),
                 Err(error) => {
-                    quit::fail_from_error(Error::from(error));
+                    quit::fail_from_error(error);
                 }
             }
         },
         None => None,
     };
 
-    // Determine if only selected users will be loaded.
-    let selected_users: Option<PathBuf> = arguments.value_of("selected-users").map(PathBuf::from);
+    // Get the unit of the time between the activation of the influencer and the influence.
+    let emit_time_delta: Option<configuration::TimeUnit> = match arguments.value_of("emit-time-delta") {
+        Some("s") => Some(configuration::TimeUnit::Seconds),
+        Some("ms") => Some(configuration::TimeUnit::Milliseconds),
+        Some("us") => Some(configuration::TimeUnit::Microseconds),
+        _ => None,
+    };
+
+    // Get the file for the time series of emitted influence edges.
+    let edge_timeseries: Option<PathBuf> = arguments.value_of("edge-timeseries").map(PathBuf::from);
+    let graph_load_statistics: Option<PathBuf> = arguments.value_of("graph-load-statistics").map(PathBuf::from);
+    let activation_log: Option<PathBuf> = arguments.value_of("activation-log").map(PathBuf::from);
+    let timestamp_join_file: Option<PathBuf> = arguments.value_of("timestamp-join-file").map(PathBuf::from);
+
+    // Get the format of the Retweet timestamps.
+    let timestamp_format: configuration::TimestampFormat = match arguments.value_of("timestamp-format") {
+        Some("epoch") => configuration::TimestampFormat::Epoch,
+        Some("rfc3339") => configuration::TimestampFormat::Rfc3339,
+        _ => configuration::TimestampFormat::Auto,
+    };
+
+    // Get the users whose cascades will be written.
+    let output_root_users: Option<configuration::InputSource> = arguments.value_of("output-root-users")
+        .map(|file| configuration::InputSource::new(file).s3(auxiliary_s3.clone()));
+
+    // Get the screen names of the users.
+    let screen_names: Option<configuration::InputSource> = arguments.value_of("screen-names")
+        .map(|file| configuration::InputSource::new(file).s3(auxiliary_s3.clone()));
+
+    // Get the users whose cascades will be reconstructed. Since the IDs have been validated, `unwrap()` cannot fail.
+    let seed_authors: Option<HashSet<crgp_lib::UserId>> = arguments.values_of("seed-users")
+        .map(|authors| authors.map(|author| author.parse().unwrap()).collect());
+
+    // Determine if only selected users will be loaded. Since the IDs have been validated, the `unwrap()` cannot fail.
+    let selected_users: Option<configuration::InputSource> = arguments.value_of("selected-users")
+        .map(|file| configuration::InputSource::new(file).s3(auxiliary_s3.clone()));
+    let selected_user_ids: Vec<crgp_lib::UserId> = arguments.values_of("user")
+        .map_or_else(Vec::new, |ids| ids.map(|id| id.parse().unwrap()).collect());
 
     // Get the logger arguments.
     let (log_to_file, log_directory): (bool, Option<String>) = match arguments.value_of("log") {
@@ -269,10 +788,19 @@ fn main() {
         4 | _ => Some(String::from("trace"))
     };
 
+    // Determine whether to use colors. Log files are never colored.
+    let color_choice: ColorChoice = if arguments.is_present("no-color") {
+        ColorChoice::Never
+    } else {
+        ColorChoice::from_name(arguments.value_of("color").unwrap())
+    };
+    let colored_logs: bool = !log_to_file && color_choice.is_enabled_for(atty::Stream::Stderr);
+    let palette = Palette::new(color_choice.is_enabled_for(atty::Stream::Stdout));
+
     // Initialize the logger.
     if let Some(verbosity) = verbosity {
         let logger_initialization = LogOptions::new()
-            .format(with_thread)
+            .format(color::log_format(colored_logs))
             .log_to_file(log_to_file)
             .duplicate_error(!log_to_file)  // Do not print errors to STDOUT if they already are written to a file.
             .directory(log_directory)
@@ -287,27 +815,124 @@ fn main() {
     }
 
     // Set the algorithm configuration.
-    let configuration = Configuration::default(retweet_path, social_graph_path)
+    let mut configuration = Configuration::default(retweet_path, social_graph_path)
+        .activation_grace_seconds(activation_grace_seconds)
+        .activation_log(activation_log)
+        .activation_set_initial_capacity(activation_set_initial_capacity)
+        .additional_social_graphs(additional_social_graphs)
         .algorithm(algorithm)
+        .assert_sorted_retweets(assert_sorted_retweets)
         .batch_size(batch_size)
+        .buffer_output(buffer_output)
+        .cascade_size_quantiles(cascade_size_quantiles)
+        .cascade_timeout(cascade_timeout)
+        .cascades_time_ordered(cascades_time_ordered)
+        .deduplicate_edges(deduplicate_edges)
+        .dry_run(dry_run)
+        .duplicate_users(duplicate_users)
+        .edge_timeseries(edge_timeseries)
+        .emit_time_delta(emit_time_delta)
+        .flush_on_cascade_complete(flush_on_cascade_complete)
+        .friend_expansion_hops(friend_expansion_hops)
+        .graph_load_statistics(graph_load_statistics)
+        .hash_output(hash_output)
         .hosts(hosts)
+        .include_quotes(include_quotes)
+        .max_dummy_ratio(max_dummy_ratio)
+        .max_expanded_friends(max_expanded_friends)
+        .max_expected_friends(max_expected_friends)
+        .max_output_edges(max_output_edges)
+        .max_retweets(max_retweets)
+        .minimal_stats(minimal_stats)
+        .output_format(output_format)
+        .output_root_users(output_root_users)
         .output_target(output_target.clone())
         .pad_with_dummy_users(pad_with_dummy_users)
         .process_id(process_id)
         .processes(processes)
+        .progress_json(progress_json)
+        .repetitions(repetitions)
         .report_connection_progress(report_connection_progess)
+        .report_top_cascades(report_top_cascades)
+        .s3_max_retries(s3_max_retries)
+        .sample_output(sample_output)
+        .screen_names(screen_names)
+        .seed(seed)
+        .seed_authors(seed_authors)
+        .selected_user_ids(selected_user_ids)
         .selected_users(selected_users)
+        .sort_friends(sort_friends)
+        .stream_retweets(stream_retweets)
+        .strict(strict)
+        .sync_timeout(sync_timeout)
+        .timestamp_format(timestamp_format)
+        .timestamp_join_file(timestamp_join_file)
+        .track_friendship_utilization(track_friendship_utilization)
         .workers(workers);
+    if let Some(graph_owner_workers) = graph_owner_workers {
+        configuration = configuration.graph_owner_workers(graph_owner_workers);
+    }
+    if arguments.is_present("progress") {
+        configuration = configuration.progress_callback(|processed: u64, total: u64| {
+            let _ = writeln!(stderr(), "Processed {processed} of {total} Retweets", processed = processed,
+                             total = total);
+        });
+    }
+
+    // Without an explicit number of processes, run one process per host.
+    if arguments.is_present("hostfile") && arguments.occurrences_of("processes") == 0 {
+        configuration = configuration.processes_from_hosts();
+    }
+
+    // Fail on inconsistent arguments before loading any data.
+    if let Err(error) = configuration.validate() {
+        quit::fail_with_message(ExitCode::IncorrectUsage, &format!("invalid configuration: {error}",
+                                                                   error = error.description()));
+    }
 
     // Execute the algorithm.
-    let results = crgp_lib::run(configuration);
+    let results = crgp_lib::run(configuration.clone());
+
+    // A dry run only reports the counts of the validated samples.
+    if let Ok(ref results) = results {
+        if results.was_dry_run {
+            if process_id == 0 {
+                println!("Dry run: the inputs are valid");
+                println!(" #Friendships (sample): {}", palette.bold(&results.number_of_friendships.to_string()));
+                println!(" #Retweets (first batch): {}", palette.bold(&results.number_of_retweets.to_string()));
+            }
+            quit::succeed();
+        }
+    }
+
+    // Write the run manifest next to the result.
+    if process_id == 0 && results.is_ok() {
+        if let Some(directory) = output_target.directory() {
+            match manifest::assemble(&configuration, manifest_sha256)
+                .and_then(|manifest| manifest::write(&manifest, directory)) {
+                Ok(path) => println!("Manifest saved to {path}", path = path.display()),
+                Err(error) => println!("{}", palette.error(&format!("Error: could not create manifest file: {error}",
+                                                                     error = error))),
+            }
+        }
+    }
 
     // Write the statistics.
     match results {
         Ok(results) => {
+            if process_id == 0 && arguments.is_present("benchmark") {
+                println!("Benchmark: {runs} runs, mean {mean:.0}ns, median {median:.0}ns, stddev {stddev:.0}ns, \
+                          {rate:.2} RT/s",
+                         runs = results.retweet_processing_times.len(),
+                         mean = results.mean_time_to_process_retweets(),
+                         median = results.median_time_to_process_retweets(),
+                         stddev = results.standard_deviation_of_time_to_process_retweets(),
+                         rate = results.mean_retweet_processing_rate());
+            }
+
             if process_id == 0 {
                 // Only save to file if output is requested.
-                if let configuration::OutputTarget::Directory(directory) = output_target {
+                if let Some(directory) = output_target.directory() {
                     // Parse the statistics to TOML.
                     if let Ok(results) = toml::to_string(&results) {
                         // Create the file name from the program name and the current time.
@@ -333,22 +958,71 @@ fn main() {
                     }
 
                     // Some error occurred along the way.
-                    println!("Error: could not create statistics file. Printing to STDOUT instead.");
+                    println!("{}", palette.error("Error: could not create statistics file. Printing to STDOUT \
+                                                  instead."));
                 }
 
                 // Writing to file failed (or was not requested) - print to STDOUT instead.
                 println!();
                 println!("Results:");
-                println!(" #Friendships: {}", results.number_of_friendships);
-                println!(" #Retweets: {}", results.number_of_retweets);
+                println!(" #Friendships: {}", palette.bold(&results.number_of_friendships.to_string()));
+                println!("   Real: {}", results.number_of_real_friendships);
+                println!("   Dummy: {}", results.number_of_dummy_friendships);
+                println!("   Padding Ratio: {:.2}%", results.dummy_friendship_ratio() * 100.0);
+                println!(" #Duplicate Users: {}", results.number_of_duplicate_users);
+                println!(" #Retweets: {}", palette.bold(&results.number_of_retweets.to_string()));
+                println!("   Missing Cascade: {}", results.retweets_missing_cascade);
+                println!("   Not Seeded: {}", results.retweets_not_seeded);
+                println!("   Skipped Tweets: {}", results.number_of_skipped_tweets);
+                println!(" #Influence Edges: {}", palette.bold(&results.number_of_influences.to_string()));
+                if results.number_of_skipped_influences > 0 {
+                    println!("   Not Written: {}", results.number_of_skipped_influences);
+                }
+                println!(" #Suppressed Self-Influences: {}", results.self_edges_suppressed);
+                println!(" #Peak Live Cascades: {}", results.peak_live_cascades);
+                if results.late_retweets > 0 {
+                    println!("   Late Retweets: {}", results.late_retweets);
+                }
+                if results.friend_iterations + results.activation_iterations > 0 {
+                    println!(" Iterated Friends: {friends} times (mean {mean_friends:.2}), Iterated Activations: \
+                              {activations} times (mean {mean_activations:.2})",
+                             friends = results.friend_iterations, mean_friends = results.mean_iterated_friends,
+                             activations = results.activation_iterations,
+                             mean_activations = results.mean_iterated_activations);
+                }
+                if configuration.track_friendship_utilization {
+                    println!(" #Used Friendships: {used} of {total} ({utilization:.2}%)",
+                             used = results.used_friendships,
+                             total = results.used_friendships + results.unused_friendships,
+                             utilization = results.friendship_utilization * 100.0);
+                }
+                if results.widest_fan_out > 0 {
+                    println!(" Widest Fan-Out: {fan_out} (user {user} in cascade {cascade})",
+                             fan_out = palette.bold(&results.widest_fan_out.to_string()),
+                             user = results.widest_fan_out_influencer, cascade = results.widest_fan_out_cascade);
+                }
+                if configuration.cascade_size_quantiles {
+                    println!(" Cascade Sizes (approx.): p50 {p50}, p90 {p90}, p99 {p99}",
+                             p50 = results.cascade_size_p50, p90 = results.cascade_size_p90,
+                             p99 = results.cascade_size_p99);
+                }
+                if !results.top_cascades.is_empty() {
+                    println!(" Top Cascades:");
+                    println!("   {:>4}  {:>20}  {:>20}  {:>10}", "Rank", "Cascade", "Root User", "Retweets");
+                    for (rank, cascade) in results.top_cascades.iter().enumerate() {
+                        println!("   {:>4}  {:>20}  {:>20}  {:>10}", rank + 1, cascade.cascade_id, cascade.root_user,
+                                 cascade.retweets);
+                    }
+                }
                 println!();
                 println!(" Time to set up the computation: {}ns", results.time_to_setup);
                 println!(" Time to load and process the social network: {}ns", results.time_to_process_social_graph);
                 println!(" Time to load the retweets: {}ns", results.time_to_load_retweets);
                 println!(" Time to process the retweets: {}ns", results.time_to_process_retweets);
-                println!(" Total time: {}ns", results.total_time);
+                println!(" Total time: {}ns", palette.bold(&results.total_time.to_string()));
                 println!();
-                println!(" Retweet Processing Rate: {} RT/s", results.retweet_processing_rate);
+                println!(" Retweet Processing Rate: {} RT/s",
+                         palette.bold(&results.retweet_processing_rate.to_string()));
             }
 
             quit::succeed();
@@ -0,0 +1,264 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Assemble the manifest of a run, recording the exact inputs and settings that produced a result.
+//!
+//! The manifest contains the version of `CRGP`, the Git commit it was built from (if known), the full configuration,
+//! and a fingerprint of the social graph and the Retweet data set. By default, the fingerprint of an input consists of
+//! its size and the time of its last modification. Optionally, a SHA-256 hash of its contents is included as well.
+
+use std::fs::File;
+use std::fs::read_dir;
+use std::io::BufWriter;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crgp_lib::Configuration;
+use crgp_lib::Error;
+use crgp_lib::Result;
+use crgp_lib::configuration::InputSource;
+use sha2::Digest;
+use sha2::Sha256;
+use toml;
+use toml::Value;
+use toml::value::Table;
+
+/// The name of the manifest file within the output directory.
+pub const FILENAME: &'static str = "manifest.toml";
+
+/// The fingerprint of an input data set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fingerprint {
+    /// The location of the data set.
+    pub location: String,
+
+    /// The total size of all files in the data set in bytes. Unknown for data sets on AWS S3.
+    pub size: Option<u64>,
+
+    /// The latest modification of any file in the data set in seconds since the epoch. Unknown for data sets on AWS
+    /// S3.
+    pub modified: Option<u64>,
+
+    /// The SHA-256 hash over the relative paths and contents of all files in the data set, if requested and known.
+    pub sha256: Option<String>,
+}
+
+impl Fingerprint {
+    /// Compute the fingerprint of the `input` data set, including the SHA-256 hash of its contents if `full_hash` is
+    /// `true`. If the `input` is a directory, all files within it are included.
+    pub fn new(input: &InputSource, full_hash: bool) -> Result<Fingerprint> {
+        let mut fingerprint = Fingerprint {
+            location: format!("{}", input),
+            size: None,
+            modified: None,
+            sha256: None,
+        };
+
+        // The contents of data sets on AWS S3 are not fingerprinted.
+        if input.s3.is_some() {
+            return Ok(fingerprint);
+        }
+
+        let mut files: Vec<PathBuf> = Vec::new();
+        list_files(&input.path, &mut files)?;
+
+        let mut size: u64 = 0;
+        let mut modified: u64 = 0;
+        let mut hasher = Sha256::default();
+        for file in &files {
+            let metadata = file.metadata()?;
+            size += metadata.len();
+            let modified_at: u64 = metadata.modified().ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_secs());
+            if modified_at > modified {
+                modified = modified_at;
+            }
+
+            if full_hash {
+                let relative_path: &Path = file.strip_prefix(&input.path).unwrap_or(file);
+                hasher.input(relative_path.to_string_lossy().as_bytes());
+                hash_file(file, &mut hasher)?;
+            }
+        }
+
+        fingerprint.size = Some(size);
+        fingerprint.modified = Some(modified);
+        if full_hash {
+            let hash: Vec<String> = hasher.result().iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            fingerprint.sha256 = Some(hash.join(""));
+        }
+
+        Ok(fingerprint)
+    }
+
+    /// Convert the fingerprint into a TOML table. Unknown values are omitted.
+    fn into_toml(self) -> Value {
+        let mut table = Table::new();
+        let _ = table.insert(String::from("location"), Value::String(self.location));
+        if let Some(size) = self.size {
+            let _ = table.insert(String::from("size"), Value::Integer(size as i64));
+        }
+        if let Some(modified) = self.modified {
+            let _ = table.insert(String::from("modified"), Value::Integer(modified as i64));
+        }
+        if let Some(sha256) = self.sha256 {
+            let _ = table.insert(String::from("sha256"), Value::String(sha256));
+        }
+
+        Value::Table(table)
+    }
+}
+
+/// Assemble the manifest for a run with the given `configuration`. If `full_hash` is `true`, the fingerprints of the
+/// input data sets include a SHA-256 hash of their contents.
+pub fn assemble(configuration: &Configuration, full_hash: bool) -> Result<Value> {
+    let configuration_value: Value = match Value::try_from(configuration) {
+        Ok(value) => value,
+        Err(message) => {
+            let message: String = format!("Could not serialize the configuration: {error}", error = message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let mut manifest = Table::new();
+    let _ = manifest.insert(String::from("version"), Value::String(String::from(env!("CARGO_PKG_VERSION"))));
+    if let Some(git_hash) = option_env!("CRGP_GIT_HASH") {
+        let _ = manifest.insert(String::from("git_hash"), Value::String(String::from(git_hash)));
+    }
+    let _ = manifest.insert(String::from("configuration"), configuration_value);
+    let _ = manifest.insert(String::from("retweets"),
+                            Fingerprint::new(&configuration.retweets, full_hash)?.into_toml());
+    let _ = manifest.insert(String::from("social_graph"),
+                            Fingerprint::new(&configuration.social_graph, full_hash)?.into_toml());
+
+    Ok(Value::Table(manifest))
+}
+
+/// Write the `manifest` to the file `manifest.toml` in the given `directory`, returning the path to the file.
+pub fn write(manifest: &Value, directory: &Path) -> Result<PathBuf> {
+    let contents: String = match toml::to_string(manifest) {
+        Ok(contents) => contents,
+        Err(message) => {
+            let message: String = format!("Could not serialize the manifest: {error}", error = message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let path: PathBuf = directory.join(FILENAME);
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(&path)?);
+    write!(writer, "{}", contents)?;
+    writer.flush()?;
+
+    Ok(path)
+}
+
+/// Add the given `path` to the `files` if it is a file, or all files within it (recursively, sorted by their path) if
+/// it is a directory.
+fn list_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = Vec::new();
+    for entry in read_dir(path)? {
+        entries.push(entry?.path());
+    }
+    entries.sort();
+
+    for entry in entries {
+        list_files(&entry, files)?;
+    }
+
+    Ok(())
+}
+
+/// Feed the contents of the file at `path` into the `hasher`.
+fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = File::open(path)?;
+    let mut buffer = [0u8; 65_536];
+    loop {
+        let read: usize = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.input(&buffer[..read]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crgp_lib::Configuration;
+    use crgp_lib::configuration::InputSource;
+    use crgp_lib::configuration::S3;
+    use toml;
+    use toml::Value;
+
+    /// Get the configuration for the data sets in the data directory.
+    fn configuration() -> Configuration {
+        let data_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data");
+        Configuration::default(InputSource::new(data_path.join("retweets.json")),
+                               InputSource::new(data_path.join("social_graph")))
+    }
+
+    #[test]
+    fn assemble() {
+        let manifest: Value = super::assemble(&configuration(), false).expect("Could not assemble the manifest");
+        assert_eq!(manifest.get("version").and_then(Value::as_str), Some(env!("CARGO_PKG_VERSION")));
+        assert!(manifest.get("configuration").and_then(Value::as_table).is_some());
+
+        for data_set in &["retweets", "social_graph"] {
+            let fingerprint: &Value = manifest.get(data_set).expect("Fingerprint missing");
+            assert!(fingerprint.get("location").and_then(Value::as_str).is_some());
+            assert!(fingerprint.get("size").and_then(Value::as_integer).map_or(false, |size| size > 0));
+            assert!(fingerprint.get("modified").and_then(Value::as_integer).is_some());
+            assert!(fingerprint.get("sha256").is_none());
+        }
+
+        // The manifest can be written as TOML.
+        let contents: String = toml::to_string(&manifest).expect("Could not serialize the manifest");
+        assert!(contents.contains("[configuration]"));
+        assert!(contents.contains("[retweets]"));
+        assert!(contents.contains("[social_graph]"));
+    }
+
+    #[test]
+    fn assemble_full_hash() {
+        let manifest: Value = super::assemble(&configuration(), true).expect("Could not assemble the manifest");
+
+        for data_set in &["retweets", "social_graph"] {
+            let hash: &str = manifest.get(data_set)
+                .and_then(|fingerprint| fingerprint.get("sha256"))
+                .and_then(Value::as_str)
+                .expect("Hash missing");
+            assert_eq!(hash.len(), 64);
+            assert!(hash.chars().all(|character| character.is_digit(16)));
+        }
+
+        // The hash only depends on the contents.
+        let second: Value = super::assemble(&configuration(), true).expect("Could not assemble the manifest");
+        assert_eq!(manifest.get("retweets"), second.get("retweets"));
+    }
+
+    #[test]
+    fn fingerprint_s3() {
+        let input = InputSource::new("path/to/retweets.json")
+            .s3(Some(S3::new("bucket", "region")));
+        let fingerprint = super::Fingerprint::new(&input, true).expect("Could not fingerprint the input");
+        assert_eq!(fingerprint.size, None);
+        assert_eq!(fingerprint.modified, None);
+        assert_eq!(fingerprint.sha256, None);
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Compare the statistics files of two runs.
+//!
+//! Only the scalar numeric fields of the statistics are compared, i.e. the configuration is ignored.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Read;
+use std::path::Path;
+
+use crgp_lib::Error;
+use crgp_lib::Result;
+use toml::Value;
+
+/// The change of a single numeric statistic between two runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delta {
+    /// The name of the statistic.
+    pub field: String,
+
+    /// The value in the first run.
+    pub before: i64,
+
+    /// The value in the second run.
+    pub after: i64,
+}
+
+impl Delta {
+    /// The absolute change from `before` to `after`.
+    pub fn absolute(&self) -> i64 {
+        self.after - self.before
+    }
+
+    /// The change from `before` to `after` in percent of `before`. If `before` is `0`, the change is undefined.
+    pub fn percent(&self) -> Option<f64> {
+        if self.before == 0 {
+            return None;
+        }
+
+        Some(self.absolute() as f64 * 100.0 / self.before as f64)
+    }
+
+    /// Determine if the change is a regression.
+    ///
+    /// Times regress if they increase by more than `threshold` percent, and rates regress if they decrease by more
+    /// than `threshold` percent; a change of exactly `threshold` percent is not a regression. Changes from `0` always
+    /// exceed the threshold. All other statistics are counts which must not change between runs on the same data, thus
+    /// any change is a regression, regardless of the threshold.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        let is_time: bool = self.field.starts_with("time_") || self.field == "total_time";
+        let is_rate: bool = self.field.ends_with("_rate");
+        let exceeds_threshold: bool = self.percent().map_or(true, |percent| percent.abs() > threshold);
+        if is_time {
+            self.after > self.before && exceeds_threshold
+        } else if is_rate {
+            self.after < self.before && exceeds_threshold
+        } else {
+            self.after != self.before
+        }
+    }
+}
+
+/// Load the scalar numeric fields from the statistics file at `path`.
+pub fn load(path: &Path) -> Result<BTreeMap<String, i64>> {
+    let mut contents = String::new();
+    let _ = File::open(path)?.read_to_string(&mut contents)?;
+
+    let statistics: Value = match contents.parse() {
+        Ok(statistics) => statistics,
+        Err(message) => {
+            let message: String = format!("Could not parse statistics file {file}: {error}",
+                                          file = path.display(), error = message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let table = match statistics.as_table() {
+        Some(table) => table,
+        None => return Ok(BTreeMap::new())
+    };
+
+    Ok(table.iter()
+        .filter_map(|(field, value)| value.as_integer().map(|value| (field.clone(), value)))
+        .collect())
+}
+
+/// Compute the changes of all statistics present in both `before` and `after`, sorted by their name.
+pub fn diff(before: &BTreeMap<String, i64>, after: &BTreeMap<String, i64>) -> Vec<Delta> {
+    before.iter()
+        .filter_map(|(field, before)| {
+            after.get(field).map(|after| Delta {
+                field: field.clone(),
+                before: *before,
+                after: *after,
+            })
+        })
+        .collect()
+}
+
+/// Format the `deltas` as a table, flagging regressions by more than `threshold` percent.
+pub fn format_table(deltas: &[Delta], threshold: f64) -> String {
+    let mut table: String = format!("{field:<30} {before:>20} {after:>20} {delta:>20} {percent:>10}\n",
+                                    field = "Statistic", before = "Before", after = "After", delta = "Delta",
+                                    percent = "Change");
+    for delta in deltas {
+        let percent: String = match delta.percent() {
+            Some(percent) => format!("{:+.2}%", percent),
+            None => String::from("n/a")
+        };
+        let flag: &str = if delta.is_regression(threshold) { "  REGRESSION" } else { "" };
+        table.push_str(&format!("{field:<30} {before:>20} {after:>20} {delta:>+20} {percent:>10}{flag}\n",
+                                field = delta.field, before = delta.before, after = delta.after,
+                                delta = delta.absolute(), percent = percent, flag = flag));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use super::*;
+
+    /// Get the path to the statistics file `name` in the data directory.
+    fn data_file(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data").join(name)
+    }
+
+    #[test]
+    fn diff_files() {
+        let before: BTreeMap<String, i64> = load(&data_file("statistics_before.toml"))
+            .expect("Could not load the statistics");
+        let after: BTreeMap<String, i64> = load(&data_file("statistics_after.toml"))
+            .expect("Could not load the statistics");
+
+        // Only the numeric fields are loaded.
+        assert_eq!(before.len(), 11);
+        assert!(!before.contains_key("cancelled"));
+        assert!(!before.contains_key("configuration"));
+
+        let deltas: Vec<Delta> = diff(&before, &after);
+        assert_eq!(deltas.len(), 11);
+
+        let regressions: Vec<&str> = deltas.iter()
+            .filter(|delta| delta.is_regression(10.0))
+            .map(|delta| delta.field.as_str())
+            .collect();
+        assert_eq!(regressions, vec!["retweet_processing_rate", "time_to_process_retweets", "total_time"]);
+
+        let table: String = format_table(&deltas, 10.0);
+        assert_eq!(table.lines().count(), 12);
+        assert_eq!(table.matches("REGRESSION").count(), 3);
+    }
+
+    #[test]
+    fn is_regression() {
+        let delta = Delta { field: String::from("time_to_setup"), before: 100, after: 120 };
+        assert_eq!(delta.absolute(), 20);
+        assert_eq!(delta.percent(), Some(20.0));
+        assert!(delta.is_regression(10.0));
+        assert!(!delta.is_regression(20.0));
+
+        // Faster is better.
+        let delta = Delta { field: String::from("total_time"), before: 100, after: 50 };
+        assert!(!delta.is_regression(10.0));
+
+        // Higher rates are better.
+        let delta = Delta { field: String::from("retweet_processing_rate"), before: 100, after: 50 };
+        assert_eq!(delta.percent(), Some(-50.0));
+        assert!(delta.is_regression(10.0));
+        let delta = Delta { field: String::from("retweet_processing_rate"), before: 100, after: 200 };
+        assert!(!delta.is_regression(10.0));
+
+        // Counts must not change.
+        let delta = Delta { field: String::from("number_of_retweets"), before: 100, after: 80 };
+        assert!(delta.is_regression(10.0));
+        let delta = Delta { field: String::from("number_of_retweets"), before: 100, after: 100 };
+        assert!(!delta.is_regression(0.0));
+        let delta = Delta { field: String::from("number_of_retweets"), before: 100, after: 101 };
+        assert!(delta.is_regression(10.0));
+
+        // Changes from zero.
+        let delta = Delta { field: String::from("number_of_dummy_friendships"), before: 0, after: 1 };
+        assert_eq!(delta.percent(), None);
+        assert!(delta.is_regression(10.0));
+    }
+
+    #[test]
+    fn is_regression_threshold() {
+        // A change of exactly the threshold is not a regression.
+        let delta = Delta { field: String::from("time_to_process_retweets"), before: 100, after: 110 };
+        assert!(!delta.is_regression(10.0));
+        let delta = Delta { field: String::from("time_to_process_retweets"), before: 100, after: 111 };
+        assert!(delta.is_regression(10.0));
+
+        let delta = Delta { field: String::from("retweet_processing_rate"), before: 100, after: 90 };
+        assert!(!delta.is_regression(10.0));
+        let delta = Delta { field: String::from("retweet_processing_rate"), before: 100, after: 89 };
+        assert!(delta.is_regression(10.0));
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Summarize the statistics files of several runs, e.g. of a parameter sweep, in a single directory.
+//!
+//! Each run is labelled by the algorithm and the batch size from the configuration saved within its statistics. Only
+//! the Retweet processing rate and the times are summarized.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::fs::read_dir;
+use std::io::Error as IOError;
+use std::io::ErrorKind as IOErrorKind;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crgp_lib::Error;
+use crgp_lib::Result;
+use toml::Value;
+
+use statistics_diff;
+
+/// The summarized statistics and their column headings, in the order of the columns.
+const FIELDS: [(&'static str, &'static str); 6] = [
+    ("retweet_processing_rate", "Rate (RT/s)"),
+    ("time_to_setup", "Setup (ns)"),
+    ("time_to_process_social_graph", "Social Graph (ns)"),
+    ("time_to_load_retweets", "Load RTs (ns)"),
+    ("time_to_process_retweets", "Process RTs (ns)"),
+    ("total_time", "Total (ns)"),
+];
+
+/// The statistics of a single run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Run {
+    /// The name of the statistics file without its extension.
+    pub name: String,
+
+    /// A description of the configuration of the run, or `"n/a"` if the configuration is not known.
+    pub label: String,
+
+    /// The scalar numeric fields of the statistics.
+    pub statistics: BTreeMap<String, i64>,
+}
+
+/// The minimum, maximum, and mean of a single statistic over all runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aggregate {
+    /// The name of the statistic.
+    pub field: String,
+
+    /// The smallest value of any run.
+    pub min: i64,
+
+    /// The largest value of any run.
+    pub max: i64,
+
+    /// The mean value of all runs.
+    pub mean: f64,
+}
+
+/// Load the statistics files of all runs in the `directory`, sorted by their name. Errors if the directory cannot be
+/// read or a statistics file cannot be parsed.
+///
+/// All TOML files containing a Retweet processing rate are considered statistics files, other files (e.g. the run
+/// manifest or the social graph load statistics) are skipped.
+pub fn load_directory(directory: &Path) -> Result<Vec<Run>> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in read_dir(directory)? {
+        let path: PathBuf = entry?.path();
+        if path.is_file() && path.extension().map_or(false, |extension| extension == "toml") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut runs: Vec<Run> = Vec::new();
+    for path in paths {
+        let statistics: BTreeMap<String, i64> = statistics_diff::load(&path)?;
+        if !statistics.contains_key("retweet_processing_rate") {
+            continue;
+        }
+
+        runs.push(Run {
+            name: path.file_stem().map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            label: load_label(&path)?,
+            statistics: statistics,
+        });
+    }
+
+    Ok(runs)
+}
+
+/// Load the configuration from the statistics file at `path` and describe it by its algorithm and batch size.
+fn load_label(path: &Path) -> Result<String> {
+    let mut contents = String::new();
+    let _ = File::open(path)?.read_to_string(&mut contents)?;
+
+    let statistics: Value = match contents.parse() {
+        Ok(statistics) => statistics,
+        Err(message) => {
+            let message: String = format!("Could not parse statistics file {file}: {error}",
+                                          file = path.display(), error = message);
+            return Err(Error::from(IOError::new(IOErrorKind::InvalidData, message)));
+        }
+    };
+
+    let configuration: &Value = match statistics.get("configuration") {
+        Some(configuration) => configuration,
+        None => return Ok(String::from("n/a"))
+    };
+    let algorithm: &str = configuration.get("algorithm")
+        .and_then(Value::as_str)
+        .unwrap_or("n/a");
+    let label: String = match configuration.get("batch_size").and_then(Value::as_integer) {
+        Some(batch_size) => format!("{algorithm}, batch size {batch_size}", algorithm = algorithm,
+                                    batch_size = batch_size),
+        None => String::from(algorithm)
+    };
+
+    Ok(label)
+}
+
+/// Compute the minimum, maximum, and mean of each summarized statistic over all `runs` that contain it.
+pub fn aggregate(runs: &[Run]) -> Vec<Aggregate> {
+    FIELDS.iter()
+        .filter_map(|&(field, _heading)| {
+            let values: Vec<i64> = runs.iter()
+                .filter_map(|run| run.statistics.get(field).cloned())
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+
+            Some(Aggregate {
+                field: String::from(field),
+                min: values.iter().cloned().min().unwrap_or(0),
+                max: values.iter().cloned().max().unwrap_or(0),
+                mean: values.iter().map(|&value| value as f64).sum::<f64>() / values.len() as f64,
+            })
+        })
+        .collect()
+}
+
+/// Format the `runs` as a table with one row per run, followed by a table of the aggregates over all runs.
+pub fn format_table(runs: &[Run]) -> String {
+    let mut table: String = format!("{name:<30} {label:<30}", name = "Run", label = "Configuration");
+    for &(_field, heading) in &FIELDS {
+        table.push_str(&format!(" {heading:>18}", heading = heading));
+    }
+    table.push('\n');
+
+    for run in runs {
+        table.push_str(&format!("{name:<30} {label:<30}", name = run.name, label = run.label));
+        for &(field, _heading) in &FIELDS {
+            let value: String = match run.statistics.get(field) {
+                Some(value) => value.to_string(),
+                None => String::from("n/a")
+            };
+            table.push_str(&format!(" {value:>18}", value = value));
+        }
+        table.push('\n');
+    }
+
+    table.push('\n');
+    table.push_str(&format!("{field:<30} {min:>20} {max:>20} {mean:>20}\n",
+                            field = "Statistic", min = "Min", max = "Max", mean = "Mean"));
+    for aggregate in aggregate(runs) {
+        table.push_str(&format!("{field:<30} {min:>20} {max:>20} {mean:>20.2}\n",
+                                field = aggregate.field, min = aggregate.min, max = aggregate.max,
+                                mean = aggregate.mean));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use super::*;
+
+    /// Get the path to the directory `name` in the data directory.
+    fn data_directory(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data").join(name)
+    }
+
+    #[test]
+    fn summarize_directory() {
+        let runs: Vec<Run> = load_directory(&data_directory("statistics_sweep"))
+            .expect("Could not load the statistics");
+
+        // The manifest and the social graph load statistics are skipped.
+        let names: Vec<&str> = runs.iter().map(|run| run.name.as_str()).collect();
+        assert_eq!(names, vec!["crgp_2017-06-01_12-00-00", "crgp_2017-06-01_13-00-00", "crgp_2017-06-01_14-00-00"]);
+        assert_eq!(runs[0].label, "GALE, batch size 50000");
+        assert_eq!(runs[1].label, "LEAF, batch size 1000");
+        assert_eq!(runs[2].label, "n/a");
+
+        let aggregates: Vec<Aggregate> = aggregate(&runs);
+        assert_eq!(aggregates.len(), 6);
+        assert_eq!(aggregates[0], Aggregate {
+            field: String::from("retweet_processing_rate"),
+            min: 250,
+            max: 750,
+            mean: 500.0,
+        });
+        assert_eq!(aggregates[5], Aggregate {
+            field: String::from("total_time"),
+            min: 2_000_000_000,
+            max: 4_000_000_000,
+            mean: 3_000_000_000.0,
+        });
+
+        // A header and a row per run, a blank line, and a header and a row per aggregate.
+        let table: String = format_table(&runs);
+        assert_eq!(table.lines().count(), 12);
+        assert!(table.contains("LEAF, batch size 1000"));
+    }
+
+    #[test]
+    fn summarize_missing_directory() {
+        assert!(load_directory(&data_directory("missing")).is_err());
+    }
+}
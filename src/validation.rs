@@ -15,6 +15,33 @@ pub fn usize(value: String) -> Result<(), String> {
     }
 }
 
+/// Ensure `value` is parsable to `u8`.
+#[cfg_attr(feature = "cargo-clippy", allow(needless_pass_by_value))]
+pub fn u8(value: String) -> Result<(), String> {
+    match value.parse::<u8>() {
+        Ok(_) => Ok(()),
+        _ => Err(String::from("The value must be an integer between 0 and 255."))
+    }
+}
+
+/// Ensure `value` is parsable to `u64`.
+#[cfg_attr(feature = "cargo-clippy", allow(needless_pass_by_value))]
+pub fn u64(value: String) -> Result<(), String> {
+    match value.parse::<u64>() {
+        Ok(_) => Ok(()),
+        _ => Err(String::from("The value must be a non-negative integer."))
+    }
+}
+
+/// Ensure `value` is parsable to `i64`.
+#[cfg_attr(feature = "cargo-clippy", allow(needless_pass_by_value))]
+pub fn i64(value: String) -> Result<(), String> {
+    match value.parse::<i64>() {
+        Ok(_) => Ok(()),
+        _ => Err(String::from("The value must be an integer."))
+    }
+}
+
 /// Ensure `value` is parsable to `usize` with a value greater than `0`.
 #[cfg_attr(feature = "cargo-clippy", allow(needless_pass_by_value))]
 pub fn positive_usize(value: String) -> Result<(), String> {
@@ -24,8 +51,78 @@ pub fn positive_usize(value: String) -> Result<(), String> {
     }
 }
 
+/// Ensure `value` is parsable to `f64` with a value from `0` to `1`.
+#[cfg_attr(feature = "cargo-clippy", allow(needless_pass_by_value))]
+pub fn ratio(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(value) if value >= 0.0 && value <= 1.0 => Ok(()),
+        _ => Err(String::from("The value must be a number from 0 to 1."))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn i64() {
+        let result: Result<(), String> = super::i64(String::from("a"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"), String::from("The value must be an integer."));
+
+        let result: Result<(), String> = super::i64(String::from("9223372036854775808"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"), String::from("The value must be an integer."));
+
+        let result: Result<(), String> = super::i64(String::from("-3"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+
+        let result: Result<(), String> = super::i64(String::from("3"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+    }
+
+    #[test]
+    fn u8() {
+        let result: Result<(), String> = super::u8(String::from("a"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"),
+                   String::from("The value must be an integer between 0 and 255."));
+
+        let result: Result<(), String> = super::u8(String::from("256"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"),
+                   String::from("The value must be an integer between 0 and 255."));
+
+        let result: Result<(), String> = super::u8(String::from("0"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+
+        let result: Result<(), String> = super::u8(String::from("255"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+    }
+
+    #[test]
+    fn u64() {
+        let result: Result<(), String> = super::u64(String::from("a"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"),
+                   String::from("The value must be a non-negative integer."));
+
+        let result: Result<(), String> = super::u64(String::from("-1"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"),
+                   String::from("The value must be a non-negative integer."));
+
+        let result: Result<(), String> = super::u64(String::from("0"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+
+        let result: Result<(), String> = super::u64(String::from("18446744073709551615"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+    }
+
     #[test]
     fn usize() {
         let result: Result<(), String> = super::usize(String::from(""));
@@ -71,4 +168,31 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.expect("Result is not ok"), ());
     }
+
+    #[test]
+    fn ratio() {
+        let result: Result<(), String> = super::ratio(String::from("a"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"), String::from("The value must be a number from 0 to 1."));
+
+        let result: Result<(), String> = super::ratio(String::from("-0.1"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"), String::from("The value must be a number from 0 to 1."));
+
+        let result: Result<(), String> = super::ratio(String::from("1.5"));
+        assert!(result.is_err());
+        assert_eq!(result.expect_err("Result is not error"), String::from("The value must be a number from 0 to 1."));
+
+        let result: Result<(), String> = super::ratio(String::from("0"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+
+        let result: Result<(), String> = super::ratio(String::from("0.25"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+
+        let result: Result<(), String> = super::ratio(String::from("1"));
+        assert!(result.is_ok());
+        assert_eq!(result.expect("Result is not ok"), ());
+    }
 }